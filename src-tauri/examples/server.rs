@@ -3,6 +3,7 @@
 use std::{
     env, io,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::Path,
     sync::Arc,
 };
 
@@ -12,13 +13,13 @@ use tokio::{net::TcpListener, signal};
 
 use cc_switch_lib::{
     store::AppState,
-    web_api::{create_router_with_auth_state, load_or_generate_web_credentials, SharedState},
+    web_api::{
+        create_router_with_auth_state, file_mode, load_or_generate_web_credentials, logging,
+        security_posture_summary, SharedState,
+    },
+    ConfigService,
 };
 
-fn init_logger() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
-}
-
 fn env_truthy(name: &str) -> bool {
     env::var(name).is_ok_and(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
 }
@@ -82,7 +83,22 @@ fn is_lan_bind_ip(ip: IpAddr) -> bool {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    init_logger();
+    logging::init_logger("info");
+
+    // `server --import <path>`：导入配置（自动备份）后输出备份 ID 并退出，不启动服务，便于部署脚本使用
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(pos) = args.iter().position(|arg| arg == "--import") {
+        let path = args.get(pos + 1).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Usage: server --import <path>")
+        })?;
+        let backup_id = ConfigService::import_config_from_cli(Path::new(path))?;
+        info!("Imported configuration from {path}");
+        println!("{backup_id}");
+        return Ok(());
+    }
+
+    // 无效的 CC_SWITCH_FILE_MODE（如其他用户可写）直接拒绝启动，避免凭证文件权限过宽
+    file_mode()?;
 
     let (auth_state, password_path) = load_or_generate_web_credentials()?;
     let username = auth_state
@@ -162,6 +178,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .into());
     }
 
+    info!("{}", security_posture_summary(addr, allow_insecure));
     info!(
         "Starting web server on http://{} with file-based credentials at {} (username: {}, token stored only on disk)",
         addr,
@@ -175,9 +192,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     let listener = TcpListener::bind(addr).await?;
-    serve(listener, app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // 提供对端地址，供 TRUSTED_PROXIES 判定是否采信 X-Forwarded-For
+    serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     info!("Server shut down cleanly");
     Ok(())
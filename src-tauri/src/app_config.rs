@@ -79,6 +79,9 @@ pub struct McpServer {
     pub docs: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// 受保护的服务器：删除时需显式指定 `force`，防止误删关键服务
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
 }
 
 /// MCP 配置：单客户端维度（v3.6.x 及以前，保留用于向后兼容）
@@ -336,6 +339,9 @@ impl CommonConfigSnippets {
 pub struct MultiAppConfig {
     #[serde(default = "default_version")]
     pub version: u32,
+    /// 配置修订号：每次保存递增，供 Web 端 `If-Match` 条件写入检测并发修改
+    #[serde(default)]
+    pub revision: u64,
     /// 应用管理器（claude/codex）
     #[serde(flatten)]
     pub apps: HashMap<String, ProviderManager>,
@@ -371,6 +377,7 @@ impl Default for MultiAppConfig {
 
         Self {
             version: 2,
+            revision: 0,
             apps,
             mcp: McpRoot::default(),
             prompts: PromptRoot::default(),
@@ -453,6 +460,11 @@ impl MultiAppConfig {
             updated = true;
         }
 
+        // 手动编辑的配置可能同时启用多个提示词，写入文件时结果不确定
+        if self.normalize_enabled_prompts() {
+            updated = true;
+        }
+
         // 迁移通用配置片段：claude_common_config_snippet → common_config_snippets.claude
         if let Some(old_claude_snippet) = self.claude_common_config_snippet.take() {
             log::info!(
@@ -465,6 +477,46 @@ impl MultiAppConfig {
         Ok(updated)
     }
 
+    /// 每个应用最多保留一个启用的提示词：保留最近更新的一个，其余全部禁用。返回是否有修正。
+    fn normalize_enabled_prompts(&mut self) -> bool {
+        let mut corrected = false;
+        for (app, config) in [
+            ("claude", &mut self.prompts.claude),
+            ("codex", &mut self.prompts.codex),
+            ("gemini", &mut self.prompts.gemini),
+            ("opencode", &mut self.prompts.opencode),
+        ] {
+            let enabled_count = config.prompts.values().filter(|p| p.enabled).count();
+            if enabled_count <= 1 {
+                continue;
+            }
+
+            // 按更新时间（缺失时取创建时间）选出保留项；时间相同则取 ID 最小者，保证结果确定
+            let keep_id = config
+                .prompts
+                .values()
+                .filter(|p| p.enabled)
+                .max_by(|a, b| {
+                    let a_time = a.updated_at.or(a.created_at).unwrap_or(0);
+                    let b_time = b.updated_at.or(b.created_at).unwrap_or(0);
+                    a_time.cmp(&b_time).then_with(|| b.id.cmp(&a.id))
+                })
+                .map(|p| p.id.clone());
+
+            for prompt in config.prompts.values_mut() {
+                if prompt.enabled && Some(&prompt.id) != keep_id.as_ref() {
+                    prompt.enabled = false;
+                }
+            }
+            log::warn!(
+                "{app} 同时启用了 {enabled_count} 个提示词，仅保留最近更新的 {}",
+                keep_id.as_deref().unwrap_or_default()
+            );
+            corrected = true;
+        }
+        corrected
+    }
+
     /// 从文件加载配置（仅支持 v2 结构）
     pub fn load() -> Result<Self, AppError> {
         let config_path = get_app_config_path()?;
@@ -526,6 +578,11 @@ impl MultiAppConfig {
         Ok(())
     }
 
+    /// 整体替换配置（导入、补丁、重置）时沿用并递增旧配置的修订号，保持其单调递增
+    pub(crate) fn inherit_revision(&mut self, previous: &MultiAppConfig) {
+        self.revision = previous.revision + 1;
+    }
+
     /// 获取指定应用的管理器
     pub fn get_manager(&self, app: &AppType) -> Option<&ProviderManager> {
         self.apps.get(app.as_str())
@@ -791,6 +848,7 @@ impl MultiAppConfig {
                             homepage,
                             docs,
                             tags,
+                            protected: false,
                         },
                     );
                 }
@@ -912,6 +970,52 @@ mod tests {
         );
     }
 
+    #[test]
+    #[serial]
+    fn keeps_only_most_recent_enabled_prompt_after_load() {
+        let _home = TempHome::new();
+        let config_path = crate::config::get_app_config_path().expect("config path resolves");
+        fs::create_dir_all(config_path.parent().expect("config dir")).expect("create config dir");
+        let raw = serde_json::json!({
+            "version": 2,
+            "claude": { "providers": {}, "current": "" },
+            "prompts": {
+                "claude": {
+                    "prompts": {
+                        "old": {
+                            "id": "old", "name": "old", "content": "old",
+                            "enabled": true, "updatedAt": 100
+                        },
+                        "new": {
+                            "id": "new", "name": "new", "content": "new",
+                            "enabled": true, "updatedAt": 200
+                        },
+                        "off": { "id": "off", "name": "off", "content": "off", "enabled": false }
+                    }
+                }
+            }
+        });
+        fs::write(&config_path, raw.to_string()).expect("write config");
+
+        let config = MultiAppConfig::load().expect("load config");
+        let prompts = &config.prompts.claude.prompts;
+        assert!(prompts["new"].enabled);
+        assert!(!prompts["old"].enabled);
+        assert!(!prompts["off"].enabled);
+
+        // 修正结果会写回磁盘
+        let reloaded = MultiAppConfig::load().expect("reload config");
+        let enabled: Vec<_> = reloaded
+            .prompts
+            .claude
+            .prompts
+            .values()
+            .filter(|p| p.enabled)
+            .map(|p| p.id.as_str())
+            .collect();
+        assert_eq!(enabled, vec!["new"]);
+    }
+
     #[test]
     #[serial]
     fn skips_empty_prompt_files_during_import() {
@@ -0,0 +1,49 @@
+//! 可替换的时钟：备份 ID、技能安装时间等时间戳统一取自 `AppState::clock()`，测试可注入固定时间
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+/// 时间来源
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟（默认）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 固定时钟：始终返回设定的时间，可手动推进，用于确定性测试
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, delta: Duration) {
+        let mut guard = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *guard += delta;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 
 use crate::config::{
     atomic_write, delete_file, get_client_config_dir_info, get_client_config_dir_path,
-    sanitize_provider_name, write_json_file, write_text_file,
+    read_json_file, sanitize_provider_name, write_json_file, write_text_file,
 };
 use crate::error::AppError;
 use serde_json::Value;
@@ -117,6 +117,43 @@ pub fn read_codex_config_text() -> Result<String, AppError> {
     }
 }
 
+/// 读取 `~/.codex/auth.json`，若不存在返回 `null`
+pub fn read_codex_auth() -> Result<Value, AppError> {
+    let path = get_codex_auth_path()?;
+    if path.exists() {
+        read_json_file(&path)
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+/// 遮蔽 auth 中的密钥与令牌（键名包含 `key` 或 `token` 的字符串），仅保留末 4 位便于辨认
+pub fn redact_codex_auth(auth: &mut Value) {
+    match auth {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let lower = key.to_ascii_lowercase();
+                let sensitive = lower.contains("key") || lower.contains("token");
+                match value {
+                    Value::String(secret) if sensitive => *secret = mask_secret(secret),
+                    _ => redact_codex_auth(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_codex_auth),
+        _ => {}
+    }
+}
+
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{tail}")
+}
+
 /// 对非空的 TOML 文本进行语法校验
 pub fn validate_config_toml(text: &str) -> Result<(), AppError> {
     if text.trim().is_empty() {
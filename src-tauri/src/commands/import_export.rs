@@ -56,6 +56,7 @@ pub async fn import_config_from_file(
 #[tauri::command]
 pub async fn sync_current_providers_live(state: State<'_, AppState>) -> Result<Value, String> {
     {
+        let _live = state.lock_all_live().await;
         let mut config_state = state
             .config
             .write()
@@ -117,6 +117,7 @@ pub async fn upsert_mcp_server_in_config(
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            protected: false,
         }
     };
 
@@ -128,6 +129,7 @@ pub async fn upsert_mcp_server_in_config(
     }
 
     McpService::upsert_server(&state, new_server)
+        .await
         .map(|_| true)
         .map_err(|e| e.to_string())
 }
@@ -139,7 +141,9 @@ pub async fn delete_mcp_server_in_config(
     _app: String, // 参数保留用于向后兼容，但在统一结构中不再需要
     id: String,
 ) -> Result<bool, String> {
-    McpService::delete_server(&state, &id).map_err(|e| e.to_string())
+    McpService::delete_server(&state, &id, false)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// 设置启用状态并同步到客户端配置
@@ -152,7 +156,9 @@ pub async fn set_mcp_enabled(
     enabled: bool,
 ) -> Result<bool, String> {
     let app_ty = AppType::parse_supported(&app).map_err(|e| e.to_string())?;
-    McpService::set_enabled(&state, app_ty, &id, enabled).map_err(|e| e.to_string())
+    McpService::set_enabled(&state, app_ty, &id, enabled)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -175,13 +181,17 @@ pub async fn upsert_mcp_server(
     state: State<'_, AppState>,
     server: McpServer,
 ) -> Result<(), String> {
-    McpService::upsert_server(&state, server).map_err(|e| e.to_string())
+    McpService::upsert_server(&state, server)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// 删除 MCP 服务器
 #[tauri::command]
 pub async fn delete_mcp_server(state: State<'_, AppState>, id: String) -> Result<bool, String> {
-    McpService::delete_server(&state, &id).map_err(|e| e.to_string())
+    McpService::delete_server(&state, &id, false)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// 切换 MCP 服务器在指定应用的启用状态
@@ -193,5 +203,7 @@ pub async fn toggle_mcp_app(
     enabled: bool,
 ) -> Result<(), String> {
     let app_ty = AppType::parse_supported(&app).map_err(|e| e.to_string())?;
-    McpService::toggle_app(&state, &server_id, app_ty, enabled).map_err(|e| e.to_string())
+    McpService::toggle_app(&state, &server_id, app_ty, enabled)
+        .await
+        .map_err(|e| e.to_string())
 }
@@ -24,7 +24,9 @@ pub async fn upsert_prompt(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let app_type = AppType::parse_supported(&app).map_err(|e| e.to_string())?;
-    PromptService::upsert_prompt(&state, app_type, &id, prompt).map_err(|e| e.to_string())
+    PromptService::upsert_prompt(&state, app_type, &id, prompt)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -34,7 +36,9 @@ pub async fn delete_prompt(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let app_type = AppType::parse_supported(&app).map_err(|e| e.to_string())?;
-    PromptService::delete_prompt(&state, app_type, &id).map_err(|e| e.to_string())
+    PromptService::delete_prompt(&state, app_type, &id)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -44,7 +48,9 @@ pub async fn enable_prompt(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let app_type = AppType::parse_supported(&app).map_err(|e| e.to_string())?;
-    PromptService::enable_prompt(&state, app_type, &id).map_err(|e| e.to_string())
+    PromptService::enable_prompt(&state, app_type, &id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -53,7 +59,9 @@ pub async fn import_prompt_from_file(
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     let app_type = AppType::parse_supported(&app).map_err(|e| e.to_string())?;
-    PromptService::import_from_file(&state, app_type).map_err(|e| e.to_string())
+    PromptService::import_from_file(&state, app_type)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
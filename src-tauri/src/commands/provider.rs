@@ -64,13 +64,15 @@ pub fn add_provider(
 
 /// 更新供应商
 #[tauri::command]
-pub fn update_provider(
+pub async fn update_provider(
     state: State<'_, AppState>,
     app: String,
     provider: Provider,
 ) -> Result<bool, String> {
     let app_type = parse_provider_app_type(&app)?;
-    ProviderService::update(state.inner(), app_type, provider).map_err(|e| e.to_string())
+    ProviderService::update(state.inner(), app_type, provider)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// 删除供应商
@@ -87,8 +89,12 @@ pub fn delete_provider(
 }
 
 /// 切换供应商
-fn switch_provider_internal(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
-    ProviderService::switch(state, app_type, id)
+async fn switch_provider_internal(
+    state: &AppState,
+    app_type: AppType,
+    id: &str,
+) -> Result<(), AppError> {
+    ProviderService::switch(state, app_type, id).await
 }
 
 #[cfg_attr(not(feature = "test-hooks"), doc(hidden))]
@@ -97,17 +103,18 @@ pub fn switch_provider_test_hook(
     app_type: AppType,
     id: &str,
 ) -> Result<(), AppError> {
-    switch_provider_internal(state, app_type, id)
+    tauri::async_runtime::block_on(switch_provider_internal(state, app_type, id))
 }
 
 #[tauri::command]
-pub fn switch_provider(
+pub async fn switch_provider(
     state: State<'_, AppState>,
     app: String,
     id: String,
 ) -> Result<bool, String> {
     let app_type = parse_provider_app_type(&app)?;
     switch_provider_internal(&state, app_type, &id)
+        .await
         .map(|_| true)
         .map_err(|e| e.to_string())
 }
@@ -3,7 +3,6 @@ use crate::error::format_skill_error;
 use crate::services::skill::SkillState;
 use crate::services::{Skill, SkillRepo, SkillService};
 use crate::store::AppState;
-use chrono::Utc;
 use serde::Serialize;
 use std::sync::Arc;
 use tauri::State;
@@ -27,7 +26,8 @@ pub async fn get_skills(
     app_state: State<'_, AppState>,
 ) -> Result<SkillsResponse, String> {
     let app = parse_skill_app(app)?;
-    let service_for_app = SkillService::new_for_app(&app).map_err(|e| e.to_string())?;
+    let service_for_app =
+        SkillService::new_for_app(&app, app_state.tasks()).map_err(|e| e.to_string())?;
 
     let (repos, mut repo_cache) = {
         let config = app_state.config.read().map_err(|e| e.to_string())?;
@@ -41,7 +41,7 @@ pub async fn get_skills(
         .list_skills(repos, &mut repo_cache)
         .await
         .map_err(|e| e.to_string())?;
-    let skills = result.skills;
+    let mut skills = result.skills;
     let warnings = result.warnings;
     let cache_hit = result.cache_hit;
     let refreshing = result.refreshing;
@@ -49,6 +49,7 @@ pub async fn get_skills(
     {
         let mut config = app_state.config.write().map_err(|e| e.to_string())?;
         config.skills.repo_cache = repo_cache;
+        service_for_app.attach_install_states(&mut skills, &config.skills.skills);
     }
     app_state.save().map_err(|e| e.to_string())?;
 
@@ -70,7 +71,8 @@ pub async fn install_skill(
 ) -> Result<bool, String> {
     let force = force.unwrap_or(false);
     let app = parse_skill_app(app)?;
-    let service_for_app = SkillService::new_for_app(&app).map_err(|e| e.to_string())?;
+    let service_for_app =
+        SkillService::new_for_app(&app, app_state.tasks()).map_err(|e| e.to_string())?;
 
     // 先在不持有写锁的情况下收集仓库与技能信息
     let (repos, mut repo_cache) = {
@@ -82,14 +84,14 @@ pub async fn install_skill(
     };
 
     let skills = service_for_app
-        .list_skills(repos, &mut repo_cache)
+        .list_skills_for_install(repos, &mut repo_cache)
         .await
-        .map_err(|e| e.to_string())?
-        .skills;
+        .map_err(|e| e.to_string())?;
 
     let skill =
         SkillService::resolve_install_target(&skills, &directory).map_err(|err| err.to_string())?;
 
+    let mut resolved_ref = None;
     if !skill.installed || force {
         let repo = SkillRepo {
             owner: skill.repo_owner.clone().ok_or_else(|| {
@@ -110,11 +112,13 @@ pub async fn install_skill(
                 .repo_branch
                 .clone()
                 .unwrap_or_else(|| "main".to_string()),
+            git_ref: skill.repo_ref.clone(),
             enabled: true,
             skills_path: skill.skills_path.clone(), // 使用技能记录的 skills_path
+            exclude: Vec::new(),
         };
 
-        service_for_app
+        resolved_ref = service_for_app
             .install_skill(directory.clone(), repo, force)
             .await
             .map_err(|e| e.to_string())?;
@@ -123,13 +127,15 @@ pub async fn install_skill(
     {
         let mut config = app_state.config.write().map_err(|e| e.to_string())?;
         config.skills.repo_cache = repo_cache;
-        config.skills.skills.insert(
-            SkillService::state_key(&app, &directory),
-            SkillState {
-                installed: true,
-                installed_at: Utc::now(),
-            },
+        let key = SkillService::state_key(&app, &directory);
+        // 记录来源仓库与 ref；跳过下载时沿用之前的记录
+        let state = SkillState::for_install(
+            skill,
+            resolved_ref,
+            config.skills.skills.get(&key),
+            app_state.clock().now(),
         );
+        config.skills.skills.insert(key, state);
     }
 
     app_state.save().map_err(|e| e.to_string())?;
@@ -145,7 +151,8 @@ pub fn uninstall_skill(
     app_state: State<'_, AppState>,
 ) -> Result<bool, String> {
     let app = parse_skill_app(app)?;
-    let service_for_app = SkillService::new_for_app(&app).map_err(|e| e.to_string())?;
+    let service_for_app =
+        SkillService::new_for_app(&app, app_state.tasks()).map_err(|e| e.to_string())?;
 
     service_for_app
         .uninstall_skill(directory.clone())
@@ -410,7 +410,7 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
     #[cfg(windows)]
     {
         // Windows 原子替换：优先使用 std::fs::rename；目标存在时删除后重试
-        if let Err(first_err) = fs::rename(&tmp, path) {
+        if let Err(first_err) = retry_transient_windows_lock(|| fs::rename(&tmp, path)) {
             if let Err(remove_err) = fs::remove_file(path) {
                 if remove_err.kind() != std::io::ErrorKind::NotFound {
                     return Err(AppError::IoContext {
@@ -424,14 +424,16 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
                 }
             }
 
-            fs::rename(&tmp, path).map_err(|e| AppError::IoContext {
-                context: format!(
-                    "原子替换失败: {} -> {}（初始错误: {}）",
-                    tmp.display(),
-                    path.display(),
-                    first_err
-                ),
-                source: e,
+            retry_transient_windows_lock(|| fs::rename(&tmp, path)).map_err(|e| {
+                AppError::IoContext {
+                    context: format!(
+                        "原子替换失败: {} -> {}（初始错误: {}）",
+                        tmp.display(),
+                        path.display(),
+                        first_err
+                    ),
+                    source: e,
+                }
             })?;
         }
     }
@@ -456,6 +458,40 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
     Ok(())
 }
 
+/// 目标文件被杀毒软件/索引器短暂占用时 Windows 的错误码：
+/// ERROR_ACCESS_DENIED(5)、ERROR_SHARING_VIOLATION(32)、ERROR_LOCK_VIOLATION(33)
+#[cfg(windows)]
+const WINDOWS_TRANSIENT_LOCK_ERRORS: [i32; 3] = [5, 32, 33];
+#[cfg(windows)]
+const WINDOWS_LOCK_RETRIES: u32 = 5;
+#[cfg(windows)]
+const WINDOWS_LOCK_BACKOFF_MS: u64 = 10;
+
+/// 对短暂的文件占用错误做有限次数的指数退避重试（10ms 起，合计约 310ms），其他错误立即返回
+#[cfg(windows)]
+fn retry_transient_windows_lock<T>(
+    mut op: impl FnMut() -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    let mut delay = std::time::Duration::from_millis(WINDOWS_LOCK_BACKOFF_MS);
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(err)
+                if attempt < WINDOWS_LOCK_RETRIES
+                    && err
+                        .raw_os_error()
+                        .is_some_and(|code| WINDOWS_TRANSIENT_LOCK_ERRORS.contains(&code)) =>
+            {
+                attempt += 1;
+                log::debug!("目标文件被占用（{err}），{delay:?} 后第 {attempt} 次重试");
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// 复制文件
 pub fn copy_file(from: &Path, to: &Path) -> Result<(), AppError> {
     fs::copy(from, to).map_err(|e| AppError::IoContext {
@@ -585,6 +621,43 @@ mod tests {
         assert_eq!(derived, PathBuf::from("claude.json"));
     }
 
+    #[cfg(windows)]
+    #[test]
+    fn atomic_write_retries_while_destination_is_briefly_locked() {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        let temp_dir = tempdir().expect("temp dir should be created");
+        let path = temp_dir.path().join("config.json");
+        fs::write(&path, "old").expect("seed file");
+
+        // share_mode(0)：独占打开，模拟杀毒软件短暂锁定目标文件
+        let lock = fs::OpenOptions::new()
+            .read(true)
+            .share_mode(0)
+            .open(&path)
+            .expect("lock file");
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            drop(lock);
+        });
+
+        atomic_write(&path, b"new").expect("write should succeed once the lock is released");
+        releaser.join().expect("releaser thread");
+        assert_eq!(fs::read_to_string(&path).expect("read file"), "new");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn retry_transient_windows_lock_gives_up_on_other_errors() {
+        let mut calls = 0;
+        let result: std::io::Result<()> = retry_transient_windows_lock(|| {
+            calls += 1;
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
     #[test]
     fn derive_mcp_path_from_root_like_dir_returns_none() {
         let override_dir = PathBuf::from("/");
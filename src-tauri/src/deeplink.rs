@@ -321,7 +321,10 @@ requires_openai_auth = true
         settings_config,
         website_url: Some(request.homepage.clone()),
         category: None,
+        group: None,
+        tags: Vec::new(),
         created_at: None,
+        last_switched_at: None,
         sort_index: None,
         notes: request.notes.clone(),
         meta: None,
@@ -43,6 +43,8 @@ pub enum AppError {
     #[error("MCP 校验失败: {0}")]
     McpValidation(String),
     #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
     Message(String),
     #[error("{zh} ({en})")]
     Localized {
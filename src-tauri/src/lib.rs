@@ -3,6 +3,7 @@ mod app_config;
 mod app_store;
 mod claude_mcp;
 mod claude_plugin;
+mod clock;
 mod codex_config;
 #[cfg(feature = "desktop")]
 mod commands;
@@ -27,6 +28,7 @@ mod usage_script;
 pub mod web_api;
 
 pub use app_config::{AppType, McpApps, McpServer, MultiAppConfig};
+pub use clock::{Clock, FixedClock, SystemClock};
 pub use codex_config::{get_codex_auth_path, get_codex_config_path, write_codex_live_atomic};
 #[cfg(feature = "desktop")]
 pub use commands::*;
@@ -35,16 +37,17 @@ pub use deeplink::{import_provider_from_deeplink, parse_deeplink_url, DeepLinkIm
 pub use error::AppError;
 pub use mcp::{
     import_from_claude, import_from_codex, import_from_gemini, import_from_opencode,
-    remove_server_from_claude, remove_server_from_codex, remove_server_from_gemini,
+    imported_count, remove_server_from_claude, remove_server_from_codex, remove_server_from_gemini,
     remove_server_from_opencode, sync_enabled_to_claude, sync_enabled_to_codex,
     sync_enabled_to_gemini, sync_single_server_to_claude, sync_single_server_to_codex,
-    sync_single_server_to_gemini, sync_single_server_to_opencode,
+    sync_single_server_to_gemini, sync_single_server_to_opencode, McpImportReport,
+    McpImportSkipped,
 };
 pub use prompt::Prompt;
 pub use provider::{Provider, ProviderMeta};
 pub use services::{
-    ConfigService, EndpointLatency, McpService, PromptService, ProviderService, SkillService,
-    SpeedtestService,
+    ConfigService, EndpointLatency, McpService, PromptBundleEntry, PromptBundleImportMode,
+    PromptBundleImportResult, PromptService, ProviderService, SkillService, SpeedtestService,
 };
 pub use settings::{update_settings, AppSettings};
 pub use store::AppState;
@@ -209,8 +212,8 @@ fn handle_provider_tray_event(app: &tauri::AppHandle, event_id: &str) -> bool {
             let app_handle = app.clone();
             let provider_id = provider_id.to_string();
             let app_type = section.app_type.clone();
-            tauri::async_runtime::spawn_blocking(move || {
-                if let Err(e) = switch_provider_internal(&app_handle, app_type, provider_id) {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = switch_provider_internal(&app_handle, app_type, provider_id).await {
                     log::error!("切换{}供应商失败: {e}", section.log_name);
                 }
             });
@@ -376,7 +379,7 @@ fn handle_deeplink_url(
 
 /// 内部切换供应商函数
 #[cfg(feature = "desktop")]
-fn switch_provider_internal(
+async fn switch_provider_internal(
     app: &tauri::AppHandle,
     app_type: crate::app_config::AppType,
     provider_id: String,
@@ -387,6 +390,7 @@ fn switch_provider_internal(
         let provider_id_clone = provider_id.clone();
 
         crate::commands::switch_provider(app_state.clone(), app_type_str.clone(), provider_id)
+            .await
             .map_err(AppError::Message)?;
 
         // 切换成功后重新创建托盘菜单
@@ -661,10 +665,11 @@ pub fn run() {
 
             let _tray = tray_builder.build(app)?;
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
+            let tasks = app_state.tasks();
             app.manage(app_state);
 
             // 初始化 SkillService
-            match SkillService::new() {
+            match SkillService::new(tasks) {
                 Ok(skill_service) => {
                     app.manage(commands::skill::SkillServiceState(Arc::new(skill_service)));
                 }
@@ -91,6 +91,76 @@ fn json_value_to_toml_item(value: &Value, field_name: &str) -> Option<toml_edit:
     }
 }
 
+/// 是否在同步到 live 配置时展开 `${VAR}` 环境变量占位符（EXPAND_ENV_IN_MCP=1 开启）
+pub(crate) fn expand_env_enabled() -> bool {
+    std::env::var("EXPAND_ENV_IN_MCP")
+        .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// 同步前展开服务器规范中 `env`、`args`、`headers` 的 `${VAR}` 占位符
+///
+/// 仅作用于写入 live 配置的副本，config.json 中仍保留占位符；引用的变量未设置时报错。
+pub(crate) fn expand_env_in_spec(spec: &Value) -> Result<Value, AppError> {
+    if !expand_env_enabled() {
+        return Ok(spec.clone());
+    }
+    expand_spec_with(spec, &|name| std::env::var(name).ok())
+}
+
+fn expand_spec_with(
+    spec: &Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<Value, AppError> {
+    let mut expanded = spec.clone();
+    let Some(obj) = expanded.as_object_mut() else {
+        return Ok(expanded);
+    };
+
+    if let Some(args) = obj.get_mut("args").and_then(|v| v.as_array_mut()) {
+        for arg in args.iter_mut() {
+            if let Value::String(s) = arg {
+                *s = expand_placeholders(s, lookup)?;
+            }
+        }
+    }
+    for field in ["env", "headers"] {
+        if let Some(map) = obj.get_mut(field).and_then(|v| v.as_object_mut()) {
+            for value in map.values_mut() {
+                if let Value::String(s) = value {
+                    *s = expand_placeholders(s, lookup)?;
+                }
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+fn expand_placeholders(
+    input: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, AppError> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            // 未闭合的占位符按原样保留
+            out.push_str(&rest[start..]);
+            return Ok(out);
+        };
+        let name = &after[..end];
+        let value = lookup(name).ok_or_else(|| {
+            AppError::McpValidation(format!("MCP 配置引用的环境变量未设置: {name}"))
+        })?;
+        out.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 /// Helper: 将 JSON MCP 服务器规范转换为 toml_edit::Table
 ///
 /// 策略：
@@ -99,6 +169,8 @@ fn json_value_to_toml_item(value: &Value, field_name: &str) -> Option<toml_edit:
 /// 3. 其他未知字段使用通用转换器尝试转换
 pub(crate) fn json_server_to_toml_table(spec: &Value) -> Result<toml_edit::Table, AppError> {
     validate_server_spec(spec)?;
+    let expanded = expand_env_in_spec(spec)?;
+    let spec = &expanded;
     let mut t = Table::new();
     let typ = spec.get("type").and_then(|v| v.as_str()).unwrap_or("stdio");
     t["type"] = toml_edit::value(typ);
@@ -215,3 +287,41 @@ pub(crate) fn json_server_to_toml_table(spec: &Value) -> Result<toml_edit::Table
 
     Ok(t)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn expands_placeholders_in_args_env_and_headers() {
+        let spec = json!({
+            "type": "stdio",
+            "command": "${KEEP}",
+            "args": ["--token", "${TOKEN}", "pre-${TOKEN}-post"],
+            "env": { "API_KEY": "${TOKEN}" },
+            "headers": { "Authorization": "Bearer ${TOKEN}" }
+        });
+        let lookup = |name: &str| (name == "TOKEN").then(|| "secret".to_string());
+
+        let expanded = expand_spec_with(&spec, &lookup).expect("expand spec");
+
+        assert_eq!(expanded["command"], "${KEEP}");
+        assert_eq!(
+            expanded["args"],
+            json!(["--token", "secret", "pre-secret-post"])
+        );
+        assert_eq!(expanded["env"]["API_KEY"], "secret");
+        assert_eq!(expanded["headers"]["Authorization"], "Bearer secret");
+    }
+
+    #[test]
+    fn errors_on_unset_placeholder() {
+        let spec = json!({ "command": "node", "args": ["${MISSING}"] });
+        let err = expand_spec_with(&spec, &|_| None).expect_err("unset var should fail");
+        assert!(
+            err.to_string().contains("MISSING"),
+            "unexpected error: {err}"
+        );
+    }
+}
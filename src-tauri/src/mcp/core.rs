@@ -1,3 +1,4 @@
+use serde::Serialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
@@ -7,6 +8,36 @@ use crate::error::AppError;
 // 子模块导入
 use super::validation::{validate_mcp_entry, validate_server_spec};
 
+/// 导入时被跳过的单个 MCP 服务器
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpImportSkipped {
+    pub id: String,
+    pub reason: String,
+}
+
+/// 从客户端配置导入 MCP 的结果：新增/启用数量与被跳过的条目
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpImportReport {
+    pub imported: usize,
+    pub skipped: Vec<McpImportSkipped>,
+}
+
+impl McpImportReport {
+    pub(crate) fn skip(&mut self, id: &str, reason: impl Into<String>) {
+        self.skipped.push(McpImportSkipped {
+            id: id.to_string(),
+            reason: reason.into(),
+        });
+    }
+}
+
+/// 仅关心导入数量的调用方使用
+pub fn imported_count(report: Result<McpImportReport, AppError>) -> Result<usize, AppError> {
+    report.map(|r| r.imported)
+}
+
 pub fn normalize_servers_for(config: &mut MultiAppConfig, app: &AppType) -> usize {
     let servers = &mut config.mcp_for_mut(app).servers;
     super::normalization::normalize_server_keys(servers)
@@ -121,16 +152,18 @@ pub fn set_enabled_flag_for(
 
 /// 从 ~/.claude.json 导入 mcpServers 到统一结构（v3.7.0+）
 /// 已存在的服务器将启用 Claude 应用，不覆盖其他字段和应用状态
-pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError> {
+pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<McpImportReport, AppError> {
     use crate::app_config::{McpApps, McpServer};
 
     let text_opt = crate::claude_mcp::read_mcp_json()?;
-    let Some(text) = text_opt else { return Ok(0) };
+    let Some(text) = text_opt else {
+        return Ok(McpImportReport::default());
+    };
 
     let v: Value = serde_json::from_str(&text)
         .map_err(|e| AppError::McpValidation(format!("解析 ~/.claude.json 失败: {e}")))?;
     let Some(map) = v.get("mcpServers").and_then(|x| x.as_object()) else {
-        return Ok(0);
+        return Ok(McpImportReport::default());
     };
 
     // 确保新结构存在
@@ -139,14 +172,13 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError
     }
     let servers = config.mcp.servers.as_mut().unwrap();
 
-    let mut changed = 0;
-    let mut errors = Vec::new();
+    let mut report = McpImportReport::default();
 
     for (id, spec) in map.iter() {
-        // 校验：单项失败不中止，收集错误继续处理
+        // 校验：单项失败不中止，记录跳过原因继续处理
         if let Err(e) = validate_server_spec(spec) {
             log::warn!("跳过无效 MCP 服务器 '{id}': {e}");
-            errors.push(format!("{id}: {e}"));
+            report.skip(id, e.to_string());
             continue;
         }
 
@@ -154,7 +186,7 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError
             // 已存在：仅启用 Claude 应用
             if !existing.apps.claude {
                 existing.apps.claude = true;
-                changed += 1;
+                report.imported += 1;
                 log::info!("MCP 服务器 '{id}' 已启用 Claude 应用");
             }
         } else {
@@ -175,18 +207,23 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError
                     homepage: None,
                     docs: None,
                     tags: Vec::new(),
+                    protected: false,
                 },
             );
-            changed += 1;
+            report.imported += 1;
             log::info!("导入新 MCP 服务器 '{id}'");
         }
     }
 
-    if !errors.is_empty() {
-        log::warn!("导入完成，但有 {} 项失败: {:?}", errors.len(), errors);
+    if !report.skipped.is_empty() {
+        log::warn!(
+            "导入完成，但有 {} 项被跳过: {:?}",
+            report.skipped.len(),
+            report.skipped
+        );
     }
 
-    Ok(changed)
+    Ok(report)
 }
 
 /// 从 ~/.codex/config.toml 导入 MCP 到统一结构（v3.7.0+）
@@ -196,12 +233,12 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, AppError
 /// - 错误格式：[mcp.servers.*]（容错读取，用于迁移错误写入的配置）
 ///
 /// 已存在的服务器将启用 Codex 应用，不覆盖其他字段和应用状态
-pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError> {
+pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<McpImportReport, AppError> {
     use crate::app_config::{McpApps, McpServer};
 
     let text = crate::codex_config::read_and_validate_codex_config_text()?;
     if text.trim().is_empty() {
-        return Ok(0);
+        return Ok(McpImportReport::default());
     }
 
     let root: toml::Table = toml::from_str(&text)
@@ -213,13 +250,13 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
     }
     let servers = config.mcp.servers.as_mut().unwrap();
 
-    let mut changed_total = 0usize;
+    let mut report = McpImportReport::default();
 
     // helper：处理一组 servers 表
     let mut import_servers_tbl = |servers_tbl: &toml::value::Table| {
-        let mut changed = 0usize;
         for (id, entry_val) in servers_tbl.iter() {
             let Some(entry_tbl) = entry_val.as_table() else {
+                report.skip(id, "MCP 服务器定义必须为 TOML 表");
                 continue;
             };
 
@@ -291,6 +328,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                 }
                 _ => {
                     log::warn!("跳过未知类型 '{typ}' 的 Codex MCP 项 '{id}'");
+                    report.skip(id, format!("未知的 MCP 服务器类型: {typ}"));
                     continue;
                 }
             }
@@ -359,6 +397,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
             // 校验：单项失败继续处理
             if let Err(e) = validate_server_spec(&spec_v) {
                 log::warn!("跳过无效 Codex MCP 项 '{id}': {e}");
+                report.skip(id, e.to_string());
                 continue;
             }
 
@@ -366,7 +405,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                 // 已存在：仅启用 Codex 应用
                 if !existing.apps.codex {
                     existing.apps.codex = true;
-                    changed += 1;
+                    report.imported += 1;
                     log::info!("MCP 服务器 '{id}' 已启用 Codex 应用");
                 }
             } else {
@@ -387,13 +426,13 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
                         homepage: None,
                         docs: None,
                         tags: Vec::new(),
+                        protected: false,
                     },
                 );
-                changed += 1;
+                report.imported += 1;
                 log::info!("导入新 MCP 服务器 '{id}'");
             }
         }
-        changed
     };
 
     // 1) 处理 mcp.servers
@@ -401,7 +440,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
         if let Some(mcp_tbl) = mcp_val.as_table() {
             if let Some(servers_val) = mcp_tbl.get("servers") {
                 if let Some(servers_tbl) = servers_val.as_table() {
-                    changed_total += import_servers_tbl(servers_tbl);
+                    import_servers_tbl(servers_tbl);
                 }
             }
         }
@@ -410,11 +449,11 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
     // 2) 处理 mcp_servers
     if let Some(servers_val) = root.get("mcp_servers") {
         if let Some(servers_tbl) = servers_val.as_table() {
-            changed_total += import_servers_tbl(servers_tbl);
+            import_servers_tbl(servers_tbl);
         }
     }
 
-    Ok(changed_total)
+    Ok(report)
 }
 
 /// 将 config.json 中 Codex 的 enabled==true 项以 TOML 形式写入 ~/.codex/config.toml
@@ -429,16 +468,18 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, AppError>
 /// 注：实现已迁移至 sync 模块，此处通过 pub use 重新导出以保持 API 兼容性
 /// 从 ~/.gemini/settings.json 导入 mcpServers 到统一结构（v3.7.0+）
 /// 已存在的服务器将启用 Gemini 应用，不覆盖其他字段和应用状态
-pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<usize, AppError> {
+pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<McpImportReport, AppError> {
     use crate::app_config::{McpApps, McpServer};
 
     let text_opt = crate::gemini_mcp::read_mcp_json()?;
-    let Some(text) = text_opt else { return Ok(0) };
+    let Some(text) = text_opt else {
+        return Ok(McpImportReport::default());
+    };
 
     let v: Value = serde_json::from_str(&text)
         .map_err(|e| AppError::McpValidation(format!("解析 ~/.gemini/settings.json 失败: {e}")))?;
     let Some(map) = v.get("mcpServers").and_then(|x| x.as_object()) else {
-        return Ok(0);
+        return Ok(McpImportReport::default());
     };
 
     // 确保新结构存在
@@ -447,14 +488,13 @@ pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<usize, AppError
     }
     let servers = config.mcp.servers.as_mut().unwrap();
 
-    let mut changed = 0;
-    let mut errors = Vec::new();
+    let mut report = McpImportReport::default();
 
     for (id, spec) in map.iter() {
-        // 校验：单项失败不中止，收集错误继续处理
+        // 校验：单项失败不中止，记录跳过原因继续处理
         if let Err(e) = validate_server_spec(spec) {
             log::warn!("跳过无效 MCP 服务器 '{id}': {e}");
-            errors.push(format!("{id}: {e}"));
+            report.skip(id, e.to_string());
             continue;
         }
 
@@ -462,7 +502,7 @@ pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<usize, AppError
             // 已存在：仅启用 Gemini 应用
             if !existing.apps.gemini {
                 existing.apps.gemini = true;
-                changed += 1;
+                report.imported += 1;
                 log::info!("MCP 服务器 '{id}' 已启用 Gemini 应用");
             }
         } else {
@@ -483,18 +523,23 @@ pub fn import_from_gemini(config: &mut MultiAppConfig) -> Result<usize, AppError
                     homepage: None,
                     docs: None,
                     tags: Vec::new(),
+                    protected: false,
                 },
             );
-            changed += 1;
+            report.imported += 1;
             log::info!("导入新 MCP 服务器 '{id}'");
         }
     }
 
-    if !errors.is_empty() {
-        log::warn!("导入完成，但有 {} 项失败: {:?}", errors.len(), errors);
+    if !report.skipped.is_empty() {
+        log::warn!(
+            "导入完成，但有 {} 项被跳过: {:?}",
+            report.skipped.len(),
+            report.skipped
+        );
     }
 
-    Ok(changed)
+    Ok(report)
 }
 
 // ============================================================================
@@ -20,7 +20,10 @@ pub(crate) mod sync;
 pub mod validation;
 
 // 从 core 模块导出导入功能
-pub use core::{import_from_claude, import_from_codex, import_from_gemini};
+pub use core::{
+    import_from_claude, import_from_codex, import_from_gemini, imported_count, McpImportReport,
+    McpImportSkipped,
+};
 pub use opencode::{
     import_from_opencode, remove_server_from_opencode, sync_single_server_to_opencode,
 };
@@ -28,6 +31,7 @@ pub use opencode::{
 // 从 sync 模块导出同步功能
 pub use sync::{
     remove_server_from_claude, remove_server_from_codex, remove_server_from_gemini,
-    sync_enabled_to_claude, sync_enabled_to_codex, sync_enabled_to_gemini,
-    sync_single_server_to_claude, sync_single_server_to_codex, sync_single_server_to_gemini,
+    render_codex_config_text, render_codex_server_toml, sync_enabled_to_claude,
+    sync_enabled_to_codex, sync_enabled_to_gemini, sync_single_server_to_claude,
+    sync_single_server_to_codex, sync_single_server_to_gemini,
 };
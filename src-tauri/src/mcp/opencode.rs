@@ -5,6 +5,7 @@ use crate::app_config::{McpApps, McpServer, MultiAppConfig};
 use crate::error::AppError;
 use crate::opencode_config;
 
+use super::core::McpImportReport;
 use super::validation::validate_server_spec;
 
 fn should_sync_opencode_mcp() -> bool {
@@ -128,33 +129,35 @@ pub fn remove_server_from_opencode(id: &str) -> Result<(), AppError> {
     opencode_config::remove_mcp_server(id)
 }
 
-pub fn import_from_opencode(config: &mut MultiAppConfig) -> Result<usize, AppError> {
+pub fn import_from_opencode(config: &mut MultiAppConfig) -> Result<McpImportReport, AppError> {
     let mcp_map = opencode_config::get_mcp_servers()?;
     if mcp_map.is_empty() {
-        return Ok(0);
+        return Ok(McpImportReport::default());
     }
 
     let servers = config.mcp.servers.get_or_insert_with(HashMap::new);
-    let mut changed = 0;
+    let mut report = McpImportReport::default();
 
     for (id, spec) in mcp_map {
         let unified_spec = match convert_from_opencode_format(&spec) {
             Ok(spec) => spec,
             Err(error) => {
                 log::warn!("Skip invalid OpenCode MCP server '{id}': {error}");
+                report.skip(&id, error.to_string());
                 continue;
             }
         };
 
         if let Err(error) = validate_server_spec(&unified_spec) {
             log::warn!("Skip invalid MCP server '{id}' after conversion: {error}");
+            report.skip(&id, error.to_string());
             continue;
         }
 
         if let Some(existing) = servers.get_mut(&id) {
             if !existing.apps.opencode {
                 existing.apps.opencode = true;
-                changed += 1;
+                report.imported += 1;
             }
         } else {
             servers.insert(
@@ -173,11 +176,12 @@ pub fn import_from_opencode(config: &mut MultiAppConfig) -> Result<usize, AppErr
                     homepage: None,
                     docs: None,
                     tags: Vec::new(),
+                    protected: false,
                 },
             );
-            changed += 1;
+            report.imported += 1;
         }
     }
 
-    Ok(changed)
+    Ok(report)
 }
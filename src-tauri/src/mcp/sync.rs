@@ -5,7 +5,7 @@ use crate::app_config::{McpConfig, MultiAppConfig};
 use crate::config::write_text_file;
 use crate::error::AppError;
 
-use super::conversion::json_server_to_toml_table;
+use super::conversion::{expand_env_in_spec, json_server_to_toml_table};
 use super::validation::validate_server_spec;
 
 // ============================================================================
@@ -35,6 +35,14 @@ fn collect_enabled_servers(cfg: &McpConfig) -> HashMap<String, Value> {
     out
 }
 
+/// 展开待写入 JSON live 配置的服务器规范中的环境变量占位符
+fn expand_servers_env(servers: HashMap<String, Value>) -> Result<HashMap<String, Value>, AppError> {
+    servers
+        .into_iter()
+        .map(|(id, spec)| Ok((id, expand_env_in_spec(&spec)?)))
+        .collect()
+}
+
 fn extract_server_spec(entry: &Value) -> Result<Value, AppError> {
     let obj = entry
         .as_object()
@@ -60,7 +68,7 @@ fn extract_server_spec(entry: &Value) -> Result<Value, AppError> {
 
 /// 将 config.json 中 enabled==true 的项投影写入 ~/.claude.json
 pub fn sync_enabled_to_claude(config: &MultiAppConfig) -> Result<(), AppError> {
-    let enabled = collect_enabled_servers(&config.mcp.claude);
+    let enabled = expand_servers_env(collect_enabled_servers(&config.mcp.claude))?;
     crate::claude_mcp::set_mcp_servers_map(&enabled)
 }
 
@@ -73,6 +81,15 @@ pub fn sync_enabled_to_claude(config: &MultiAppConfig) -> Result<(), AppError> {
 /// - 仅更新 `mcp_servers` 表，保留其它键
 /// - 仅写入启用项；无启用项时清理 mcp_servers 表
 pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), AppError> {
+    let new_text = render_codex_config_text(config)?;
+    // 仅改 TOML，不触碰 auth.json
+    let path = crate::codex_config::get_codex_config_path()?;
+    write_text_file(&path, &new_text)?;
+    Ok(())
+}
+
+/// 生成 `sync_enabled_to_codex` 将写入的 config.toml 全文，但不写文件（用于预览）
+pub fn render_codex_config_text(config: &MultiAppConfig) -> Result<String, AppError> {
     use toml_edit::{Item, Table};
 
     // 1) 收集启用项（Codex 维度）
@@ -125,16 +142,24 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), AppError> {
         doc["mcp_servers"] = Item::Table(servers_tbl);
     }
 
-    // 6) 写回（仅改 TOML，不触碰 auth.json）；toml_edit 会尽量保留未改区域的注释/空白/顺序
-    let new_text = doc.to_string();
-    let path = crate::codex_config::get_codex_config_path()?;
-    write_text_file(&path, &new_text)?;
-    Ok(())
+    // 6) 序列化；toml_edit 会尽量保留未改区域的注释/空白/顺序
+    Ok(doc.to_string())
+}
+
+/// 单个服务器转换后的 `[mcp_servers.<id>]` 片段，不读取也不写入 config.toml（用于预览）
+pub fn render_codex_server_toml(id: &str, spec: &Value) -> Result<String, AppError> {
+    use toml_edit::{Item, Table};
+
+    let mut servers_tbl = Table::new();
+    servers_tbl[id] = Item::Table(json_server_to_toml_table(spec)?);
+    let mut doc = toml_edit::DocumentMut::new();
+    doc["mcp_servers"] = Item::Table(servers_tbl);
+    Ok(doc.to_string())
 }
 
 /// 将 config.json 中 enabled==true 的项投影写入 ~/.gemini/settings.json
 pub fn sync_enabled_to_gemini(config: &MultiAppConfig) -> Result<(), AppError> {
-    let enabled = collect_enabled_servers(&config.mcp.gemini);
+    let enabled = expand_servers_env(collect_enabled_servers(&config.mcp.gemini))?;
     crate::gemini_mcp::set_mcp_servers_map(&enabled)
 }
 
@@ -155,7 +180,7 @@ pub fn sync_single_server_to_claude(
 
     // 创建新的 HashMap，包含现有的所有服务器 + 当前要同步的服务器
     let mut updated = current;
-    updated.insert(id.to_string(), server_spec.clone());
+    updated.insert(id.to_string(), expand_env_in_spec(server_spec)?);
 
     // 写回
     crate::claude_mcp::set_mcp_servers_map(&updated)
@@ -225,7 +250,7 @@ pub fn sync_single_server_to_gemini(
 
     // 创建新的 HashMap，包含现有的所有服务器 + 当前要同步的服务器
     let mut updated = current;
-    updated.insert(id.to_string(), server_spec.clone());
+    updated.insert(id.to_string(), expand_env_in_spec(server_spec)?);
 
     // 写回
     crate::gemini_mcp::set_mcp_servers_map(&updated)
@@ -1,8 +1,67 @@
 //! MCP 服务器配置验证模块
 
+use std::sync::OnceLock;
+
 use crate::error::AppError;
+use jsonschema::JSONSchema;
 use serde_json::Value;
 
+/// 内置的 MCP 服务器连接定义 JSON Schema（draft-07），前端可通过 `GET /api/mcp/schema` 共用
+const SERVER_SPEC_SCHEMA: &str = include_str!("server_spec.schema.json");
+
+pub fn server_spec_schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        serde_json::from_str(SERVER_SPEC_SCHEMA).expect("bundled MCP server schema is valid JSON")
+    })
+}
+
+/// 按内置 Schema 校验连接定义：可发现拼错的字段名（不允许未知字段）与 env/headers 等字段的类型错误。
+///
+/// `existing` 为服务器当前保存的定义：其中原样保留的未知字段（如从 Codex 导入的 `env_vars`、
+/// `http_headers`）不参与严格校验，只有本次新增或改动的字段会被当作拼写错误拒绝。
+/// 从各客户端导入或同步的既有配置仍只走 `validate_server_spec`。
+pub(crate) fn validate_server_spec_schema(
+    spec: &Value,
+    existing: Option<&Value>,
+) -> Result<(), AppError> {
+    static VALIDATOR: OnceLock<JSONSchema> = OnceLock::new();
+    let validator = VALIDATOR.get_or_init(|| {
+        JSONSchema::compile(server_spec_schema()).expect("bundled MCP server schema compiles")
+    });
+    let checked = match (spec, existing.and_then(Value::as_object)) {
+        (Value::Object(fields), Some(previous)) => {
+            let known = server_spec_schema()
+                .get("properties")
+                .and_then(Value::as_object);
+            let mut fields = fields.clone();
+            fields.retain(|key, value| {
+                known.is_some_and(|known| known.contains_key(key))
+                    || previous.get(key) != Some(value)
+            });
+            Value::Object(fields)
+        }
+        _ => spec.clone(),
+    };
+    if let Err(errors) = validator.validate(&checked) {
+        let details: Vec<String> = errors
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                if path.is_empty() {
+                    error.to_string()
+                } else {
+                    format!("{path}: {error}")
+                }
+            })
+            .collect();
+        return Err(AppError::McpValidation(format!(
+            "MCP 服务器连接定义不符合 Schema：{}",
+            details.join("；")
+        )));
+    }
+    Ok(())
+}
+
 /// 基础校验：允许 stdio/http/sse；或省略 type（视为 stdio）。对应必填字段存在
 pub(crate) fn validate_server_spec(spec: &Value) -> Result<(), AppError> {
     if !spec.is_object() {
@@ -37,6 +96,9 @@ pub(crate) fn validate_server_spec(spec: &Value) -> Result<(), AppError> {
                 "stdio 类型的 MCP 服务器缺少 command 字段".into(),
             ));
         }
+        if let Some(allowed) = allowed_commands() {
+            check_command_allowed(cmd, &allowed)?;
+        }
     }
     if is_http {
         let url = spec.get("url").and_then(|x| x.as_str()).unwrap_or("");
@@ -57,6 +119,80 @@ pub(crate) fn validate_server_spec(spec: &Value) -> Result<(), AppError> {
     Ok(())
 }
 
+/// stdio 服务器直接 spawn、不经过 shell，出现这些字符通常意味着注入尝试
+const SHELL_METACHARS: [&str; 6] = [";", "|", "`", "$(", "&&", "\n"];
+
+/// 检查 stdio 服务器的 command 与 args 中的 shell 元字符
+///
+/// 仅用于用户提交的定义；已保存或从客户端导入的配置（如 `bash -c "cd x && npx y"`）在同步与导入时不受影响。
+pub(crate) fn reject_shell_metachars_in_spec(spec: &Value) -> Result<(), AppError> {
+    let is_stdio = matches!(
+        spec.get("type").and_then(|x| x.as_str()),
+        None | Some("stdio")
+    );
+    if !is_stdio || allow_shell_metachars() {
+        return Ok(());
+    }
+    if let Some(cmd) = spec.get("command").and_then(|x| x.as_str()) {
+        reject_shell_metachars("command", cmd)?;
+    }
+    if let Some(args) = spec.get("args").and_then(|x| x.as_array()) {
+        for arg in args.iter().filter_map(|x| x.as_str()) {
+            reject_shell_metachars("args", arg)?;
+        }
+    }
+    Ok(())
+}
+
+/// 设置 `MCP_ALLOW_SHELL_METACHARS=1` 可关闭 shell 元字符检查
+pub(crate) fn allow_shell_metachars() -> bool {
+    std::env::var("MCP_ALLOW_SHELL_METACHARS")
+        .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+fn reject_shell_metachars(field: &str, value: &str) -> Result<(), AppError> {
+    if let Some(found) = SHELL_METACHARS.iter().find(|m| value.contains(**m)) {
+        let shown = if *found == "\n" { "\\n" } else { found };
+        return Err(AppError::McpValidation(format!(
+            "MCP 服务器 {field} 包含 shell 元字符 '{shown}'：stdio 服务器不经过 shell 启动，请拆分为 command 与 args（如确需保留，可设置 MCP_ALLOW_SHELL_METACHARS=1）"
+        )));
+    }
+    Ok(())
+}
+
+/// `MCP_ALLOWED_COMMANDS`（逗号分隔的可执行文件名）限制 stdio 服务器可启动的程序；未设置表示不限制
+pub(crate) fn allowed_commands() -> Option<Vec<String>> {
+    let raw = std::env::var("MCP_ALLOWED_COMMANDS").ok()?;
+    Some(
+        raw.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+fn command_basename(cmd: &str) -> &str {
+    let cmd = cmd.trim();
+    let name = cmd.rsplit(['/', '\\']).next().unwrap_or(cmd);
+    let stem_len = name.len().saturating_sub(4);
+    match name.get(stem_len..) {
+        Some(ext) if stem_len > 0 && ext.eq_ignore_ascii_case(".exe") => &name[..stem_len],
+        _ => name,
+    }
+}
+
+fn check_command_allowed(cmd: &str, allowed: &[String]) -> Result<(), AppError> {
+    let name = command_basename(cmd);
+    if allowed.iter().any(|entry| command_basename(entry) == name) {
+        return Ok(());
+    }
+    Err(AppError::McpValidation(format!(
+        "MCP 服务器 command '{name}' 不在允许列表中（MCP_ALLOWED_COMMANDS）"
+    )))
+}
+
 #[allow(dead_code)] // v3.7.0: 旧的验证逻辑，保留用于未来可能的迁移
 pub(crate) fn validate_mcp_entry(entry: &Value) -> Result<(), AppError> {
     let obj = entry
@@ -99,3 +235,103 @@ pub(crate) fn validate_mcp_entry(entry: &Value) -> Result<(), AppError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn accepts_plain_stdio_command() {
+        let spec = json!({ "type": "stdio", "command": "npx", "args": ["-y", "pkg@1.0", "--url=https://a.b/?x=1&y=2"] });
+        assert!(validate_server_spec(&spec).is_ok());
+    }
+
+    #[test]
+    fn rejects_shell_metachars_in_command_and_args() {
+        let spec = json!({ "type": "stdio", "command": "echo hi; rm -rf /" });
+        let err =
+            reject_shell_metachars_in_spec(&spec).expect_err("command with ';' should be rejected");
+        assert!(err.to_string().contains("shell"), "unexpected error: {err}");
+
+        let spec = json!({ "command": "node", "args": ["server.js", "`whoami`"] });
+        let err = reject_shell_metachars_in_spec(&spec)
+            .expect_err("args with backticks should be rejected");
+        assert!(err.to_string().contains("args"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn basic_validation_keeps_existing_shell_style_commands() {
+        let spec = json!({ "command": "bash", "args": ["-c", "cd x && npx y"] });
+        assert!(validate_server_spec(&spec).is_ok());
+    }
+
+    #[test]
+    fn schema_accepts_well_formed_specs() {
+        let stdio = json!({ "command": "npx", "args": ["-y", "pkg"], "env": { "TOKEN": "x" }, "timeout": 30 });
+        assert!(validate_server_spec_schema(&stdio, None).is_ok());
+        let http = json!({ "type": "http", "url": "https://mcp.example.com", "headers": { "Authorization": "Bearer x" } });
+        assert!(validate_server_spec_schema(&http, None).is_ok());
+    }
+
+    #[test]
+    fn schema_rejects_unknown_fields_and_wrong_types() {
+        let spec = json!({ "type": "stdio", "commnd": "npx" });
+        let err = validate_server_spec_schema(&spec, None)
+            .expect_err("misspelled field should be rejected");
+        assert!(
+            err.to_string().contains("commnd"),
+            "unexpected error: {err}"
+        );
+
+        let spec = json!({ "command": "npx", "env": { "PORT": 8080 } });
+        let err = validate_server_spec_schema(&spec, None)
+            .expect_err("non-string env should be rejected");
+        assert!(
+            err.to_string().contains("/env/PORT"),
+            "unexpected error: {err}"
+        );
+
+        let spec = json!({ "type": "sse" });
+        assert!(validate_server_spec_schema(&spec, None).is_err());
+    }
+
+    #[test]
+    fn schema_keeps_unknown_fields_already_stored() {
+        let stored =
+            json!({ "command": "npx", "env_vars": ["TOKEN"], "http_headers": { "X": "1" } });
+
+        let updated =
+            json!({ "command": "uvx", "env_vars": ["TOKEN"], "http_headers": { "X": "1" } });
+        assert!(validate_server_spec_schema(&updated, Some(&stored)).is_ok());
+
+        // 新增或改动的未知字段仍按拼写错误拒绝，已知字段照常校验类型
+        let renamed =
+            json!({ "command": "npx", "env_vars": ["OTHER"], "http_headers": { "X": "1" } });
+        assert!(validate_server_spec_schema(&renamed, Some(&stored)).is_err());
+        let typo = json!({ "commnd": "npx", "command": "npx", "env_vars": ["TOKEN"] });
+        assert!(validate_server_spec_schema(&typo, Some(&stored)).is_err());
+        let wrong_type = json!({ "command": "npx", "env": { "PORT": 1 }, "env_vars": ["TOKEN"] });
+        assert!(validate_server_spec_schema(&wrong_type, Some(&stored)).is_err());
+    }
+
+    #[test]
+    fn allowlist_accepts_listed_command_by_basename() {
+        let allowed = vec!["npx".to_string(), "uvx".to_string()];
+        assert!(check_command_allowed("npx", &allowed).is_ok());
+        assert!(check_command_allowed("/usr/local/bin/npx", &allowed).is_ok());
+        assert!(check_command_allowed("C:\\Program Files\\nodejs\\npx.exe", &allowed).is_ok());
+    }
+
+    #[test]
+    fn allowlist_rejects_unlisted_command() {
+        let allowed = vec!["npx".to_string()];
+        let err =
+            check_command_allowed("/bin/bash", &allowed).expect_err("bash is not allowlisted");
+        assert!(
+            err.to_string().contains("MCP_ALLOWED_COMMANDS"),
+            "unexpected error: {err}"
+        );
+        assert!(check_command_allowed("npx-evil", &allowed).is_err());
+    }
+}
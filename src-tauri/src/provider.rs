@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // SSOT 模式：不再写供应商副本文件
 
@@ -16,9 +16,19 @@ pub struct Provider {
     pub website_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
+    /// 分组（如 "work"、"personal"），用于组织数量较多的供应商
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// 标签（可多个），用于在界面中筛选和着色
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "createdAt")]
     pub created_at: Option<i64>,
+    /// 最近一次切换到该供应商的时间戳（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "lastSwitchedAt")]
+    pub last_switched_at: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "sortIndex")]
     pub sort_index: Option<usize>,
@@ -44,7 +54,10 @@ impl Provider {
             settings_config,
             website_url,
             category: None,
+            group: None,
+            tags: Vec::new(),
             created_at: None,
+            last_switched_at: None,
             sort_index: None,
             notes: None,
             meta: None,
@@ -64,6 +77,24 @@ pub struct ProviderManager {
         skip_serializing_if = "Option::is_none"
     )]
     pub backup_current: Option<String>,
+    /// 最近的切换记录（最新在前，最多保留 MAX_SWITCH_HISTORY 条）
+    #[serde(
+        default,
+        rename = "switchHistory",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub switch_history: Vec<SwitchHistoryEntry>,
+}
+
+/// 切换历史保留条数上限
+pub const MAX_SWITCH_HISTORY: usize = 20;
+
+/// 单条供应商切换记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchHistoryEntry {
+    pub provider_id: String,
+    pub switched_at: i64,
 }
 
 /// 用量查询脚本配置
@@ -120,6 +151,19 @@ pub struct UsageData {
     pub unit: Option<String>,
 }
 
+/// 每个供应商保留的用量脚本测试记录条数上限
+pub const MAX_USAGE_TEST_HISTORY: usize = 10;
+
+/// 单次用量脚本测试结果（仅保存在内存中，重启后清空）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTestRecord {
+    pub tested_at: i64,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// 用量查询结果（支持多套餐）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageResult {
@@ -128,6 +172,13 @@ pub struct UsageResult {
     pub data: Option<Vec<UsageData>>, // 支持返回多个套餐
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// 仅测试脚本时返回：白名单内的响应头（请求 ID、限流信息等）
+    #[serde(
+        default,
+        rename = "responseHeaders",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub response_headers: Option<BTreeMap<String, String>>,
 }
 
 /// 供应商元数据
@@ -155,4 +206,19 @@ impl ProviderManager {
     pub fn get_all_providers(&self) -> &HashMap<String, Provider> {
         &self.providers
     }
+
+    /// 记录一次切换：更新供应商的 lastSwitchedAt，并写入有界的切换历史
+    pub fn record_switch(&mut self, provider_id: &str, switched_at: i64) {
+        if let Some(provider) = self.providers.get_mut(provider_id) {
+            provider.last_switched_at = Some(switched_at);
+        }
+        self.switch_history.insert(
+            0,
+            SwitchHistoryEntry {
+                provider_id: provider_id.to_string(),
+                switched_at,
+            },
+        );
+        self.switch_history.truncate(MAX_SWITCH_HISTORY);
+    }
 }
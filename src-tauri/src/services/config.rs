@@ -1,19 +1,78 @@
 use super::provider::ProviderService;
 use crate::app_config::{AppType, MultiAppConfig};
+use crate::clock::Clock;
 use crate::config::atomic_write;
 use crate::error::AppError;
 use crate::provider::Provider;
-use crate::store::AppState;
-use chrono::Utc;
+use crate::store::{check_expected_revision, AppState};
+use serde::Serialize;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fs;
+use std::io::Read;
 use std::path::{Component, Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 const MAX_BACKUPS: usize = 10;
+/// 导入配置文件的默认大小上限（16MB），可通过 CONFIG_IMPORT_MAX_BYTES 调整
+const DEFAULT_CONFIG_IMPORT_MAX_BYTES: u64 = 16 * 1024 * 1024;
 static BACKUP_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// 各应用的供应商数量
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCounts {
+    pub claude: usize,
+    pub codex: usize,
+    pub gemini: usize,
+}
+
+/// 配置体积与实体数量统计，用于诊断与仪表盘展示
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigStats {
+    pub config_bytes: u64,
+    pub provider_counts: ProviderCounts,
+    pub mcp_server_count: usize,
+    pub prompt_count: usize,
+    pub skill_count: usize,
+    pub backup_count: usize,
+}
+
+/// 单个应用的 live 全量同步结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSyncReport {
+    pub app: String,
+    /// 是否写入了当前供应商
+    pub provider_synced: bool,
+    /// 写入的 MCP 服务器数量；该应用没有 MCP 全量同步时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<usize>,
+    /// 是否写入了已启用的提示词
+    pub prompt_synced: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// 单个应用同步当前供应商的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSyncOutcome {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 各应用同步当前供应商的结果（按应用名索引），`success` 仅在全部成功时为 `true`
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentProvidersSyncReport {
+    pub success: bool,
+    /// 全部成功时为固定提示，否则汇总失败应用的错误（兼容只读取 `message` 的旧客户端）
+    pub message: String,
+    #[serde(flatten)]
+    pub apps: BTreeMap<String, ProviderSyncOutcome>,
+}
+
 /// 配置导入导出相关业务逻辑
 pub struct ConfigService;
 
@@ -150,7 +209,8 @@ impl ConfigService {
     }
 
     /// 为当前 config.json 创建备份，返回备份 ID（若文件不存在则返回空字符串）。
-    pub fn create_backup(config_path: &Path) -> Result<String, AppError> {
+    /// 备份 ID 中的时间戳取自 `clock`。
+    pub fn create_backup(config_path: &Path, clock: &dyn Clock) -> Result<String, AppError> {
         if !config_path.exists() {
             return Ok(String::new());
         }
@@ -162,7 +222,7 @@ impl ConfigService {
 
         fs::create_dir_all(&backup_dir).map_err(|e| AppError::io(&backup_dir, e))?;
 
-        let timestamp_ms = Utc::now().timestamp_millis();
+        let timestamp_ms = clock.now().timestamp_millis();
         let counter = BACKUP_COUNTER.fetch_add(1, Ordering::Relaxed);
         let backup_id = format!("backup_{timestamp_ms}_{counter}");
 
@@ -220,6 +280,66 @@ impl ConfigService {
         Ok(())
     }
 
+    /// 统计内存配置中的实体数量，并读取 config.json 的实际大小（文件不存在时为 0）
+    pub fn stats(state: &AppState) -> Result<ConfigStats, AppError> {
+        let config_path = crate::config::get_app_config_path()?;
+        let config_bytes = match fs::metadata(&config_path) {
+            Ok(meta) => meta.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(AppError::io(&config_path, e)),
+        };
+        let backup_count = config_path
+            .parent()
+            .map(|dir| Self::count_backups(&dir.join("backups")))
+            .unwrap_or(0);
+
+        let config = state.config.read().map_err(AppError::from)?;
+        let provider_count = |app: AppType| {
+            config
+                .get_manager(&app)
+                .map(|manager| manager.providers.len())
+                .unwrap_or(0)
+        };
+        let prompts = &config.prompts;
+
+        Ok(ConfigStats {
+            config_bytes,
+            provider_counts: ProviderCounts {
+                claude: provider_count(AppType::Claude),
+                codex: provider_count(AppType::Codex),
+                gemini: provider_count(AppType::Gemini),
+            },
+            mcp_server_count: config.mcp.servers.as_ref().map_or(0, |s| s.len()),
+            prompt_count: prompts.claude.prompts.len()
+                + prompts.codex.prompts.len()
+                + prompts.gemini.prompts.len()
+                + prompts.opencode.prompts.len(),
+            skill_count: config
+                .skills
+                .skills
+                .values()
+                .filter(|skill| skill.installed)
+                .count(),
+            backup_count,
+        })
+    }
+
+    fn count_backups(backup_dir: &Path) -> usize {
+        match fs::read_dir(backup_dir) {
+            Ok(iter) => iter
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    entry
+                        .path()
+                        .extension()
+                        .map(|ext| ext == "json")
+                        .unwrap_or(false)
+                })
+                .count(),
+            Err(_) => 0,
+        }
+    }
+
     /// 将当前 config.json 拷贝到目标路径。
     pub fn export_config_to_path(target_path: &Path) -> Result<(), AppError> {
         let target_path = Self::validate_transfer_path(target_path)?;
@@ -232,36 +352,230 @@ impl ConfigService {
     /// 从磁盘文件加载配置并进行校验，返回新配置。
     pub fn load_config_for_import(file_path: &Path) -> Result<MultiAppConfig, AppError> {
         let file_path = Self::validate_transfer_path(file_path)?;
-        let import_content =
-            fs::read_to_string(&file_path).map_err(|e| AppError::io(&file_path, e))?;
+        Self::load_config_file(&file_path)
+    }
 
+    /// 读取并校验配置文件（不做导入目录限制，调用方负责路径校验）
+    fn load_config_file(file_path: &Path) -> Result<MultiAppConfig, AppError> {
+        let import_content = Self::read_import_file(file_path)?;
+
+        Self::parse_config_content(file_path, &import_content)
+    }
+
+    /// 读取待导入文件：仅允许普通文件，且大小不超过上限，避免设备文件或超大文件拖垮进程
+    fn read_import_file(path: &Path) -> Result<String, AppError> {
+        let max_bytes = Self::import_max_bytes();
+        let metadata = fs::metadata(path).map_err(|e| AppError::io(path, e))?;
+        if !metadata.is_file() {
+            return Err(AppError::InvalidInput(format!(
+                "Import path is not a regular file: {}",
+                path.display()
+            )));
+        }
+        if metadata.len() > max_bytes {
+            return Err(AppError::InvalidInput(format!(
+                "Import file exceeds {max_bytes} bytes: {}",
+                path.display()
+            )));
+        }
+
+        // stat 与读取之间文件可能被替换，读取时再限制一次长度
+        let file = fs::File::open(path).map_err(|e| AppError::io(path, e))?;
+        let mut content = String::new();
+        file.take(max_bytes + 1)
+            .read_to_string(&mut content)
+            .map_err(|e| AppError::io(path, e))?;
+        if content.len() as u64 > max_bytes {
+            return Err(AppError::InvalidInput(format!(
+                "Import file exceeds {max_bytes} bytes: {}",
+                path.display()
+            )));
+        }
+        Ok(content)
+    }
+
+    fn import_max_bytes() -> u64 {
+        let raw = match std::env::var("CONFIG_IMPORT_MAX_BYTES") {
+            Ok(value) => value,
+            Err(_) => return DEFAULT_CONFIG_IMPORT_MAX_BYTES,
+        };
+
+        match raw.trim().parse::<u64>() {
+            Ok(value) if value > 0 => value,
+            _ => {
+                log::warn!(
+                    "环境变量 CONFIG_IMPORT_MAX_BYTES 无法解析: {}，使用默认值 {} 字节",
+                    raw,
+                    DEFAULT_CONFIG_IMPORT_MAX_BYTES
+                );
+                DEFAULT_CONFIG_IMPORT_MAX_BYTES
+            }
+        }
+    }
+
+    /// 解析配置文件内容（拒绝 v1 结构并执行加载后规范化）
+    fn parse_config_content(path: &Path, content: &str) -> Result<MultiAppConfig, AppError> {
         let value: serde_json::Value =
-            serde_json::from_str(&import_content).map_err(|e| AppError::json(&file_path, e))?;
+            serde_json::from_str(content).map_err(|e| AppError::json(path, e))?;
         MultiAppConfig::ensure_not_v1_value(&value)?;
         let has_skills_in_config = value
             .as_object()
             .is_some_and(|map| map.contains_key("skills"));
         let mut new_config: MultiAppConfig =
-            serde_json::from_value(value).map_err(|e| AppError::json(&file_path, e))?;
+            serde_json::from_value(value).map_err(|e| AppError::json(path, e))?;
         let _ = new_config.normalize_after_load(has_skills_in_config)?;
         Ok(new_config)
     }
 
+    /// 按备份 ID（如 `backup_1700000000000_0`）读取 `backups/` 目录下的配置快照
+    pub fn load_backup(backup_id: &str) -> Result<MultiAppConfig, AppError> {
+        let backup_id = backup_id.trim();
+        if backup_id.is_empty()
+            || !backup_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid backup id: {backup_id}"
+            )));
+        }
+
+        let backup_path = crate::config::get_app_config_dir()?
+            .join("backups")
+            .join(format!("{backup_id}.json"));
+        if !backup_path.exists() {
+            return Err(AppError::localized(
+                "config.backup.not_found",
+                format!("备份不存在: {backup_id}"),
+                format!("Backup not found: {backup_id}"),
+            ));
+        }
+        let content =
+            fs::read_to_string(&backup_path).map_err(|e| AppError::io(&backup_path, e))?;
+        Self::parse_config_content(&backup_path, &content)
+    }
+
     /// 将外部配置文件内容加载并写入应用状态。
     pub fn import_config_from_path(file_path: &Path, state: &AppState) -> Result<String, AppError> {
         let new_config = Self::load_config_for_import(file_path)?;
         Self::apply_import_config(new_config, state)
     }
 
+    /// 命令行一次性导入（`server --import <path>`）：加载当前配置，校验并导入文件（导入前备份），返回备份 ID。
+    ///
+    /// 调用者已拥有本机 shell 权限，因此不套用 Web 导入的目录限制，相对路径按当前工作目录解析；
+    /// 大小上限与内容校验与 Web 导入一致。
+    pub fn import_config_from_cli(file_path: &Path) -> Result<String, AppError> {
+        let new_config = Self::load_config_file(file_path)?;
+        let state = AppState::try_new()?;
+        Self::apply_import_config(new_config, &state)
+    }
+
     /// 将导入配置写入磁盘并同步到 AppState，返回备份 ID。
     pub fn apply_import_config(
-        new_config: MultiAppConfig,
+        mut new_config: MultiAppConfig,
         state: &AppState,
     ) -> Result<String, AppError> {
         let mut guard = state.config.write().map_err(AppError::from)?;
+        check_expected_revision(guard.revision)?;
         let config_path = crate::config::get_app_config_path()?;
-        let backup_id = Self::create_backup(&config_path)?;
+        let backup_id = Self::create_backup(&config_path, state.clock())?;
 
+        new_config.inherit_revision(&guard);
+        Self::save_config_to_path(&new_config, &config_path)?;
+        *guard = new_config;
+
+        Ok(backup_id)
+    }
+
+    /// 对序列化后的完整配置应用 RFC 6902 JSON Patch，校验通过后备份并保存，返回备份 ID。
+    /// 任一操作失败或结果无效时整体拒绝，内存与磁盘中的配置保持不变。
+    pub fn apply_json_patch(
+        state: &AppState,
+        patch: &json_patch::Patch,
+    ) -> Result<String, AppError> {
+        let mut guard = state.config.write().map_err(AppError::from)?;
+        check_expected_revision(guard.revision)?;
+
+        let mut value = serde_json::to_value(&*guard)
+            .map_err(|e| AppError::Config(format!("序列化配置失败: {e}")))?;
+        json_patch::patch(&mut value, patch)
+            .map_err(|e| AppError::InvalidInput(format!("JSON Patch 应用失败: {e}")))?;
+
+        MultiAppConfig::ensure_not_v1_value(&value)?;
+        let mut new_config: MultiAppConfig = serde_json::from_value(value)
+            .map_err(|e| AppError::InvalidInput(format!("补丁后的配置无效: {e}")))?;
+        Self::validate_patched_config(&new_config)?;
+        new_config.inherit_revision(&guard);
+
+        let config_path = crate::config::get_app_config_path()?;
+        let backup_id = Self::create_backup(&config_path, state.clock())?;
+        Self::save_config_to_path(&new_config, &config_path)?;
+        *guard = new_config;
+
+        Ok(backup_id)
+    }
+
+    /// 补丁可以改动任意字段，这里检查反序列化无法覆盖的引用关系与 MCP 定义
+    fn validate_patched_config(config: &MultiAppConfig) -> Result<(), AppError> {
+        for (app, manager) in &config.apps {
+            if !manager.current.is_empty() && !manager.providers.contains_key(&manager.current) {
+                return Err(AppError::InvalidInput(format!(
+                    "{app} 的当前供应商 '{}' 不存在",
+                    manager.current
+                )));
+            }
+            for (id, provider) in &manager.providers {
+                if provider.id != *id {
+                    return Err(AppError::InvalidInput(format!(
+                        "{app} 供应商键 '{id}' 与其 id '{}' 不一致",
+                        provider.id
+                    )));
+                }
+            }
+        }
+
+        if let Some(servers) = config.mcp.servers.as_ref() {
+            for (id, server) in servers {
+                crate::mcp::validation::validate_server_spec(&server.server)
+                    .map_err(|e| AppError::InvalidInput(format!("MCP 服务器 '{id}' 无效: {e}")))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 将单个应用的供应商、MCP 启用标记与提示词重置为空（先备份），其他应用保持不变。
+    /// 仅修改 config.json，不改动该应用的 live 配置文件。
+    pub fn reset_app(state: &AppState, app: &AppType) -> Result<String, AppError> {
+        let mut guard = state.config.write().map_err(AppError::from)?;
+        check_expected_revision(guard.revision)?;
+        let config_path = crate::config::get_app_config_path()?;
+        let backup_id = Self::create_backup(&config_path, state.clock())?;
+
+        let mut new_config = guard.clone();
+        if let Some(manager) = new_config.get_manager_mut(app) {
+            *manager = Default::default();
+        }
+        if let Some(servers) = new_config.mcp.servers.as_mut() {
+            for server in servers.values_mut() {
+                server.apps.set_enabled_for(app, false);
+            }
+        }
+        // Omo 没有独立的 MCP/提示词存储（mcp_for 会回落到 Codex），不能顺带清空
+        let prompts = match app {
+            AppType::Claude => Some(&mut new_config.prompts.claude.prompts),
+            AppType::Codex => Some(&mut new_config.prompts.codex.prompts),
+            AppType::Gemini => Some(&mut new_config.prompts.gemini.prompts),
+            AppType::Opencode => Some(&mut new_config.prompts.opencode.prompts),
+            AppType::Omo => None,
+        };
+        if let Some(prompts) = prompts {
+            prompts.clear();
+            new_config.mcp_for_mut(app).servers.clear();
+        }
+
+        new_config.inherit_revision(&guard);
         Self::save_config_to_path(&new_config, &config_path)?;
         *guard = new_config;
 
@@ -283,13 +597,158 @@ impl ConfigService {
     }
 
     /// 同步当前供应商到对应的 live 配置。
+    ///
+    /// 调用方需在获取 `config` 写锁之前先持有 [`AppState::lock_all_live`]。
+    /// 单个应用失败不会中断其他应用，返回第一个错误。
     pub fn sync_current_providers_to_live(config: &mut MultiAppConfig) -> Result<(), AppError> {
-        Self::sync_current_provider_for_app(config, &AppType::Claude)?;
-        Self::sync_current_provider_for_app(config, &AppType::Codex)?;
-        Self::sync_current_provider_for_app(config, &AppType::Gemini)?;
-        Self::sync_current_provider_for_app(config, &AppType::Opencode)?;
-        Self::sync_current_provider_for_app(config, &AppType::Omo)?;
-        Ok(())
+        Self::sync_current_providers_each(config)
+            .into_iter()
+            .try_for_each(|(_, result)| result)
+    }
+
+    /// 同 [`Self::sync_current_providers_to_live`]，但返回每个应用各自的结果，便于界面指出失败的应用。
+    pub fn sync_current_providers_to_live_report(
+        config: &mut MultiAppConfig,
+    ) -> CurrentProvidersSyncReport {
+        let apps: BTreeMap<String, ProviderSyncOutcome> = Self::sync_current_providers_each(config)
+            .into_iter()
+            .map(|(app, result)| {
+                let outcome = ProviderSyncOutcome {
+                    ok: result.is_ok(),
+                    error: result.err().map(|e| e.to_string()),
+                };
+                (app.as_str().to_string(), outcome)
+            })
+            .collect();
+        let failures: Vec<String> = apps
+            .iter()
+            .filter_map(|(app, outcome)| outcome.error.as_ref().map(|e| format!("{app}: {e}")))
+            .collect();
+        CurrentProvidersSyncReport {
+            success: failures.is_empty(),
+            message: if failures.is_empty() {
+                "Live configuration synchronized".to_string()
+            } else {
+                failures.join("; ")
+            },
+            apps,
+        }
+    }
+
+    fn sync_current_providers_each(
+        config: &mut MultiAppConfig,
+    ) -> Vec<(AppType, Result<(), AppError>)> {
+        [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::Opencode,
+            AppType::Omo,
+        ]
+        .into_iter()
+        .map(|app| {
+            let result = Self::sync_current_provider_for_app(config, &app);
+            if let Err(e) = &result {
+                log::warn!("同步 {} 当前供应商到 live 配置失败: {e}", app.as_str());
+            }
+            (app, result)
+        })
+        .collect()
+    }
+
+    /// 按 config.json 重写所有 live 文件：当前供应商、MCP 服务器与已启用的提示词。
+    /// 单个应用失败不会中断其他应用，错误记录在对应的报告中。
+    pub async fn sync_all_to_live(state: &AppState) -> Result<Vec<LiveSyncReport>, AppError> {
+        let mut reports = Vec::new();
+        {
+            let _live = state.lock_all_live().await;
+            let mut config = state.config.write().map_err(AppError::from)?;
+            let mcp_view = Self::mcp_sync_view(&config);
+
+            for app in [
+                AppType::Claude,
+                AppType::Codex,
+                AppType::Gemini,
+                AppType::Opencode,
+                AppType::Omo,
+            ] {
+                let mut report = LiveSyncReport {
+                    app: app.as_str().to_string(),
+                    provider_synced: false,
+                    mcp_servers: None,
+                    prompt_synced: false,
+                    errors: Vec::new(),
+                };
+
+                let has_current = config.get_manager(&app).is_some_and(|manager| {
+                    !manager.current.is_empty() && manager.providers.contains_key(&manager.current)
+                });
+                if has_current {
+                    match Self::sync_current_provider_for_app(&mut config, &app) {
+                        Ok(()) => report.provider_synced = true,
+                        Err(e) => report.errors.push(e.to_string()),
+                    }
+                }
+
+                // 供应商同步（如 Codex）可能按旧结构写过 MCP，这里以统一结构为准再覆盖一次
+                let mcp_result = match app {
+                    AppType::Claude => Some(crate::mcp::sync_enabled_to_claude(&mcp_view)),
+                    AppType::Codex => Some(crate::mcp::sync_enabled_to_codex(&mcp_view)),
+                    AppType::Gemini => Some(crate::mcp::sync_enabled_to_gemini(&mcp_view)),
+                    AppType::Opencode | AppType::Omo => None,
+                };
+                match mcp_result {
+                    Some(Ok(())) => {
+                        report.mcp_servers = Some(mcp_view.mcp_for(&app).servers.len());
+                    }
+                    Some(Err(e)) => report.errors.push(e.to_string()),
+                    None => {}
+                }
+
+                let prompts = match app {
+                    AppType::Claude => Some(&config.prompts.claude.prompts),
+                    AppType::Codex => Some(&config.prompts.codex.prompts),
+                    AppType::Gemini => Some(&config.prompts.gemini.prompts),
+                    AppType::Opencode => Some(&config.prompts.opencode.prompts),
+                    AppType::Omo => None,
+                };
+                // 没有启用的提示词时保留现有文件，避免清空用户手写的内容
+                if let Some(prompt) = prompts.and_then(|p| p.values().find(|p| p.enabled)) {
+                    let written = crate::prompt_files::prompt_file_path(&app)
+                        .and_then(|path| crate::config::write_text_file(&path, &prompt.content));
+                    match written {
+                        Ok(()) => report.prompt_synced = true,
+                        Err(e) => report.errors.push(e.to_string()),
+                    }
+                }
+
+                reports.push(report);
+            }
+        }
+        state.save()?;
+
+        Ok(reports)
+    }
+
+    /// 以统一 MCP 结构为准，生成各应用的旧版视图（enabled + server），供全量同步函数使用
+    pub(crate) fn mcp_sync_view(config: &MultiAppConfig) -> MultiAppConfig {
+        let mut view = config.clone();
+        let Some(servers) = config.mcp.servers.as_ref() else {
+            return view;
+        };
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            view.mcp_for_mut(&app).servers = servers
+                .iter()
+                .filter(|(_, server)| server.apps.is_enabled_for(&app))
+                .map(|(id, server)| {
+                    (
+                        id.clone(),
+                        serde_json::json!({ "enabled": true, "server": server.server }),
+                    )
+                })
+                .collect();
+        }
+        view
     }
 
     fn sync_current_provider_for_app(
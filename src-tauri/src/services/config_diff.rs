@@ -0,0 +1,336 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::app_config::{AppType, MultiAppConfig};
+
+/// 单个 JSON 节点的变化类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JsonChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// 单个 JSON 节点的变化（path 为 JSON Pointer，相对于所在条目）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonChange {
+    pub path: String,
+    pub kind: JsonChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Value>,
+}
+
+/// 一个按 ID 索引的条目发生的变化
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedEntry {
+    pub id: String,
+    pub changes: Vec<JsonChange>,
+}
+
+/// 一组按 ID 索引的条目（供应商 / MCP 服务器 / 提示词）的差异
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ChangedEntry>,
+}
+
+impl EntryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// 当前供应商的变化
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentChange {
+    pub left: String,
+    pub right: String,
+}
+
+/// 单个应用维度的差异
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfigDiff {
+    pub providers: EntryDiff,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<CurrentChange>,
+    pub mcp_servers: EntryDiff,
+    pub prompts: EntryDiff,
+}
+
+impl AppConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+            && self.current.is_none()
+            && self.mcp_servers.is_empty()
+            && self.prompts.is_empty()
+    }
+}
+
+/// 两份配置之间的差异（left 视为基准，right 视为目标）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    pub left: String,
+    pub right: String,
+    pub identical: bool,
+    /// 仅包含存在差异的应用
+    pub apps: BTreeMap<String, AppConfigDiff>,
+}
+
+const DIFF_APPS: [AppType; 5] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::Opencode,
+    AppType::Omo,
+];
+
+/// 按应用比较两份配置中的供应商、MCP 服务器与提示词
+pub fn diff_configs(
+    left_label: &str,
+    left: &MultiAppConfig,
+    right_label: &str,
+    right: &MultiAppConfig,
+) -> ConfigDiff {
+    let mut apps = BTreeMap::new();
+    for app in DIFF_APPS {
+        let left_manager = left.get_manager(&app);
+        let right_manager = right.get_manager(&app);
+
+        let providers = diff_entries(
+            &left_manager
+                .map(|m| to_value_map(&m.providers))
+                .unwrap_or_default(),
+            &right_manager
+                .map(|m| to_value_map(&m.providers))
+                .unwrap_or_default(),
+        );
+
+        let left_current = left_manager.map(|m| m.current.clone()).unwrap_or_default();
+        let right_current = right_manager.map(|m| m.current.clone()).unwrap_or_default();
+        let current = (left_current != right_current).then_some(CurrentChange {
+            left: left_current,
+            right: right_current,
+        });
+
+        let app_diff = AppConfigDiff {
+            providers,
+            current,
+            mcp_servers: diff_entries(&mcp_servers_for(left, &app), &mcp_servers_for(right, &app)),
+            prompts: diff_entries(&prompts_for(left, &app), &prompts_for(right, &app)),
+        };
+        if !app_diff.is_empty() {
+            apps.insert(app.as_str().to_string(), app_diff);
+        }
+    }
+
+    ConfigDiff {
+        left: left_label.to_string(),
+        right: right_label.to_string(),
+        identical: apps.is_empty(),
+        apps,
+    }
+}
+
+fn to_value_map<T: Serialize>(
+    entries: &std::collections::HashMap<String, T>,
+) -> BTreeMap<String, Value> {
+    entries
+        .iter()
+        .map(|(id, entry)| {
+            (
+                id.clone(),
+                serde_json::to_value(entry).unwrap_or(Value::Null),
+            )
+        })
+        .collect()
+}
+
+/// 统一 MCP 结构下，某个应用启用的服务器集合
+fn mcp_servers_for(config: &MultiAppConfig, app: &AppType) -> BTreeMap<String, Value> {
+    config
+        .mcp
+        .servers
+        .as_ref()
+        .map(|servers| {
+            servers
+                .iter()
+                .filter(|(_, server)| server.apps.is_enabled_for(app))
+                .map(|(id, server)| {
+                    (
+                        id.clone(),
+                        serde_json::to_value(server).unwrap_or(Value::Null),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn prompts_for(config: &MultiAppConfig, app: &AppType) -> BTreeMap<String, Value> {
+    let prompts = match app {
+        AppType::Claude => &config.prompts.claude.prompts,
+        AppType::Codex => &config.prompts.codex.prompts,
+        AppType::Gemini => &config.prompts.gemini.prompts,
+        AppType::Opencode => &config.prompts.opencode.prompts,
+        AppType::Omo => return BTreeMap::new(),
+    };
+    to_value_map(prompts)
+}
+
+/// 比较两组按 ID 索引的条目，已变化的条目附带逐字段差异
+pub fn diff_entries(left: &BTreeMap<String, Value>, right: &BTreeMap<String, Value>) -> EntryDiff {
+    let mut diff = EntryDiff::default();
+    for (id, left_value) in left {
+        match right.get(id) {
+            None => diff.removed.push(id.clone()),
+            Some(right_value) => {
+                let changes = diff_json(left_value, right_value);
+                if !changes.is_empty() {
+                    diff.changed.push(ChangedEntry {
+                        id: id.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    diff.added = right
+        .keys()
+        .filter(|id| !left.contains_key(*id))
+        .cloned()
+        .collect();
+    diff
+}
+
+/// 通用递归 JSON 比较：对象按键、数组按下标逐层比较，其余类型按值比较
+pub fn diff_json(left: &Value, right: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_json_at(String::new(), left, right, &mut changes);
+    changes
+}
+
+fn diff_json_at(path: String, left: &Value, right: &Value, out: &mut Vec<JsonChange>) {
+    match (left, right) {
+        (Value::Object(left_map), Value::Object(right_map)) => {
+            let mut keys: Vec<&String> = left_map.keys().chain(right_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{path}/{}", escape_pointer_token(key));
+                match (left_map.get(key), right_map.get(key)) {
+                    (Some(l), Some(r)) => diff_json_at(child, l, r, out),
+                    (Some(l), None) => out.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Removed,
+                        left: Some(l.clone()),
+                        right: None,
+                    }),
+                    (None, Some(r)) => out.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Added,
+                        left: None,
+                        right: Some(r.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(left_items), Value::Array(right_items)) => {
+            let len = left_items.len().max(right_items.len());
+            for index in 0..len {
+                let child = format!("{path}/{index}");
+                match (left_items.get(index), right_items.get(index)) {
+                    (Some(l), Some(r)) => diff_json_at(child, l, r, out),
+                    (Some(l), None) => out.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Removed,
+                        left: Some(l.clone()),
+                        right: None,
+                    }),
+                    (None, Some(r)) => out.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Added,
+                        left: None,
+                        right: Some(r.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => {
+            if left != right {
+                out.push(JsonChange {
+                    path,
+                    kind: JsonChangeKind::Changed,
+                    left: Some(left.clone()),
+                    right: Some(right.clone()),
+                });
+            }
+        }
+    }
+}
+
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+
+    #[test]
+    fn diff_json_reports_nested_changes_with_pointer_paths() {
+        let left = json!({ "env": { "A/B": "1", "KEEP": "x" }, "list": [1, 2] });
+        let right = json!({ "env": { "A/B": "2", "KEEP": "x", "NEW": true }, "list": [1] });
+
+        let changes = diff_json(&left, &right);
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].path, "/env/A~1B");
+        assert_eq!(changes[0].kind, JsonChangeKind::Changed);
+        assert_eq!(changes[1].path, "/env/NEW");
+        assert_eq!(changes[1].kind, JsonChangeKind::Added);
+        assert_eq!(changes[2].path, "/list/1");
+        assert_eq!(changes[2].kind, JsonChangeKind::Removed);
+    }
+
+    #[test]
+    fn diff_configs_groups_provider_changes_per_app() {
+        let left = MultiAppConfig::default();
+        let mut right = MultiAppConfig::default();
+        let manager = right
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.insert(
+            "p1".to_string(),
+            Provider::with_id("p1".into(), "P1".into(), json!({ "env": {} }), None),
+        );
+        manager.current = "p1".to_string();
+
+        let diff = diff_configs("left", &left, "right", &right);
+
+        assert!(!diff.identical);
+        assert_eq!(diff.apps.len(), 1);
+        let claude = diff.apps.get("claude").expect("claude diff");
+        assert_eq!(claude.providers.added, vec!["p1".to_string()]);
+        assert!(claude.providers.removed.is_empty());
+        assert_eq!(
+            claude.current.as_ref().map(|c| c.right.as_str()),
+            Some("p1")
+        );
+        assert!(diff_configs("a", &left, "b", &left).identical);
+    }
+}
@@ -1,10 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
 
 use crate::app_config::{AppType, McpApps, McpServer, MultiAppConfig};
 use crate::error::AppError;
 use crate::mcp;
+use crate::services::ConfigService;
 use crate::store::AppState;
 
+/// 批量应用启用矩阵的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpEnableMatrixReport {
+    /// 启用状态发生变化的服务器 ID（排序）
+    pub updated: Vec<String>,
+    /// 矩阵中存在但本机没有的服务器 ID（已忽略）
+    pub unknown: Vec<String>,
+}
+
+/// 批量删除中单个服务器的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpDeleteResult {
+    pub id: String,
+    /// `false` 表示该 ID 不存在、受保护（未指定 force）或在请求中重复出现
+    pub deleted: bool,
+}
+
+/// 从 URL 导入时单个服务器的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpUrlImportResult {
+    pub id: String,
+    pub imported: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
 pub struct McpService;
 
@@ -16,10 +48,10 @@ impl McpService {
 
             // 从各客户端配置导入 MCP，确保统一结构完整
             let mut need_save = cfg.mcp.servers.is_none();
-            let imported_from_claude = mcp::import_from_claude(&mut cfg)?;
-            let imported_from_codex = mcp::import_from_codex(&mut cfg)?;
-            let imported_from_gemini = mcp::import_from_gemini(&mut cfg)?;
-            let imported_from_opencode = mcp::import_from_opencode(&mut cfg)?;
+            let imported_from_claude = mcp::imported_count(mcp::import_from_claude(&mut cfg))?;
+            let imported_from_codex = mcp::imported_count(mcp::import_from_codex(&mut cfg))?;
+            let imported_from_gemini = mcp::imported_count(mcp::import_from_gemini(&mut cfg))?;
+            let imported_from_opencode = mcp::imported_count(mcp::import_from_opencode(&mut cfg))?;
             if imported_from_claude > 0
                 || imported_from_codex > 0
                 || imported_from_gemini > 0
@@ -66,6 +98,7 @@ impl McpService {
                                 homepage: None,
                                 docs: None,
                                 tags: Vec::new(),
+                                protected: false,
                             },
                         );
                     }
@@ -88,8 +121,9 @@ impl McpService {
         Ok(servers)
     }
 
-    /// 添加或更新 MCP 服务器
-    pub fn upsert_server(state: &AppState, server: McpServer) -> Result<(), AppError> {
+    /// 添加或更新 MCP 服务器（连接定义来自用户提交，新增或改动的字段按内置 Schema 严格校验）
+    pub async fn upsert_server(state: &AppState, server: McpServer) -> Result<(), AppError> {
+        let _live = state.lock_all_live().await;
         {
             let mut cfg = state.config.write()?;
 
@@ -101,6 +135,11 @@ impl McpService {
             let servers = cfg.mcp.servers.as_mut().unwrap();
             let id = server.id.clone();
 
+            let existing = servers.get(&id).map(|stored| &stored.server);
+            mcp::validation::validate_server_spec_schema(&server.server, existing)?;
+            mcp::validation::validate_server_spec(&server.server)?;
+            mcp::validation::reject_shell_metachars_in_spec(&server.server)?;
+
             // 插入或更新
             servers.insert(id, server.clone());
         }
@@ -113,12 +152,76 @@ impl McpService {
         Ok(())
     }
 
+    /// 预览 Codex MCP 全量同步将写入的 config.toml 全文（不写文件）
+    pub fn preview_codex_config(state: &AppState) -> Result<String, AppError> {
+        let cfg = state.config.read()?;
+        mcp::render_codex_config_text(&ConfigService::mcp_sync_view(&cfg))
+    }
+
+    /// 将部分连接定义合并到已有服务器的 `server` 对象（按顶层键覆盖，值为 null 表示删除该键）
+    ///
+    /// 名称、标签、描述与各应用启用状态保持不变；返回 `Ok(None)` 表示服务器不存在。
+    pub async fn patch_server_spec(
+        state: &AppState,
+        id: &str,
+        patch: serde_json::Value,
+    ) -> Result<Option<McpServer>, AppError> {
+        let _live = state.lock_all_live().await;
+        let serde_json::Value::Object(patch) = patch else {
+            return Err(AppError::InvalidInput(
+                "MCP 服务器更新内容必须为 JSON 对象".into(),
+            ));
+        };
+
+        // 查找、合并、校验与写回在同一把写锁内完成，避免并发更新互相覆盖
+        let server = {
+            let mut cfg = state.config.write()?;
+            let Some(server) = cfg
+                .mcp
+                .servers
+                .as_mut()
+                .and_then(|servers| servers.get_mut(id))
+            else {
+                return Ok(None);
+            };
+
+            let mut spec = server.server.as_object().cloned().unwrap_or_default();
+            for (key, value) in patch {
+                if value.is_null() {
+                    spec.remove(&key);
+                } else {
+                    spec.insert(key, value);
+                }
+            }
+            let spec = serde_json::Value::Object(spec);
+            mcp::validation::validate_server_spec_schema(&spec, Some(&server.server))?;
+            mcp::validation::validate_server_spec(&spec)?;
+            mcp::validation::reject_shell_metachars_in_spec(&spec)?;
+            server.server = spec;
+            server.clone()
+        };
+
+        state.save()?;
+        Self::sync_server_to_apps(state, &server)?;
+        Ok(Some(server))
+    }
+
+    /// 获取单个 MCP 服务器（与 `get_all_servers` 相同的统一视图）
+    pub fn get_server(state: &AppState, id: &str) -> Result<Option<McpServer>, AppError> {
+        Ok(Self::get_all_servers(state)?.remove(id))
+    }
+
     /// 删除 MCP 服务器
-    pub fn delete_server(state: &AppState, id: &str) -> Result<bool, AppError> {
+    /// 删除 MCP 服务器；受保护的服务器除非 `force` 为 true，否则拒绝删除
+    pub async fn delete_server(state: &AppState, id: &str, force: bool) -> Result<bool, AppError> {
+        let _live = state.lock_all_live().await;
         let server = {
             let mut cfg = state.config.write()?;
 
             if let Some(servers) = &mut cfg.mcp.servers {
+                if !force && servers.get(id).is_some_and(|server| server.protected) {
+                    return Err(Self::protected_error(id));
+                }
                 servers.remove(id)
             } else {
                 None
@@ -136,13 +239,208 @@ impl McpService {
         }
     }
 
+    fn protected_error(id: &str) -> AppError {
+        AppError::InvalidInput(format!(
+            "MCP 服务器 '{id}' 已标记为受保护，拒绝删除（确需删除请指定 force=true）"
+        ))
+    }
+
+    /// 批量删除 MCP 服务器：在一次写锁内从统一配置中移除，保存一次，
+    /// 每个受影响的应用只做一次全量同步（OpenCode 没有全量同步，逐项移除）。
+    /// 未指定 `force` 时跳过受保护的服务器
+    pub async fn delete_servers(
+        state: &AppState,
+        ids: &[String],
+        force: bool,
+    ) -> Result<Vec<McpDeleteResult>, AppError> {
+        let _live = state.lock_all_live().await;
+        let mut results = Vec::with_capacity(ids.len());
+        let mut affected_apps = Vec::new();
+        let mut opencode_removed = Vec::new();
+        {
+            let mut cfg = state.config.write()?;
+            for id in ids {
+                let removed = cfg.mcp.servers.as_mut().and_then(|servers| {
+                    if !force && servers.get(id).is_some_and(|server| server.protected) {
+                        None
+                    } else {
+                        servers.remove(id)
+                    }
+                });
+                if let Some(server) = &removed {
+                    for app in server.apps.enabled_apps() {
+                        if app == AppType::Opencode {
+                            opencode_removed.push(id.clone());
+                        }
+                        if !affected_apps.contains(&app) {
+                            affected_apps.push(app);
+                        }
+                    }
+                }
+                results.push(McpDeleteResult {
+                    id: id.clone(),
+                    deleted: removed.is_some(),
+                });
+            }
+        }
+
+        if results.iter().all(|result| !result.deleted) {
+            return Ok(results);
+        }
+        state.save()?;
+
+        let cfg = state.config.read()?;
+        let view = ConfigService::mcp_sync_view(&cfg);
+        for app in &affected_apps {
+            match app {
+                AppType::Claude => mcp::sync_enabled_to_claude(&view)?,
+                AppType::Codex => mcp::sync_enabled_to_codex(&view)?,
+                AppType::Gemini => mcp::sync_enabled_to_gemini(&view)?,
+                AppType::Opencode => {
+                    for id in &opencode_removed {
+                        mcp::remove_server_from_opencode(id)?;
+                    }
+                }
+                AppType::Omo => {}
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// 导入 `{ "mcpServers": { id: spec } }`（或直接的 `{ id: spec }`）形式的服务器定义：
+    /// 逐个校验，合法项在一次写锁内写入（已存在的服务器仅替换连接定义、保留启用状态），
+    /// 保存一次后再同步已启用的应用；非法项在结果中给出原因
+    pub async fn import_servers_from_json(
+        state: &AppState,
+        document: &serde_json::Value,
+    ) -> Result<Vec<McpUrlImportResult>, AppError> {
+        let _live = state.lock_all_live().await;
+        let servers = document
+            .get("mcpServers")
+            .unwrap_or(document)
+            .as_object()
+            .ok_or_else(|| {
+                AppError::InvalidInput("导入内容必须是 mcpServers 对象（id → 服务器定义）".into())
+            })?;
+
+        let mut results = Vec::with_capacity(servers.len());
+        let mut to_sync = Vec::new();
+        {
+            let mut cfg = state.config.write()?;
+            let existing = cfg.mcp.servers.get_or_insert_with(HashMap::new);
+            for (id, spec) in servers {
+                let id = id.trim();
+                let outcome = if id.is_empty() {
+                    Err(AppError::InvalidInput("MCP 服务器 ID 不能为空".into()))
+                } else {
+                    mcp::validation::validate_server_spec_schema(spec, None)
+                        .and_then(|_| mcp::validation::validate_server_spec(spec))
+                        .and_then(|_| mcp::validation::reject_shell_metachars_in_spec(spec))
+                };
+                if let Err(err) = outcome {
+                    results.push(McpUrlImportResult {
+                        id: id.to_string(),
+                        imported: false,
+                        error: Some(err.to_string()),
+                    });
+                    continue;
+                }
+
+                let server = existing
+                    .entry(id.to_string())
+                    .and_modify(|server| server.server = spec.clone())
+                    .or_insert_with(|| McpServer {
+                        id: id.to_string(),
+                        name: id.to_string(),
+                        server: spec.clone(),
+                        apps: McpApps::default(),
+                        description: None,
+                        homepage: None,
+                        docs: None,
+                        tags: Vec::new(),
+                        protected: false,
+                    });
+                if !server.apps.enabled_apps().is_empty() {
+                    to_sync.push(server.clone());
+                }
+                results.push(McpUrlImportResult {
+                    id: id.to_string(),
+                    imported: true,
+                    error: None,
+                });
+            }
+        }
+
+        if results.iter().any(|result| result.imported) {
+            state.save()?;
+            for server in &to_sync {
+                Self::sync_server_to_apps(state, server)?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// 重命名 MCP 服务器：迁移统一配置中的键，并在各启用应用的 live 配置中以新 ID 重新同步
+    ///
+    /// 返回 `Ok(None)` 表示原服务器不存在；新 ID 与已有服务器冲突时返回错误。
+    pub async fn rename_server(
+        state: &AppState,
+        id: &str,
+        new_id: &str,
+    ) -> Result<Option<McpServer>, AppError> {
+        let _live = state.lock_all_live().await;
+        let new_id = new_id.trim();
+        if new_id.is_empty() {
+            return Err(AppError::InvalidInput("新的 MCP 服务器 ID 不能为空".into()));
+        }
+
+        let (old, renamed) = {
+            let mut cfg = state.config.write()?;
+            let Some(servers) = cfg.mcp.servers.as_mut() else {
+                return Ok(None);
+            };
+            if !servers.contains_key(id) {
+                return Ok(None);
+            }
+            if new_id == id {
+                return Ok(servers.get(id).cloned());
+            }
+            if servers.contains_key(new_id) {
+                return Err(AppError::InvalidInput(format!(
+                    "MCP 服务器 ID '{new_id}' 已存在"
+                )));
+            }
+
+            let old = servers.remove(id).expect("checked above");
+            let mut renamed = old.clone();
+            renamed.id = new_id.to_string();
+            // 名称沿用旧 ID 时（如导入产生的条目）一并更新
+            if renamed.name == id {
+                renamed.name = new_id.to_string();
+            }
+            servers.insert(renamed.id.clone(), renamed.clone());
+            (old, renamed)
+        };
+
+        state.save()?;
+
+        // 先从 live 配置中移除旧 ID，再以新 ID 重新同步（启用状态保持不变）
+        Self::remove_server_from_all_apps(state, id, &old)?;
+        Self::sync_server_to_apps(state, &renamed)?;
+
+        Ok(Some(renamed))
+    }
+
     /// 切换指定应用的启用状态
-    pub fn toggle_app(
+    pub async fn toggle_app(
         state: &AppState,
         server_id: &str,
         app: AppType,
         enabled: bool,
     ) -> Result<(), AppError> {
+        let _live = state.lock_all_live().await;
         let server = {
             let mut cfg = state.config.write()?;
 
@@ -172,6 +470,208 @@ impl McpService {
         Ok(())
     }
 
+    /// 一次性设置多个应用的启用状态，仅对状态发生变化的应用各执行一次同步/移除
+    ///
+    /// 服务器不存在时返回 `Ok(None)`；成功时返回更新后的应用开关。
+    pub async fn set_apps(
+        state: &AppState,
+        server_id: &str,
+        updates: &[(AppType, bool)],
+    ) -> Result<Option<McpApps>, AppError> {
+        let _live = state.lock_all_live().await;
+        let (server, changed) = {
+            let mut cfg = state.config.write()?;
+            let Some(server) = cfg
+                .mcp
+                .servers
+                .as_mut()
+                .and_then(|servers| servers.get_mut(server_id))
+            else {
+                return Ok(None);
+            };
+
+            let mut changed = Vec::new();
+            for (app, enabled) in updates {
+                if server.apps.is_enabled_for(app) != *enabled {
+                    server.apps.set_enabled_for(app, *enabled);
+                    changed.push((app.clone(), *enabled));
+                }
+            }
+            (server.clone(), changed)
+        };
+
+        if changed.is_empty() {
+            return Ok(Some(server.apps));
+        }
+        state.save()?;
+
+        for (app, enabled) in &changed {
+            if *enabled {
+                Self::sync_server_to_app(state, &server, app)?;
+            } else {
+                Self::remove_server_from_app(state, server_id, app)?;
+            }
+        }
+
+        Ok(Some(server.apps))
+    }
+
+    /// 导出各服务器在各应用的启用矩阵（按 ID 排序），便于迁移到其他机器
+    pub fn enable_matrix(state: &AppState) -> Result<BTreeMap<String, McpApps>, AppError> {
+        Ok(Self::get_all_servers(state)?
+            .into_iter()
+            .map(|(id, server)| (id, server.apps))
+            .collect())
+    }
+
+    /// 批量应用启用矩阵：只修改本机已存在的服务器，未知 ID 忽略并在结果中列出；
+    /// 每个受影响的应用只做一次全量同步（OpenCode 没有全量同步，逐项同步/移除变化的服务器）
+    pub async fn apply_enable_matrix(
+        state: &AppState,
+        matrix: &BTreeMap<String, Vec<(AppType, bool)>>,
+    ) -> Result<McpEnableMatrixReport, AppError> {
+        let _live = state.lock_all_live().await;
+        let mut report = McpEnableMatrixReport::default();
+        let mut changed_apps = Vec::new();
+        let mut opencode_changes = Vec::new();
+        {
+            let mut cfg = state.config.write()?;
+            for (id, updates) in matrix {
+                let Some(server) = cfg
+                    .mcp
+                    .servers
+                    .as_mut()
+                    .and_then(|servers| servers.get_mut(id))
+                else {
+                    report.unknown.push(id.clone());
+                    continue;
+                };
+                let mut server_changed = false;
+                for (app, enabled) in updates {
+                    if server.apps.is_enabled_for(app) == *enabled {
+                        continue;
+                    }
+                    server.apps.set_enabled_for(app, *enabled);
+                    server_changed = true;
+                    if !changed_apps.contains(app) {
+                        changed_apps.push(app.clone());
+                    }
+                    if *app == AppType::Opencode {
+                        opencode_changes.push((server.clone(), *enabled));
+                    }
+                }
+                if server_changed {
+                    report.updated.push(id.clone());
+                }
+            }
+        }
+
+        if report.updated.is_empty() {
+            return Ok(report);
+        }
+        state.save()?;
+
+        let cfg = state.config.read()?;
+        let view = ConfigService::mcp_sync_view(&cfg);
+        for app in &changed_apps {
+            match app {
+                AppType::Claude => mcp::sync_enabled_to_claude(&view)?,
+                AppType::Codex => mcp::sync_enabled_to_codex(&view)?,
+                AppType::Gemini => mcp::sync_enabled_to_gemini(&view)?,
+                AppType::Opencode => {
+                    for (server, enabled) in &opencode_changes {
+                        if *enabled {
+                            Self::sync_server_to_app_internal(&cfg, server, app)?;
+                        } else {
+                            mcp::remove_server_from_opencode(&server.id)?;
+                        }
+                    }
+                }
+                AppType::Omo => {}
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 查找连接定义（`server` 字段）完全相同的服务器，每组按 ID 排序，仅返回两个及以上的分组
+    pub fn find_duplicates(state: &AppState) -> Result<Vec<Vec<String>>, AppError> {
+        let cfg = state.config.read()?;
+        let Some(servers) = cfg.mcp.servers.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (id, server) in servers {
+            groups
+                .entry(server.server.to_string())
+                .or_default()
+                .push(id.clone());
+        }
+
+        let mut duplicates: Vec<Vec<String>> = groups
+            .into_values()
+            .filter(|ids| ids.len() > 1)
+            .map(|mut ids| {
+                ids.sort();
+                ids
+            })
+            .collect();
+        duplicates.sort();
+        Ok(duplicates)
+    }
+
+    /// 将若干服务器合并到 `keep_id`：启用状态取并集，删除被合并的服务器并重新同步 live 配置
+    ///
+    /// `keep_id` 不存在时返回 `Ok(None)`；任一被合并的 ID 不存在时返回错误且不做任何修改。
+    pub async fn merge_servers(
+        state: &AppState,
+        keep_id: &str,
+        merge_ids: &[String],
+    ) -> Result<Option<McpServer>, AppError> {
+        let _live = state.lock_all_live().await;
+        let (kept, removed) = {
+            let mut cfg = state.config.write()?;
+            let Some(servers) = cfg.mcp.servers.as_mut() else {
+                return Ok(None);
+            };
+            if !servers.contains_key(keep_id) {
+                return Ok(None);
+            }
+            let merge_ids: Vec<&String> = merge_ids
+                .iter()
+                .filter(|id| id.as_str() != keep_id)
+                .collect();
+            if let Some(missing) = merge_ids
+                .iter()
+                .find(|id| !servers.contains_key(id.as_str()))
+            {
+                return Err(AppError::NotFound(format!("MCP 服务器不存在: {missing}")));
+            }
+
+            let removed: Vec<McpServer> = merge_ids
+                .into_iter()
+                .filter_map(|id| servers.remove(id))
+                .collect();
+            let kept = servers.get_mut(keep_id).expect("checked above");
+            for server in &removed {
+                for app in server.apps.enabled_apps() {
+                    kept.apps.set_enabled_for(&app, true);
+                }
+            }
+            (kept.clone(), removed)
+        };
+
+        state.save()?;
+
+        for server in &removed {
+            Self::remove_server_from_all_apps(state, &server.id, server)?;
+        }
+        Self::sync_server_to_apps(state, &kept)?;
+
+        Ok(Some(kept))
+    }
+
     /// 将 MCP 服务器同步到所有启用的应用
     fn sync_server_to_apps(state: &AppState, server: &McpServer) -> Result<(), AppError> {
         let cfg = state.config.read()?;
@@ -253,7 +753,8 @@ impl McpService {
     }
 
     /// 手动同步所有启用的 MCP 服务器到对应的应用
-    pub fn sync_all_enabled(state: &AppState) -> Result<(), AppError> {
+    pub async fn sync_all_enabled(state: &AppState) -> Result<(), AppError> {
+        let _live = state.lock_all_live().await;
         let servers = Self::get_all_servers(state)?;
 
         for server in servers.values() {
@@ -263,6 +764,19 @@ impl McpService {
         Ok(())
     }
 
+    /// 将启用的 MCP 服务器重新同步到单个应用；调用方需已持有该应用的 live 锁
+    pub(crate) fn sync_app_enabled_locked(state: &AppState, app: &AppType) -> Result<(), AppError> {
+        let servers = Self::get_all_servers(state)?;
+
+        for server in servers.values() {
+            if server.apps.is_enabled_for(app) {
+                Self::sync_server_to_app(state, server, app)?;
+            }
+        }
+
+        Ok(())
+    }
+
     // ========================================================================
     // 兼容层：支持旧的 v3.6.x 命令（已废弃，将在 v4.0 移除）
     // ========================================================================
@@ -287,34 +801,27 @@ impl McpService {
 
     /// [已废弃] 设置 MCP 服务器在指定应用的启用状态（兼容旧 API）
     #[deprecated(since = "3.7.0", note = "Use toggle_app instead")]
-    pub fn set_enabled(
+    pub async fn set_enabled(
         state: &AppState,
         app: AppType,
         id: &str,
         enabled: bool,
     ) -> Result<bool, AppError> {
-        Self::toggle_app(state, id, app, enabled)?;
+        Self::toggle_app(state, id, app, enabled).await?;
         Ok(true)
     }
 
     /// [已废弃] 同步启用的 MCP 到指定应用（兼容旧 API）
     #[deprecated(since = "3.7.0", note = "Use sync_all_enabled instead")]
-    pub fn sync_enabled(state: &AppState, app: AppType) -> Result<(), AppError> {
-        let servers = Self::get_all_servers(state)?;
-
-        for server in servers.values() {
-            if server.apps.is_enabled_for(&app) {
-                Self::sync_server_to_app(state, server, &app)?;
-            }
-        }
-
-        Ok(())
+    pub async fn sync_enabled(state: &AppState, app: AppType) -> Result<(), AppError> {
+        let _live = state.lock_live(&app).await;
+        Self::sync_app_enabled_locked(state, &app)
     }
 
     /// 从 Claude 导入 MCP（v3.7.0 已更新为统一结构）
     pub fn import_from_claude(state: &AppState) -> Result<usize, AppError> {
         let mut cfg = state.config.write()?;
-        let count = mcp::import_from_claude(&mut cfg)?;
+        let count = mcp::import_from_claude(&mut cfg)?.imported;
         drop(cfg);
         state.save()?;
         Ok(count)
@@ -323,7 +830,7 @@ impl McpService {
     /// 从 Codex 导入 MCP（v3.7.0 已更新为统一结构）
     pub fn import_from_codex(state: &AppState) -> Result<usize, AppError> {
         let mut cfg = state.config.write()?;
-        let count = mcp::import_from_codex(&mut cfg)?;
+        let count = mcp::import_from_codex(&mut cfg)?.imported;
         drop(cfg);
         state.save()?;
         Ok(count)
@@ -332,15 +839,41 @@ impl McpService {
     /// 从 Gemini 导入 MCP（v3.7.0 已更新为统一结构）
     pub fn import_from_gemini(state: &AppState) -> Result<usize, AppError> {
         let mut cfg = state.config.write()?;
-        let count = mcp::import_from_gemini(&mut cfg)?;
+        let count = mcp::import_from_gemini(&mut cfg)?.imported;
         drop(cfg);
         state.save()?;
         Ok(count)
     }
 
+    /// 从指定应用导入 MCP，返回完整结果（含被跳过的条目及原因）
+    pub fn import_from_app(
+        state: &AppState,
+        app: AppType,
+    ) -> Result<mcp::McpImportReport, AppError> {
+        let mut cfg = state.config.write()?;
+        let report = match app {
+            AppType::Claude => mcp::import_from_claude(&mut cfg)?,
+            AppType::Codex => mcp::import_from_codex(&mut cfg)?,
+            AppType::Gemini => mcp::import_from_gemini(&mut cfg)?,
+            AppType::Opencode => mcp::import_from_opencode(&mut cfg)?,
+            AppType::Omo => {
+                return Err(AppError::localized(
+                    "app_not_supported_yet",
+                    format!("应用 '{}' 暂未支持，敬请期待。", app.as_str()),
+                    format!("App '{}' is not supported yet.", app.as_str()),
+                ));
+            }
+        };
+        drop(cfg);
+        if report.imported > 0 {
+            state.save()?;
+        }
+        Ok(report)
+    }
+
     pub fn import_from_opencode(state: &AppState) -> Result<usize, AppError> {
         let mut cfg = state.config.write()?;
-        let count = mcp::import_from_opencode(&mut cfg)?;
+        let count = mcp::import_from_opencode(&mut cfg)?.imported;
         drop(cfg);
         state.save()?;
         Ok(count)
@@ -1,7 +1,9 @@
 pub mod config;
+pub mod config_diff;
 pub mod env_checker;
 pub mod env_manager;
 pub mod mcp;
+pub mod preflight;
 pub mod prompt;
 pub mod provider;
 pub mod skill;
@@ -9,7 +11,9 @@ pub mod speedtest;
 
 pub use config::ConfigService;
 pub use mcp::McpService;
-pub use prompt::PromptService;
+pub use prompt::{
+    PromptBundleEntry, PromptBundleImportMode, PromptBundleImportResult, PromptService,
+};
 pub use provider::{ProviderService, ProviderSortUpdate};
 pub use skill::{Skill, SkillRepo, SkillService};
 pub use speedtest::{EndpointLatency, SpeedtestService};
@@ -0,0 +1,237 @@
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+
+/// 被检查路径的类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PreflightPathKind {
+    Dir,
+    File,
+}
+
+/// 单个目录/文件的检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightPathStatus {
+    pub kind: PreflightPathKind,
+    pub path: String,
+    pub exists: bool,
+    pub writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// 单个应用的检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppPreflight {
+    pub app: String,
+    pub ok: bool,
+    pub paths: Vec<PreflightPathStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub ok: bool,
+    pub apps: Vec<AppPreflight>,
+}
+
+const PREFLIGHT_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::Opencode,
+];
+
+/// 检查各应用 live 配置目录与文件是否存在、是否可写（只读检查，不创建或修改任何文件）
+pub fn run_preflight() -> Result<PreflightReport, AppError> {
+    let mut apps = Vec::new();
+    for app in PREFLIGHT_APPS {
+        if let Some(status) = check_app(&app)? {
+            apps.push(status);
+        }
+    }
+
+    Ok(PreflightReport {
+        ok: apps.iter().all(|app| app.ok),
+        apps,
+    })
+}
+
+/// 检查单个应用的 live 配置路径；不涉及 live 文件的应用返回 `None`
+pub fn check_app(app: &AppType) -> Result<Option<AppPreflight>, AppError> {
+    let (dir, files) = match app {
+        AppType::Claude => (
+            crate::config::get_claude_config_dir()?,
+            vec![
+                crate::config::get_claude_settings_path()?,
+                crate::config::get_claude_mcp_path()?,
+            ],
+        ),
+        AppType::Codex => (
+            crate::codex_config::get_codex_config_dir()?,
+            vec![
+                crate::codex_config::get_codex_config_path()?,
+                crate::codex_config::get_codex_auth_path()?,
+            ],
+        ),
+        AppType::Gemini => (
+            crate::gemini_config::get_gemini_dir()?,
+            vec![
+                crate::gemini_config::get_gemini_env_path()?,
+                crate::gemini_config::get_gemini_settings_path()?,
+            ],
+        ),
+        AppType::Opencode => (
+            crate::opencode_config::get_opencode_dir(),
+            vec![crate::opencode_config::get_opencode_config_path()],
+        ),
+        AppType::Omo => return Ok(None),
+    };
+    Ok(Some(check_paths(app, dir, files)))
+}
+
+fn check_paths(app: &AppType, dir: PathBuf, files: Vec<PathBuf>) -> AppPreflight {
+    let mut paths = vec![check_dir(&dir)];
+    paths.extend(files.iter().map(|file| check_file(file)));
+    AppPreflight {
+        app: app.as_str().to_string(),
+        ok: paths.iter().all(|status| status.writable),
+        paths,
+    }
+}
+
+fn check_dir(dir: &Path) -> PreflightPathStatus {
+    if dir.is_dir() {
+        let (writable, reason) = dir_writable(dir);
+        return status(PreflightPathKind::Dir, dir, true, writable, reason);
+    }
+    if dir.exists() {
+        return status(
+            PreflightPathKind::Dir,
+            dir,
+            true,
+            false,
+            Some("路径已存在但不是目录".into()),
+        );
+    }
+    // 目录不存在：切换时会自动创建，取决于最近的已存在祖先目录是否可写
+    let (writable, reason) = ancestor_writable(dir);
+    status(PreflightPathKind::Dir, dir, false, writable, reason)
+}
+
+fn check_file(file: &Path) -> PreflightPathStatus {
+    if file.is_dir() {
+        return status(
+            PreflightPathKind::File,
+            file,
+            true,
+            false,
+            Some("路径是目录而不是文件".into()),
+        );
+    }
+    if file.exists() {
+        let readonly = file
+            .metadata()
+            .map(|meta| meta.permissions().readonly())
+            .unwrap_or(true);
+        if readonly {
+            return status(
+                PreflightPathKind::File,
+                file,
+                true,
+                false,
+                Some("文件为只读".into()),
+            );
+        }
+        // 仅以写模式打开（不截断、不创建），不会修改文件内容
+        return match OpenOptions::new().write(true).open(file) {
+            Ok(_) => status(PreflightPathKind::File, file, true, true, None),
+            Err(err) => status(
+                PreflightPathKind::File,
+                file,
+                true,
+                false,
+                Some(err.to_string()),
+            ),
+        };
+    }
+    let (writable, reason) = ancestor_writable(file);
+    status(PreflightPathKind::File, file, false, writable, reason)
+}
+
+/// 按当前进程的有效用户判断目录是否可写（避免写入探测文件）
+///
+/// 仅看权限位无法识别"目录属于其他用户"的情况，因此交由 `faccessat(AT_EACCESS)` 判断。
+#[cfg(unix)]
+fn dir_writable(dir: &Path) -> (bool, Option<String>) {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = match CString::new(dir.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(err) => return (false, Some(err.to_string())),
+    };
+    // 在目录中创建文件需要写入与进入（执行）权限；
+    // SAFETY: c_path 是以 NUL 结尾的有效字符串，且在调用期间保持存活
+    let rc = unsafe {
+        libc::faccessat(
+            libc::AT_FDCWD,
+            c_path.as_ptr(),
+            libc::W_OK | libc::X_OK,
+            libc::AT_EACCESS,
+        )
+    };
+    if rc == 0 {
+        return (true, None);
+    }
+    let err = std::io::Error::last_os_error();
+    let reason = match err.raw_os_error() {
+        Some(libc::EACCES) => "当前用户无权写入该目录".to_string(),
+        Some(libc::EROFS) => "目录所在文件系统为只读".to_string(),
+        _ => err.to_string(),
+    };
+    (false, Some(reason))
+}
+
+/// 基于权限位判断目录是否可写（避免写入探测文件）
+#[cfg(not(unix))]
+fn dir_writable(dir: &Path) -> (bool, Option<String>) {
+    match dir.metadata() {
+        Ok(meta) if meta.permissions().readonly() => (false, Some("目录为只读".into())),
+        Ok(_) => (true, None),
+        Err(err) => (false, Some(err.to_string())),
+    }
+}
+
+fn ancestor_writable(path: &Path) -> (bool, Option<String>) {
+    match path.ancestors().skip(1).find(|ancestor| ancestor.is_dir()) {
+        Some(ancestor) => {
+            let (writable, reason) = dir_writable(ancestor);
+            let reason = reason.map(|r| format!("{}: {r}", ancestor.display()));
+            (writable, reason)
+        }
+        None => (false, Some("找不到可用的父目录".into())),
+    }
+}
+
+fn status(
+    kind: PreflightPathKind,
+    path: &Path,
+    exists: bool,
+    writable: bool,
+    reason: Option<String>,
+) -> PreflightPathStatus {
+    PreflightPathStatus {
+        kind,
+        path: path.display().to_string(),
+        exists,
+        writable,
+        reason,
+    }
+}
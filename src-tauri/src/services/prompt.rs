@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
+
 use crate::app_config::AppType;
 use crate::config::write_text_file;
 use crate::error::AppError;
@@ -8,6 +10,34 @@ use crate::prompt::Prompt;
 use crate::prompt_files::prompt_file_path;
 use crate::store::AppState;
 
+/// 提示词包中的单个条目（导出/批量导入共用）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptBundleEntry {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// 批量导入时遇到 ID 冲突的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptBundleImportMode {
+    /// 保留现有提示词，跳过包内同 ID 条目
+    #[default]
+    Skip,
+    /// 用包内条目覆盖现有提示词的名称与内容
+    Merge,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PromptBundleImportResult {
+    pub imported: Vec<String>,
+    pub merged: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
 pub struct PromptService;
 
 impl PromptService {
@@ -32,7 +62,26 @@ impl PromptService {
         Ok(prompts.clone())
     }
 
-    pub fn upsert_prompt(
+    pub fn get_prompt(
+        state: &AppState,
+        app: AppType,
+        id: &str,
+    ) -> Result<Option<Prompt>, AppError> {
+        Ok(Self::get_prompts(state, app)?.remove(id))
+    }
+
+    pub async fn upsert_prompt(
+        state: &AppState,
+        app: AppType,
+        id: &str,
+        prompt: Prompt,
+    ) -> Result<(), AppError> {
+        let _live = state.lock_live(&app).await;
+        Self::upsert_prompt_locked(state, app, id, prompt)
+    }
+
+    /// 调用方需已持有该应用的 live 锁
+    fn upsert_prompt_locked(
         state: &AppState,
         app: AppType,
         id: &str,
@@ -70,7 +119,8 @@ impl PromptService {
         Ok(())
     }
 
-    pub fn delete_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
+    /// 删除提示词，返回是否确实删除了（不存在时返回 false）；检查与删除在同一把写锁内完成
+    pub fn delete_prompt(state: &AppState, app: AppType, id: &str) -> Result<bool, AppError> {
         let mut cfg = state.config.write()?;
         let prompts = match app {
             AppType::Claude => &mut cfg.prompts.claude.prompts,
@@ -92,13 +142,16 @@ impl PromptService {
             }
         }
 
-        prompts.remove(id);
+        if prompts.remove(id).is_none() {
+            return Ok(false);
+        }
         drop(cfg);
         state.save()?;
-        Ok(())
+        Ok(true)
     }
 
-    pub fn enable_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
+    pub async fn enable_prompt(state: &AppState, app: AppType, id: &str) -> Result<(), AppError> {
+        let _live = state.lock_live(&app).await;
         // 回填当前 live 文件内容到已启用的提示词，或创建备份
         let target_path = prompt_file_path(&app)?;
         if target_path.exists() {
@@ -194,7 +247,8 @@ impl PromptService {
         Ok(())
     }
 
-    pub fn import_from_file(state: &AppState, app: AppType) -> Result<String, AppError> {
+    pub async fn import_from_file(state: &AppState, app: AppType) -> Result<String, AppError> {
+        let _live = state.lock_live(&app).await;
         let file_path = prompt_file_path(&app)?;
 
         if !file_path.exists() {
@@ -219,10 +273,107 @@ impl PromptService {
             updated_at: Some(timestamp),
         };
 
-        Self::upsert_prompt(state, app, &id, prompt)?;
+        Self::upsert_prompt_locked(state, app, &id, prompt)?;
         Ok(id)
     }
 
+    /// 导出某应用的全部提示词（按 ID 排序）
+    pub fn export_bundle(
+        state: &AppState,
+        app: AppType,
+    ) -> Result<Vec<PromptBundleEntry>, AppError> {
+        let mut entries: Vec<PromptBundleEntry> = Self::get_prompts(state, app)?
+            .into_values()
+            .map(|prompt| PromptBundleEntry {
+                id: prompt.id,
+                name: prompt.name,
+                content: prompt.content,
+                enabled: prompt.enabled,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(entries)
+    }
+
+    /// 批量导入提示词包。
+    ///
+    /// 新导入的条目一律为未启用状态，避免一次导入多个启用项；合并时保留现有条目的启用状态，
+    /// 若覆盖的是已启用提示词则同步写入对应文件。
+    pub async fn import_bundle(
+        state: &AppState,
+        app: AppType,
+        entries: Vec<PromptBundleEntry>,
+        mode: PromptBundleImportMode,
+    ) -> Result<PromptBundleImportResult, AppError> {
+        let _live = state.lock_live(&app).await;
+        if let Some(entry) = entries.iter().find(|entry| entry.id.trim().is_empty()) {
+            return Err(AppError::InvalidInput(format!(
+                "提示词包中存在空 ID: {}",
+                entry.name
+            )));
+        }
+
+        let timestamp = Self::unix_timestamp()?;
+        let mut result = PromptBundleImportResult::default();
+        let mut enabled_content = None;
+        {
+            let mut cfg = state.config.write()?;
+            let prompts = match app {
+                AppType::Claude => &mut cfg.prompts.claude.prompts,
+                AppType::Codex => &mut cfg.prompts.codex.prompts,
+                AppType::Gemini => &mut cfg.prompts.gemini.prompts,
+                AppType::Opencode => &mut cfg.prompts.opencode.prompts,
+                AppType::Omo => {
+                    return Err(AppError::localized(
+                        "app_not_supported_yet",
+                        format!("应用 '{}' 暂未支持，敬请期待。", app.as_str()),
+                        format!("App '{}' is not supported yet.", app.as_str()),
+                    ));
+                }
+            };
+
+            for entry in entries {
+                match prompts.get_mut(&entry.id) {
+                    Some(_) if mode == PromptBundleImportMode::Skip => {
+                        result.skipped.push(entry.id);
+                    }
+                    Some(existing) => {
+                        existing.name = entry.name;
+                        existing.content = entry.content;
+                        existing.updated_at = Some(timestamp);
+                        if existing.enabled {
+                            enabled_content = Some(existing.content.clone());
+                        }
+                        result.merged.push(entry.id);
+                    }
+                    None => {
+                        prompts.insert(
+                            entry.id.clone(),
+                            Prompt {
+                                id: entry.id.clone(),
+                                name: entry.name,
+                                content: entry.content,
+                                description: None,
+                                enabled: false,
+                                created_at: Some(timestamp),
+                                updated_at: Some(timestamp),
+                            },
+                        );
+                        result.imported.push(entry.id);
+                    }
+                }
+            }
+        }
+        state.save()?;
+
+        if let Some(content) = enabled_content {
+            let target_path = prompt_file_path(&app)?;
+            write_text_file(&target_path, &content)?;
+        }
+
+        Ok(result)
+    }
+
     pub fn get_current_file_content(app: AppType) -> Result<Option<String>, AppError> {
         let file_path = prompt_file_path(&app)?;
         if !file_path.exists() {
@@ -1,7 +1,9 @@
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::app_config::{AppType, MultiAppConfig};
@@ -11,7 +13,10 @@ use crate::config::{
     write_json_file, write_text_file,
 };
 use crate::error::AppError;
-use crate::provider::{Provider, ProviderMeta, UsageData, UsageResult};
+use crate::provider::{
+    Provider, ProviderMeta, SwitchHistoryEntry, UsageData, UsageResult, UsageTestRecord,
+    MAX_USAGE_TEST_HISTORY,
+};
 use crate::settings::{self, CustomEndpoint};
 use crate::store::AppState;
 use crate::usage_script;
@@ -170,6 +175,39 @@ mod tests {
         assert_eq!(api_key, "token");
         assert_eq!(base_url, "https://claude.example");
     }
+
+    #[test]
+    fn find_duplicates_groups_same_base_url_and_key() {
+        let claude = |id: &str, base_url: &str, key: &str| {
+            Provider::with_id(
+                id.into(),
+                id.into(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": key, "ANTHROPIC_BASE_URL": base_url } }),
+                None,
+            )
+        };
+        let mut config = MultiAppConfig::default();
+        let manager = config.get_manager_mut(&AppType::Claude).unwrap();
+        for provider in [
+            claude("a", "https://relay.example/", "sk-secret"),
+            claude("b", "https://RELAY.example", "sk-secret"),
+            claude("c", "https://relay.example", "sk-other"),
+        ] {
+            manager.providers.insert(provider.id.clone(), provider);
+        }
+        let state = AppState::new(config);
+
+        let clusters = ProviderService::find_duplicates(&state, AppType::Claude).unwrap();
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(
+            clusters[0].provider_ids,
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(clusters[0].base_url, "https://relay.example");
+        let serialized = serde_json::to_string(&clusters).unwrap();
+        assert!(!serialized.contains("sk-secret"));
+    }
 }
 
 /// Gemini 认证类型枚举
@@ -575,9 +613,11 @@ impl ProviderService {
         Ok(result)
     }
 
-    fn restore_config_only(state: &AppState, snapshot: MultiAppConfig) -> Result<(), AppError> {
+    fn restore_config_only(state: &AppState, mut snapshot: MultiAppConfig) -> Result<(), AppError> {
         {
             let mut guard = state.config.write().map_err(AppError::from)?;
+            // 回滚内容但不回退修订号，避免与失败前已下发的修订号重复
+            snapshot.revision = guard.revision;
             *guard = snapshot;
         }
         state.save()
@@ -595,9 +635,9 @@ impl ProviderService {
     fn apply_post_commit(state: &AppState, action: &PostCommitAction) -> Result<(), AppError> {
         Self::write_live_snapshot(&action.app_type, &action.provider)?;
         if action.sync_mcp {
-            // 使用 v3.7.0 统一的 MCP 同步机制，支持所有应用
+            // 重写 live 可能覆盖其中的 MCP 配置，按统一结构重新同步该应用（调用方只持有该应用的 live 锁）
             use crate::services::mcp::McpService;
-            McpService::sync_all_enabled(state)?;
+            McpService::sync_app_enabled_locked(state, &action.app_type)?;
         }
         if action.refresh_snapshot {
             Self::refresh_provider_snapshot(state, &action.app_type, &action.provider.id)?;
@@ -839,6 +879,194 @@ impl ProviderService {
         Ok(manager.get_all_providers().clone())
     }
 
+    /// 获取单个供应商，不存在时返回 `provider.not_found`
+    pub fn get(state: &AppState, app_type: AppType, id: &str) -> Result<Provider, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        manager.providers.get(id).cloned().ok_or_else(|| {
+            AppError::localized(
+                "provider.not_found",
+                format!("供应商不存在: {id}"),
+                format!("Provider not found: {id}"),
+            )
+        })
+    }
+
+    /// 列出指定应用下已使用的供应商分组（去重并排序）
+    pub fn list_groups(state: &AppState, app_type: AppType) -> Result<Vec<String>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        let groups: BTreeSet<String> = manager
+            .providers
+            .values()
+            .filter_map(|provider| provider.group.as_deref())
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(groups.into_iter().collect())
+    }
+
+    /// 列出指定应用下已使用的供应商标签（去重并排序）
+    pub fn list_tags(state: &AppState, app_type: AppType) -> Result<Vec<String>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        let tags: BTreeSet<String> = manager
+            .providers
+            .values()
+            .flat_map(|provider| provider.tags.iter())
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(tags.into_iter().collect())
+    }
+
+    /// 获取应用最近的供应商切换记录（最新在前）
+    pub fn switch_history(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Vec<SwitchHistoryEntry>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+        Ok(manager.switch_history.clone())
+    }
+
+    /// 查找疑似重复的供应商：Base URL 与 API Key 指纹均相同的归为一组
+    ///
+    /// 指纹为加盐哈希的前缀，响应中不会出现原始密钥；无法提取凭证的供应商会被忽略。
+    pub fn find_duplicates(
+        state: &AppState,
+        app_type: AppType,
+    ) -> Result<Vec<DuplicateProviderCluster>, AppError> {
+        let config = state.config.read().map_err(AppError::from)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| Self::app_not_found(&app_type))?;
+
+        let mut clusters: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+        for (id, provider) in &manager.providers {
+            let Ok((api_key, base_url)) = Self::extract_credentials(provider, &app_type) else {
+                continue;
+            };
+            let base_url = normalize_base_url(&base_url);
+            if api_key.trim().is_empty() || base_url.is_empty() {
+                continue;
+            }
+            clusters
+                .entry((base_url, key_fingerprint(api_key.trim())))
+                .or_default()
+                .push(id.clone());
+        }
+
+        let mut duplicates: Vec<DuplicateProviderCluster> = clusters
+            .into_iter()
+            .filter(|(_, ids)| ids.len() > 1)
+            .map(|((base_url, key_fingerprint), mut provider_ids)| {
+                provider_ids.sort();
+                DuplicateProviderCluster {
+                    base_url,
+                    key_fingerprint,
+                    provider_ids,
+                }
+            })
+            .collect();
+        duplicates.sort_by(|a, b| a.provider_ids.cmp(&b.provider_ids));
+        Ok(duplicates)
+    }
+
+    /// 仅校验 settings 结构（Claude 对象 / Codex auth+config / Gemini env 等），不保存也不写 live
+    pub fn validate_settings(
+        app_type: &AppType,
+        provider_id: &str,
+        settings: Value,
+    ) -> SettingsValidation {
+        let provider = Provider::with_id(
+            provider_id.to_string(),
+            provider_id.to_string(),
+            settings,
+            None,
+        );
+        let errors = match Self::validate_provider_settings(app_type, &provider) {
+            Ok(()) => Vec::new(),
+            Err(err) => vec![err.to_string()],
+        };
+        SettingsValidation {
+            ok: errors.is_empty(),
+            errors,
+        }
+    }
+
+    /// 切换前预检：执行与写入 live 配置相同的校验（配置结构、必填字段、路径可写性），但不写入任何文件
+    pub fn preflight_switch(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+    ) -> Result<SwitchPreflight, AppError> {
+        let provider = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(&app_type)
+                .ok_or_else(|| Self::app_not_found(&app_type))?
+                .providers
+                .get(provider_id)
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+
+        let mut issues = Vec::new();
+        if let Err(err) = Self::validate_provider_settings(&app_type, &provider) {
+            issues.push(SwitchPreflightIssue {
+                kind: SwitchPreflightIssueKind::Settings,
+                message: err.to_string(),
+                path: None,
+            });
+        }
+        if let Some(paths) = crate::services::preflight::check_app(&app_type)? {
+            issues.extend(
+                paths
+                    .paths
+                    .into_iter()
+                    .filter(|status| !status.writable)
+                    .map(|status| SwitchPreflightIssue {
+                        kind: SwitchPreflightIssueKind::Path,
+                        message: status.reason.unwrap_or_else(|| "路径不可写".to_string()),
+                        path: Some(status.path),
+                    }),
+            );
+        }
+
+        Ok(SwitchPreflight {
+            ok: issues.is_empty(),
+            issues,
+        })
+    }
+
+    /// 切换时写入的主 live 配置文件路径（Claude settings.json / Codex config.toml / Gemini .env 等）
+    pub fn live_file_path(app_type: &AppType) -> Result<std::path::PathBuf, AppError> {
+        Ok(match app_type {
+            AppType::Claude => get_claude_settings_path()?,
+            AppType::Codex => get_codex_config_path()?,
+            AppType::Gemini => crate::gemini_config::get_gemini_env_path()?,
+            AppType::Opencode => crate::opencode_config::get_opencode_config_path(),
+            AppType::Omo => crate::omo_config::resolve_omo_config_path(),
+        })
+    }
+
     /// 获取当前供应商 ID
     pub fn current(state: &AppState, app_type: AppType) -> Result<String, AppError> {
         let config = state.config.read().map_err(AppError::from)?;
@@ -924,12 +1152,175 @@ impl ProviderService {
         })
     }
 
-    /// 更新供应商
-    pub fn update(
+    /// 将供应商复制到另一个应用，新供应商使用新的 ID
+    ///
+    /// 同一应用内原样复制；跨应用时各应用的配置结构不同，仅迁移 API Key 与 Base URL，
+    /// 并在返回结果中给出警告。无法提取凭证时返回错误。
+    pub fn copy_to_app(
+        state: &AppState,
+        from_app: AppType,
+        provider_id: &str,
+        to_app: AppType,
+    ) -> Result<ProviderCopyResult, AppError> {
+        let source = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(&from_app)
+                .ok_or_else(|| Self::app_not_found(&from_app))?
+                .providers
+                .get(provider_id)
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::localized(
+                        "provider.not_found",
+                        format!("供应商不存在: {provider_id}"),
+                        format!("Provider not found: {provider_id}"),
+                    )
+                })?
+        };
+
+        let mut warnings = Vec::new();
+        let (settings_config, meta) = if from_app == to_app {
+            (source.settings_config.clone(), source.meta.clone())
+        } else {
+            let (api_key, base_url) = Self::extract_credentials(&source, &from_app)?;
+            let settings =
+                Self::settings_from_credentials(&to_app, &source.name, &api_key, &base_url)?;
+            warnings.push(format!(
+                "{} 与 {} 的配置结构不同，仅复制了 API Key 与 Base URL，模型等其他设置需手动调整",
+                from_app.as_str(),
+                to_app.as_str()
+            ));
+            (settings, None)
+        };
+
+        let new_id = format!("{}-{}", source.id, Self::now_millis());
+        let provider = Provider {
+            id: new_id.clone(),
+            name: source.name,
+            settings_config,
+            website_url: source.website_url,
+            category: source.category,
+            group: source.group,
+            tags: source.tags,
+            created_at: Some(Self::now_millis()),
+            last_switched_at: None,
+            sort_index: None,
+            notes: source.notes,
+            meta,
+        };
+        Self::add(state, to_app, provider)?;
+
+        Ok(ProviderCopyResult {
+            id: new_id,
+            warnings,
+        })
+    }
+
+    /// 将当前 live 配置快照为一个新的命名供应商（不切换当前供应商）
+    ///
+    /// OpenCode 的 live 配置包含多个供应商，无法整体快照为单个供应商，因此不支持。
+    pub fn import_live_as_provider(
+        state: &AppState,
+        app_type: AppType,
+        name: &str,
+    ) -> Result<Provider, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::localized(
+                "provider.name.empty",
+                "供应商名称不能为空",
+                "Provider name is required",
+            ));
+        }
+        if matches!(app_type, AppType::Opencode) {
+            return Err(Self::app_not_supported(&app_type));
+        }
+
+        let mut settings_config = Self::read_live_settings(app_type.clone())?;
+        if matches!(app_type, AppType::Claude) {
+            let _ = Self::normalize_claude_models_in_value(&mut settings_config);
+        }
+
+        let mut provider = Provider::with_id(
+            format!("live-{}", Self::now_millis()),
+            name.to_string(),
+            settings_config,
+            None,
+        );
+        provider.category = Some("custom".to_string());
+        provider.created_at = Some(Self::now_millis());
+        Self::add(state, app_type, provider.clone())?;
+
+        Ok(provider)
+    }
+
+    /// 按目标应用的配置结构组装仅包含凭证的 settingsConfig
+    fn settings_from_credentials(
+        app_type: &AppType,
+        name: &str,
+        api_key: &str,
+        base_url: &str,
+    ) -> Result<Value, AppError> {
+        let base_url = base_url.trim().trim_end_matches('/');
+        match app_type {
+            AppType::Claude => Ok(json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": api_key,
+                    "ANTHROPIC_BASE_URL": base_url,
+                }
+            })),
+            AppType::Codex => {
+                let mut provider_key: String = name
+                    .to_lowercase()
+                    .chars()
+                    .map(|c| match c {
+                        'a'..='z' | '0'..='9' | '_' => c,
+                        _ => '_',
+                    })
+                    .collect();
+                provider_key = provider_key.trim_matches('_').to_string();
+                if provider_key.is_empty() {
+                    provider_key = "custom".to_string();
+                }
+                let config_toml = format!(
+                    r#"model_provider = "{provider_key}"
+
+[model_providers.{provider_key}]
+name = "{provider_key}"
+base_url = "{base_url}"
+wire_api = "responses"
+requires_openai_auth = true
+"#
+                );
+                Ok(json!({
+                    "auth": { "OPENAI_API_KEY": api_key },
+                    "config": config_toml,
+                }))
+            }
+            AppType::Gemini => Ok(json!({
+                "env": {
+                    "GEMINI_API_KEY": api_key,
+                    "GOOGLE_GEMINI_BASE_URL": base_url,
+                }
+            })),
+            AppType::Opencode => Ok(json!({
+                "options": {
+                    "apiKey": api_key,
+                    "baseURL": base_url,
+                }
+            })),
+            AppType::Omo => Err(Self::app_not_supported(app_type)),
+        }
+    }
+
+    /// 更新供应商（更新当前供应商时会重写 live，因此持有该应用的 live 锁）
+    pub async fn update(
         state: &AppState,
         app_type: AppType,
         provider: Provider,
     ) -> Result<bool, AppError> {
+        let _live = state.lock_live(&app_type).await;
         let mut provider = provider;
         // 归一化 Claude 模型键
         Self::normalize_provider_if_claude(&app_type, &mut provider);
@@ -1463,22 +1854,25 @@ impl ProviderService {
     /// 执行用量脚本并格式化结果（私有辅助方法）
     async fn execute_and_format_usage_result(
         script_code: &str,
-        api_key: &str,
-        base_url: &str,
+        values: &usage_script::TemplateValues<'_>,
         timeout: u64,
-        access_token: Option<&str>,
-        user_id: Option<&str>,
+        capture_headers: bool,
     ) -> Result<UsageResult, AppError> {
-        match usage_script::execute_usage_script(
-            script_code,
-            api_key,
-            base_url,
-            timeout,
-            access_token,
-            user_id,
-        )
-        .await
-        {
+        let mut headers = BTreeMap::new();
+        let outcome = if capture_headers {
+            usage_script::execute_usage_script_capturing_headers(
+                script_code,
+                values,
+                timeout,
+                &mut headers,
+            )
+            .await
+        } else {
+            usage_script::execute_usage_script(script_code, values, timeout).await
+        };
+        let response_headers = capture_headers.then_some(headers);
+
+        match outcome {
             Ok(data) => {
                 let usage_list: Vec<UsageData> = if data.is_array() {
                     serde_json::from_value(data).map_err(|e| {
@@ -1503,6 +1897,7 @@ impl ProviderService {
                     success: true,
                     data: Some(usage_list),
                     error: None,
+                    response_headers,
                 })
             }
             Err(err) => {
@@ -1525,18 +1920,46 @@ impl ProviderService {
                     success: false,
                     data: None,
                     error: Some(msg),
+                    response_headers,
                 })
             }
         }
     }
 
+    /// 从供应商配置中读取主模型（用于用量脚本的 `{{model}}`）
+    fn provider_model(app_type: &AppType, provider: &Provider) -> Option<String> {
+        let settings = &provider.settings_config;
+        let model = match app_type {
+            AppType::Claude => settings
+                .pointer("/env/ANTHROPIC_MODEL")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            AppType::Gemini => settings
+                .pointer("/env/GEMINI_MODEL")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            AppType::Codex => settings
+                .get("config")
+                .and_then(Value::as_str)
+                .and_then(|text| toml::from_str::<toml::Table>(text).ok())
+                .and_then(|table| {
+                    table
+                        .get("model")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                }),
+            AppType::Opencode | AppType::Omo => None,
+        };
+        model.filter(|m| !m.trim().is_empty())
+    }
+
     /// 查询供应商用量（使用已保存的脚本配置）
     pub async fn query_usage(
         state: &AppState,
         app_type: AppType,
         provider_id: &str,
     ) -> Result<UsageResult, AppError> {
-        let (script_code, timeout, api_key, base_url, access_token, user_id) = {
+        let (script_code, timeout, api_key, base_url, access_token, user_id, name, model) = {
             let config = state.config.read().map_err(AppError::from)?;
             let manager = config
                 .get_manager(&app_type)
@@ -1576,26 +1999,28 @@ impl ProviderService {
                 usage_script.base_url.clone().unwrap_or_default(),
                 usage_script.access_token.clone(),
                 usage_script.user_id.clone(),
+                provider.name.clone(),
+                Self::provider_model(&app_type, &provider),
             )
         };
 
-        Self::execute_and_format_usage_result(
-            &script_code,
-            &api_key,
-            &base_url,
-            timeout,
-            access_token.as_deref(),
-            user_id.as_deref(),
-        )
-        .await
+        let values = usage_script::TemplateValues {
+            api_key: &api_key,
+            base_url: &base_url,
+            access_token: access_token.as_deref(),
+            user_id: user_id.as_deref(),
+            provider_name: Some(&name),
+            model: model.as_deref(),
+        };
+        Self::execute_and_format_usage_result(&script_code, &values, timeout, false).await
     }
 
     /// 测试用量脚本（使用临时脚本内容，不保存）
     #[allow(clippy::too_many_arguments)]
     pub async fn test_usage_script(
-        _state: &AppState,
-        _app_type: AppType,
-        _provider_id: &str,
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
         script_code: &str,
         timeout: u64,
         api_key: Option<&str>,
@@ -1603,20 +2028,85 @@ impl ProviderService {
         access_token: Option<&str>,
         user_id: Option<&str>,
     ) -> Result<UsageResult, AppError> {
+        // 供应商名称与模型取自已保存的配置（新建中的供应商可能尚未保存，此时保留占位符）
+        let (name, model) = {
+            let config = state.config.read().map_err(AppError::from)?;
+            config
+                .get_manager(&app_type)
+                .and_then(|manager| manager.providers.get(provider_id))
+                .map(|provider| {
+                    (
+                        Some(provider.name.clone()),
+                        Self::provider_model(&app_type, provider),
+                    )
+                })
+                .unwrap_or_default()
+        };
+
         // 直接使用传入的凭证参数进行测试
-        Self::execute_and_format_usage_result(
-            script_code,
-            api_key.unwrap_or(""),
-            base_url.unwrap_or(""),
-            timeout,
+        let values = usage_script::TemplateValues {
+            api_key: api_key.unwrap_or(""),
+            base_url: base_url.unwrap_or(""),
             access_token,
             user_id,
-        )
-        .await
+            provider_name: name.as_deref(),
+            model: model.as_deref(),
+        };
+        let result =
+            Self::execute_and_format_usage_result(script_code, &values, timeout, true).await;
+
+        let (success, error) = match &result {
+            Ok(usage) => (usage.success, usage.error.clone()),
+            Err(err) => (false, Some(err.to_string())),
+        };
+        Self::record_usage_test(
+            state,
+            &app_type,
+            provider_id,
+            UsageTestRecord {
+                tested_at: Self::now_millis(),
+                success,
+                error,
+            },
+        );
+        result
+    }
+
+    fn record_usage_test(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+        record: UsageTestRecord,
+    ) {
+        let mut records = state.usage_tests();
+        let history = records
+            .entry((app_type.as_str().to_string(), provider_id.to_string()))
+            .or_default();
+        history.insert(0, record);
+        history.truncate(MAX_USAGE_TEST_HISTORY);
+    }
+
+    /// 获取最近的用量脚本测试结果（最新在前）
+    pub fn usage_test_history(
+        state: &AppState,
+        app_type: &AppType,
+        provider_id: &str,
+    ) -> Vec<UsageTestRecord> {
+        state
+            .usage_tests()
+            .get(&(app_type.as_str().to_string(), provider_id.to_string()))
+            .cloned()
+            .unwrap_or_default()
     }
 
     /// 切换指定应用的供应商
-    pub fn switch(state: &AppState, app_type: AppType, provider_id: &str) -> Result<(), AppError> {
+    pub async fn switch(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+    ) -> Result<(), AppError> {
+        // 读取旧 live、写入新 live 与回填快照需整体串行，避免同一应用的并发切换交错写文件
+        let _live = state.lock_live(&app_type).await;
         let app_type_clone = app_type.clone();
         let provider_id_owned = provider_id.to_string();
 
@@ -1629,6 +2119,9 @@ impl ProviderService {
                 AppType::Opencode => Self::prepare_switch_opencode(config, &provider_id_owned)?,
                 AppType::Omo => Self::prepare_switch_omo(config, &provider_id_owned)?,
             };
+            if let Some(manager) = config.get_manager_mut(&app_type_clone) {
+                manager.record_switch(&provider_id_owned, Self::now_millis());
+            }
 
             let action = PostCommitAction {
                 app_type: app_type_clone.clone(),
@@ -1773,6 +2266,10 @@ impl ProviderService {
                     format!("Provider not found: {provider_id}"),
                 )
             })?;
+        // 回填当前供应商之前先校验目标配置，避免校验失败时已改动内存状态（Google 官方使用 OAuth，无需 API Key）
+        if Self::detect_gemini_auth_type(&provider) != GeminiAuthType::GoogleOfficial {
+            crate::gemini_config::validate_gemini_settings_strict(&provider.settings_config)?;
+        }
 
         Self::backfill_gemini_current(config, provider_id)?;
 
@@ -2177,6 +2674,31 @@ impl ProviderService {
             }
         }
 
+        Self::validate_tags(&provider.tags)?;
+
+        Ok(())
+    }
+
+    /// 验证标签：不能为空白，且（忽略首尾空白后）不能重复
+    fn validate_tags(tags: &[String]) -> Result<(), AppError> {
+        let mut seen = BTreeSet::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                return Err(AppError::localized(
+                    "provider.tags.empty",
+                    "标签不能为空",
+                    "Tags cannot be empty",
+                ));
+            }
+            if !seen.insert(tag) {
+                return Err(AppError::localized(
+                    "provider.tags.duplicate",
+                    format!("标签重复: {tag}"),
+                    format!("Duplicate tag: {tag}"),
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -2200,7 +2722,6 @@ impl ProviderService {
         Ok(())
     }
 
-    #[allow(dead_code)]
     fn extract_credentials(
         provider: &Provider,
         app_type: &AppType,
@@ -2468,6 +2989,95 @@ impl ProviderService {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SwitchPreflightIssueKind {
+    Settings,
+    Path,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchPreflightIssue {
+    pub kind: SwitchPreflightIssueKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// 切换前预检结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchPreflight {
+    pub ok: bool,
+    pub issues: Vec<SwitchPreflightIssue>,
+}
+
+/// 切换结果：附带本次写入的 live 配置文件路径，便于排查“切换后不生效”
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchResult {
+    pub success: bool,
+    pub live_file_path: String,
+}
+
+/// 供应商 settings 校验结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsValidation {
+    pub ok: bool,
+    pub errors: Vec<String>,
+}
+
+/// 跨应用复制供应商的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCopyResult {
+    pub id: String,
+    pub warnings: Vec<String>,
+}
+
+/// 一组疑似重复的供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateProviderCluster {
+    pub base_url: String,
+    pub key_fingerprint: String,
+    pub provider_ids: Vec<String>,
+}
+
+fn normalize_base_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// 进程级随机盐：指纹仅用于同一进程内比对，重启后即失效，无法离线反推密钥
+fn fingerprint_salt() -> &'static [u8; 16] {
+    use std::hash::{BuildHasher, Hasher};
+
+    static SALT: OnceLock<[u8; 16]> = OnceLock::new();
+    SALT.get_or_init(|| {
+        let mut salt = [0u8; 16];
+        for chunk in salt.chunks_mut(8) {
+            let random = std::collections::hash_map::RandomState::new()
+                .build_hasher()
+                .finish();
+            chunk.copy_from_slice(&random.to_le_bytes());
+        }
+        salt
+    })
+}
+
+fn key_fingerprint(api_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(fingerprint_salt());
+    hasher.update(api_key.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ProviderSortUpdate {
     pub id: String,
@@ -1,22 +1,35 @@
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
+use futures::future::{AbortHandle, Abortable, Aborted};
 use futures::StreamExt;
 use reqwest::{header, Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::io::{ErrorKind, Read};
 use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::time::timeout;
 
 use crate::app_config::AppType;
 use crate::config::{get_app_config_dir, get_home_dir, write_json_file};
 use crate::error::format_skill_error;
+use crate::store::{BackgroundTaskKind, TaskRegistry};
 
-const MAX_SKILL_SCAN_DEPTH: usize = 32;
+const DEFAULT_MAX_SKILL_SCAN_DEPTH: usize = 32;
+/// SKILLS_MAX_SCAN_DEPTH 的上限，防止配置过大导致深层递归
+const MAX_SKILL_SCAN_DEPTH_CEILING: usize = 256;
 const DEFAULT_SKILL_CACHE_TTL_SECS: u64 = 0;
+const DEFAULT_SKILLS_LIST_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_SKILLS_INSTALL_TIMEOUT_SECS: u64 = 180;
+const DEFAULT_ARCHIVE_BASE_URL: &str = "https://github.com";
+/// 仓库文件清单的内存缓存时长
+const SKILL_MANIFEST_CACHE_TTL_SECS: u64 = 60;
+const DEFAULT_MAX_CONCURRENT_INSTALLS: usize = 2;
+const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 4;
 const DEFAULT_MAX_ZIP_BYTES: u64 = 50 * 1024 * 1024;
 const DEFAULT_MAX_ZIP_ENTRIES: usize = 20_000;
 const DEFAULT_MAX_TOTAL_UNCOMPRESSED_BYTES: u64 = 500 * 1024 * 1024;
@@ -25,6 +38,9 @@ const DEFAULT_MAX_COMPRESSION_RATIO: u64 = 200;
 const DEFAULT_MAX_PATH_COMPONENTS: usize = 64;
 const DEFAULT_MAX_PATH_LENGTH: usize = 240;
 
+/// skills-cache.json 的读-改-写锁（列表请求与后台刷新共用）
+static REPO_CACHE_FILE_LOCK: Mutex<()> = Mutex::new(());
+
 /// 技能对象
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -63,12 +79,22 @@ pub struct Skill {
     /// 分支名称
     #[serde(rename = "repoBranch")]
     pub repo_branch: Option<String>,
+    /// 固定的提交 SHA 或标签（若仓库设置了 ref）
+    #[serde(rename = "repoRef", default, skip_serializing_if = "Option::is_none")]
+    pub repo_ref: Option<String>,
     /// 技能所在的子目录路径 (可选, 如 "skills")
     #[serde(rename = "skillsPath")]
     pub skills_path: Option<String>,
     /// workflows 中的命令
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub commands: Vec<SkillCommand>,
+    /// 当前应用的安装记录（来源仓库、ref 等），未记录时为空
+    #[serde(
+        rename = "installState",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub install_state: Option<SkillState>,
 }
 
 /// 技能 workflows 命令
@@ -92,11 +118,34 @@ pub struct SkillRepo {
     pub name: String,
     /// 分支 (默认 "main")
     pub branch: String,
+    /// 固定的提交 SHA 或标签 (可选，优先于 branch，用于可复现安装)
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
     /// 是否启用
     pub enabled: bool,
     /// 技能所在的子目录路径 (可选, 如 "skills", "my-skills/subdir")
     #[serde(rename = "skillsPath")]
     pub skills_path: Option<String>,
+    /// 扫描时跳过的目录 glob（相对扫描目录，如 "examples", "tests/**"），与仓库根目录的 `.skillignore` 合并
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+/// 仓库归档中的单个文件条目（路径已去除公共根目录）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillManifestEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+/// 仓库归档的文件清单
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillRepoManifest {
+    /// 实际下载的 ref
+    pub resolved_ref: String,
+    pub entries: Vec<SkillManifestEntry>,
 }
 
 /// 技能安装状态
@@ -107,6 +156,59 @@ pub struct SkillState {
     /// 安装时间
     #[serde(rename = "installedAt")]
     pub installed_at: DateTime<Utc>,
+    /// 安装时实际下载的 ref（固定的 SHA/标签，或命中的分支名）
+    #[serde(
+        rename = "resolvedRef",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub resolved_ref: Option<String>,
+    /// 安装来源仓库所有者（本地技能或旧版本记录为空）
+    #[serde(rename = "repoOwner", default, skip_serializing_if = "Option::is_none")]
+    pub repo_owner: Option<String>,
+    /// 安装来源仓库名称
+    #[serde(rename = "repoName", default, skip_serializing_if = "Option::is_none")]
+    pub repo_name: Option<String>,
+    /// 安装时请求的 ref（仓库固定的 SHA/标签，否则为分支名）
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+}
+
+impl SkillState {
+    /// 记录一次发生在 `installed_at` 的安装：实际下载时来源取自解析到的技能；
+    /// 跳过下载（已安装）时沿用之前记录的 ref 与来源
+    pub fn for_install(
+        skill: &Skill,
+        resolved_ref: Option<String>,
+        previous: Option<&SkillState>,
+        installed_at: DateTime<Utc>,
+    ) -> Self {
+        let previous_source = previous
+            .filter(|state| resolved_ref.is_none() && state.repo_owner.is_some())
+            .map(|state| {
+                (
+                    state.repo_owner.clone(),
+                    state.repo_name.clone(),
+                    state.git_ref.clone(),
+                )
+            });
+        let (repo_owner, repo_name, git_ref) = previous_source.unwrap_or_else(|| {
+            (
+                skill.repo_owner.clone(),
+                skill.repo_name.clone(),
+                skill.repo_ref.clone().or_else(|| skill.repo_branch.clone()),
+            )
+        });
+        Self {
+            installed: true,
+            installed_at,
+            resolved_ref: resolved_ref
+                .or_else(|| previous.and_then(|state| state.resolved_ref.clone())),
+            repo_owner,
+            repo_name,
+            git_ref,
+        }
+    }
 }
 
 /// 仓库技能缓存
@@ -126,6 +228,20 @@ pub struct SkillRepoCache {
     pub last_modified: Option<String>,
 }
 
+/// 仓库缓存概览（供管理接口展示）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillRepoCacheSummary {
+    /// 缓存键 `owner/name/ref[:skillsPath]`
+    pub key: String,
+    pub fetched_at: DateTime<Utc>,
+    pub skill_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
 /// 缓存存储结构
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SkillCacheStore {
@@ -157,6 +273,42 @@ pub struct SkillListResult {
     pub refreshing: bool,
 }
 
+/// 已安装技能校验报告
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillVerifyReport {
+    /// 缺少可解析 SKILL.md 的目录（疑似中断或不完整的安装）
+    pub broken: Vec<String>,
+    /// 状态中记录为已安装、但磁盘上不存在的目录
+    pub missing_on_disk: Vec<String>,
+    /// 磁盘上存在有效技能、但状态中没有记录的目录
+    pub untracked: Vec<String>,
+    /// repair 模式下删除的目录
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_dirs: Vec<String>,
+    /// repair 模式下清理的状态记录（state key）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_states: Vec<String>,
+    /// 仍记录为已安装的目录及其安装来源
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub install_states: BTreeMap<String, SkillState>,
+}
+
+impl SkillRepo {
+    /// 固定的 ref（去除空白后非空时生效）
+    pub fn pinned_ref(&self) -> Option<&str> {
+        self.git_ref
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 实际用于下载与链接的 ref：固定 ref 优先，否则使用分支
+    pub fn effective_ref(&self) -> &str {
+        self.pinned_ref().unwrap_or(self.branch.as_str())
+    }
+}
+
 impl Default for SkillStore {
     fn default() -> Self {
         SkillStore {
@@ -166,22 +318,28 @@ impl Default for SkillStore {
                     owner: "ComposioHQ".to_string(),
                     name: "awesome-claude-skills".to_string(),
                     branch: "master".to_string(),
+                    git_ref: None,
                     enabled: true,
                     skills_path: None, // 扫描根目录
+                    exclude: Vec::new(),
                 },
                 SkillRepo {
                     owner: "anthropics".to_string(),
                     name: "skills".to_string(),
                     branch: "main".to_string(),
+                    git_ref: None,
                     enabled: true,
                     skills_path: None, // 扫描根目录
+                    exclude: Vec::new(),
                 },
                 SkillRepo {
                     owner: "cexll".to_string(),
                     name: "myclaude".to_string(),
                     branch: "master".to_string(),
+                    git_ref: None,
                     enabled: true,
                     skills_path: Some("skills".to_string()), // 扫描 skills 子目录
+                    exclude: Vec::new(),
                 },
             ],
             repo_cache: HashMap::new(),
@@ -202,10 +360,38 @@ struct WorkflowMetadata {
     pub description: Option<String>,
 }
 
+#[derive(Clone)]
 pub struct SkillService {
     http_client: Client,
     install_dir: PathBuf,
     app: AppType,
+    /// 安装（下载 + 解压 + 复制）并发许可，进程内共享
+    install_permits: Arc<Semaphore>,
+    /// 列表刷新时仓库下载的并发许可，与安装分开计数
+    fetch_permits: Arc<Semaphore>,
+    /// 后台任务注册表（来自 `AppState`）
+    tasks: Arc<TaskRegistry>,
+}
+
+/// 进行中安装的取消控制
+struct InstallControl {
+    abort: AbortHandle,
+    /// 已开始复制文件：此后取消不再生效
+    copying: bool,
+}
+
+/// 安装结束（完成、失败、取消或请求被丢弃）时注销进行中的安装
+struct InFlightInstall {
+    key: String,
+}
+
+impl Drop for InFlightInstall {
+    fn drop(&mut self) {
+        SkillService::in_flight_installs()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.key);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -227,6 +413,7 @@ struct ZipLimits {
 
 struct DownloadedRepo {
     temp_dir: tempfile::TempDir,
+    resolved_ref: String,
     etag: Option<String>,
     last_modified: Option<String>,
 }
@@ -244,6 +431,21 @@ enum RepoDownloadResult {
     NotModified,
 }
 
+/// 单个仓库递归扫描时不变的上下文
+#[derive(Clone, Copy)]
+struct RepoScan<'a> {
+    repo: &'a SkillRepo,
+    normalized_skills_path: Option<&'a str>,
+    excludes: &'a [String],
+}
+
+/// 仓库后台刷新状态：进行中的缓存键与最近一次失败原因
+#[derive(Default)]
+struct RepoRefreshState {
+    in_flight: HashSet<String>,
+    failures: HashMap<String, String>,
+}
+
 enum RepoFetchOutcome {
     Updated {
         skills: Vec<Skill>,
@@ -254,11 +456,11 @@ enum RepoFetchOutcome {
 }
 
 impl SkillService {
-    pub fn new() -> Result<Self> {
-        Self::new_for_app(&AppType::Claude)
+    pub fn new(tasks: Arc<TaskRegistry>) -> Result<Self> {
+        Self::new_for_app(&AppType::Claude, tasks)
     }
 
-    pub fn new_for_app(app: &AppType) -> Result<Self> {
+    pub fn new_for_app(app: &AppType, tasks: Arc<TaskRegistry>) -> Result<Self> {
         let install_dir = Self::get_install_dir_for_app(app)?;
 
         // 确保目录存在
@@ -274,9 +476,56 @@ impl SkillService {
             http_client,
             install_dir,
             app: app.clone(),
+            install_permits: Self::shared_install_permits(),
+            fetch_permits: Self::shared_fetch_permits(),
+            tasks,
         })
     }
 
+    /// 进程级共享的安装许可：每次请求都会新建 SkillService，因此信号量不能挂在实例上
+    fn shared_install_permits() -> Arc<Semaphore> {
+        static PERMITS: OnceLock<Arc<Semaphore>> = OnceLock::new();
+        PERMITS
+            .get_or_init(|| {
+                let limit = Self::parse_env_usize(
+                    "CC_SWITCH_SKILLS_MAX_CONCURRENT_INSTALLS",
+                    DEFAULT_MAX_CONCURRENT_INSTALLS,
+                );
+                Arc::new(Semaphore::new(limit.max(1)))
+            })
+            .clone()
+    }
+
+    fn shared_fetch_permits() -> Arc<Semaphore> {
+        static PERMITS: OnceLock<Arc<Semaphore>> = OnceLock::new();
+        PERMITS
+            .get_or_init(|| {
+                let limit = Self::parse_env_usize(
+                    "CC_SWITCH_SKILLS_MAX_CONCURRENT_FETCHES",
+                    DEFAULT_MAX_CONCURRENT_FETCHES,
+                );
+                Arc::new(Semaphore::new(limit.max(1)))
+            })
+            .clone()
+    }
+
+    /// 获取安装许可；超出上限的调用会在此排队等待
+    async fn acquire_install_permit(&self) -> Result<OwnedSemaphorePermit> {
+        self.install_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| anyhow!("技能安装队列已关闭: {e}"))
+    }
+
+    async fn acquire_fetch_permit(&self) -> Result<OwnedSemaphorePermit> {
+        self.fetch_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| anyhow!("技能仓库下载队列已关闭: {e}"))
+    }
+
     fn get_install_dir_for_app(app: &AppType) -> Result<PathBuf> {
         let home = get_home_dir().context(format_skill_error(
             "GET_HOME_DIR_FAILED",
@@ -305,6 +554,19 @@ impl SkillService {
         format!("{}:{directory}", app.as_str())
     }
 
+    /// 为列表中的技能附上当前应用的安装记录
+    pub fn attach_install_states(
+        &self,
+        skills: &mut [Skill],
+        states: &HashMap<String, SkillState>,
+    ) {
+        for skill in skills {
+            skill.install_state = states
+                .get(&Self::state_key(&self.app, &skill.directory))
+                .cloned();
+        }
+    }
+
     fn installed_apps_for_directory(directory: &str) -> Vec<String> {
         [
             AppType::Claude,
@@ -469,12 +731,13 @@ impl SkillService {
             .trim()
             .trim_matches(|c| c == '/' || c == '\\')
             .replace('\\', "/");
+        let git_ref = repo.effective_ref();
         if normalized_path.is_empty() {
-            format!("{}/{}/{}", repo.owner, repo.name, repo.branch)
+            format!("{}/{}/{}", repo.owner, repo.name, git_ref)
         } else {
             format!(
                 "{}/{}/{}:{}",
-                repo.owner, repo.name, repo.branch, normalized_path
+                repo.owner, repo.name, git_ref, normalized_path
             )
         }
     }
@@ -499,6 +762,47 @@ impl SkillService {
         }
     }
 
+    /// 列表加载单个仓库的超时，可通过 SKILLS_LIST_TIMEOUT_SECS 调整
+    fn list_timeout() -> Duration {
+        Duration::from_secs(Self::parse_env_u64(
+            "SKILLS_LIST_TIMEOUT_SECS",
+            DEFAULT_SKILLS_LIST_TIMEOUT_SECS,
+        ))
+    }
+
+    /// 安装时下载仓库的超时，可通过 SKILLS_INSTALL_TIMEOUT_SECS 调整
+    fn install_timeout() -> Duration {
+        Duration::from_secs(Self::parse_env_u64(
+            "SKILLS_INSTALL_TIMEOUT_SECS",
+            DEFAULT_SKILLS_INSTALL_TIMEOUT_SECS,
+        ))
+    }
+
+    async fn download_with_timeout<T>(
+        repo: &SkillRepo,
+        limit: Duration,
+        download: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let secs = limit.as_secs().to_string();
+        timeout(limit, download).await.map_err(|_| {
+            anyhow!(format_skill_error(
+                "DOWNLOAD_TIMEOUT",
+                &[
+                    ("owner", &repo.owner),
+                    ("name", &repo.name),
+                    ("timeout", &secs)
+                ],
+                Some("checkNetwork"),
+            ))
+        })?
+    }
+
+    /// 技能扫描的最大递归深度，可通过 SKILLS_MAX_SCAN_DEPTH 调整（限制在 1..=256）
+    fn max_scan_depth() -> usize {
+        Self::parse_env_usize("SKILLS_MAX_SCAN_DEPTH", DEFAULT_MAX_SKILL_SCAN_DEPTH)
+            .clamp(1, MAX_SKILL_SCAN_DEPTH_CEILING)
+    }
+
     fn parse_env_usize(name: &str, default: usize) -> usize {
         let raw = match env::var(name) {
             Ok(value) => value,
@@ -623,6 +927,133 @@ impl SkillService {
         }
     }
 
+    /// 将条目合并写入 skills-cache.json（读-改-写期间持锁，避免与后台刷新互相覆盖），返回合并后的内容
+    fn persist_repo_cache(&self, entries: &HashMap<String, SkillRepoCache>) -> SkillCacheStore {
+        let _guard = REPO_CACHE_FILE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut cache_store = self.load_repo_cache();
+        if Self::merge_repo_cache(&mut cache_store, entries) {
+            self.save_repo_cache(&cache_store);
+        }
+        cache_store
+    }
+
+    fn repo_refresh_state() -> &'static Mutex<RepoRefreshState> {
+        static STATE: OnceLock<Mutex<RepoRefreshState>> = OnceLock::new();
+        STATE.get_or_init(|| Mutex::new(RepoRefreshState::default()))
+    }
+
+    /// 取出某仓库最近一次后台刷新失败的原因（取出后清除）
+    fn take_refresh_failure(cache_key: &str) -> Option<String> {
+        Self::repo_refresh_state()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .failures
+            .remove(cache_key)
+    }
+
+    /// 在后台执行刷新任务；同一缓存键同时只允许一个任务，已有任务在跑时返回 false
+    fn spawn_repo_refresh<F>(&self, cache_key: String, task: F) -> bool
+    where
+        F: std::future::Future<Output = std::result::Result<(), String>> + Send + 'static,
+    {
+        {
+            let mut state = Self::repo_refresh_state()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if !state.in_flight.insert(cache_key.clone()) {
+                return false;
+            }
+        }
+
+        let registered = self
+            .tasks
+            .register(BackgroundTaskKind::SkillRepoRefresh, cache_key.clone());
+        tokio::spawn(async move {
+            let result = task.await;
+            drop(registered);
+            let mut state = Self::repo_refresh_state()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.in_flight.remove(&cache_key);
+            match result {
+                Ok(()) => {
+                    state.failures.remove(&cache_key);
+                }
+                Err(error) => {
+                    state.failures.insert(cache_key, error);
+                }
+            }
+        });
+        true
+    }
+
+    /// 后台重新下载仓库并写回缓存，下一次列表请求即可拿到新数据
+    fn spawn_background_refresh(
+        &self,
+        repo: SkillRepo,
+        cache_key: String,
+        cached_entry: Option<SkillRepoCache>,
+    ) {
+        let service = self.clone();
+        let task_key = cache_key.clone();
+        self.spawn_repo_refresh(task_key, async move {
+            let entry = match service
+                .fetch_repo_skills_with_cache(&repo, cached_entry.as_ref())
+                .await
+            {
+                Ok(RepoFetchOutcome::Updated {
+                    skills,
+                    etag,
+                    last_modified,
+                }) => SkillRepoCache {
+                    fetched_at: Utc::now(),
+                    skills,
+                    etag,
+                    last_modified,
+                },
+                Ok(RepoFetchOutcome::NotModified) => match cached_entry {
+                    Some(mut entry) => {
+                        entry.fetched_at = Utc::now();
+                        entry
+                    }
+                    None => {
+                        let warning =
+                            format!("仓库 {}/{} 返回 304，但本地没有缓存", repo.owner, repo.name);
+                        log::warn!("{warning}");
+                        return Err(warning);
+                    }
+                },
+                Err(e) => {
+                    log::warn!("后台刷新仓库 {}/{} 失败: {}", repo.owner, repo.name, e);
+                    return Err(e.to_string());
+                }
+            };
+            service.persist_repo_cache(&HashMap::from([(cache_key, entry)]));
+            Ok(())
+        });
+    }
+
+    /// 将配置中的仓库缓存合并进缓存文件内容（保留较新的条目），返回是否有更新
+    fn merge_repo_cache(
+        cache_store: &mut SkillCacheStore,
+        repo_cache: &HashMap<String, SkillRepoCache>,
+    ) -> bool {
+        let mut cache_updated = false;
+        for (key, entry) in repo_cache.iter() {
+            let should_replace = match cache_store.repos.get(key) {
+                None => true,
+                Some(existing) => entry.fetched_at > existing.fetched_at,
+            };
+            if should_replace {
+                cache_store.repos.insert(key.clone(), entry.clone());
+                cache_updated = true;
+            }
+        }
+        cache_updated
+    }
+
     /// 列出所有技能
     pub async fn list_skills(
         &self,
@@ -632,121 +1063,120 @@ impl SkillService {
         let mut skills = Vec::new();
         let mut warnings = Vec::new();
         let mut cache_store = self.load_repo_cache();
-        let mut cache_updated = false;
-
-        if !repo_cache.is_empty() {
-            for (key, entry) in repo_cache.iter() {
-                let should_replace = match cache_store.repos.get(key) {
-                    None => true,
-                    Some(existing) => entry.fetched_at > existing.fetched_at,
-                };
-                if should_replace {
-                    cache_store.repos.insert(key.clone(), entry.clone());
-                    cache_updated = true;
-                }
-            }
-        }
+        let cache_updated = Self::merge_repo_cache(&mut cache_store, repo_cache);
 
-        // 仅使用启用的仓库，并行获取技能列表，避免单个无效仓库拖慢整体刷新
-        let enabled_repos: Vec<SkillRepo> = repos.into_iter().filter(|repo| repo.enabled).collect();
-        let mut fetch_tasks = Vec::new();
-
-        for repo in enabled_repos.iter().cloned() {
+        // stale-while-revalidate：立即返回已有缓存，过期或缺失的仓库交给后台刷新，
+        // 避免冷缓存时请求被整个下载过程阻塞
+        let mut cache_hit = true;
+        let mut refreshing = false;
+        for repo in repos.into_iter().filter(|repo| repo.enabled) {
             let cache_key = Self::cache_key(&repo);
             let cached_entry = cache_store.repos.get(&cache_key).cloned();
 
-            if let Some(entry) = cached_entry.as_ref() {
-                if Self::is_cache_fresh(entry.fetched_at) {
+            if let Some(error) = Self::take_refresh_failure(&cache_key) {
+                let warning = if cached_entry.is_some() {
+                    format!(
+                        "获取仓库 {}/{} 失败: {}，使用缓存",
+                        repo.owner, repo.name, error
+                    )
+                } else {
+                    format!("获取仓库 {}/{} 失败: {}", repo.owner, repo.name, error)
+                };
+                warnings.push(warning);
+            }
+
+            match cached_entry.as_ref() {
+                Some(entry) => {
                     skills.extend(entry.skills.clone());
-                    continue;
+                    if Self::is_cache_fresh(entry.fetched_at) {
+                        continue;
+                    }
                 }
+                None => cache_hit = false,
             }
 
-            fetch_tasks.push(async move {
-                let result = self
-                    .fetch_repo_skills_with_cache(&repo, cached_entry.as_ref())
-                    .await;
-                (repo, cache_key, cached_entry, result)
-            });
+            refreshing = true;
+            self.spawn_background_refresh(repo, cache_key, cached_entry);
         }
 
-        let refreshing = !fetch_tasks.is_empty();
-        let cache_hit = !refreshing;
+        if cache_updated {
+            cache_store = self.persist_repo_cache(&cache_store.repos);
+        }
 
-        let results: Vec<(
-            SkillRepo,
-            String,
-            Option<SkillRepoCache>,
-            Result<RepoFetchOutcome>,
-        )> = futures::future::join_all(fetch_tasks).await;
+        repo_cache.clear();
+        repo_cache.extend(cache_store.repos.clone());
 
-        for (repo, cache_key, cached_entry, result) in results {
-            match result {
-                Ok(outcome) => match outcome {
-                    RepoFetchOutcome::Updated {
-                        skills: repo_skills,
-                        etag,
-                        last_modified,
-                    } => {
-                        let fetched_at = Utc::now();
-                        skills.extend(repo_skills.clone());
-                        cache_store.repos.insert(
-                            cache_key,
-                            SkillRepoCache {
-                                fetched_at,
-                                skills: repo_skills,
-                                etag,
-                                last_modified,
-                            },
-                        );
-                        cache_updated = true;
-                    }
-                    RepoFetchOutcome::NotModified => {
-                        if let Some(mut entry) = cached_entry {
-                            entry.fetched_at = Utc::now();
-                            skills.extend(entry.skills.clone());
-                            cache_store.repos.insert(cache_key, entry);
-                            cache_updated = true;
-                        } else {
-                            let warning = format!(
-                                "仓库 {}/{} 返回 304，但本地没有缓存",
-                                repo.owner, repo.name
-                            );
-                            log::warn!("{warning}");
-                            warnings.push(warning);
-                        }
-                    }
-                },
+        self.finish_skill_list(&mut skills)?;
+
+        Ok(SkillListResult {
+            skills,
+            warnings,
+            cache_hit,
+            refreshing,
+        })
+    }
+
+    /// 解析安装目标用的技能列表：没有缓存的仓库先同步下载，
+    /// 不能像列表接口那样交给后台刷新，否则冷缓存时会找不到要安装的技能
+    pub async fn list_skills_for_install(
+        &self,
+        repos: Vec<SkillRepo>,
+        repo_cache: &mut HashMap<String, SkillRepoCache>,
+    ) -> Result<Vec<Skill>> {
+        let mut skills = Vec::new();
+        let mut cache_store = self.load_repo_cache();
+        let mut cache_updated = Self::merge_repo_cache(&mut cache_store, repo_cache);
+
+        for repo in repos.into_iter().filter(|repo| repo.enabled) {
+            let cache_key = Self::cache_key(&repo);
+            if let Some(entry) = cache_store.repos.get(&cache_key) {
+                skills.extend(entry.skills.clone());
+                continue;
+            }
+
+            match self.fetch_repo_skills_with_cache(&repo, None).await {
+                Ok(RepoFetchOutcome::Updated {
+                    skills: repo_skills,
+                    etag,
+                    last_modified,
+                }) => {
+                    skills.extend(repo_skills.clone());
+                    cache_store.repos.insert(
+                        cache_key,
+                        SkillRepoCache {
+                            fetched_at: Utc::now(),
+                            skills: repo_skills,
+                            etag,
+                            last_modified,
+                        },
+                    );
+                    cache_updated = true;
+                }
+                Ok(RepoFetchOutcome::NotModified) => {
+                    log::warn!("仓库 {}/{} 返回 304，但本地没有缓存", repo.owner, repo.name);
+                }
                 Err(e) => {
-                    if let Some(entry) = cached_entry {
-                        let warning = format!(
-                            "获取仓库 {}/{} 失败: {}，使用缓存",
-                            repo.owner, repo.name, e
-                        );
-                        log::warn!("{warning}");
-                        warnings.push(warning);
-                        skills.extend(entry.skills);
-                    } else {
-                        let warning = format!("获取仓库 {}/{} 失败: {}", repo.owner, repo.name, e);
-                        log::warn!("{warning}");
-                        warnings.push(warning);
-                    }
+                    log::warn!("获取仓库 {}/{} 失败: {}", repo.owner, repo.name, e);
                 }
             }
         }
 
         if cache_updated {
-            self.save_repo_cache(&cache_store);
+            cache_store = self.persist_repo_cache(&cache_store.repos);
         }
 
         repo_cache.clear();
-        repo_cache.extend(cache_store.repos.clone());
+        repo_cache.extend(cache_store.repos);
+
+        self.finish_skill_list(&mut skills)?;
+        Ok(skills)
+    }
 
-        // 合并本地技能
-        self.merge_local_skills(&mut skills)?;
+    /// 合并本地技能、去重、标记安装状态并排序
+    fn finish_skill_list(&self, skills: &mut Vec<Skill>) -> Result<()> {
+        self.merge_local_skills(skills)?;
 
-        // 去重并排序
-        Self::deduplicate_skills(&mut skills);
+        Self::deduplicate_skills(skills);
         for skill in skills.iter_mut() {
             let installed_apps = Self::installed_apps_for_directory(&skill.directory);
             skill.installed = installed_apps
@@ -755,13 +1185,7 @@ impl SkillService {
             skill.installed_apps = installed_apps;
         }
         skills.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-
-        Ok(SkillListResult {
-            skills,
-            warnings,
-            cache_hit,
-            refreshing,
-        })
+        Ok(())
     }
 
     /// 从仓库获取技能列表
@@ -775,23 +1199,16 @@ impl SkillService {
             last_modified: entry.last_modified.clone(),
         });
 
-        // 为单个仓库加载增加整体超时，避免无效链接长时间阻塞
-        let download_result = timeout(
-            Duration::from_secs(180),
+        // 限制同时下载的仓库数量，排队时间不计入下方的超时
+        let _permit = self.acquire_fetch_permit().await?;
+
+        // 为单个仓库加载增加整体超时，避免无效链接长时间阻塞浏览
+        let download_result = Self::download_with_timeout(
+            repo,
+            Self::list_timeout(),
             self.download_repo(repo, cache_headers.as_ref()),
         )
-        .await
-        .map_err(|_| {
-            anyhow!(format_skill_error(
-                "DOWNLOAD_TIMEOUT",
-                &[
-                    ("owner", &repo.owner),
-                    ("name", &repo.name),
-                    ("timeout", "180")
-                ],
-                Some("checkNetwork"),
-            ))
-        })??;
+        .await?;
 
         let download = match download_result {
             RepoDownloadResult::NotModified => {
@@ -836,11 +1253,13 @@ impl SkillService {
             temp_path.clone()
         };
 
+        let excludes = Self::collect_scan_excludes(repo, &temp_path);
         self.scan_skills_recursive(
             &scan_dir,
             &scan_dir,
             repo,
             normalized_skills_path.as_deref(),
+            &excludes,
             &mut skills,
         )?;
 
@@ -851,6 +1270,77 @@ impl SkillService {
         })
     }
 
+    /// 合并仓库配置的 `exclude` 与仓库根目录 `.skillignore`（每行一个 glob，`#` 开头为注释）
+    fn collect_scan_excludes(repo: &SkillRepo, repo_root: &Path) -> Vec<String> {
+        let mut excludes: Vec<String> = repo
+            .exclude
+            .iter()
+            .filter_map(|pattern| Self::normalize_exclude_pattern(pattern))
+            .collect();
+        if let Ok(content) = fs::read_to_string(repo_root.join(".skillignore")) {
+            excludes.extend(content.lines().filter_map(Self::normalize_exclude_pattern));
+        }
+        excludes
+    }
+
+    fn normalize_exclude_pattern(raw: &str) -> Option<String> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return None;
+        }
+        let pattern = trimmed.replace('\\', "/");
+        let pattern = pattern.trim_matches('/');
+        (!pattern.is_empty()).then(|| pattern.to_string())
+    }
+
+    /// 目录是否被排除：不含 `/` 的模式匹配任意一级目录名，否则匹配相对扫描目录的完整路径
+    fn is_scan_excluded(excludes: &[String], relative_path: &str) -> bool {
+        let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+        excludes.iter().any(|pattern| {
+            if pattern.contains('/') {
+                Self::glob_match(pattern, relative_path)
+            } else {
+                Self::glob_match(pattern, name)
+            }
+        })
+    }
+
+    /// 简单 glob：`*` 匹配除 `/` 外的任意字符，`**` 跨目录匹配，`?` 匹配单个字符
+    fn glob_match(pattern: &str, text: &str) -> bool {
+        fn matches(pattern: &[u8], text: &[u8]) -> bool {
+            match pattern.first() {
+                None => text.is_empty(),
+                Some(b'*') if pattern.get(1) == Some(&b'*') => {
+                    let rest = &pattern[2..];
+                    // `**/` 也可以匹配零级目录
+                    if let Some(after_slash) = rest.strip_prefix(b"/") {
+                        if matches(after_slash, text) {
+                            return true;
+                        }
+                    }
+                    (0..=text.len()).any(|i| matches(rest, &text[i..]))
+                }
+                Some(b'*') => {
+                    let rest = &pattern[1..];
+                    for i in 0..=text.len() {
+                        if matches(rest, &text[i..]) {
+                            return true;
+                        }
+                        if text.get(i) == Some(&b'/') {
+                            break;
+                        }
+                    }
+                    false
+                }
+                Some(b'?') => {
+                    !text.is_empty() && text[0] != b'/' && matches(&pattern[1..], &text[1..])
+                }
+                Some(ch) => text.first() == Some(ch) && matches(&pattern[1..], &text[1..]),
+            }
+        }
+        matches(pattern.as_bytes(), text.as_bytes())
+    }
+
     /// 递归扫描目录树，查找所有 SKILL.md
     fn scan_skills_recursive(
         &self,
@@ -858,6 +1348,7 @@ impl SkillService {
         current_dir: &Path,
         repo: &SkillRepo,
         normalized_skills_path: Option<&str>,
+        excludes: &[String],
         skills: &mut Vec<Skill>,
     ) -> Result<()> {
         let root_metadata = match fs::symlink_metadata(current_dir) {
@@ -877,25 +1368,27 @@ impl SkillService {
             return Ok(());
         }
 
-        self.scan_skills_recursive_inner(
-            scan_root,
-            current_dir,
+        let scan = RepoScan {
             repo,
             normalized_skills_path,
-            skills,
-            0,
-        )
+            excludes,
+        };
+        self.scan_skills_recursive_inner(scan_root, current_dir, scan, skills, 0)
     }
 
     fn scan_skills_recursive_inner(
         &self,
         scan_root: &Path,
         current_dir: &Path,
-        repo: &SkillRepo,
-        normalized_skills_path: Option<&str>,
+        scan: RepoScan<'_>,
         skills: &mut Vec<Skill>,
         depth: usize,
     ) -> Result<()> {
+        let RepoScan {
+            repo,
+            normalized_skills_path,
+            excludes,
+        } = scan;
         let (components, root_skill) = if current_dir == scan_root {
             if let Some(skills_path) = normalized_skills_path {
                 let leaf = skills_path.rsplit('/').next().unwrap_or("").trim();
@@ -957,15 +1450,20 @@ impl SkillService {
                                         depth,
                                         readme_url: Some(format!(
                                             "https://github.com/{}/{}/tree/{}/{}",
-                                            repo.owner, repo.name, repo.branch, readme_path
+                                            repo.owner,
+                                            repo.name,
+                                            repo.effective_ref(),
+                                            readme_path
                                         )),
                                         installed: false,
                                         installed_apps: Vec::new(),
                                         repo_owner: Some(repo.owner.clone()),
                                         repo_name: Some(repo.name.clone()),
                                         repo_branch: Some(repo.branch.clone()),
+                                        repo_ref: repo.pinned_ref().map(str::to_string),
                                         skills_path: repo.skills_path.clone(),
                                         commands,
+                                        install_state: None,
                                     });
                                 }
                             }
@@ -981,11 +1479,12 @@ impl SkillService {
             }
         }
 
-        if depth >= MAX_SKILL_SCAN_DEPTH {
+        let max_depth = Self::max_scan_depth();
+        if depth >= max_depth {
             log::warn!(
-                "扫描目录 {} 已达到最大深度 {}, 停止向下递归",
+                "扫描目录 {} 已达到最大深度 {}, 停止向下递归（可通过 SKILLS_MAX_SCAN_DEPTH 调整）",
                 current_dir.display(),
-                MAX_SKILL_SCAN_DEPTH
+                max_depth
             );
             return Ok(());
         }
@@ -1016,14 +1515,17 @@ impl SkillService {
             if !file_type.is_dir() || file_type.is_symlink() {
                 continue;
             }
-            self.scan_skills_recursive_inner(
-                scan_root,
-                &entry.path(),
-                repo,
-                normalized_skills_path,
-                skills,
-                depth + 1,
-            )?;
+            let entry_path = entry.path();
+            if !excludes.is_empty() {
+                let relative = Self::relative_path_components(scan_root, &entry_path)
+                    .map(|components| components.join("/"))
+                    .unwrap_or_default();
+                if Self::is_scan_excluded(excludes, &relative) {
+                    log::debug!("按排除规则跳过目录 {}", entry_path.display());
+                    continue;
+                }
+            }
+            self.scan_skills_recursive_inner(scan_root, &entry_path, scan, skills, depth + 1)?;
         }
 
         Ok(())
@@ -1262,8 +1764,10 @@ impl SkillService {
                                     repo_owner: None,
                                     repo_name: None,
                                     repo_branch: None,
+                                    repo_ref: None,
                                     skills_path: None,
                                     commands,
+                                    install_state: None,
                                 });
                             }
                         }
@@ -1277,11 +1781,12 @@ impl SkillService {
             }
         }
 
-        if depth >= MAX_SKILL_SCAN_DEPTH {
+        let max_depth = Self::max_scan_depth();
+        if depth >= max_depth {
             log::warn!(
-                "扫描目录 {} 已达到最大深度 {}, 停止向下递归",
+                "扫描目录 {} 已达到最大深度 {}, 停止向下递归（可通过 SKILLS_MAX_SCAN_DEPTH 调整）",
                 current_dir.display(),
-                MAX_SKILL_SCAN_DEPTH
+                max_depth
             );
             return Ok(());
         }
@@ -1328,26 +1833,87 @@ impl SkillService {
         });
     }
 
-    /// 下载仓库
-    async fn download_repo(
-        &self,
-        repo: &SkillRepo,
-        cache_headers: Option<&RepoCacheHeaders>,
-    ) -> Result<RepoDownloadResult> {
-        // 尝试多个分支
-        let branches = if repo.branch.is_empty() {
-            vec!["main", "master"]
-        } else {
-            vec![repo.branch.as_str(), "main", "master"]
-        };
+    /// 生成待尝试的归档下载地址：(ref, url)
+    ///
+    /// 设置了固定 ref 时只下载该 ref，不回退到其它分支，保证安装可复现。
+    fn archive_candidates(repo: &SkillRepo) -> Vec<(String, String)> {
+        let fallbacks =
+            Self::parse_fallback_branches(env::var("SKILLS_FALLBACK_BRANCHES").ok().as_deref());
+        Self::archive_candidates_with_fallbacks(repo, &fallbacks, &Self::archive_base_url())
+    }
+
+    /// 归档下载的站点地址，可通过 SKILLS_ARCHIVE_BASE_URL 指向 GitHub 镜像
+    fn archive_base_url() -> String {
+        env::var("SKILLS_ARCHIVE_BASE_URL")
+            .ok()
+            .map(|raw| raw.trim().trim_end_matches('/').to_string())
+            .filter(|base| !base.is_empty())
+            .unwrap_or_else(|| DEFAULT_ARCHIVE_BASE_URL.to_string())
+    }
+
+    /// 解析 `SKILLS_FALLBACK_BRANCHES`（逗号分隔）；未设置或为空时回落为 `main,master`
+    fn parse_fallback_branches(raw: Option<&str>) -> Vec<String> {
+        let branches: Vec<String> = raw
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|branch| !branch.is_empty())
+            .map(str::to_string)
+            .collect();
+        if branches.is_empty() {
+            vec!["main".to_string(), "master".to_string()]
+        } else {
+            branches
+        }
+    }
+
+    fn archive_candidates_with_fallbacks(
+        repo: &SkillRepo,
+        fallbacks: &[String],
+        base_url: &str,
+    ) -> Vec<(String, String)> {
+        if let Some(git_ref) = repo.pinned_ref() {
+            return vec![(
+                git_ref.to_string(),
+                format!(
+                    "{}/{}/{}/archive/{}.zip",
+                    base_url, repo.owner, repo.name, git_ref
+                ),
+            )];
+        }
+
+        // 先尝试仓库自身的分支，再依次尝试回退分支（跳过重复项）
+        let mut branches: Vec<&str> = Vec::new();
+        for branch in
+            std::iter::once(repo.branch.as_str()).chain(fallbacks.iter().map(String::as_str))
+        {
+            if !branch.is_empty() && !branches.contains(&branch) {
+                branches.push(branch);
+            }
+        }
+        branches
+            .into_iter()
+            .map(|branch| {
+                (
+                    branch.to_string(),
+                    format!(
+                        "{}/{}/{}/archive/refs/heads/{}.zip",
+                        base_url, repo.owner, repo.name, branch
+                    ),
+                )
+            })
+            .collect()
+    }
 
+    /// 下载仓库
+    async fn download_repo(
+        &self,
+        repo: &SkillRepo,
+        cache_headers: Option<&RepoCacheHeaders>,
+    ) -> Result<RepoDownloadResult> {
         let mut last_error = None;
-        for branch in branches {
+        for (resolved_ref, url) in Self::archive_candidates(repo) {
             let temp_dir = tempfile::tempdir()?;
-            let url = format!(
-                "https://github.com/{}/{}/archive/refs/heads/{}.zip",
-                repo.owner, repo.name, branch
-            );
 
             match self
                 .download_and_extract(&url, temp_dir.path(), cache_headers)
@@ -1359,6 +1925,7 @@ impl SkillService {
                 }) => {
                     return Ok(RepoDownloadResult::Downloaded(DownloadedRepo {
                         temp_dir,
+                        resolved_ref,
                         etag,
                         last_modified,
                     }));
@@ -1383,7 +1950,28 @@ impl SkillService {
         dest: &Path,
         cache_headers: Option<&RepoCacheHeaders>,
     ) -> Result<DownloadOutcome> {
-        // 下载 ZIP
+        let Some((bytes, etag, last_modified)) = self.download_zip(url, cache_headers).await?
+        else {
+            return Ok(DownloadOutcome::NotModified);
+        };
+
+        let limits = Self::zip_limits();
+        let dest = dest.to_path_buf();
+        tokio::task::spawn_blocking(move || Self::extract_zip_to_dir(bytes, dest, limits))
+            .await??;
+
+        Ok(DownloadOutcome::Downloaded {
+            etag,
+            last_modified,
+        })
+    }
+
+    /// 下载 ZIP 原始字节（受大小上限约束），返回 `None` 表示 304 未修改
+    async fn download_zip(
+        &self,
+        url: &str,
+        cache_headers: Option<&RepoCacheHeaders>,
+    ) -> Result<Option<(Vec<u8>, Option<String>, Option<String>)>> {
         let mut request = self.http_client.get(url);
         if let Some(headers) = cache_headers {
             if let Some(etag) = headers.etag.as_deref() {
@@ -1396,7 +1984,7 @@ impl SkillService {
 
         let response = request.send().await?;
         if response.status() == StatusCode::NOT_MODIFIED {
-            return Ok(DownloadOutcome::NotModified);
+            return Ok(None);
         }
         if !response.status().is_success() {
             let status = response.status().as_u16().to_string();
@@ -1455,22 +2043,75 @@ impl SkillService {
             }
             bytes.extend_from_slice(&chunk);
         }
-        let dest = dest.to_path_buf();
-        tokio::task::spawn_blocking(move || Self::extract_zip_to_dir(bytes, dest, limits))
-            .await??;
 
-        Ok(DownloadOutcome::Downloaded {
-            etag,
-            last_modified,
-        })
+        Ok(Some((bytes, etag, last_modified)))
     }
 
-    fn extract_zip_to_dir(bytes: Vec<u8>, dest: PathBuf, limits: ZipLimits) -> Result<()> {
-        // 解压
+    /// 获取仓库归档的文件清单（不解压、不落盘），结果短暂缓存
+    pub async fn repo_manifest(&self, repo: &SkillRepo) -> Result<SkillRepoManifest> {
+        let cache_key = Self::cache_key(repo);
+        if let Some(manifest) = Self::cached_manifest(&cache_key) {
+            return Ok(manifest);
+        }
+
+        let _permit = self.acquire_fetch_permit().await?;
+        let manifest =
+            Self::download_with_timeout(repo, Self::list_timeout(), self.fetch_repo_manifest(repo))
+                .await?;
+
+        Self::manifest_cache()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(cache_key, (std::time::Instant::now(), manifest.clone()));
+        Ok(manifest)
+    }
+
+    async fn fetch_repo_manifest(&self, repo: &SkillRepo) -> Result<SkillRepoManifest> {
+        let mut last_error = None;
+        for (resolved_ref, url) in Self::archive_candidates(repo) {
+            match self.download_zip(&url, None).await {
+                Ok(Some((bytes, _, _))) => {
+                    let limits = Self::zip_limits();
+                    let entries =
+                        tokio::task::spawn_blocking(move || Self::list_zip_entries(bytes, limits))
+                            .await??;
+                    return Ok(SkillRepoManifest {
+                        resolved_ref,
+                        entries,
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支下载失败")))
+    }
+
+    fn manifest_cache() -> &'static Mutex<HashMap<String, (std::time::Instant, SkillRepoManifest)>>
+    {
+        static CACHE: OnceLock<Mutex<HashMap<String, (std::time::Instant, SkillRepoManifest)>>> =
+            OnceLock::new();
+        CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    fn cached_manifest(cache_key: &str) -> Option<SkillRepoManifest> {
+        let mut cache = Self::manifest_cache()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let ttl = Duration::from_secs(SKILL_MANIFEST_CACHE_TTL_SECS);
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+        cache.get(cache_key).map(|(_, manifest)| manifest.clone())
+    }
+
+    /// 打开 ZIP 并校验条目数量（拒绝空归档与条目过多的归档）
+    fn open_zip_archive(
+        bytes: Vec<u8>,
+        limits: &ZipLimits,
+    ) -> Result<zip::ZipArchive<std::io::Cursor<Vec<u8>>>> {
         let cursor = std::io::Cursor::new(bytes);
-        let mut archive = zip::ZipArchive::new(cursor)?;
+        let archive = zip::ZipArchive::new(cursor)?;
 
-        // 获取根目录名称 (GitHub 的 zip 会有一个根目录)
         let entry_count = archive.len();
         if entry_count > limits.max_zip_entries {
             return Err(anyhow::anyhow!(format_skill_error(
@@ -1491,158 +2132,197 @@ impl SkillService {
             )));
         }
 
+        Ok(archive)
+    }
+
+    /// 获取根目录名称 (GitHub 的 zip 会有一个根目录)
+    fn zip_common_root(
+        archive: &mut zip::ZipArchive<std::io::Cursor<Vec<u8>>>,
+    ) -> Result<Option<String>> {
         let mut common_root: Option<String> = None;
-        for i in 0..entry_count {
+        for i in 0..archive.len() {
             let file = archive.by_index(i)?;
             let name = file.name();
             let first_component = name.split('/').next().unwrap_or("");
             if first_component.is_empty() {
-                common_root = None;
-                break;
+                return Ok(None);
             }
             match &common_root {
                 None => common_root = Some(first_component.to_string()),
                 Some(root) => {
                     if root != first_component {
-                        common_root = None;
-                        break;
+                        return Ok(None);
                     }
                 }
             }
         }
+        Ok(common_root)
+    }
 
-        let mut total_uncompressed_bytes: u64 = 0;
-        let mut extracted_count: usize = 0;
-
-        // 解压所有文件
-        for i in 0..entry_count {
-            let file = archive.by_index(i)?;
-            let file_path = file.name();
-
-            let relative_path = if let Some(root) = common_root.as_deref() {
-                if let Some(stripped) = file_path.strip_prefix(&format!("{root}/")) {
-                    stripped
-                } else if file_path == root {
-                    ""
-                } else {
-                    file_path
-                }
+    /// 去掉公共根目录并做路径安全检查；返回 `None` 表示该条目即根目录本身
+    fn checked_entry_path(
+        file_path: &str,
+        common_root: Option<&str>,
+        limits: &ZipLimits,
+    ) -> Result<Option<String>> {
+        let relative_path = if let Some(root) = common_root {
+            if let Some(stripped) = file_path.strip_prefix(&format!("{root}/")) {
+                stripped
+            } else if file_path == root {
+                ""
             } else {
                 file_path
-            };
-
-            if relative_path.is_empty() {
-                continue;
             }
+        } else {
+            file_path
+        };
 
-            let relative_path = relative_path.to_string();
-            let relative_path_obj = Path::new(&relative_path);
-            let has_traversal = relative_path_obj.components().any(|c| {
-                matches!(
-                    c,
-                    Component::ParentDir | Component::RootDir | Component::Prefix(_)
-                )
-            }) || relative_path
-                .split(['/', '\\'])
-                .any(|segment| segment == "..");
+        if relative_path.is_empty() {
+            return Ok(None);
+        }
 
-            if relative_path_obj.is_absolute() || has_traversal {
-                return Err(anyhow!(format_skill_error(
-                    "INVALID_ARCHIVE_PATH",
-                    &[("path", file_path)],
-                    Some("checkRepoUrl"),
-                )));
-            }
+        let relative_path = relative_path.to_string();
+        let relative_path_obj = Path::new(&relative_path);
+        let has_traversal = relative_path_obj.components().any(|c| {
+            matches!(
+                c,
+                Component::ParentDir | Component::RootDir | Component::Prefix(_)
+            )
+        }) || relative_path
+            .split(['/', '\\'])
+            .any(|segment| segment == "..");
 
-            let component_count = relative_path_obj
-                .components()
-                .filter(|component| matches!(component, Component::Normal(_)))
-                .count();
-            if component_count > limits.max_path_components {
-                return Err(anyhow!(format_skill_error(
-                    "ZIP_PATH_TOO_DEEP",
-                    &[
-                        ("path", &relative_path),
-                        ("components", &component_count.to_string()),
-                        ("maxComponents", &limits.max_path_components.to_string())
-                    ],
-                    Some("checkRepoUrl"),
-                )));
-            }
+        if relative_path_obj.is_absolute() || has_traversal {
+            return Err(anyhow!(format_skill_error(
+                "INVALID_ARCHIVE_PATH",
+                &[("path", file_path)],
+                Some("checkRepoUrl"),
+            )));
+        }
 
-            if relative_path.len() > limits.max_path_length {
-                return Err(anyhow!(format_skill_error(
-                    "ZIP_PATH_TOO_LONG",
-                    &[
-                        ("path", &relative_path),
-                        ("length", &relative_path.len().to_string()),
-                        ("maxLength", &limits.max_path_length.to_string())
-                    ],
-                    Some("checkRepoUrl"),
-                )));
-            }
+        let component_count = relative_path_obj
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .count();
+        if component_count > limits.max_path_components {
+            return Err(anyhow!(format_skill_error(
+                "ZIP_PATH_TOO_DEEP",
+                &[
+                    ("path", &relative_path),
+                    ("components", &component_count.to_string()),
+                    ("maxComponents", &limits.max_path_components.to_string())
+                ],
+                Some("checkRepoUrl"),
+            )));
+        }
 
-            let outpath = dest.join(relative_path_obj);
+        if relative_path.len() > limits.max_path_length {
+            return Err(anyhow!(format_skill_error(
+                "ZIP_PATH_TOO_LONG",
+                &[
+                    ("path", &relative_path),
+                    ("length", &relative_path.len().to_string()),
+                    ("maxLength", &limits.max_path_length.to_string())
+                ],
+                Some("checkRepoUrl"),
+            )));
+        }
 
-            if file.is_dir() {
-                fs::create_dir_all(&outpath)?;
-                extracted_count = extracted_count.saturating_add(1);
-            } else {
-                let file_size = file.size();
-                if file_size > limits.max_single_file_bytes {
-                    return Err(anyhow!(format_skill_error(
-                        "ZIP_FILE_TOO_LARGE",
-                        &[
-                            ("path", &relative_path),
-                            ("size", &file_size.to_string()),
-                            ("maxBytes", &limits.max_single_file_bytes.to_string())
-                        ],
-                        Some("checkRepoUrl"),
-                    )));
-                }
+        Ok(Some(relative_path))
+    }
+
+    /// 校验单个文件条目的大小与压缩比，并累加解压总量
+    fn check_entry_size(
+        relative_path: &str,
+        file_size: u64,
+        compressed_size: u64,
+        total_uncompressed_bytes: &mut u64,
+        limits: &ZipLimits,
+    ) -> Result<()> {
+        if file_size > limits.max_single_file_bytes {
+            return Err(anyhow!(format_skill_error(
+                "ZIP_FILE_TOO_LARGE",
+                &[
+                    ("path", relative_path),
+                    ("size", &file_size.to_string()),
+                    ("maxBytes", &limits.max_single_file_bytes.to_string())
+                ],
+                Some("checkRepoUrl"),
+            )));
+        }
 
-                let compressed_size = file.compressed_size();
-                if compressed_size == 0 && file_size > 0 {
+        if compressed_size == 0 && file_size > 0 {
+            return Err(anyhow!(format_skill_error(
+                "ZIP_INVALID_COMPRESSION",
+                &[
+                    ("path", relative_path),
+                    ("size", &file_size.to_string()),
+                    ("compressedSize", "0")
+                ],
+                Some("checkRepoUrl"),
+            )));
+        }
+        if compressed_size > 0 {
+            if let Some(max_allowed) = compressed_size.checked_mul(limits.max_compression_ratio) {
+                if file_size > max_allowed {
                     return Err(anyhow!(format_skill_error(
-                        "ZIP_INVALID_COMPRESSION",
+                        "ZIP_COMPRESSION_RATIO_TOO_HIGH",
                         &[
-                            ("path", &relative_path),
+                            ("path", relative_path),
                             ("size", &file_size.to_string()),
-                            ("compressedSize", "0")
+                            ("compressedSize", &compressed_size.to_string()),
+                            ("maxRatio", &limits.max_compression_ratio.to_string())
                         ],
                         Some("checkRepoUrl"),
                     )));
                 }
-                if compressed_size > 0 {
-                    if let Some(max_allowed) =
-                        compressed_size.checked_mul(limits.max_compression_ratio)
-                    {
-                        if file_size > max_allowed {
-                            return Err(anyhow!(format_skill_error(
-                                "ZIP_COMPRESSION_RATIO_TOO_HIGH",
-                                &[
-                                    ("path", &relative_path),
-                                    ("size", &file_size.to_string()),
-                                    ("compressedSize", &compressed_size.to_string()),
-                                    ("maxRatio", &limits.max_compression_ratio.to_string())
-                                ],
-                                Some("checkRepoUrl"),
-                            )));
-                        }
-                    }
-                }
+            }
+        }
 
-                total_uncompressed_bytes = total_uncompressed_bytes.saturating_add(file_size);
-                if total_uncompressed_bytes > limits.max_total_uncompressed_bytes {
-                    return Err(anyhow!(format_skill_error(
-                        "ZIP_TOTAL_TOO_LARGE",
-                        &[
-                            ("totalBytes", &total_uncompressed_bytes.to_string()),
-                            ("maxBytes", &limits.max_total_uncompressed_bytes.to_string())
-                        ],
-                        Some("checkRepoUrl"),
-                    )));
-                }
+        *total_uncompressed_bytes = total_uncompressed_bytes.saturating_add(file_size);
+        if *total_uncompressed_bytes > limits.max_total_uncompressed_bytes {
+            return Err(anyhow!(format_skill_error(
+                "ZIP_TOTAL_TOO_LARGE",
+                &[
+                    ("totalBytes", &total_uncompressed_bytes.to_string()),
+                    ("maxBytes", &limits.max_total_uncompressed_bytes.to_string())
+                ],
+                Some("checkRepoUrl"),
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn extract_zip_to_dir(bytes: Vec<u8>, dest: PathBuf, limits: ZipLimits) -> Result<()> {
+        // 解压
+        let mut archive = Self::open_zip_archive(bytes, &limits)?;
+        let common_root = Self::zip_common_root(&mut archive)?;
+
+        let mut total_uncompressed_bytes: u64 = 0;
+        let mut extracted_count: usize = 0;
+
+        // 解压所有文件
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)?;
+            let Some(relative_path) =
+                Self::checked_entry_path(file.name(), common_root.as_deref(), &limits)?
+            else {
+                continue;
+            };
+            let outpath = dest.join(&relative_path);
+
+            if file.is_dir() {
+                fs::create_dir_all(&outpath)?;
+                extracted_count = extracted_count.saturating_add(1);
+            } else {
+                Self::check_entry_size(
+                    &relative_path,
+                    file.size(),
+                    file.compressed_size(),
+                    &mut total_uncompressed_bytes,
+                    &limits,
+                )?;
 
                 if let Some(parent) = outpath.parent() {
                     fs::create_dir_all(parent)?;
@@ -1676,40 +2356,178 @@ impl SkillService {
         Ok(())
     }
 
+    /// 列出 ZIP 中的文件条目（不解压），执行与解压相同的路径与大小检查
+    fn list_zip_entries(bytes: Vec<u8>, limits: ZipLimits) -> Result<Vec<SkillManifestEntry>> {
+        let mut archive = Self::open_zip_archive(bytes, &limits)?;
+        let common_root = Self::zip_common_root(&mut archive)?;
+
+        let mut total_uncompressed_bytes: u64 = 0;
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)?;
+            let Some(path) =
+                Self::checked_entry_path(file.name(), common_root.as_deref(), &limits)?
+            else {
+                continue;
+            };
+            if file.is_dir() {
+                continue;
+            }
+            Self::check_entry_size(
+                &path,
+                file.size(),
+                file.compressed_size(),
+                &mut total_uncompressed_bytes,
+                &limits,
+            )?;
+            entries.push(SkillManifestEntry {
+                path,
+                size: file.size(),
+            });
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
     /// 安装技能（仅负责下载和文件操作，状态更新由上层负责）
+    ///
+    /// 返回实际下载的 ref；目标已存在且未强制覆盖时返回 `None`。
     pub async fn install_skill(
         &self,
         directory: String,
         repo: SkillRepo,
         force: bool,
-    ) -> Result<()> {
+    ) -> Result<Option<String>> {
         Self::validate_skill_directory(&directory)?;
         let dest = self.install_dir.join(&directory);
 
         // 若目标目录已存在，则视为已安装，避免重复下载
         if dest.exists() && !force {
-            return Ok(());
+            return Ok(None);
+        }
+
+        self.run_cancellable_install(
+            &directory,
+            &dest,
+            self.download_and_install(&directory, &dest, repo, force),
+        )
+        .await
+    }
+
+    /// 进行中的安装：state_key -> 取消控制（每次请求都会新建 SkillService，因此放在进程级）
+    fn in_flight_installs() -> &'static Mutex<HashMap<String, InstallControl>> {
+        static INSTALLS: OnceLock<Mutex<HashMap<String, InstallControl>>> = OnceLock::new();
+        INSTALLS.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// 登记安装任务以便取消；被取消时清理本次新建的目标目录（强制重装时不删除原有内容）
+    async fn run_cancellable_install<T>(
+        &self,
+        directory: &str,
+        dest: &Path,
+        install: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let key = Self::state_key(&self.app, directory);
+        let (handle, registration) = AbortHandle::new_pair();
+        {
+            let mut installs = Self::in_flight_installs()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if installs.contains_key(&key) {
+                return Err(anyhow!(format_skill_error(
+                    "INSTALL_IN_PROGRESS",
+                    &[("directory", directory)],
+                    None,
+                )));
+            }
+            installs.insert(
+                key.clone(),
+                InstallControl {
+                    abort: handle,
+                    copying: false,
+                },
+            );
+        }
+        let _task = self
+            .tasks
+            .register(BackgroundTaskKind::SkillInstall, key.clone());
+        // 请求被中途丢弃时同样需要注销，因此用 guard 而不是在末尾手动移除
+        let _registered = InFlightInstall { key };
+
+        let existed_before = dest.exists();
+        match Abortable::new(install, registration).await {
+            Ok(result) => result,
+            Err(Aborted) => {
+                if !existed_before && dest.exists() {
+                    if let Err(err) = fs::remove_dir_all(dest) {
+                        log::warn!("清理已取消安装的目录失败 {}: {}", dest.display(), err);
+                    }
+                }
+                Err(anyhow!(format_skill_error(
+                    "INSTALL_CANCELLED",
+                    &[("directory", directory)],
+                    None,
+                )))
+            }
+        }
+    }
+
+    /// 取消进行中的安装，返回是否确实有安装被取消
+    ///
+    /// 复制阶段是同步执行的，中止句柄无法打断；进入复制后返回 false，安装会照常完成。
+    pub fn cancel_install(&self, directory: &str) -> Result<bool> {
+        Self::validate_skill_directory(directory)?;
+        let key = Self::state_key(&self.app, directory);
+        let installs = Self::in_flight_installs()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match installs.get(&key) {
+            Some(control) if !control.copying && !control.abort.is_aborted() => {
+                control.abort.abort();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// 进入不可取消的复制阶段；若在此之前已被取消，则放弃安装
+    fn begin_install_copy(&self, directory: &str) -> Result<()> {
+        let key = Self::state_key(&self.app, directory);
+        let mut installs = Self::in_flight_installs()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(control) = installs.get_mut(&key) {
+            if control.abort.is_aborted() {
+                return Err(anyhow!(format_skill_error(
+                    "INSTALL_CANCELLED",
+                    &[("directory", directory)],
+                    None,
+                )));
+            }
+            control.copying = true;
         }
+        Ok(())
+    }
+
+    async fn download_and_install(
+        &self,
+        directory: &str,
+        dest: &Path,
+        repo: SkillRepo,
+        force: bool,
+    ) -> Result<Option<String>> {
+        // 限制并发安装数量，多余的请求在此排队；许可持有到复制完成
+        let _permit = self.acquire_install_permit().await?;
 
         // 下载仓库时增加总超时，防止无效链接导致长时间卡住安装过程
-        let temp_dir = timeout(
-            std::time::Duration::from_secs(180),
+        let temp_dir = Self::download_with_timeout(
+            &repo,
+            Self::install_timeout(),
             self.download_repo(&repo, None),
         )
-        .await
-        .map_err(|_| {
-            anyhow!(format_skill_error(
-                "DOWNLOAD_TIMEOUT",
-                &[
-                    ("owner", &repo.owner),
-                    ("name", &repo.name),
-                    ("timeout", "180")
-                ],
-                Some("checkNetwork"),
-            ))
-        })??;
-        let temp_dir = match temp_dir {
-            RepoDownloadResult::Downloaded(download) => download.temp_dir,
+        .await?;
+        let (temp_dir, resolved_ref) = match temp_dir {
+            RepoDownloadResult::Downloaded(download) => (download.temp_dir, download.resolved_ref),
             RepoDownloadResult::NotModified => {
                 return Err(anyhow::anyhow!(format_skill_error(
                     "DOWNLOAD_FAILED",
@@ -1722,7 +2540,7 @@ impl SkillService {
 
         // 根据 skills_path 确定源目录路径
         let source =
-            Self::resolve_install_source_path(&temp_path, &directory, repo.skills_path.as_deref())?;
+            Self::resolve_install_source_path(&temp_path, directory, repo.skills_path.as_deref())?;
 
         if !source.exists() {
             return Err(anyhow::anyhow!(format_skill_error(
@@ -1732,9 +2550,10 @@ impl SkillService {
             )));
         }
 
-        Self::install_from_source(&source, &dest, force)?;
+        self.begin_install_copy(directory)?;
+        Self::install_from_source(&source, dest, force)?;
 
-        Ok(())
+        Ok(Some(resolved_ref))
     }
 
     fn resolve_install_source_path(
@@ -1798,19 +2617,178 @@ impl SkillService {
             }
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    /// 卸载技能（仅负责文件操作，状态更新由上层负责）
+    pub fn uninstall_skill(&self, directory: String) -> Result<()> {
+        Self::validate_skill_directory(&directory)?;
+        let dest = self.install_dir.join(&directory);
+
+        if dest.exists() {
+            fs::remove_dir_all(&dest)?;
+        }
+
+        Ok(())
+    }
+
+    /// 校验已安装技能：找出缺少有效 SKILL.md 的目录，以及状态与磁盘不一致的条目
+    ///
+    /// `repair` 为 true 时删除不完整的目录，并清理磁盘上已不存在的状态记录；
+    /// 未被记录但有效的本地技能只做报告，不会被删除。
+    pub fn verify_installed_skills(
+        &self,
+        states: &mut HashMap<String, SkillState>,
+        repair: bool,
+    ) -> Result<SkillVerifyReport> {
+        let mut report = SkillVerifyReport::default();
+        let mut valid_dirs = Vec::new();
+
+        if self.install_dir.exists() {
+            for entry in fs::read_dir(&self.install_dir)? {
+                let entry = entry?;
+                let file_type = entry.file_type()?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !file_type.is_dir() || file_type.is_symlink() || name.starts_with('.') {
+                    continue;
+                }
+                let path = entry.path();
+                if !Self::collect_valid_skill_dirs(
+                    &self.install_dir,
+                    &path,
+                    &mut valid_dirs,
+                    &mut report.broken,
+                    0,
+                ) {
+                    report.broken.push(name);
+                }
+            }
+        }
+
+        let prefix = format!("{}:", self.app.as_str());
+        let tracked: Vec<(String, String)> = states
+            .iter()
+            .filter(|(_, state)| state.installed)
+            .filter_map(|(key, _)| {
+                key.strip_prefix(&prefix)
+                    .map(|directory| (key.clone(), directory.to_string()))
+            })
+            .collect();
+
+        for (key, directory) in &tracked {
+            if Self::validate_skill_directory(directory).is_err() {
+                continue;
+            }
+            let path = self.install_dir.join(directory);
+            if !path.exists() {
+                report.missing_on_disk.push(directory.clone());
+                if repair {
+                    states.remove(key);
+                    report.removed_states.push(key.clone());
+                }
+            } else if !Self::has_valid_skill_md(&path)
+                && !report
+                    .broken
+                    .iter()
+                    .any(|dir| dir.eq_ignore_ascii_case(directory))
+            {
+                report.broken.push(directory.clone());
+            }
+        }
+
+        report.untracked = valid_dirs
+            .into_iter()
+            .filter(|dir| {
+                !tracked
+                    .iter()
+                    .any(|(_, directory)| directory.eq_ignore_ascii_case(dir))
+            })
+            .collect();
+
+        if repair {
+            for directory in &report.broken {
+                Self::validate_skill_directory(directory)?;
+                let path = self.install_dir.join(directory);
+                if path.exists() {
+                    fs::remove_dir_all(&path)?;
+                    report.removed_dirs.push(directory.clone());
+                }
+                let key = Self::state_key(&self.app, directory);
+                if states.remove(&key).is_some() {
+                    report.removed_states.push(key);
+                }
+            }
+        }
+
+        report.broken.sort();
+        report.missing_on_disk.sort();
+        report.untracked.sort();
+        report.removed_dirs.sort();
+        report.removed_states.sort();
+        report.install_states = tracked
+            .into_iter()
+            .filter_map(|(key, directory)| states.get(&key).map(|state| (directory, state.clone())))
+            .collect();
+        Ok(report)
     }
 
-    /// 卸载技能（仅负责文件操作，状态更新由上层负责）
-    pub fn uninstall_skill(&self, directory: String) -> Result<()> {
-        Self::validate_skill_directory(&directory)?;
-        let dest = self.install_dir.join(&directory);
+    /// 递归收集有效技能目录；子树中存在有效技能时返回 true
+    ///
+    /// 含 SKILL.md 但内容为空或无法读取的目录记为损坏，不再向下递归。
+    fn collect_valid_skill_dirs(
+        root: &Path,
+        current_dir: &Path,
+        valid: &mut Vec<String>,
+        broken: &mut Vec<String>,
+        depth: usize,
+    ) -> bool {
+        let components = Self::relative_path_components(root, current_dir).unwrap_or_default();
+        let relative = components.join("/");
+        let skill_md = current_dir.join("SKILL.md");
+        if skill_md.exists() {
+            if Self::has_valid_skill_md(current_dir) {
+                valid.push(relative);
+                return true;
+            }
+            broken.push(relative);
+            return true;
+        }
 
-        if dest.exists() {
-            fs::remove_dir_all(&dest)?;
+        if depth >= Self::max_scan_depth() {
+            return false;
         }
 
-        Ok(())
+        let entries = match fs::read_dir(current_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("读取目录 {} 失败: {}", current_dir.display(), e);
+                return false;
+            }
+        };
+
+        let mut found = false;
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_dir() || file_type.is_symlink() {
+                continue;
+            }
+            if Self::collect_valid_skill_dirs(root, &entry.path(), valid, broken, depth + 1) {
+                found = true;
+            }
+        }
+        found
+    }
+
+    fn has_valid_skill_md(dir: &Path) -> bool {
+        let skill_md = dir.join("SKILL.md");
+        match fs::symlink_metadata(&skill_md) {
+            Ok(metadata) if metadata.is_file() => fs::read_to_string(&skill_md)
+                .map(|content| !content.trim_start_matches('\u{feff}').trim().is_empty())
+                .unwrap_or(false),
+            _ => false,
+        }
     }
 
     /// 列出仓库
@@ -1819,7 +2797,9 @@ impl SkillService {
     }
 
     /// 添加仓库
-    pub fn add_repo(&self, store: &mut SkillStore, repo: SkillRepo) -> Result<()> {
+    pub fn add_repo(&self, store: &mut SkillStore, mut repo: SkillRepo) -> Result<()> {
+        Self::normalize_repo(&mut repo)?;
+
         // 检查重复
         if let Some(pos) = store
             .repos
@@ -1834,6 +2814,93 @@ impl SkillService {
         Ok(())
     }
 
+    /// GitHub owner/仓库名允许的字符：字母数字、`-`、`_`、`.`（不允许单独的 `.` 或 `..`）
+    pub(crate) fn is_valid_repo_segment(value: &str) -> bool {
+        !value.is_empty()
+            && value != "."
+            && value != ".."
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }
+
+    /// 校验仓库 owner/name 并规范化分支（空白时回落为 `main`），避免拼出无效的 GitHub 地址
+    fn normalize_repo(repo: &mut SkillRepo) -> Result<()> {
+        repo.owner = repo.owner.trim().to_string();
+        repo.name = repo.name.trim().to_string();
+        for (field, value) in [("owner", &repo.owner), ("name", &repo.name)] {
+            if value.is_empty() {
+                return Err(anyhow!(format_skill_error(
+                    "MISSING_REPO_INFO",
+                    &[("field", field)],
+                    None,
+                )));
+            }
+            if !Self::is_valid_repo_segment(value) {
+                return Err(anyhow!(format_skill_error(
+                    "SKILL_REPO_INVALID",
+                    &[("field", field), ("value", value)],
+                    Some("checkRepoUrl"),
+                )));
+            }
+        }
+
+        let branch = repo.branch.trim();
+        repo.branch = if branch.is_empty() {
+            "main".to_string()
+        } else {
+            branch.to_string()
+        };
+        Ok(())
+    }
+
+    /// 列出仓库缓存（合并配置与 skills-cache.json），按缓存键排序
+    pub fn list_repo_cache(&self, store: &SkillStore) -> Vec<SkillRepoCacheSummary> {
+        let mut cache_store = self.load_repo_cache();
+        Self::merge_repo_cache(&mut cache_store, &store.repo_cache);
+        let mut entries: Vec<SkillRepoCacheSummary> = cache_store
+            .repos
+            .into_iter()
+            .map(|(key, entry)| SkillRepoCacheSummary {
+                key,
+                fetched_at: entry.fetched_at,
+                skill_count: entry.skills.len(),
+                etag: entry.etag,
+                last_modified: entry.last_modified,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    /// 清除仓库缓存；`repo` 为 `owner/name` 时仅清除该仓库的条目，返回被清除的缓存键
+    pub fn clear_repo_cache(&self, store: &mut SkillStore, repo: Option<&str>) -> Vec<String> {
+        let _guard = REPO_CACHE_FILE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut cache_store = self.load_repo_cache();
+        Self::merge_repo_cache(&mut cache_store, &store.repo_cache);
+
+        let prefix = repo.map(|repo| format!("{}/", repo.trim().trim_matches('/').to_lowercase()));
+        let mut removed: Vec<String> = cache_store
+            .repos
+            .keys()
+            .filter(|key| match prefix.as_deref() {
+                Some(prefix) => key.to_lowercase().starts_with(prefix),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        removed.sort();
+
+        for key in &removed {
+            cache_store.repos.remove(key);
+        }
+        store.repo_cache = cache_store.repos.clone();
+        self.save_repo_cache(&cache_store);
+        removed
+    }
+
     /// 删除仓库
     pub fn remove_repo(&self, store: &mut SkillStore, owner: String, name: String) -> Result<()> {
         store
@@ -1859,6 +2926,9 @@ mod tests {
                 .expect("client build should succeed"),
             install_dir: dir,
             app: AppType::Claude,
+            install_permits: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_INSTALLS)),
+            fetch_permits: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_FETCHES)),
+            tasks: Arc::new(TaskRegistry::default()),
         }
     }
 
@@ -1876,11 +2946,60 @@ mod tests {
             repo_owner: None,
             repo_name: None,
             repo_branch: None,
+            repo_ref: None,
             skills_path: None,
             commands: Vec::new(),
+            install_state: None,
+        }
+    }
+
+    fn make_repo(owner: &str, name: &str, branch: &str) -> SkillRepo {
+        SkillRepo {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            branch: branch.to_string(),
+            git_ref: None,
+            enabled: true,
+            skills_path: None,
+            exclude: Vec::new(),
         }
     }
 
+    #[test]
+    fn test_add_repo_rejects_empty_or_invalid_owner() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let service = build_service_with_install_dir(temp.path().to_path_buf());
+        let mut store = SkillStore::default();
+        store.repos.clear();
+
+        let err = service
+            .add_repo(&mut store, make_repo("  ", "skills", "main"))
+            .expect_err("empty owner should be rejected");
+        assert!(err.to_string().contains("MISSING_REPO_INFO"), "{err}");
+
+        let err = service
+            .add_repo(&mut store, make_repo("foo/bar", "skills", "main"))
+            .expect_err("owner with slash should be rejected");
+        assert!(err.to_string().contains("SKILL_REPO_INVALID"), "{err}");
+        assert!(store.repos.is_empty());
+    }
+
+    #[test]
+    fn test_add_repo_defaults_blank_branch_to_main() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let service = build_service_with_install_dir(temp.path().to_path_buf());
+        let mut store = SkillStore::default();
+        store.repos.clear();
+
+        service
+            .add_repo(&mut store, make_repo(" acme ", "skills.repo", "  "))
+            .expect("valid repo should be added");
+        assert_eq!(store.repos.len(), 1);
+        assert_eq!(store.repos[0].owner, "acme");
+        assert_eq!(store.repos[0].name, "skills.repo");
+        assert_eq!(store.repos[0].branch, "main");
+    }
+
     #[test]
     fn test_normalize_skills_path() {
         let normalized = SkillService::normalize_skills_path("/skills\\nested//")
@@ -1981,8 +3100,10 @@ description: Useful skill
             owner: "owner".to_string(),
             name: "repo".to_string(),
             branch: "main".to_string(),
+            git_ref: None,
             enabled: true,
             skills_path: None,
+            exclude: Vec::new(),
         };
 
         service
@@ -1994,6 +3115,389 @@ description: Useful skill
         assert_eq!(content, "old");
     }
 
+    #[tokio::test]
+    async fn test_background_refresh_returns_immediately_and_dedupes() {
+        let key = "test-owner/bg-refresh/main".to_string();
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let service = build_service_with_install_dir(temp_dir.path().join("install"));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let filled = Arc::new(Mutex::new(None::<usize>));
+
+        let started = std::time::Instant::now();
+        let task_filled = filled.clone();
+        assert!(service.spawn_repo_refresh(key.clone(), async move {
+            let _ = release_rx.await;
+            *task_filled.lock().unwrap() = Some(3);
+            Ok(())
+        }));
+        // 冷缓存刷新不阻塞调用方，且同一仓库不会重复启动刷新
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert!(filled.lock().unwrap().is_none());
+        assert!(!service.spawn_repo_refresh(key.clone(), async { Ok(()) }));
+
+        release_tx.send(()).expect("release refresh task");
+        for _ in 0..100 {
+            let done = !SkillService::repo_refresh_state()
+                .lock()
+                .unwrap()
+                .in_flight
+                .contains(&key);
+            if done {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*filled.lock().unwrap(), Some(3));
+
+        // 失败原因会在下一次列表请求时以警告形式取出
+        assert!(service.spawn_repo_refresh(key.clone(), async { Err("boom".to_string()) }));
+        let mut failure = None;
+        for _ in 0..100 {
+            failure = SkillService::take_refresh_failure(&key);
+            if failure.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(failure.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_install_permits_cap_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let service = Arc::new(build_service_with_install_dir(
+            temp_dir.path().to_path_buf(),
+        ));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..6 {
+            let service = service.clone();
+            let active = active.clone();
+            let peak = peak.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = service
+                    .acquire_install_permit()
+                    .await
+                    .expect("permit should be granted");
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+        for handle in handles {
+            handle.await.expect("task should finish");
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), DEFAULT_MAX_CONCURRENT_INSTALLS);
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_install_removes_partial_directory() {
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let install_dir = temp_dir.path().join("install");
+        let service = Arc::new(build_service_with_install_dir(install_dir.clone()));
+        let dest = install_dir.join("cancel-demo");
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let task_service = service.clone();
+        let task_dest = dest.clone();
+        let install = tokio::spawn(async move {
+            let partial = task_dest.clone();
+            task_service
+                .run_cancellable_install("cancel-demo", &task_dest, async move {
+                    fs::create_dir_all(&partial)?;
+                    fs::write(partial.join("SKILL.md"), "partial")?;
+                    let _ = started_tx.send(());
+                    std::future::pending::<()>().await;
+                    Ok::<(), anyhow::Error>(())
+                })
+                .await
+        });
+
+        started_rx.await.expect("install should start");
+        assert!(dest.exists());
+        assert!(service
+            .cancel_install("cancel-demo")
+            .expect("cancel should succeed"));
+
+        let err = install
+            .await
+            .expect("install task should finish")
+            .expect_err("cancelled install should fail");
+        assert!(err.to_string().contains("INSTALL_CANCELLED"));
+        assert!(!dest.exists(), "partial directory should be removed");
+        // 没有进行中的安装时返回 false
+        assert!(!service
+            .cancel_install("cancel-demo")
+            .expect("cancel should succeed"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_install_is_refused_once_copy_started() {
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let install_dir = temp_dir.path().join("install");
+        let service = Arc::new(build_service_with_install_dir(install_dir.clone()));
+        let dest = install_dir.join("copy-demo");
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel::<()>();
+        let (resume_tx, resume_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let task_service = service.clone();
+        let task_dest = dest.clone();
+        let install = tokio::spawn(async move {
+            let copy_service = task_service.clone();
+            let partial = task_dest.clone();
+            task_service
+                .run_cancellable_install("copy-demo", &task_dest, async move {
+                    copy_service.begin_install_copy("copy-demo")?;
+                    let _ = started_tx.send(());
+                    let _ = resume_rx.await;
+                    fs::create_dir_all(&partial)?;
+                    fs::write(partial.join("SKILL.md"), "done")?;
+                    Ok::<(), anyhow::Error>(())
+                })
+                .await
+        });
+
+        started_rx.await.expect("copy should start");
+        // 复制一旦开始就无法中断，取消必须如实返回 false
+        assert!(!service
+            .cancel_install("copy-demo")
+            .expect("cancel should succeed"));
+        resume_tx.send(()).expect("resume copy");
+
+        install
+            .await
+            .expect("install task should finish")
+            .expect("install should complete");
+        assert!(dest.join("SKILL.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_install_does_not_start_copy() {
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let install_dir = temp_dir.path().join("install");
+        let service = Arc::new(build_service_with_install_dir(install_dir.clone()));
+        let dest = install_dir.join("late-demo");
+
+        let task_service = service.clone();
+        let partial = dest.clone();
+        let result = service
+            .run_cancellable_install("late-demo", &dest, async move {
+                // 取消恰好发生在下载完成之后、复制开始之前（同一次 poll 内，中止句柄来不及生效）
+                assert!(task_service
+                    .cancel_install("late-demo")
+                    .expect("cancel should succeed"));
+                task_service.begin_install_copy("late-demo")?;
+                fs::create_dir_all(&partial)?;
+                Ok::<(), anyhow::Error>(())
+            })
+            .await;
+
+        let err = result.expect_err("cancelled install should fail");
+        assert!(err.to_string().contains("INSTALL_CANCELLED"));
+        assert!(!dest.exists(), "copy should not start after cancel");
+    }
+
+    #[test]
+    fn test_skill_state_deserializes_legacy_records_without_source() {
+        let state: SkillState =
+            serde_json::from_str(r#"{"installed":true,"installedAt":"2024-01-01T00:00:00Z"}"#)
+                .expect("legacy state should deserialize");
+        assert!(state.installed);
+        assert!(state.repo_owner.is_none());
+        assert!(state.repo_name.is_none());
+        assert!(state.git_ref.is_none());
+
+        let value = serde_json::to_value(&state).expect("serialize state");
+        assert!(value.get("repoOwner").is_none());
+        assert!(value.get("ref").is_none());
+    }
+
+    #[test]
+    fn test_skill_state_for_install_keeps_previous_source_when_skipped() {
+        let mut skill = make_skill("acme/toolbox:toolkit", "toolkit");
+        skill.repo_owner = Some("acme".to_string());
+        skill.repo_name = Some("toolbox".to_string());
+        skill.repo_branch = Some("main".to_string());
+
+        let fresh = SkillState::for_install(&skill, Some("main".to_string()), None, Utc::now());
+        assert_eq!(fresh.repo_owner.as_deref(), Some("acme"));
+        assert_eq!(fresh.git_ref.as_deref(), Some("main"));
+        assert_eq!(fresh.resolved_ref.as_deref(), Some("main"));
+
+        // 跳过下载时沿用已记录的来源与 ref，即使列表中的技能来自其他仓库
+        skill.repo_owner = Some("other".to_string());
+        let skipped = SkillState::for_install(&skill, None, Some(&fresh), Utc::now());
+        assert_eq!(skipped.repo_owner.as_deref(), Some("acme"));
+        assert_eq!(skipped.resolved_ref.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_verify_installed_skills_flags_partial_install() {
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let install_dir = temp_dir.path().join("install");
+        let service = build_service_with_install_dir(install_dir.clone());
+
+        let good = install_dir.join("good");
+        fs::create_dir_all(&good).expect("good dir should exist");
+        fs::write(good.join("SKILL.md"), "---\nname: Good\n---\n").expect("write skill");
+        let partial = install_dir.join("partial");
+        fs::create_dir_all(partial.join("scripts")).expect("partial dir should exist");
+        fs::write(partial.join("scripts").join("run.sh"), "echo").expect("write script");
+        let local = install_dir.join("local");
+        fs::create_dir_all(&local).expect("local dir should exist");
+        fs::write(local.join("SKILL.md"), "# Local").expect("write local skill");
+
+        let state = SkillState {
+            installed: true,
+            installed_at: Utc::now(),
+            resolved_ref: None,
+            repo_owner: None,
+            repo_name: None,
+            git_ref: None,
+        };
+        let mut states = HashMap::from([
+            ("claude:good".to_string(), state.clone()),
+            ("claude:partial".to_string(), state.clone()),
+            ("claude:gone".to_string(), state.clone()),
+            ("codex:elsewhere".to_string(), state),
+        ]);
+
+        let report = service
+            .verify_installed_skills(&mut states, false)
+            .expect("verify should succeed");
+        assert_eq!(report.broken, vec!["partial".to_string()]);
+        assert_eq!(report.missing_on_disk, vec!["gone".to_string()]);
+        assert_eq!(report.untracked, vec!["local".to_string()]);
+        assert!(partial.exists(), "report-only mode must not touch disk");
+        assert_eq!(states.len(), 4);
+
+        let repaired = service
+            .verify_installed_skills(&mut states, true)
+            .expect("repair should succeed");
+        assert_eq!(repaired.removed_dirs, vec!["partial".to_string()]);
+        assert_eq!(
+            repaired.removed_states,
+            vec!["claude:gone".to_string(), "claude:partial".to_string()]
+        );
+        assert!(!partial.exists());
+        assert!(local.exists(), "untracked valid skills are kept");
+        assert!(states.contains_key("claude:good"));
+        assert!(states.contains_key("codex:elsewhere"));
+    }
+
+    #[tokio::test]
+    async fn test_list_timeout_is_shorter_than_install_timeout() {
+        std::env::remove_var("SKILLS_INSTALL_TIMEOUT_SECS");
+        std::env::set_var("SKILLS_LIST_TIMEOUT_SECS", "1");
+        let repo = SkillRepo {
+            owner: "owner".to_string(),
+            name: "slow-repo".to_string(),
+            branch: "main".to_string(),
+            git_ref: None,
+            enabled: true,
+            skills_path: None,
+            exclude: Vec::new(),
+        };
+        let slow_repo = || async {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            Ok::<(), anyhow::Error>(())
+        };
+
+        let started = std::time::Instant::now();
+        let err =
+            SkillService::download_with_timeout(&repo, SkillService::list_timeout(), slow_repo())
+                .await
+                .expect_err("listing should time out");
+        assert!(started.elapsed() < Duration::from_secs(3));
+        let parsed: Value = serde_json::from_str(&err.to_string()).expect("error json");
+        assert_eq!(parsed["code"], "DOWNLOAD_TIMEOUT");
+        assert_eq!(parsed["context"]["timeout"], "1");
+
+        // 同一个慢仓库在安装超时（默认 180 秒）内不会被提前中断
+        assert_eq!(SkillService::install_timeout(), Duration::from_secs(180));
+        SkillService::download_with_timeout(&repo, SkillService::install_timeout(), slow_repo())
+            .await
+            .expect("install should wait for the slow repo");
+        std::env::remove_var("SKILLS_LIST_TIMEOUT_SECS");
+    }
+
+    #[test]
+    fn test_archive_candidates_prefers_pinned_ref() {
+        let mut repo = SkillRepo {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            branch: "main".to_string(),
+            git_ref: Some(" 0123abcd ".to_string()),
+            enabled: true,
+            skills_path: None,
+            exclude: Vec::new(),
+        };
+
+        let pinned = SkillService::archive_candidates(&repo);
+        assert_eq!(
+            pinned,
+            vec![(
+                "0123abcd".to_string(),
+                "https://github.com/owner/repo/archive/0123abcd.zip".to_string()
+            )]
+        );
+        assert_eq!(SkillService::cache_key(&repo), "owner/repo/0123abcd");
+
+        repo.git_ref = Some("  ".to_string());
+        let branches = SkillService::archive_candidates_with_fallbacks(
+            &repo,
+            &SkillService::parse_fallback_branches(None),
+            DEFAULT_ARCHIVE_BASE_URL,
+        );
+        // 仓库分支与默认回退分支 main 重复时只尝试一次
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].0, "main");
+        assert_eq!(branches[1].0, "master");
+        assert_eq!(
+            branches[0].1,
+            "https://github.com/owner/repo/archive/refs/heads/main.zip"
+        );
+    }
+
+    #[test]
+    fn test_archive_candidates_use_configured_fallback_branches() {
+        let repo = SkillRepo {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            branch: "feature".to_string(),
+            git_ref: None,
+            enabled: true,
+            skills_path: None,
+            exclude: Vec::new(),
+        };
+
+        let fallbacks = SkillService::parse_fallback_branches(Some(" develop, ,trunk "));
+        assert_eq!(fallbacks, vec!["develop", "trunk"]);
+        let candidates = SkillService::archive_candidates_with_fallbacks(
+            &repo,
+            &fallbacks,
+            DEFAULT_ARCHIVE_BASE_URL,
+        );
+        let refs: Vec<&str> = candidates.iter().map(|(r, _)| r.as_str()).collect();
+        assert_eq!(refs, vec!["feature", "develop", "trunk"]);
+        assert_eq!(
+            candidates[1].1,
+            "https://github.com/owner/repo/archive/refs/heads/develop.zip"
+        );
+
+        assert_eq!(
+            SkillService::parse_fallback_branches(Some("  ")),
+            vec!["main", "master"]
+        );
+    }
+
     #[test]
     fn test_install_from_source_respects_force() {
         let temp_dir = tempfile::tempdir().expect("temp dir should exist");
@@ -2043,6 +3547,105 @@ description: Useful skill
         assert_eq!(parsed["code"], "SKILL_PATH_INVALID");
     }
 
+    #[test]
+    fn test_scan_respects_configured_max_depth() {
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let root = temp_dir.path().join("repo");
+        let mut skill_dir = root.clone();
+        for level in 0..(DEFAULT_MAX_SKILL_SCAN_DEPTH + 4) {
+            skill_dir = skill_dir.join(format!("d{level}"));
+        }
+        fs::create_dir_all(&skill_dir).expect("should create nested dir");
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: Deep Skill\ndescription: nested\n---\n",
+        )
+        .expect("should write skill metadata");
+
+        let service = build_service_with_install_dir(temp_dir.path().to_path_buf());
+        let repo = SkillRepo {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            branch: "main".to_string(),
+            git_ref: None,
+            enabled: true,
+            skills_path: None,
+            exclude: Vec::new(),
+        };
+
+        let mut skills = Vec::new();
+        service
+            .scan_skills_recursive(&root, &root, &repo, None, &[], &mut skills)
+            .expect("scan should succeed");
+        assert!(
+            skills.is_empty(),
+            "default depth should not reach the skill"
+        );
+
+        env::set_var("SKILLS_MAX_SCAN_DEPTH", "64");
+        let mut skills = Vec::new();
+        let result = service.scan_skills_recursive(&root, &root, &repo, None, &[], &mut skills);
+        env::remove_var("SKILLS_MAX_SCAN_DEPTH");
+        result.expect("scan should succeed");
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].name, "Deep Skill");
+    }
+
+    #[test]
+    fn test_glob_match_supports_wildcards() {
+        assert!(SkillService::glob_match("examples", "examples"));
+        assert!(SkillService::glob_match("test*", "tests"));
+        assert!(!SkillService::glob_match("*", "a/b"));
+        assert!(SkillService::glob_match("docs/**", "docs/a/b"));
+        assert!(SkillService::glob_match("**/fixtures", "fixtures"));
+        assert!(SkillService::glob_match("**/fixtures", "a/b/fixtures"));
+        assert!(SkillService::glob_match("v?", "v1"));
+        assert!(!SkillService::glob_match("v?", "v10"));
+    }
+
+    #[test]
+    fn test_scan_skips_excluded_directories() {
+        let temp_dir = tempfile::tempdir().expect("temp dir should exist");
+        let root = temp_dir.path().join("repo");
+        let write_skill = |relative: &str, name: &str| {
+            let dir = root.join(relative);
+            fs::create_dir_all(&dir).expect("should create skill dir");
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: demo\n---\n"),
+            )
+            .expect("should write skill metadata");
+        };
+        write_skill("real-skill", "Real");
+        write_skill("examples/sample-skill", "Example");
+        write_skill("packages/demo/tests/fixture-skill", "Fixture");
+        fs::write(root.join(".skillignore"), "# 测试夹具\n**/tests\n").expect("write ignore file");
+
+        let service = build_service_with_install_dir(temp_dir.path().to_path_buf());
+        let repo = SkillRepo {
+            owner: "owner".to_string(),
+            name: "repo".to_string(),
+            branch: "main".to_string(),
+            git_ref: None,
+            enabled: true,
+            skills_path: None,
+            exclude: vec!["examples".to_string()],
+        };
+        let excludes = SkillService::collect_scan_excludes(&repo, &root);
+        assert_eq!(
+            excludes,
+            vec!["examples".to_string(), "**/tests".to_string()]
+        );
+
+        let mut skills = Vec::new();
+        service
+            .scan_skills_recursive(&root, &root, &repo, None, &excludes, &mut skills)
+            .expect("scan should succeed");
+
+        let names: Vec<&str> = skills.iter().map(|skill| skill.name.as_str()).collect();
+        assert_eq!(names, vec!["Real"]);
+    }
+
     #[test]
     fn test_scan_root_skill_md() {
         let temp_dir = tempfile::tempdir().expect("temp dir should exist");
@@ -2060,8 +3663,10 @@ description: Root level skill
             owner: "owner".to_string(),
             name: "repo".to_string(),
             branch: "main".to_string(),
+            git_ref: None,
             enabled: true,
             skills_path: Some("skills/foo".to_string()),
+            exclude: Vec::new(),
         };
         let mut skills = Vec::new();
 
@@ -2071,6 +3676,7 @@ description: Root level skill
                 &skill_dir,
                 &repo,
                 Some("skills/foo"),
+                &[],
                 &mut skills,
             )
             .expect("scan should succeed");
@@ -2115,4 +3721,55 @@ description: Root level skill
         assert!(dest_dir.path().join("skills/SKILL.md").is_file());
         assert!(dest_dir.path().join("README.md").is_file());
     }
+
+    #[test]
+    fn test_list_zip_entries_returns_manifest_without_extracting() {
+        let mut buffer = Vec::new();
+        {
+            let cursor = std::io::Cursor::new(&mut buffer);
+            let mut zip_writer = zip::ZipWriter::new(cursor);
+            let options: FileOptions<'_, ()> = FileOptions::default();
+            zip_writer
+                .add_directory("repo-main/skills/", options)
+                .expect("add skills dir");
+            zip_writer
+                .start_file("repo-main/skills/SKILL.md", options)
+                .expect("start skill file");
+            zip_writer
+                .write_all(b"---\nname: Skill\n---\n")
+                .expect("write skill file");
+            zip_writer
+                .start_file("repo-main/README.md", options)
+                .expect("start readme file");
+            zip_writer.write_all(b"readme").expect("write readme file");
+            zip_writer.finish().expect("finish zip");
+        }
+
+        let entries = SkillService::list_zip_entries(buffer, SkillService::zip_limits())
+            .expect("manifest should succeed");
+        let listed: Vec<(&str, u64)> = entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.size))
+            .collect();
+        assert_eq!(listed, vec![("README.md", 6), ("skills/SKILL.md", 20)]);
+
+        let mut buffer = Vec::new();
+        {
+            let cursor = std::io::Cursor::new(&mut buffer);
+            let mut zip_writer = zip::ZipWriter::new(cursor);
+            let options: FileOptions<'_, ()> = FileOptions::default();
+            zip_writer
+                .start_file("repo-main/ok.txt", options)
+                .expect("start ok file");
+            zip_writer.write_all(b"ok").expect("write ok file");
+            zip_writer
+                .start_file("../escape.txt", options)
+                .expect("start escape file");
+            zip_writer.write_all(b"x").expect("write escape file");
+            zip_writer.finish().expect("finish zip");
+        }
+        let err = SkillService::list_zip_entries(buffer, SkillService::zip_limits())
+            .expect_err("traversal entries must be rejected");
+        assert!(err.to_string().contains("INVALID_ARCHIVE_PATH"));
+    }
 }
@@ -1,26 +1,250 @@
-use crate::app_config::MultiAppConfig;
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::clock::{Clock, SystemClock};
 use crate::error::AppError;
-use std::sync::RwLock;
+use crate::provider::UsageTestRecord;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::Instant;
+
+/// 带 `Idempotency-Key` 的添加请求：(app, key) -> (预留时间, 请求体哈希, 结果)。结果为 `None` 表示首个请求仍在执行
+pub type IdempotencyMap = HashMap<(String, String), (Instant, String, Option<bool>)>;
+
+tokio::task_local! {
+    /// 当前请求 `If-Match` 允许的修订号；由 Web 层在处理请求期间设置，首次写入配置时校验并消费
+    static EXPECTED_REVISION: Cell<Option<Vec<u64>>>;
+}
+
+/// 用量脚本测试历史：(app, provider_id) -> 记录（最新在前）
+pub type UsageTestRecords = HashMap<(String, String), Vec<UsageTestRecord>>;
 
 /// 全局应用状态
 pub struct AppState {
     pub config: RwLock<MultiAppConfig>,
+    idempotency: Mutex<IdempotencyMap>,
+    usage_tests: Mutex<UsageTestRecords>,
+    clock: Arc<dyn Clock>,
+    live_locks: LiveLocks,
+    tasks: Arc<TaskRegistry>,
 }
 
 impl AppState {
+    /// 以已加载的配置创建应用状态
+    pub fn new(config: MultiAppConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            idempotency: Mutex::new(HashMap::new()),
+            usage_tests: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+            live_locks: LiveLocks::default(),
+            tasks: Arc::new(TaskRegistry::default()),
+        }
+    }
+
+    /// 替换时间来源（测试注入固定时钟）
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 时间来源：备份 ID、技能安装时间等时间戳统一从这里取值
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
     /// 创建新的应用状态
     /// 注意：仅在配置成功加载时返回；不会在失败时回退默认值。
     pub fn try_new() -> Result<Self, AppError> {
         let config = MultiAppConfig::load()?;
-        Ok(Self {
-            config: RwLock::new(config),
-        })
+        Ok(Self::new(config))
     }
 
-    /// 保存配置到文件
+    /// 保存配置到文件，成功时递增修订号
     pub fn save(&self) -> Result<(), AppError> {
-        let config = self.config.read().map_err(AppError::from)?;
+        let mut config = self.config.write().map_err(AppError::from)?;
+
+        // 与递增修订号处于同一写锁内，携带相同 `If-Match` 的并发写入只有一个成功
+        check_expected_revision(config.revision)?;
+        config.revision += 1;
+        if let Err(err) = config.save() {
+            config.revision -= 1;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// 在 `fut` 执行期间启用条件写入：其中首次写入配置时，当前修订号必须是 `allowed` 之一，否则返回冲突错误
+    pub async fn with_expected_revision<F: Future>(allowed: Vec<u64>, fut: F) -> F::Output {
+        EXPECTED_REVISION.scope(Cell::new(Some(allowed)), fut).await
+    }
+
+    /// 添加供应商的幂等记录（仅保存在内存中）
+    ///
+    /// 调用方只应在检查与预留期间持有该锁，不要跨越实际的添加操作。
+    pub fn idempotency(&self) -> MutexGuard<'_, IdempotencyMap> {
+        self.idempotency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 用量脚本测试历史（仅用于调试，不写入配置文件）
+    pub fn usage_tests(&self) -> MutexGuard<'_, UsageTestRecords> {
+        self.usage_tests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 后台任务注册表
+    ///
+    /// 服务按请求新建、后台任务也不持有 `AppState`，因此返回共享引用，由 `SkillService` 等在创建时持有。
+    pub fn tasks(&self) -> Arc<TaskRegistry> {
+        self.tasks.clone()
+    }
+
+    /// 获取指定应用的 live 文件写锁：同一应用的写入串行，不同应用互不阻塞
+    ///
+    /// 所有写 live 文件（供应商配置、MCP、提示词）的入口都要先取此锁，再取 `config` 锁，否则会与切换流程死锁。
+    pub async fn lock_live(&self, app_type: &AppType) -> tokio::sync::MutexGuard<'_, ()> {
+        self.live_locks.get(app_type).lock().await
+    }
+
+    /// 按固定顺序获取全部应用的 live 文件写锁，用于一次写入多个应用
+    pub async fn lock_all_live(&self) -> Vec<tokio::sync::MutexGuard<'_, ()>> {
+        let mut guards = Vec::with_capacity(LIVE_APPS.len());
+        for app_type in LIVE_APPS.iter() {
+            guards.push(self.lock_live(app_type).await);
+        }
+        guards
+    }
+}
+
+/// `lock_all_live` 的加锁顺序
+const LIVE_APPS: [AppType; 5] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::Opencode,
+    AppType::Omo,
+];
+
+/// 各应用 live 文件的写锁；持锁期间会跨越 `await`，因此使用 `tokio::sync::Mutex`
+#[derive(Default)]
+struct LiveLocks {
+    claude: tokio::sync::Mutex<()>,
+    codex: tokio::sync::Mutex<()>,
+    gemini: tokio::sync::Mutex<()>,
+    opencode: tokio::sync::Mutex<()>,
+    omo: tokio::sync::Mutex<()>,
+}
+
+impl LiveLocks {
+    fn get(&self, app_type: &AppType) -> &tokio::sync::Mutex<()> {
+        match app_type {
+            AppType::Claude => &self.claude,
+            AppType::Codex => &self.codex,
+            AppType::Gemini => &self.gemini,
+            AppType::Opencode => &self.opencode,
+            AppType::Omo => &self.omo,
+        }
+    }
+}
+
+/// 校验当前请求的条件写入，须在持有 `config` 写锁、即将递增修订号时调用
+///
+/// 期望值只校验一次：同一请求中后续的保存（含失败后的回滚）不再受限。
+pub(crate) fn check_expected_revision(current: u64) -> Result<(), AppError> {
+    let allowed = EXPECTED_REVISION
+        .try_with(|slot| slot.take())
+        .ok()
+        .flatten();
+    match allowed {
+        Some(allowed) if !allowed.contains(&current) => Err(AppError::localized(
+            "config.revision_conflict",
+            format!("配置已被修改（当前修订号 {current}），请刷新后重试"),
+            format!("Config has been modified (current revision {current}); reload and retry"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 后台任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackgroundTaskKind {
+    SkillInstall,
+    SkillRepoRefresh,
+}
+
+/// 进行中的后台任务
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundTask {
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub kind: BackgroundTaskKind,
+    /// 任务对象，如技能的 `app:directory` 或仓库的缓存键
+    pub target: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// 进行中后台任务的注册表，用于排查卡住的安装等问题
+#[derive(Default)]
+pub struct TaskRegistry {
+    next_id: AtomicU64,
+    tasks: Mutex<BTreeMap<u64, BackgroundTask>>,
+}
+
+impl TaskRegistry {
+    /// 登记任务；返回的 guard 被丢弃（任务结束、取消或 panic）时自动注销
+    pub fn register(
+        self: &Arc<Self>,
+        kind: BackgroundTaskKind,
+        target: impl Into<String>,
+    ) -> TaskGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let task = BackgroundTask {
+            id,
+            kind,
+            target: target.into(),
+            started_at: Utc::now(),
+        };
+        self.tasks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(id, task);
+        TaskGuard {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    /// 按启动顺序列出进行中的任务
+    pub fn list(&self) -> Vec<BackgroundTask> {
+        self.tasks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// 任务登记凭证，drop 时从注册表移除
+pub struct TaskGuard {
+    registry: Arc<TaskRegistry>,
+    id: u64,
+}
 
-        config.save()
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.registry
+            .tasks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.id);
     }
 }
@@ -3,7 +3,7 @@ use reqwest::{redirect::Policy, Client};
 use rquickjs::{Context, Function, Runtime};
 use serde_json::Value;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     env,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     time::{Duration, Instant},
@@ -15,30 +15,112 @@ use crate::error::AppError;
 
 const JS_MEMORY_LIMIT_BYTES: usize = 32 * 1024 * 1024; // 32MB 上限，防止脚本占用过大内存
 const JS_MAX_STACK_SIZE: usize = 512 * 1024; // 512KB 调用栈
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 1_048_576;
+const MIN_TIMEOUT_SECS: u64 = 2;
+const DEFAULT_MAX_TIMEOUT_SECS: u64 = 30;
+/// `USAGE_SCRIPT_MAX_TIMEOUT_SECS` 的封顶值，配置得再大单次执行也不超过 10 分钟
+const HARD_MAX_TIMEOUT_SECS: u64 = 600;
+
+/// 用量脚本中可用的模板变量（脚本里写作 `{{name}}`）
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct TemplateVariable {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// 模板变量的唯一定义：替换逻辑按此顺序取值（见 [`TemplateValues::ordered`]），接口也直接返回此列表
+pub const TEMPLATE_VARIABLES: [TemplateVariable; 6] = [
+    TemplateVariable {
+        name: "apiKey",
+        description: "API key configured for the usage script",
+    },
+    TemplateVariable {
+        name: "baseUrl",
+        description: "Base URL configured for the usage script",
+    },
+    TemplateVariable {
+        name: "accessToken",
+        description: "Access token (left untouched when not configured)",
+    },
+    TemplateVariable {
+        name: "userId",
+        description: "User ID (left untouched when not configured)",
+    },
+    TemplateVariable {
+        name: "providerName",
+        description: "Display name of the provider (left untouched when unknown)",
+    },
+    TemplateVariable {
+        name: "model",
+        description: "Model configured in the provider settings (left untouched when not set)",
+    },
+];
+
+/// 模板变量的取值；为 `None` 的变量不替换，占位符保持原样
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemplateValues<'a> {
+    pub api_key: &'a str,
+    pub base_url: &'a str,
+    pub access_token: Option<&'a str>,
+    pub user_id: Option<&'a str>,
+    pub provider_name: Option<&'a str>,
+    pub model: Option<&'a str>,
+}
+
+impl<'a> TemplateValues<'a> {
+    /// 与 [`TEMPLATE_VARIABLES`] 顺序一致的取值
+    fn ordered(&self) -> [Option<&'a str>; TEMPLATE_VARIABLES.len()] {
+        [
+            Some(self.api_key),
+            Some(self.base_url),
+            self.access_token,
+            self.user_id,
+            self.provider_name,
+            self.model,
+        ]
+    }
+}
+
+fn substitute_template_variables(script_code: &str, values: &TemplateValues<'_>) -> String {
+    let mut replaced = script_code.to_string();
+    for (variable, value) in TEMPLATE_VARIABLES.iter().zip(values.ordered()) {
+        if let Some(value) = value {
+            replaced = replaced.replace(&format!("{{{{{}}}}}", variable.name), value);
+        }
+    }
+    replaced
+}
 
 /// 执行用量查询脚本
 pub async fn execute_usage_script(
     script_code: &str,
-    api_key: &str,
-    base_url: &str,
+    values: &TemplateValues<'_>,
     timeout_secs: u64,
-    access_token: Option<&str>,
-    user_id: Option<&str>,
 ) -> Result<Value, AppError> {
-    // 1. 替换变量
-    let mut replaced = script_code
-        .replace("{{apiKey}}", api_key)
-        .replace("{{baseUrl}}", base_url);
+    run_usage_script(script_code, values, timeout_secs, None).await
+}
 
-    // 替换 accessToken 和 userId
-    if let Some(token) = access_token {
-        replaced = replaced.replace("{{accessToken}}", token);
-    }
-    if let Some(uid) = user_id {
-        replaced = replaced.replace("{{userId}}", uid);
-    }
+/// 执行用量查询脚本（测试模式）：同时收集白名单内的响应头，请求失败时同样保留，便于排查
+pub async fn execute_usage_script_capturing_headers(
+    script_code: &str,
+    values: &TemplateValues<'_>,
+    timeout_secs: u64,
+    response_headers: &mut BTreeMap<String, String>,
+) -> Result<Value, AppError> {
+    run_usage_script(script_code, values, timeout_secs, Some(response_headers)).await
+}
 
-    let script_source = replaced; // 复用同一份字符串，避免重复 clone
+async fn run_usage_script(
+    script_code: &str,
+    values: &TemplateValues<'_>,
+    timeout_secs: u64,
+    response_headers: Option<&mut BTreeMap<String, String>>,
+) -> Result<Value, AppError> {
+    // JS 中断期限与 HTTP 超时共用同一个约束后的值
+    let timeout_secs = resolve_timeout_secs(timeout_secs, max_timeout_secs());
+
+    // 1. 替换变量
+    let script_source = substitute_template_variables(script_code, values);
 
     // 2. 在独立作用域中提取 request 配置（确保 Runtime/Context 在 await 前释放）
     let request_config = {
@@ -110,7 +192,7 @@ pub async fn execute_usage_script(
     })?;
 
     // 4. 发送 HTTP 请求
-    let response_data = send_http_request(&request, timeout_secs).await?;
+    let response_data = send_http_request(&request, timeout_secs, response_headers).await?;
 
     // 5. 在独立作用域中执行 extractor（确保 Runtime/Context 在函数结束前释放）
     let result: Value = {
@@ -216,7 +298,11 @@ struct RequestConfig {
 }
 
 /// 发送 HTTP 请求
-async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<String, AppError> {
+async fn send_http_request(
+    config: &RequestConfig,
+    timeout_secs: u64,
+    response_headers: Option<&mut BTreeMap<String, String>>,
+) -> Result<String, AppError> {
     let url = validate_request_url(&config.url).await?;
 
     let max_body_bytes = parse_env_usize("USAGE_SCRIPT_MAX_BODY_BYTES", 65_536);
@@ -230,33 +316,7 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
         }
     }
 
-    let max_header_count = parse_env_usize("USAGE_SCRIPT_MAX_HEADER_COUNT", 32);
-    if config.headers.len() > max_header_count {
-        return Err(AppError::localized(
-            "usage_script.header_count_exceeded",
-            format!(
-                "请求头数量超过限制: {} / {}",
-                config.headers.len(),
-                max_header_count
-            ),
-            format!(
-                "Request header count exceeds limit: {} / {}",
-                config.headers.len(),
-                max_header_count
-            ),
-        ));
-    }
-
-    for name in config.headers.keys() {
-        let normalized = name.trim().to_ascii_lowercase();
-        if is_forbidden_header_name(&normalized) {
-            return Err(AppError::localized(
-                "usage_script.forbidden_header",
-                format!("不允许设置请求头: {name}"),
-                format!("Forbidden header name: {name}"),
-            ));
-        }
-    }
+    validate_request_headers(&config.headers)?;
 
     let allow_redirects = env_flag("USAGE_SCRIPT_ALLOW_REDIRECTS");
     let redirect_policy = if allow_redirects {
@@ -265,10 +325,8 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
         Policy::none()
     };
 
-    // 约束超时范围，防止异常配置导致长时间阻塞
-    let timeout = timeout_secs.clamp(2, 30);
     let client = Client::builder()
-        .timeout(Duration::from_secs(timeout))
+        .timeout(Duration::from_secs(timeout_secs))
         .redirect(redirect_policy)
         .build()
         .map_err(|e| {
@@ -347,7 +405,13 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
     })?;
 
     let status = resp.status();
-    let max_response_bytes = parse_env_usize("USAGE_SCRIPT_MAX_RESPONSE_BYTES", 1_048_576);
+    if let Some(captured) = response_headers {
+        capture_passthrough_headers(resp.headers(), captured);
+    }
+    let max_response_bytes = parse_env_usize(
+        "USAGE_SCRIPT_MAX_RESPONSE_BYTES",
+        DEFAULT_MAX_RESPONSE_BYTES,
+    );
     let text = read_response_body(resp, max_response_bytes).await?;
 
     if !status.is_success() {
@@ -373,6 +437,185 @@ async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<
     Ok(text)
 }
 
+/// 用量脚本语法与结构检查结果
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UsageScriptParseResult {
+    pub ok: bool,
+    pub errors: Vec<String>,
+}
+
+/// 仅检查脚本语法与结构：在沙箱中求值，要求得到带 `request` 对象的配置，
+/// `extractor` 存在时必须是函数。不替换模板变量，也不发起 HTTP 请求。
+pub fn parse_usage_script(script_code: &str) -> UsageScriptParseResult {
+    let errors = check_script_shape(script_code).unwrap_or_else(|err| vec![err.to_string()]);
+    UsageScriptParseResult {
+        ok: errors.is_empty(),
+        errors,
+    }
+}
+
+fn check_script_shape(script_code: &str) -> Result<Vec<String>, AppError> {
+    let runtime = build_sandboxed_runtime(MIN_TIMEOUT_SECS)?;
+    let context = Context::full(&runtime).map_err(|e| {
+        AppError::localized(
+            "usage_script.context_create_failed",
+            format!("创建 JS 上下文失败: {e}"),
+            format!("Failed to create JS context: {e}"),
+        )
+    })?;
+
+    context.with(|ctx| {
+        let value: rquickjs::Value = ctx.eval(script_code).map_err(|e| {
+            AppError::localized(
+                "usage_script.config_parse_failed",
+                format!("解析配置失败: {e}"),
+                format!("Failed to parse config: {e}"),
+            )
+        })?;
+        let is_plain_object = |value: &rquickjs::Value| {
+            value.is_object() && !value.is_function() && !value.is_array()
+        };
+        let Some(config) = value.as_object().filter(|_| is_plain_object(&value)) else {
+            return Ok(vec![AppError::localized(
+                "usage_script.config_not_object",
+                "脚本必须返回配置对象",
+                "Script must evaluate to a config object",
+            )
+            .to_string()]);
+        };
+
+        let mut errors = Vec::new();
+        let request: rquickjs::Value = config.get("request").map_err(|e| {
+            AppError::localized(
+                "usage_script.request_missing",
+                format!("缺少 request 配置: {e}"),
+                format!("Missing request config: {e}"),
+            )
+        })?;
+        if !is_plain_object(&request) {
+            errors.push(
+                AppError::localized(
+                    "usage_script.request_missing",
+                    "缺少 request 配置对象",
+                    "Missing request config object",
+                )
+                .to_string(),
+            );
+        }
+
+        let extractor: rquickjs::Value = config.get("extractor").map_err(|e| {
+            AppError::localized(
+                "usage_script.extractor_invalid",
+                format!("读取 extractor 失败: {e}"),
+                format!("Failed to read extractor: {e}"),
+            )
+        })?;
+        if !extractor.is_undefined() && !extractor.is_function() {
+            errors.push(
+                AppError::localized(
+                    "usage_script.extractor_invalid",
+                    "extractor 必须是函数",
+                    "extractor must be a function",
+                )
+                .to_string(),
+            );
+        }
+
+        Ok(errors)
+    })
+}
+
+/// URL 可达性探测结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlProbeResult {
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 探测 HTTP/SSE 端点是否可达：先经过与用量脚本相同的出站校验，再发送 HEAD（不支持时回退 GET）
+///
+/// 只要收到任意 HTTP 响应即视为可达；被出站策略拦截时返回错误。
+pub async fn probe_url(
+    raw_url: &str,
+    headers: &HashMap<String, String>,
+    timeout_secs: u64,
+) -> Result<UrlProbeResult, AppError> {
+    let url = validate_request_url(raw_url).await?;
+    validate_request_headers(headers)?;
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(timeout_secs.clamp(1, 30)))
+        .redirect(Policy::none())
+        .build()
+        .map_err(|e| {
+            AppError::localized(
+                "usage_script.client_create_failed",
+                format!("创建客户端失败: {e}"),
+                format!("Failed to create client: {e}"),
+            )
+        })?;
+
+    let send = |method: reqwest::Method| {
+        let mut req = client.request(method, url.clone());
+        for (k, v) in headers {
+            req = req.header(k, v);
+        }
+        req.send()
+    };
+
+    let result = match send(reqwest::Method::HEAD).await {
+        Ok(resp)
+            if matches!(
+                resp.status(),
+                reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED
+            ) =>
+        {
+            send(reqwest::Method::GET).await
+        }
+        other => other,
+    };
+
+    Ok(match result {
+        Ok(resp) => UrlProbeResult {
+            reachable: true,
+            status: Some(resp.status().as_u16()),
+            error: None,
+        },
+        Err(e) => UrlProbeResult {
+            reachable: false,
+            status: None,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+/// 经过与用量脚本相同的出站校验后 GET 指定 URL，并将响应解析为 JSON（用于从 gist/raw 链接导入配置）
+pub async fn fetch_json(raw_url: &str, timeout_secs: u64) -> Result<Value, AppError> {
+    let config = RequestConfig {
+        url: raw_url.to_string(),
+        method: "GET".to_string(),
+        headers: HashMap::new(),
+        body: None,
+    };
+    let text = send_http_request(
+        &config,
+        resolve_timeout_secs(timeout_secs, max_timeout_secs()),
+        None,
+    )
+    .await?;
+    serde_json::from_str(&text).map_err(|e| {
+        AppError::localized(
+            "usage_script.response_not_json",
+            format!("响应不是有效的 JSON: {e}"),
+            format!("Response is not valid JSON: {e}"),
+        )
+    })
+}
+
 async fn read_response_body(resp: reqwest::Response, max_bytes: usize) -> Result<String, AppError> {
     let mut stream = resp.bytes_stream();
     let mut buf = Vec::new();
@@ -399,6 +642,38 @@ async fn read_response_body(resp: reqwest::Response, max_bytes: usize) -> Result
     Ok(String::from_utf8_lossy(&buf).to_string())
 }
 
+/// 测试模式下回传的响应头：仅限请求 ID 与限流相关信息，绝不包含 set-cookie 或鉴权回显
+const PASSTHROUGH_HEADERS: [&str; 3] = ["x-request-id", "request-id", "retry-after"];
+const PASSTHROUGH_HEADER_PREFIXES: [&str; 2] = ["ratelimit-", "x-ratelimit-"];
+const PASSTHROUGH_HEADER_MAX_VALUE_LEN: usize = 256;
+
+fn is_passthrough_header(name: &str) -> bool {
+    PASSTHROUGH_HEADERS.contains(&name)
+        || PASSTHROUGH_HEADER_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+fn capture_passthrough_headers(
+    headers: &reqwest::header::HeaderMap,
+    captured: &mut BTreeMap<String, String>,
+) {
+    for (name, value) in headers {
+        let name = name.as_str();
+        if !is_passthrough_header(name) {
+            continue;
+        }
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        let value: String = value
+            .chars()
+            .take(PASSTHROUGH_HEADER_MAX_VALUE_LEN)
+            .collect();
+        captured.insert(name.to_string(), value);
+    }
+}
+
 fn parse_env_usize(name: &str, default: usize) -> usize {
     env::var(name)
         .ok()
@@ -406,12 +681,64 @@ fn parse_env_usize(name: &str, default: usize) -> usize {
         .unwrap_or(default)
 }
 
+/// 约束超时范围，防止异常配置导致长时间阻塞
+fn resolve_timeout_secs(timeout_secs: u64, ceiling: u64) -> u64 {
+    timeout_secs.clamp(MIN_TIMEOUT_SECS, ceiling)
+}
+
+/// 超时上限，可通过 `USAGE_SCRIPT_MAX_TIMEOUT_SECS` 调整
+fn max_timeout_secs() -> u64 {
+    timeout_ceiling(
+        env::var("USAGE_SCRIPT_MAX_TIMEOUT_SECS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok()),
+    )
+}
+
+fn timeout_ceiling(configured: Option<u64>) -> u64 {
+    configured
+        .unwrap_or(DEFAULT_MAX_TIMEOUT_SECS)
+        .clamp(MIN_TIMEOUT_SECS, HARD_MAX_TIMEOUT_SECS)
+}
+
 fn env_flag(name: &str) -> bool {
     env::var(name)
         .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
         .unwrap_or(false)
 }
 
+fn validate_request_headers(headers: &HashMap<String, String>) -> Result<(), AppError> {
+    let max_header_count = parse_env_usize("USAGE_SCRIPT_MAX_HEADER_COUNT", 32);
+    if headers.len() > max_header_count {
+        return Err(AppError::localized(
+            "usage_script.header_count_exceeded",
+            format!(
+                "请求头数量超过限制: {} / {}",
+                headers.len(),
+                max_header_count
+            ),
+            format!(
+                "Request header count exceeds limit: {} / {}",
+                headers.len(),
+                max_header_count
+            ),
+        ));
+    }
+
+    let extra_forbidden = parse_extra_forbidden_headers().unwrap_or_default();
+    for name in headers.keys() {
+        let normalized = name.trim().to_ascii_lowercase();
+        if is_forbidden_header_name(&normalized) || extra_forbidden.contains(&normalized) {
+            return Err(AppError::localized(
+                "usage_script.forbidden_header",
+                format!("不允许设置请求头: {name}"),
+                format!("Forbidden header name: {name}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn is_forbidden_header_name(name: &str) -> bool {
     matches!(
         name,
@@ -425,12 +752,56 @@ fn is_forbidden_header_name(name: &str) -> bool {
     )
 }
 
+/// `USAGE_SCRIPT_EXTRA_FORBIDDEN_HEADERS`（逗号分隔）在内置列表之外追加禁止的请求头，不会放开内置项
+fn parse_extra_forbidden_headers() -> Option<Vec<String>> {
+    let value = env::var("USAGE_SCRIPT_EXTRA_FORBIDDEN_HEADERS").ok()?;
+    let entries = value
+        .split(',')
+        .map(|entry| entry.trim().to_ascii_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        None
+    } else {
+        Some(entries)
+    }
+}
+
 #[derive(Clone, Copy)]
 enum EgressPolicy {
     Strict,
     Trusted,
 }
 
+/// 当前生效的出站请求开关（供运行时诊断使用）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressFlags {
+    pub egress_policy: &'static str,
+    pub allowed_hosts: Option<Vec<String>>,
+    pub extra_forbidden_headers: Option<Vec<String>>,
+    pub allow_redirects: bool,
+    pub max_response_bytes: usize,
+    pub max_timeout_secs: u64,
+}
+
+pub fn egress_flags() -> EgressFlags {
+    EgressFlags {
+        egress_policy: match parse_egress_policy() {
+            EgressPolicy::Strict => "strict",
+            EgressPolicy::Trusted => "trusted",
+        },
+        allowed_hosts: parse_allowed_hosts(),
+        extra_forbidden_headers: parse_extra_forbidden_headers(),
+        allow_redirects: env_flag("USAGE_SCRIPT_ALLOW_REDIRECTS"),
+        max_response_bytes: parse_env_usize(
+            "USAGE_SCRIPT_MAX_RESPONSE_BYTES",
+            DEFAULT_MAX_RESPONSE_BYTES,
+        ),
+        max_timeout_secs: max_timeout_secs(),
+    }
+}
+
 async fn validate_request_url(raw_url: &str) -> Result<Url, AppError> {
     let url = Url::parse(raw_url).map_err(|e| {
         AppError::localized(
@@ -469,7 +840,10 @@ async fn validate_request_url(raw_url: &str) -> Result<Url, AppError> {
     let allowed_hosts = parse_allowed_hosts();
     if let Some(allowed) = allowed_hosts {
         let normalized_host = host_str.trim_end_matches('.').to_ascii_lowercase();
-        if !allowed.iter().any(|entry| entry == &normalized_host) {
+        if !allowed
+            .iter()
+            .any(|entry| host_matches_allowed(&normalized_host, entry))
+        {
             return Err(AppError::localized(
                 "usage_script.url_host_not_allowed",
                 format!("主机名不在允许列表中: {host_str}"),
@@ -521,6 +895,18 @@ fn parse_allowed_hosts() -> Option<Vec<String>> {
     }
 }
 
+/// 判断主机名是否命中允许列表条目（均已小写且去掉末尾的点）
+/// `*.example.com` 匹配任意层级的子域名，但不匹配 `example.com` 本身
+fn host_matches_allowed(host: &str, entry: &str) -> bool {
+    match entry.strip_prefix("*.") {
+        Some(suffix) if !suffix.is_empty() => host
+            .strip_suffix(suffix)
+            .and_then(|prefix| prefix.strip_suffix('.'))
+            .is_some_and(|label| !label.is_empty()),
+        _ => host == entry,
+    }
+}
+
 async fn resolve_host_ips(host: &str, port: u16) -> Result<Vec<IpAddr>, AppError> {
     let resolved = lookup_host((host, port)).await.map_err(|e| {
         AppError::localized(
@@ -637,9 +1023,11 @@ fn build_sandboxed_runtime(timeout_secs: u64) -> Result<Runtime, AppError> {
     runtime.set_memory_limit(JS_MEMORY_LIMIT_BYTES);
     runtime.set_max_stack_size(JS_MAX_STACK_SIZE);
 
-    let bounded = timeout_secs.clamp(2, 30);
-    let max_ms = bounded * 1_000;
-    let deadline = Instant::now() + Duration::from_millis(max_ms);
+    let max_ms = timeout_secs.saturating_mul(1_000);
+    let now = Instant::now();
+    let deadline = now
+        .checked_add(Duration::from_millis(max_ms))
+        .unwrap_or_else(|| now + Duration::from_secs(HARD_MAX_TIMEOUT_SECS));
     runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() >= deadline)));
 
     Ok(runtime)
@@ -754,3 +1142,101 @@ fn validate_single_usage(result: &Value) -> Result<(), AppError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitution_covers_every_listed_variable() {
+        let script = TEMPLATE_VARIABLES
+            .iter()
+            .map(|variable| format!("{{{{{}}}}}", variable.name))
+            .collect::<Vec<_>>()
+            .join(",");
+        let values = TemplateValues {
+            api_key: "k",
+            base_url: "u",
+            access_token: Some("t"),
+            user_id: Some("i"),
+            provider_name: Some("p"),
+            model: Some("m"),
+        };
+        assert_eq!(
+            substitute_template_variables(&script, &values),
+            "k,u,t,i,p,m"
+        );
+
+        let partial = TemplateValues {
+            api_key: "k",
+            base_url: "u",
+            ..Default::default()
+        };
+        assert_eq!(
+            substitute_template_variables(&script, &partial),
+            "k,u,{{accessToken}},{{userId}},{{providerName}},{{model}}"
+        );
+    }
+
+    #[test]
+    fn parse_accepts_well_formed_script_without_substitution() {
+        let result = parse_usage_script(
+            "({ request: { url: \"{{baseUrl}}/usage\", method: \"GET\" }, \
+             extractor: function (response) { return { isValid: true }; } })",
+        );
+        assert!(result.ok, "unexpected errors: {:?}", result.errors);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn parse_reports_syntax_and_shape_errors() {
+        let broken = parse_usage_script("({ request: { url: ");
+        assert!(!broken.ok);
+        assert_eq!(broken.errors.len(), 1);
+
+        let missing_request = parse_usage_script("({ extractor: 42 })");
+        assert!(!missing_request.ok);
+        assert_eq!(
+            missing_request.errors.len(),
+            2,
+            "{:?}",
+            missing_request.errors
+        );
+
+        let not_object = parse_usage_script("1 + 1");
+        assert!(!not_object.ok);
+    }
+
+    #[test]
+    fn timeout_ceiling_follows_config_within_bounds() {
+        assert_eq!(timeout_ceiling(None), 30);
+        assert_eq!(resolve_timeout_secs(60, timeout_ceiling(None)), 30);
+        assert_eq!(resolve_timeout_secs(0, timeout_ceiling(None)), 2);
+
+        let ceiling = timeout_ceiling(Some(120));
+        assert_eq!(resolve_timeout_secs(60, ceiling), 60);
+        assert_eq!(resolve_timeout_secs(600, ceiling), 120);
+        assert_eq!(resolve_timeout_secs(1, ceiling), 2);
+
+        // 上限低于下限时以下限为准，过大时封顶
+        assert_eq!(resolve_timeout_secs(60, timeout_ceiling(Some(1))), 2);
+        assert_eq!(timeout_ceiling(Some(u64::MAX)), 600);
+        assert_eq!(
+            resolve_timeout_secs(u64::MAX, timeout_ceiling(Some(u64::MAX))),
+            600
+        );
+    }
+
+    #[test]
+    fn allowed_hosts_support_exact_and_wildcard_entries() {
+        assert!(host_matches_allowed("api.example.com", "api.example.com"));
+        assert!(!host_matches_allowed("www.example.com", "api.example.com"));
+
+        assert!(host_matches_allowed("api.example.com", "*.example.com"));
+        assert!(host_matches_allowed("a.b.example.com", "*.example.com"));
+        assert!(!host_matches_allowed("example.com", "*.example.com"));
+        assert!(!host_matches_allowed("badexample.com", "*.example.com"));
+        assert!(!host_matches_allowed(".example.com", "*.example.com"));
+        assert!(!host_matches_allowed("example.com", "*."));
+    }
+}
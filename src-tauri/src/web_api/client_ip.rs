@@ -0,0 +1,239 @@
+//! 反向代理后的真实客户端 IP 解析
+//!
+//! 只有当直连对端命中 `TRUSTED_PROXIES`（逗号分隔的 CIDR 或单个 IP）时才读取
+//! `X-Forwarded-For` / `X-Real-IP`；不可信对端发来的这些头一律忽略，防止伪造。
+
+use std::{
+    env,
+    net::{IpAddr, SocketAddr},
+};
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{HeaderMap, Request},
+    middleware,
+    response::Response,
+};
+
+/// 解析后的客户端 IP，作为请求扩展供日志与限流使用
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// 可信代理网段列表
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    /// 从 `TRUSTED_PROXIES` 读取；未设置时为空（不信任任何转发头）
+    pub fn from_env() -> Self {
+        env::var("TRUSTED_PROXIES")
+            .map(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+
+    /// 解析逗号分隔的 CIDR 列表，无效条目记录警告后跳过
+    pub fn parse(raw: &str) -> Self {
+        let networks = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| {
+                let parsed = parse_cidr(item);
+                if parsed.is_none() {
+                    log::warn!("忽略无效的 TRUSTED_PROXIES 条目: {item}");
+                }
+                parsed
+            })
+            .collect();
+        Self { networks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.networks
+            .iter()
+            .any(|(network, prefix)| cidr_contains(*network, *prefix, ip))
+    }
+
+    /// 以规范形式输出（用于诊断快照）
+    pub fn describe(&self) -> Vec<String> {
+        self.networks
+            .iter()
+            .map(|(network, prefix)| format!("{network}/{prefix}"))
+            .collect()
+    }
+}
+
+fn parse_cidr(item: &str) -> Option<(IpAddr, u8)> {
+    let (addr, prefix) = match item.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (item, None),
+    };
+    let addr: IpAddr = addr.trim().parse().ok()?;
+    let addr = addr.to_canonical();
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix.trim().parse::<u8>().ok().filter(|p| *p <= max)?,
+        None => max,
+    };
+    Some((addr, prefix))
+}
+
+fn cidr_contains(network: IpAddr, prefix: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+/// 转发头中的单个地址，兼容 `1.2.3.4:5678` 与 `[::1]:80` 这类带端口的写法
+fn parse_hop(raw: &str) -> Option<IpAddr> {
+    let raw = raw.trim().trim_matches('"');
+    raw.parse::<IpAddr>()
+        .ok()
+        .or_else(|| raw.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .map(|ip| ip.to_canonical())
+}
+
+/// 计算客户端 IP：对端不可信时直接使用对端地址；
+/// 否则沿 `X-Forwarded-For` 从右向左跳过可信代理，取第一个不可信的地址（更左侧的条目可被客户端伪造）。
+/// 链中全部可信时取最左侧地址；没有 `X-Forwarded-For` 时回退到 `X-Real-IP`。
+pub fn resolve_client_ip(peer: IpAddr, headers: &HeaderMap, trusted: &TrustedProxies) -> IpAddr {
+    let peer = peer.to_canonical();
+    if !trusted.contains(peer) {
+        return peer;
+    }
+
+    let mut hops = Vec::new();
+    for value in headers.get_all("x-forwarded-for") {
+        let Ok(value) = value.to_str() else {
+            return peer;
+        };
+        for raw in value.split(',') {
+            // 无法解析的转发链不可信，退回对端地址
+            let Some(hop) = parse_hop(raw) else {
+                return peer;
+            };
+            hops.push(hop);
+        }
+    }
+
+    if hops.is_empty() {
+        return headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_hop)
+            .unwrap_or(peer);
+    }
+
+    hops.iter()
+        .rev()
+        .find(|hop| !trusted.contains(**hop))
+        .or_else(|| hops.first())
+        .copied()
+        .unwrap_or(peer)
+}
+
+/// 为每个请求解析 [`ClientIp`] 并写入扩展；没有连接信息（如进程内测试）时不写入
+pub(crate) async fn client_ip_middleware(
+    trusted: TrustedProxies,
+    mut req: Request<Body>,
+    next: middleware::Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(peer) = peer {
+        let client_ip = resolve_client_ip(peer, req.headers(), &trusted);
+        log::debug!("{} {} from {client_ip}", req.method(), req.uri().path());
+        req.extensions_mut().insert(ClientIp(client_ip));
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    fn ip(raw: &str) -> IpAddr {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn ignores_forwarded_headers_from_untrusted_peer() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8");
+        let spoofed = headers(&[("x-forwarded-for", "1.2.3.4"), ("x-real-ip", "5.6.7.8")]);
+
+        assert_eq!(
+            resolve_client_ip(ip("203.0.113.9"), &spoofed, &trusted),
+            ip("203.0.113.9")
+        );
+        // 未配置可信代理时，即使来自回环地址也不读取转发头
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &spoofed, &TrustedProxies::default()),
+            ip("127.0.0.1")
+        );
+    }
+
+    #[test]
+    fn uses_first_untrusted_hop_behind_trusted_proxy() {
+        let trusted = TrustedProxies::parse("127.0.0.1, 10.0.0.0/8, ::1");
+        // 客户端伪造了最左侧的 6.6.6.6，真实地址由可信代理追加
+        let chain = headers(&[("x-forwarded-for", "6.6.6.6, 198.51.100.7, 10.1.2.3")]);
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &chain, &trusted),
+            ip("198.51.100.7")
+        );
+
+        let real_ip = headers(&[("x-real-ip", "198.51.100.8")]);
+        assert_eq!(
+            resolve_client_ip(ip("::1"), &real_ip, &trusted),
+            ip("198.51.100.8")
+        );
+
+        let mapped_peer = headers(&[("x-forwarded-for", "198.51.100.9:443")]);
+        assert_eq!(
+            resolve_client_ip(ip("::ffff:10.0.0.1"), &mapped_peer, &trusted),
+            ip("198.51.100.9")
+        );
+
+        let garbage = headers(&[("x-forwarded-for", "not-an-ip")]);
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &garbage, &trusted),
+            ip("127.0.0.1")
+        );
+    }
+
+    #[test]
+    fn parses_cidr_entries() {
+        let trusted = TrustedProxies::parse("192.168.0.0/16, fd00::/8, bogus, 10.0.0.1/40");
+        assert_eq!(trusted.describe(), vec!["192.168.0.0/16", "fd00::/8"]);
+        assert!(trusted.contains(ip("192.168.3.4")));
+        assert!(!trusted.contains(ip("192.169.0.1")));
+        assert!(trusted.contains(ip("fd12::1")));
+    }
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
@@ -20,8 +20,12 @@ use crate::{
     },
     error::AppError,
     gemini_config,
-    services::ConfigService,
-    store::AppState,
+    services::{
+        config::LiveSyncReport,
+        config_diff::{self, ConfigDiff},
+        ConfigService,
+    },
+    store::{check_expected_revision, AppState},
 };
 
 #[derive(Serialize)]
@@ -37,6 +41,34 @@ pub struct ConfigTransferResult {
     pub message: String,
     pub file_path: Option<String>,
     pub backup_id: Option<String>,
+    /// 仅 dry-run 时返回：当前配置与待导入配置的差异
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<ConfigDiff>,
+}
+
+#[derive(Deserialize)]
+pub struct CodexEffectiveQuery {
+    /// 默认遮蔽 auth 中的密钥，`?redact=false` 时返回原文
+    #[serde(default = "default_redact")]
+    pub redact: bool,
+}
+
+fn default_redact() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexEffectiveConfig {
+    pub auth: Value,
+    pub config_toml: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 #[derive(Deserialize)]
@@ -78,11 +110,13 @@ pub async fn export_config(
         message: "Configuration exported successfully".into(),
         file_path: Some(file_path),
         backup_id: None,
+        diff: None,
     })))
 }
 
 pub async fn import_config(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ImportQuery>,
     Json(body): Json<Value>,
 ) -> ApiResult<ConfigTransferResult> {
     // 三种输入形态：
@@ -90,31 +124,38 @@ pub async fn import_config(
     // 2) { content } 直接传配置文本（Web 手动粘贴）
     // 3) 直接传 MultiAppConfig JSON（bash 测试）
 
+    // dry-run：只解析与比较，不写盘、不创建备份、不修改内存状态
+    if query.dry_run {
+        let incoming = parse_import_body(body)?;
+        let current = state
+            .config
+            .read()
+            .map(|cfg| cfg.clone())
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        return Ok(Json(ConfigTransferResult {
+            success: true,
+            message: "Dry run: configuration not imported".into(),
+            file_path: None,
+            backup_id: None,
+            diff: Some(config_diff::diff_configs(
+                "current", &current, "import", &incoming,
+            )),
+        }));
+    }
+
     // 3) 纯配置 JSON
     let is_plain_config = body.get("providers").is_some() || body.get("mcp").is_some();
     if is_plain_config {
-        let content = serde_json::to_string(&body)
-            .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
         let config_path = resolve_app_config_path().map_err(ApiError::from)?;
-        let backup_id = ConfigService::create_backup(&config_path).map_err(ApiError::from)?;
-        let parsed: MultiAppConfig =
-            serde_json::from_value(body).map_err(|e| ApiError::bad_request(e.to_string()))?;
-        atomic_write(&config_path, content.as_bytes()).map_err(ApiError::from)?;
-
-        {
-            let mut guard = state
-                .config
-                .write()
-                .map_err(AppError::from)
-                .map_err(ApiError::from)?;
-            *guard = parsed;
-        }
+        let backup_id = replace_with_import(&state, &config_path, body)?;
 
         return Ok(Json(ConfigTransferResult {
             success: true,
             message: "Configuration imported successfully".into(),
             file_path: Some(config_path.to_string_lossy().to_string()),
             backup_id: Some(backup_id),
+            diff: None,
         }));
     }
 
@@ -123,42 +164,74 @@ pub async fn import_config(
         .map_err(|e| ApiError::bad_request(format!("invalid payload: {e}")))?;
     let mut file_path_ret = payload.file_path.clone();
 
-    let mut updated_state = false;
-    let (new_config, backup_id) = if let Some(content) = payload.content {
+    let backup_id = if let Some(content) = payload.content {
         let config_path = resolve_app_config_path().map_err(ApiError::from)?;
-        let backup_id = ConfigService::create_backup(&config_path).map_err(ApiError::from)?;
-        let parsed: MultiAppConfig =
+        let value: Value =
             serde_json::from_str(&content).map_err(|e| ApiError::bad_request(e.to_string()))?;
-        atomic_write(&config_path, content.as_bytes()).map_err(ApiError::from)?;
-        (parsed, backup_id)
+        replace_with_import(&state, &config_path, value)?
     } else if let Some(file_path) = &payload.file_path {
         let path_buf = ConfigService::sanitize_transfer_path(file_path).map_err(ApiError::from)?;
         let parsed = ConfigService::load_config_for_import(&path_buf).map_err(ApiError::from)?;
-        let backup_id = ConfigService::apply_import_config(parsed.clone(), state.as_ref())
-            .map_err(ApiError::from)?;
-        updated_state = true;
-        (parsed, backup_id)
+        ConfigService::apply_import_config(parsed, state.as_ref()).map_err(ApiError::from)?
     } else {
         return Err(ApiError::bad_request("filePath or content is required"));
     };
 
-    if !updated_state {
-        let mut guard = state
-            .config
-            .write()
-            .map_err(AppError::from)
-            .map_err(ApiError::from)?;
-        *guard = new_config;
-    }
-
     Ok(Json(ConfigTransferResult {
         success: true,
         message: "Configuration imported successfully".into(),
         file_path: file_path_ret.take(),
         backup_id: Some(backup_id),
+        diff: None,
     }))
 }
 
+/// 备份后将导入内容原样写盘并替换内存配置，返回备份 ID
+///
+/// 全程持有配置写锁：`If-Match` 校验与修订号递增在同一临界区内完成。
+/// 修订号沿用当前值递增，避免回退到导入文件中记录的旧值。
+fn replace_with_import(
+    state: &AppState,
+    config_path: &std::path::Path,
+    mut value: Value,
+) -> Result<String, ApiError> {
+    let mut guard = state
+        .config
+        .write()
+        .map_err(AppError::from)
+        .map_err(ApiError::from)?;
+    check_expected_revision(guard.revision).map_err(ApiError::from)?;
+
+    if let Some(map) = value.as_object_mut() {
+        map.insert("revision".into(), Value::from(guard.revision + 1));
+    }
+    let parsed: MultiAppConfig =
+        serde_json::from_value(value.clone()).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let backup_id =
+        ConfigService::create_backup(config_path, state.clock()).map_err(ApiError::from)?;
+    atomic_write(config_path, value.to_string().as_bytes()).map_err(ApiError::from)?;
+    *guard = parsed;
+    Ok(backup_id)
+}
+
+/// 按与正式导入相同的三种输入形态解析配置，但不产生任何副作用
+fn parse_import_body(body: Value) -> Result<MultiAppConfig, ApiError> {
+    if body.get("providers").is_some() || body.get("mcp").is_some() {
+        return serde_json::from_value(body).map_err(|e| ApiError::bad_request(e.to_string()));
+    }
+
+    let payload: FilePathPayload = serde_json::from_value(body)
+        .map_err(|e| ApiError::bad_request(format!("invalid payload: {e}")))?;
+    if let Some(content) = payload.content {
+        serde_json::from_str(&content).map_err(|e| ApiError::bad_request(e.to_string()))
+    } else if let Some(file_path) = &payload.file_path {
+        let path_buf = ConfigService::sanitize_transfer_path(file_path).map_err(ApiError::from)?;
+        ConfigService::load_config_for_import(&path_buf).map_err(ApiError::from)
+    } else {
+        Err(ApiError::bad_request("filePath or content is required"))
+    }
+}
+
 /// GET 导出：直接返回当前配置内容，便于 Web 端下载。
 pub async fn export_config_snapshot(
     State(state): State<Arc<AppState>>,
@@ -172,6 +245,93 @@ pub async fn export_config_snapshot(
     Ok(Json(config))
 }
 
+/// 以 RFC 6902 JSON Patch 修改完整配置；结果无效时拒绝且不写盘
+pub async fn patch_config(
+    State(state): State<Arc<AppState>>,
+    Json(patch): Json<json_patch::Patch>,
+) -> ApiResult<ConfigTransferResult> {
+    let backup_id = ConfigService::apply_json_patch(&state, &patch)?;
+    Ok(Json(ConfigTransferResult {
+        success: true,
+        message: "Configuration patched successfully".into(),
+        file_path: None,
+        backup_id: Some(backup_id),
+        diff: None,
+    }))
+}
+
+/// 读取 Codex live 的 `auth.json` 与 `config.toml`，组合返回当前生效的配置
+pub async fn get_codex_effective_config(
+    Query(query): Query<CodexEffectiveQuery>,
+) -> ApiResult<CodexEffectiveConfig> {
+    let mut auth = codex_config::read_codex_auth()?;
+    if query.redact {
+        codex_config::redact_codex_auth(&mut auth);
+    }
+    let config_toml = codex_config::read_codex_config_text()?;
+    Ok(Json(CodexEffectiveConfig { auth, config_toml }))
+}
+
+/// 按 config.json 重写所有 live 文件（供应商、MCP、提示词），返回各应用的同步结果
+pub async fn sync_all_live(State(state): State<Arc<AppState>>) -> ApiResult<Vec<LiveSyncReport>> {
+    let reports = ConfigService::sync_all_to_live(&state).await?;
+    Ok(Json(reports))
+}
+
+/// 将单个应用的供应商、MCP 启用标记与提示词重置为空；重置前自动备份
+pub async fn reset_app_config(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<ConfigTransferResult> {
+    let app_type = parse_app_type(&app)?;
+    let backup_id = ConfigService::reset_app(&state, &app_type)?;
+    Ok(Json(ConfigTransferResult {
+        success: true,
+        message: format!("Configuration for {} has been reset", app_type.as_str()),
+        file_path: None,
+        backup_id: Some(backup_id),
+        diff: None,
+    }))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffPayload {
+    pub left: String,
+    pub right: String,
+}
+
+/// 比较两份配置（备份 ID 或 `current`），返回按应用分组的结构化差异
+pub async fn diff_configs(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<DiffPayload>,
+) -> ApiResult<ConfigDiff> {
+    let left = resolve_diff_side(&state, &payload.left)?;
+    let right = resolve_diff_side(&state, &payload.right)?;
+    Ok(Json(config_diff::diff_configs(
+        payload.left.trim(),
+        &left,
+        payload.right.trim(),
+        &right,
+    )))
+}
+
+fn resolve_diff_side(state: &AppState, side: &str) -> Result<MultiAppConfig, ApiError> {
+    let side = side.trim();
+    if side.is_empty() {
+        return Err(ApiError::bad_request("left and right are required"));
+    }
+    if side.eq_ignore_ascii_case("current") {
+        return state
+            .config
+            .read()
+            .map(|cfg| cfg.clone())
+            .map_err(AppError::from)
+            .map_err(ApiError::from);
+    }
+    ConfigService::load_backup(side).map_err(ApiError::from)
+}
+
 pub async fn get_config_dir(Path(app): Path<String>) -> ApiResult<String> {
     let app_type = parse_config_app_type(&app)?;
     let dir = get_supported_config_dir(app_type)?;
@@ -203,7 +363,7 @@ pub async fn open_config_folder(Path(app): Path<String>) -> ApiResult<bool> {
     Ok(Json(true))
 }
 
-fn get_supported_config_dir(app_type: AppType) -> Result<std::path::PathBuf, ApiError> {
+pub(crate) fn get_supported_config_dir(app_type: AppType) -> Result<std::path::PathBuf, ApiError> {
     match app_type {
         AppType::Claude => crate::config::get_claude_config_dir().map_err(ApiError::from),
         AppType::Codex => codex_config::get_codex_config_dir().map_err(ApiError::from),
@@ -292,8 +452,7 @@ pub async fn set_common_config_snippet(
     Path(app): Path<String>,
     Json(payload): Json<SnippetPayload>,
 ) -> ApiResult<bool> {
-    let app_type =
-        AppType::parse_supported(&app).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let app_type = super::parse_app_type(&app)?;
     let mut guard = state
         .config
         .write()
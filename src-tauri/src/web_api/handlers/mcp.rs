@@ -1,36 +1,57 @@
 #![cfg(feature = "web-server")]
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::Response,
     Json,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    app_config::{AppType, McpServer},
+    app_config::{AppType, McpApps, McpServer},
     claude_mcp,
     error::AppError,
-    services::McpService,
+    mcp::{self, validation, McpImportReport},
+    services::{
+        mcp::{McpDeleteResult, McpEnableMatrixReport, McpUrlImportResult},
+        McpService,
+    },
     store::AppState,
+    usage_script::{self, UrlProbeResult},
 };
 
-use super::{ApiError, ApiResult};
+use super::{conditional_json, ApiError, ApiResult};
 
+/// 列出 MCP 服务器；附带弱 ETag，`If-None-Match` 命中时返回 304
 pub async fn list_servers(
     State(state): State<Arc<AppState>>,
-) -> ApiResult<HashMap<String, McpServer>> {
-    let servers = McpService::get_all_servers(&state).map_err(internal_error)?;
-    Ok(Json(servers))
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let servers: BTreeMap<String, McpServer> = McpService::get_all_servers(&state)
+        .map_err(internal_error)?
+        .into_iter()
+        .collect();
+    conditional_json(&headers, &servers)
+}
+
+/// Bundled JSON Schema for MCP server specs, shared with the frontend editor.
+pub async fn get_schema() -> ApiResult<serde_json::Value> {
+    Ok(Json(validation::server_spec_schema().clone()))
 }
 
 pub async fn upsert_server(
     State(state): State<Arc<AppState>>,
     Json(server): Json<McpServer>,
 ) -> ApiResult<bool> {
-    McpService::upsert_server(&state, server).map_err(internal_error)?;
+    McpService::upsert_server(&state, server)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(true))
 }
 
@@ -45,16 +66,95 @@ pub async fn update_server(
         return Err(ApiError::bad_request("server id mismatch"));
     }
 
-    McpService::upsert_server(&state, server).map_err(internal_error)?;
+    McpService::upsert_server(&state, server)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(true))
 }
 
+/// 局部更新 MCP 服务器连接定义（仅合并提交的顶层字段）
+pub async fn patch_server(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(patch): Json<serde_json::Value>,
+) -> ApiResult<McpServer> {
+    match McpService::patch_server_spec(&state, &id, patch)
+        .await
+        .map_err(ApiError::from)?
+    {
+        Some(server) => Ok(Json(server)),
+        None => Err(AppError::NotFound(format!("MCP 服务器不存在: {id}")).into()),
+    }
+}
+
+/// 获取单个 MCP 服务器
+pub async fn get_server(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<McpServer> {
+    match McpService::get_server(&state, &id).map_err(internal_error)? {
+        Some(server) => Ok(Json(server)),
+        None => Err(AppError::NotFound(format!("MCP 服务器不存在: {id}")).into()),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ForceDeleteQuery {
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// 删除 MCP 服务器；受保护的服务器需附带 `?force=true`，否则返回 400
 pub async fn delete_server(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
+    Query(query): Query<ForceDeleteQuery>,
 ) -> ApiResult<bool> {
-    let deleted = McpService::delete_server(&state, &id).map_err(internal_error)?;
-    Ok(Json(deleted))
+    let deleted = McpService::delete_server(&state, &id, query.force)
+        .await
+        .map_err(ApiError::from)?;
+    if !deleted {
+        return Err(AppError::NotFound(format!("MCP 服务器不存在: {id}")).into());
+    }
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+pub struct DeleteServersPayload {
+    pub ids: Vec<String>,
+}
+
+/// 批量删除 MCP 服务器，返回每个 ID 的删除结果（不存在的 ID 标记为 `deleted: false`）
+pub async fn delete_servers(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ForceDeleteQuery>,
+    Json(payload): Json<DeleteServersPayload>,
+) -> ApiResult<Vec<McpDeleteResult>> {
+    let results = McpService::delete_servers(&state, &payload.ids, query.force)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(results))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameServerPayload {
+    pub new_id: String,
+}
+
+/// 重命名 MCP 服务器（保留各应用的启用状态）
+pub async fn rename_server(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<RenameServerPayload>,
+) -> ApiResult<McpServer> {
+    match McpService::rename_server(&state, &id, &payload.new_id)
+        .await
+        .map_err(ApiError::from)?
+    {
+        Some(server) => Ok(Json(server)),
+        None => Err(AppError::NotFound(format!("MCP 服务器不存在: {id}")).into()),
+    }
 }
 
 #[derive(Deserialize)]
@@ -76,10 +176,111 @@ pub async fn toggle_app(
     Json(payload): Json<ToggleAppPayload>,
 ) -> ApiResult<bool> {
     let app_ty = super::parse_app_type(&app)?;
-    McpService::toggle_app(&state, &id, app_ty, payload.enabled).map_err(internal_error)?;
+    McpService::toggle_app(&state, &id, app_ty, payload.enabled)
+        .await
+        .map_err(internal_error)?;
     Ok(Json(true))
 }
 
+/// 批量设置应用开关：未提供的应用保持不变
+#[derive(Deserialize)]
+pub struct SetAppsPayload {
+    pub claude: Option<bool>,
+    pub codex: Option<bool>,
+    pub gemini: Option<bool>,
+    pub opencode: Option<bool>,
+}
+
+impl SetAppsPayload {
+    fn updates(&self) -> Vec<(AppType, bool)> {
+        [
+            (AppType::Claude, self.claude),
+            (AppType::Codex, self.codex),
+            (AppType::Gemini, self.gemini),
+            (AppType::Opencode, self.opencode),
+        ]
+        .into_iter()
+        .filter_map(|(app, enabled)| enabled.map(|enabled| (app, enabled)))
+        .collect()
+    }
+}
+
+pub async fn set_apps(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<SetAppsPayload>,
+) -> ApiResult<McpApps> {
+    let updates = payload.updates();
+
+    McpService::set_apps(&state, &id, &updates)
+        .await
+        .map_err(ApiError::from)?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("MCP 服务器不存在: {id}")).into())
+}
+
+/// 导出启用矩阵：`{ serverId: { claude, codex, gemini, opencode } }`
+pub async fn get_enable_matrix(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<BTreeMap<String, McpApps>> {
+    let matrix = McpService::enable_matrix(&state).map_err(internal_error)?;
+    Ok(Json(matrix))
+}
+
+/// 批量应用启用矩阵：每项的应用开关格式同 `set_apps`，未提供的应用保持不变
+pub async fn apply_enable_matrix(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<BTreeMap<String, SetAppsPayload>>,
+) -> ApiResult<McpEnableMatrixReport> {
+    let matrix = payload
+        .iter()
+        .map(|(id, apps)| (id.clone(), apps.updates()))
+        .collect();
+    let report = McpService::apply_enable_matrix(&state, &matrix)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(report))
+}
+
+/// 列出连接定义完全相同的服务器分组
+pub async fn list_duplicate_servers(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Vec<Vec<String>>> {
+    let duplicates = McpService::find_duplicates(&state).map_err(ApiError::from)?;
+    Ok(Json(duplicates))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeServersPayload {
+    pub keep_id: String,
+    pub merge_ids: Vec<String>,
+}
+
+/// 合并重复的 MCP 服务器：启用状态并入 `keepId`，其余服务器被删除
+pub async fn merge_servers(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MergeServersPayload>,
+) -> ApiResult<McpServer> {
+    match McpService::merge_servers(&state, &payload.keep_id, &payload.merge_ids)
+        .await
+        .map_err(ApiError::from)?
+    {
+        Some(server) => Ok(Json(server)),
+        None => Err(AppError::NotFound(format!("MCP 服务器不存在: {}", payload.keep_id)).into()),
+    }
+}
+
+/// 从指定应用的客户端配置导入 MCP，返回导入数量与被跳过的条目
+pub async fn import_from_app(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<McpImportReport> {
+    let app_ty = super::parse_app_type(&app)?;
+    let report = McpService::import_from_app(&state, app_ty).map_err(ApiError::from)?;
+    Ok(Json(report))
+}
+
 /// 获取 Claude MCP 状态
 pub async fn get_status() -> ApiResult<claude_mcp::McpStatus> {
     let status = claude_mcp::get_mcp_status().map_err(internal_error)?;
@@ -116,13 +317,80 @@ pub async fn validate_command(Json(payload): Json<ValidatePayload>) -> ApiResult
     Ok(Json(true))
 }
 
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct CodexPreviewPayload {
+    pub id: Option<String>,
+    pub spec: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub struct CodexPreviewResponse {
+    pub toml: String,
+}
+
+/// 预览 Codex MCP 同步生成的 TOML，不写入任何文件：
+/// 提交 `spec` 时仅转换该服务器（`id` 缺省为 `preview`），否则返回全量同步后的 config.toml 全文
+pub async fn preview_codex_toml(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CodexPreviewPayload>,
+) -> ApiResult<CodexPreviewResponse> {
+    let toml = match payload.spec {
+        Some(spec) => {
+            let id = payload.id.unwrap_or_else(|| "preview".to_string());
+            mcp::render_codex_server_toml(&id, &spec)
+        }
+        None => McpService::preview_codex_config(&state),
+    }
+    .map_err(ApiError::from)?;
+    Ok(Json(CodexPreviewResponse { toml }))
+}
+
+/// HTTP/SSE MCP 服务器的可达性探测超时（秒）
+const MCP_URL_TEST_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Deserialize)]
+pub struct TestUrlPayload {
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// 测试 http/sse MCP 服务器是否可达（复用用量脚本的出站校验，拦截内网/回环等受限地址）
+pub async fn test_url(Json(payload): Json<TestUrlPayload>) -> ApiResult<UrlProbeResult> {
+    let result = usage_script::probe_url(&payload.url, &payload.headers, MCP_URL_TEST_TIMEOUT_SECS)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(result))
+}
+
+const MCP_IMPORT_URL_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Deserialize)]
+pub struct ImportUrlPayload {
+    pub url: String,
+}
+
+/// 从 gist/raw 链接导入 MCP 服务器（复用用量脚本的出站校验），返回每个服务器的导入结果
+pub async fn import_from_url(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ImportUrlPayload>,
+) -> ApiResult<Vec<McpUrlImportResult>> {
+    let document = usage_script::fetch_json(&payload.url, MCP_IMPORT_URL_TIMEOUT_SECS)
+        .await
+        .map_err(ApiError::from)?;
+    let results = McpService::import_servers_from_json(&state, &document)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(results))
+}
+
 /// 兼容旧版：返回指定应用下的 MCP servers（来自统一配置）
 pub async fn get_config(
     State(state): State<Arc<AppState>>,
     Path(app): Path<String>,
 ) -> ApiResult<McpConfigResponse> {
-    let app_ty =
-        AppType::parse_supported(&app).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let app_ty = super::parse_app_type(&app)?;
     let config_path = crate::config::get_app_config_path()
         .map_err(internal_error)?
         .to_string_lossy()
@@ -184,6 +452,7 @@ pub async fn upsert_server_in_config(
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            protected: false,
         }
     };
 
@@ -196,13 +465,16 @@ pub async fn upsert_server_in_config(
         }
     }
 
-    McpService::upsert_server(&state, server).map_err(internal_error)?;
+    McpService::upsert_server(&state, server)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(true))
 }
 
 pub async fn delete_server_in_config(
     State(state): State<Arc<AppState>>,
     Path((app, id)): Path<(String, String)>,
+    Query(query): Query<ForceDeleteQuery>,
     payload: Option<Json<serde_json::Value>>,
 ) -> ApiResult<bool> {
     let app_ty = super::parse_app_type(&app)?;
@@ -212,10 +484,14 @@ pub async fn delete_server_in_config(
         .and_then(|v| v.as_bool());
 
     // 删除统一服务器
-    let deleted = McpService::delete_server(&state, &id).map_err(internal_error)?;
+    let deleted = McpService::delete_server(&state, &id, query.force)
+        .await
+        .map_err(ApiError::from)?;
 
     if deleted && !sync_other_side.unwrap_or(false) {
-        McpService::toggle_app(&state, &id, app_ty, false).map_err(internal_error)?;
+        McpService::toggle_app(&state, &id, app_ty, false)
+            .await
+            .map_err(internal_error)?;
     }
 
     Ok(Json(deleted))
@@ -227,7 +503,9 @@ pub async fn set_enabled(
     Json(payload): Json<ToggleAppPayload>,
 ) -> ApiResult<bool> {
     let app_ty = super::parse_app_type(&app)?;
-    McpService::toggle_app(&state, &id, app_ty, payload.enabled).map_err(internal_error)?;
+    McpService::toggle_app(&state, &id, app_ty, payload.enabled)
+        .await
+        .map_err(internal_error)?;
     Ok(Json(true))
 }
 
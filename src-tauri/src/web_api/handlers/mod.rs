@@ -1,11 +1,12 @@
 #![cfg(feature = "web-server")]
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
 use crate::{app_config::AppType, error::AppError};
@@ -36,11 +37,22 @@ impl ApiError {
     pub fn bad_request(message: impl Into<String>) -> Self {
         Self::new(StatusCode::BAD_REQUEST, message)
     }
+
+    pub(crate) fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 impl From<AppError> for ApiError {
     fn from(err: AppError) -> Self {
         let status = match err {
+            // 本地化错误沿用 `*.not_found` 键（如 provider.not_found）表示目标不存在
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Localized { key, .. } if key.ends_with(".not_found") => StatusCode::NOT_FOUND,
+            AppError::Localized {
+                key: "config.revision_conflict",
+                ..
+            } => StatusCode::CONFLICT,
             AppError::InvalidInput(_)
             | AppError::Config(_)
             | AppError::McpValidation(_)
@@ -67,12 +79,76 @@ struct ErrorResponse {
 
 pub type ApiResult<T> = Result<Json<T>, ApiError>;
 
+/// 轮询类列表接口的条件 GET：以响应体的 SHA-256 生成弱 ETag，`If-None-Match` 命中时返回 304（无响应体）。
+/// 调用方需传入顺序稳定的数据（如 BTreeMap），否则内容相同也可能得到不同的 ETag
+pub fn conditional_json<T: Serialize>(
+    headers: &HeaderMap,
+    value: &T,
+) -> Result<Response, ApiError> {
+    let body = serde_json::to_vec(value).map_err(|source| AppError::JsonSerialize { source })?;
+    let digest: String = Sha256::digest(&body)
+        .iter()
+        .take(16)
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    let etag = format!("W/\"{digest}\"");
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| if_none_match_allows(value, &etag));
+    let cache_headers = [
+        (
+            header::ETAG,
+            HeaderValue::from_str(&etag)
+                .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        ),
+        // 允许缓存但每次都需重新验证，保证变更后立即可见
+        (header::CACHE_CONTROL, HeaderValue::from_static("no-cache")),
+    ];
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+    Ok((
+        cache_headers,
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )],
+        body,
+    )
+        .into_response())
+}
+
+/// `If-None-Match` 使用弱比较：`*` 匹配任意值，否则逗号分隔的任一标签（忽略 `W/` 前缀）相同即命中
+fn if_none_match_allows(value: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let current = opaque(etag);
+    value
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == current)
+}
+
 pub fn parse_app_type(app: &str) -> Result<AppType, ApiError> {
-    AppType::parse_supported(app).map_err(|e| ApiError::bad_request(e.to_string()))
+    let app_type =
+        AppType::parse_supported(app).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    ensure_app_enabled(app_type)
 }
 
 pub fn parse_known_app_type(app: &str) -> Result<AppType, ApiError> {
-    AppType::from_str(app).map_err(|e| ApiError::bad_request(e.to_string()))
+    let app_type = AppType::from_str(app).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    ensure_app_enabled(app_type)
+}
+
+/// 被 `ENABLED_APPS` 排除的应用按不存在处理（404），前端不会再看到对应入口
+fn ensure_app_enabled(app_type: AppType) -> Result<AppType, ApiError> {
+    if super::app_enabled(&app_type) {
+        return Ok(app_type);
+    }
+    Err(ApiError::new(
+        StatusCode::NOT_FOUND,
+        format!("App '{}' is disabled (ENABLED_APPS)", app_type.as_str()),
+    ))
 }
 
 #[cfg(test)]
@@ -1,24 +1,50 @@
 #![cfg(feature = "web-server")]
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Response,
     Json,
 };
+use serde::Deserialize;
 
-use crate::{prompt::Prompt, services::PromptService, store::AppState};
+use crate::{
+    error::AppError,
+    prompt::Prompt,
+    services::{
+        PromptBundleEntry, PromptBundleImportMode, PromptBundleImportResult, PromptService,
+    },
+    store::AppState,
+};
 
-use super::{parse_app_type, ApiError, ApiResult};
+use super::{conditional_json, parse_app_type, ApiError, ApiResult};
 
+/// 列出提示词；附带弱 ETag，`If-None-Match` 命中时返回 304
 pub async fn list_prompts(
     State(state): State<Arc<AppState>>,
     Path(app): Path<String>,
-) -> ApiResult<HashMap<String, Prompt>> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let app_type = parse_app_type(&app)?;
+    let prompts: BTreeMap<String, Prompt> = PromptService::get_prompts(&state, app_type)
+        .map_err(ApiError::from)?
+        .into_iter()
+        .collect();
+    conditional_json(&headers, &prompts)
+}
+
+pub async fn get_prompt(
+    State(state): State<Arc<AppState>>,
+    Path((app, id)): Path<(String, String)>,
+) -> ApiResult<Prompt> {
     let app_type = parse_app_type(&app)?;
-    let prompts = PromptService::get_prompts(&state, app_type).map_err(ApiError::from)?;
-    Ok(Json(prompts))
+    match PromptService::get_prompt(&state, app_type, &id).map_err(ApiError::from)? {
+        Some(prompt) => Ok(Json(prompt)),
+        None => Err(AppError::NotFound(format!("提示词不存在: {id}")).into()),
+    }
 }
 
 pub async fn upsert_prompt(
@@ -34,7 +60,9 @@ pub async fn upsert_prompt(
         return Err(ApiError::bad_request("prompt id mismatch"));
     }
 
-    PromptService::upsert_prompt(&state, app_type, &id, prompt).map_err(ApiError::from)?;
+    PromptService::upsert_prompt(&state, app_type, &id, prompt)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(true))
 }
 
@@ -43,7 +71,11 @@ pub async fn delete_prompt(
     Path((app, id)): Path<(String, String)>,
 ) -> ApiResult<bool> {
     let app_type = parse_app_type(&app)?;
-    PromptService::delete_prompt(&state, app_type, &id).map_err(ApiError::from)?;
+    // 服务层删除保持幂等；Web 端对不存在的目标返回 404 以便客户端区分
+    let deleted = PromptService::delete_prompt(&state, app_type, &id).map_err(ApiError::from)?;
+    if !deleted {
+        return Err(AppError::NotFound(format!("提示词不存在: {id}")).into());
+    }
     Ok(Json(true))
 }
 
@@ -52,7 +84,9 @@ pub async fn enable_prompt(
     Path((app, id)): Path<(String, String)>,
 ) -> ApiResult<bool> {
     let app_type = parse_app_type(&app)?;
-    PromptService::enable_prompt(&state, app_type, &id).map_err(ApiError::from)?;
+    PromptService::enable_prompt(&state, app_type, &id)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(true))
 }
 
@@ -61,10 +95,42 @@ pub async fn import_from_file(
     Path(app): Path<String>,
 ) -> ApiResult<String> {
     let app_type = parse_app_type(&app)?;
-    let id = PromptService::import_from_file(&state, app_type).map_err(ApiError::from)?;
+    let id = PromptService::import_from_file(&state, app_type)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(id))
 }
 
+/// 导出某应用的全部提示词，便于备份或分享
+pub async fn export_bundle(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<Vec<PromptBundleEntry>> {
+    let app_type = parse_app_type(&app)?;
+    let entries = PromptService::export_bundle(&state, app_type).map_err(ApiError::from)?;
+    Ok(Json(entries))
+}
+
+#[derive(Deserialize)]
+pub struct ImportBundleQuery {
+    #[serde(default)]
+    pub mode: PromptBundleImportMode,
+}
+
+/// 批量导入提示词包；`mode=skip`（默认）跳过同 ID 条目，`mode=merge` 覆盖其名称与内容
+pub async fn import_bundle(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+    Query(query): Query<ImportBundleQuery>,
+    Json(entries): Json<Vec<PromptBundleEntry>>,
+) -> ApiResult<PromptBundleImportResult> {
+    let app_type = parse_app_type(&app)?;
+    let result = PromptService::import_bundle(&state, app_type, entries, query.mode)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(result))
+}
+
 pub async fn current_file_content(Path(app): Path<String>) -> ApiResult<Option<String>> {
     let app_type = parse_app_type(&app)?;
     let content = PromptService::get_current_file_content(app_type).map_err(ApiError::from)?;
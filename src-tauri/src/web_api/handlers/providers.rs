@@ -1,22 +1,33 @@
 #![cfg(feature = "web-server")]
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Response,
     Json,
 };
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
-use super::{parse_known_app_type, ApiError, ApiResult};
+use super::{conditional_json, parse_known_app_type, ApiError, ApiResult};
 use crate::{
     app_config::AppType,
     error::AppError,
-    provider::{Provider, UsageResult},
-    services::provider::ProviderSortUpdate,
-    services::ConfigService,
+    provider::{Provider, SwitchHistoryEntry, UsageResult, UsageTestRecord},
+    services::provider::{
+        DuplicateProviderCluster, ProviderCopyResult, ProviderSortUpdate, SettingsValidation,
+        SwitchPreflight, SwitchResult,
+    },
     services::ProviderService,
+    services::{config::CurrentProvidersSyncReport, ConfigService},
     store::AppState,
+    usage_script::{self, TemplateVariable, UsageScriptParseResult},
 };
 
 #[derive(Debug, Deserialize)]
@@ -32,13 +43,65 @@ pub enum SortOrderPayload {
     Direct(Vec<ProviderSortUpdate>),
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ListProvidersQuery {
+    pub group: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// 列出供应商；附带弱 ETag，`If-None-Match` 命中时返回 304
 pub async fn list_providers(
     State(state): State<Arc<AppState>>,
     Path(app): Path<String>,
-) -> ApiResult<HashMap<String, Provider>> {
+    Query(query): Query<ListProvidersQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let app_type = parse_known_app_type(&app)?;
+    let mut providers = ProviderService::list(&state, app_type).map_err(ApiError::from)?;
+    if let Some(group) = query.group.as_deref().map(str::trim) {
+        providers.retain(|_, provider| provider.group.as_deref().map(str::trim) == Some(group));
+    }
+    if let Some(tag) = query.tag.as_deref().map(str::trim) {
+        providers.retain(|_, provider| provider.tags.iter().any(|t| t.trim() == tag));
+    }
+    let providers: BTreeMap<String, Provider> = providers.into_iter().collect();
+    conditional_json(&headers, &providers)
+}
+
+pub async fn list_duplicate_providers(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<Vec<DuplicateProviderCluster>> {
+    let app_type = parse_known_app_type(&app)?;
+    let duplicates = ProviderService::find_duplicates(&state, app_type).map_err(ApiError::from)?;
+    Ok(Json(duplicates))
+}
+
+pub async fn list_provider_groups(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<Vec<String>> {
+    let app_type = parse_known_app_type(&app)?;
+    let groups = ProviderService::list_groups(&state, app_type).map_err(ApiError::from)?;
+    Ok(Json(groups))
+}
+
+pub async fn list_provider_tags(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<Vec<String>> {
+    let app_type = parse_known_app_type(&app)?;
+    let tags = ProviderService::list_tags(&state, app_type).map_err(ApiError::from)?;
+    Ok(Json(tags))
+}
+
+pub async fn switch_history(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+) -> ApiResult<Vec<SwitchHistoryEntry>> {
     let app_type = parse_known_app_type(&app)?;
-    let providers = ProviderService::list(&state, app_type).map_err(ApiError::from)?;
-    Ok(Json(providers))
+    let history = ProviderService::switch_history(&state, app_type).map_err(ApiError::from)?;
+    Ok(Json(history))
 }
 
 pub async fn current_provider(
@@ -74,14 +137,78 @@ pub async fn set_backup_provider(
     Ok(Json(true))
 }
 
+/// 同一 `Idempotency-Key` 的重复添加在此期间直接返回首次结果
+const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(10 * 60);
+const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+
+/// 添加供应商；携带 `Idempotency-Key` 时，网络重试产生的重复请求不会再次创建
 pub async fn add_provider(
     State(state): State<Arc<AppState>>,
     Path(app): Path<String>,
+    headers: HeaderMap,
     Json(provider): Json<Provider>,
 ) -> ApiResult<bool> {
     let app_type = parse_known_app_type(&app)?;
-    let created = ProviderService::add(&state, app_type, provider).map_err(ApiError::from)?;
-    Ok(Json(created))
+    let Some(raw_key) = headers.get("idempotency-key") else {
+        let created = ProviderService::add(&state, app_type, provider).map_err(ApiError::from)?;
+        return Ok(Json(created));
+    };
+    let key = raw_key
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= IDEMPOTENCY_KEY_MAX_LEN)
+        .ok_or_else(|| ApiError::bad_request("invalid Idempotency-Key header"))?;
+    let entry_key = (app_type.as_str().to_string(), key.to_string());
+    let body_hash = idempotency_body_hash(&provider)?;
+
+    // 检查与预留在同一把锁内完成，避免并发的重试同时创建；添加本身在锁外执行
+    {
+        let mut recent = state.idempotency();
+        recent.retain(|_, (at, _, _)| at.elapsed() < IDEMPOTENCY_KEY_TTL);
+        match recent.get(&entry_key) {
+            Some((_, _, None)) => {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "a request with this Idempotency-Key is still in progress",
+                ))
+            }
+            // 同一个 key 只能用于同一个请求，换了请求体说明客户端误用了 key
+            Some((_, seen_hash, Some(_))) if *seen_hash != body_hash => {
+                return Err(ApiError::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "Idempotency-Key was already used with a different request body",
+                ))
+            }
+            Some((_, _, Some(created))) => return Ok(Json(*created)),
+            None => {
+                recent.insert(entry_key.clone(), (Instant::now(), body_hash.clone(), None));
+            }
+        }
+    }
+
+    let result = ProviderService::add(&state, app_type, provider);
+    let mut recent = state.idempotency();
+    match result {
+        Ok(created) => {
+            recent.insert(entry_key, (Instant::now(), body_hash, Some(created)));
+            Ok(Json(created))
+        }
+        // 失败的请求释放预留，允许客户端用同一个 key 重试
+        Err(err) => {
+            recent.remove(&entry_key);
+            Err(ApiError::from(err))
+        }
+    }
+}
+
+/// 请求体的 SHA-256（基于反序列化后重新序列化的内容，忽略空白与字段顺序差异）
+fn idempotency_body_hash(provider: &Provider) -> Result<String, ApiError> {
+    let body = serde_json::to_vec(provider).map_err(|source| AppError::JsonSerialize { source })?;
+    Ok(Sha256::digest(&body)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
 }
 
 pub async fn update_provider(
@@ -96,10 +223,33 @@ pub async fn update_provider(
         return Err(ApiError::bad_request("provider id mismatch"));
     }
 
-    let updated = ProviderService::update(&state, app_type, provider).map_err(ApiError::from)?;
+    let updated = ProviderService::update(&state, app_type, provider)
+        .await
+        .map_err(ApiError::from)?;
     Ok(Json(updated))
 }
 
+/// 复制供应商到另一个应用（跨应用时仅迁移凭证，并返回警告）
+pub async fn copy_provider_to_app(
+    State(state): State<Arc<AppState>>,
+    Path((from_app, id, to_app)): Path<(String, String, String)>,
+) -> ApiResult<ProviderCopyResult> {
+    let from_type = parse_known_app_type(&from_app)?;
+    let to_type = parse_known_app_type(&to_app)?;
+    let result =
+        ProviderService::copy_to_app(&state, from_type, &id, to_type).map_err(ApiError::from)?;
+    Ok(Json(result))
+}
+
+pub async fn get_provider(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<ProviderPath>,
+) -> ApiResult<Provider> {
+    let app_type = parse_known_app_type(&path.app)?;
+    let provider = ProviderService::get(&state, app_type, &path.id).map_err(ApiError::from)?;
+    Ok(Json(provider))
+}
+
 pub async fn delete_provider(
     State(state): State<Arc<AppState>>,
     Path(path): Path<ProviderPath>,
@@ -112,10 +262,48 @@ pub async fn delete_provider(
 pub async fn switch_provider(
     State(state): State<Arc<AppState>>,
     Path(path): Path<ProviderPath>,
-) -> ApiResult<bool> {
+) -> ApiResult<SwitchResult> {
     let app_type = parse_known_app_type(&path.app)?;
-    ProviderService::switch(&state, app_type, &path.id).map_err(ApiError::from)?;
-    Ok(Json(true))
+    let live_file_path = ProviderService::live_file_path(&app_type).map_err(ApiError::from)?;
+    ProviderService::switch(&state, app_type, &path.id)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(Json(SwitchResult {
+        success: true,
+        live_file_path: live_file_path.to_string_lossy().to_string(),
+    }))
+}
+
+pub async fn preflight_switch(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<ProviderPath>,
+) -> ApiResult<SwitchPreflight> {
+    let app_type = parse_known_app_type(&path.app)?;
+    let report =
+        ProviderService::preflight_switch(&state, app_type, &path.id).map_err(ApiError::from)?;
+    Ok(Json(report))
+}
+
+#[derive(Deserialize)]
+pub struct ValidateSettingsPayload {
+    pub settings: serde_json::Value,
+    /// 编辑已有供应商时传入其 ID，仅用于错误信息
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+/// 校验编辑中的供应商 settings，不保存；结构问题通过 `{ ok: false, errors }` 返回而非 4xx
+pub async fn validate_settings(
+    Path(app): Path<String>,
+    Json(payload): Json<ValidateSettingsPayload>,
+) -> ApiResult<SettingsValidation> {
+    let app_type = parse_known_app_type(&app)?;
+    let id = payload.id.unwrap_or_else(|| "new".to_string());
+    Ok(Json(ProviderService::validate_settings(
+        &app_type,
+        &id,
+        payload.settings,
+    )))
 }
 
 pub async fn import_default_config(
@@ -145,6 +333,23 @@ pub async fn read_live_provider_settings(
     Ok(Json(live_settings))
 }
 
+#[derive(Deserialize)]
+pub struct ImportLivePayload {
+    pub name: String,
+}
+
+/// 将当前 live 配置保存为一个新的命名供应商，不切换当前供应商
+pub async fn import_live_provider(
+    State(state): State<Arc<AppState>>,
+    Path(app): Path<String>,
+    Json(payload): Json<ImportLivePayload>,
+) -> ApiResult<Provider> {
+    let app_type = parse_known_app_type(&app)?;
+    let provider = ProviderService::import_live_as_provider(&state, app_type, &payload.name)
+        .map_err(ApiError::from)?;
+    Ok(Json(provider))
+}
+
 pub async fn update_sort_order(
     State(state): State<Arc<AppState>>,
     Path(app): Path<String>,
@@ -172,6 +377,7 @@ pub async fn query_provider_usage(
             success: false,
             data: None,
             error: Some(err.to_string()),
+            response_headers: None,
         })),
     }
 }
@@ -211,25 +417,53 @@ pub async fn test_usage_script(
             success: false,
             data: None,
             error: Some(err.to_string()),
+            response_headers: None,
         })),
     }
 }
 
-/// 将当前供应商写入对应应用的 live 配置文件。
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseUsageScriptRequest {
+    script_code: String,
+}
+
+/// 仅检查用量脚本的语法与结构（保存前快速校验），不替换变量、不发送请求
+pub async fn parse_usage_script(
+    Json(req): Json<ParseUsageScriptRequest>,
+) -> ApiResult<UsageScriptParseResult> {
+    Ok(Json(usage_script::parse_usage_script(&req.script_code)))
+}
+
+/// 列出用量脚本支持的模板变量（如 `{{apiKey}}`）及说明
+pub async fn usage_script_variables() -> ApiResult<Vec<TemplateVariable>> {
+    Ok(Json(usage_script::TEMPLATE_VARIABLES.to_vec()))
+}
+
+/// 返回最近的用量脚本测试结果（最新在前，仅保存在内存中）
+pub async fn usage_test_history(
+    State(state): State<Arc<AppState>>,
+    Path((app, id)): Path<(String, String)>,
+) -> ApiResult<Vec<UsageTestRecord>> {
+    let app_type = parse_known_app_type(&app)?;
+    Ok(Json(ProviderService::usage_test_history(
+        &state, &app_type, &id,
+    )))
+}
+
+/// 将当前供应商写入对应应用的 live 配置文件，返回每个应用各自的结果（单个应用失败不影响其他应用）。
 pub async fn sync_current_providers_live(
     State(state): State<Arc<AppState>>,
-) -> ApiResult<serde_json::Value> {
-    {
+) -> ApiResult<CurrentProvidersSyncReport> {
+    let report = {
+        let _live = state.lock_all_live().await;
         let mut config_guard = state
             .config
             .write()
             .map_err(AppError::from)
             .map_err(ApiError::from)?;
-        ConfigService::sync_current_providers_to_live(&mut config_guard).map_err(ApiError::from)?;
-    }
+        ConfigService::sync_current_providers_to_live_report(&mut config_guard)
+    };
     state.save().map_err(ApiError::from)?;
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Live configuration synchronized"
-    })))
+    Ok(Json(report))
 }
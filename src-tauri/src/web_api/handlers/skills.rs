@@ -4,10 +4,10 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{Method, StatusCode},
     Json,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 use crate::{
@@ -15,7 +15,11 @@ use crate::{
     error::format_skill_error,
     error::AppError,
     services::{
-        skill::SkillCommand as ServiceSkillCommand, Skill as ServiceSkill, SkillRepo, SkillService,
+        skill::{
+            SkillCommand as ServiceSkillCommand, SkillRepoCacheSummary, SkillRepoManifest,
+            SkillState, SkillVerifyReport,
+        },
+        Skill as ServiceSkill, SkillRepo, SkillService,
     },
     store::AppState,
 };
@@ -70,9 +74,13 @@ pub struct SkillResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repo_branch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub skills_path: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub commands: Vec<SkillCommand>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_state: Option<SkillState>,
 }
 
 impl From<ServiceSkill> for SkillResponse {
@@ -89,8 +97,10 @@ impl From<ServiceSkill> for SkillResponse {
             repo_owner: skill.repo_owner,
             repo_name: skill.repo_name,
             repo_branch: skill.repo_branch,
+            repo_ref: skill.repo_ref,
             skills_path: skill.skills_path,
             commands: skill.commands.into_iter().map(SkillCommand::from).collect(),
+            install_state: skill.install_state,
         }
     }
 }
@@ -106,7 +116,7 @@ pub async fn install_skill(
     } = payload;
     let force = force.unwrap_or(false);
     let app = parse_skill_app(app)?;
-    let service = SkillService::new_for_app(&app).map_err(internal_error)?;
+    let service = SkillService::new_for_app(&app, state.tasks()).map_err(skill_error)?;
 
     // 收集仓库信息并查找目标技能
     let (repos, mut repo_cache) = {
@@ -118,41 +128,11 @@ pub async fn install_skill(
         (cfg.skills.repos.clone(), cfg.skills.repo_cache.clone())
     };
     let skills = service
-        .list_skills(repos, &mut repo_cache)
+        .list_skills_for_install(repos, &mut repo_cache)
         .await
-        .map_err(internal_error)?;
-    let skill = SkillService::resolve_install_target(&skills.skills, &directory)
-        .map_err(ApiError::bad_request)?;
-
-    if !skill.installed || force {
-        let repo = SkillRepo {
-            owner: skill.repo_owner.clone().ok_or_else(|| {
-                ApiError::bad_request(format_skill_error(
-                    "MISSING_REPO_INFO",
-                    &[("directory", directory.as_str()), ("field", "owner")],
-                    None,
-                ))
-            })?,
-            name: skill.repo_name.clone().ok_or_else(|| {
-                ApiError::bad_request(format_skill_error(
-                    "MISSING_REPO_INFO",
-                    &[("directory", directory.as_str()), ("field", "name")],
-                    None,
-                ))
-            })?,
-            branch: skill
-                .repo_branch
-                .clone()
-                .unwrap_or_else(|| "main".to_string()),
-            enabled: true,
-            skills_path: skill.skills_path.clone(),
-        };
-
-        service
-            .install_skill(directory.clone(), repo, force)
-            .await
-            .map_err(internal_error)?;
-    }
+        .map_err(skill_error)?;
+    let skill = SkillService::resolve_install_target(&skills, &directory).map_err(skill_error)?;
+    let resolved_ref = install_resolved_skill(&service, skill, &directory, force).await?;
 
     // 写入状态
     {
@@ -162,12 +142,13 @@ pub async fn install_skill(
             .map_err(AppError::from)
             .map_err(ApiError::from)?;
         cfg.skills.repo_cache = repo_cache;
-        cfg.skills.skills.insert(
-            SkillService::state_key(&app, &directory),
-            crate::services::skill::SkillState {
-                installed: true,
-                installed_at: Utc::now(),
-            },
+        record_installed_skill(
+            &mut cfg,
+            &app,
+            skill,
+            &directory,
+            resolved_ref,
+            state.clock().now(),
         );
     }
     state.save().map_err(internal_error)?;
@@ -175,17 +156,183 @@ pub async fn install_skill(
     Ok(Json(true))
 }
 
+/// 安装已解析的技能：未安装或强制重装时才下载，返回解析到的 ref（跳过下载时为 `None`）
+async fn install_resolved_skill(
+    service: &SkillService,
+    skill: &ServiceSkill,
+    directory: &str,
+    force: bool,
+) -> Result<Option<String>, ApiError> {
+    if skill.installed && !force {
+        return Ok(None);
+    }
+    let repo = SkillRepo {
+        owner: skill.repo_owner.clone().ok_or_else(|| {
+            ApiError::bad_request(format_skill_error(
+                "MISSING_REPO_INFO",
+                &[("directory", directory), ("field", "owner")],
+                None,
+            ))
+        })?,
+        name: skill.repo_name.clone().ok_or_else(|| {
+            ApiError::bad_request(format_skill_error(
+                "MISSING_REPO_INFO",
+                &[("directory", directory), ("field", "name")],
+                None,
+            ))
+        })?,
+        branch: skill
+            .repo_branch
+            .clone()
+            .unwrap_or_else(|| "main".to_string()),
+        git_ref: skill.repo_ref.clone(),
+        enabled: true,
+        skills_path: skill.skills_path.clone(),
+        exclude: Vec::new(),
+    };
+
+    service
+        .install_skill(directory.to_string(), repo, force)
+        .await
+        .map_err(skill_error)
+}
+
+/// 写入安装状态，记录来源仓库与 ref
+fn record_installed_skill(
+    cfg: &mut crate::app_config::MultiAppConfig,
+    app: &AppType,
+    skill: &ServiceSkill,
+    directory: &str,
+    resolved_ref: Option<String>,
+    installed_at: DateTime<Utc>,
+) {
+    let key = SkillService::state_key(app, directory);
+    let state = SkillState::for_install(
+        skill,
+        resolved_ref,
+        cfg.skills.skills.get(&key),
+        installed_at,
+    );
+    cfg.skills.skills.insert(key, state);
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallBatchPayload {
+    pub directories: Vec<String>,
+    #[serde(default)]
+    pub force: Option<bool>,
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// 批量安装中单个技能的结果
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillInstallResult {
+    pub directory: String,
+    pub installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 批量安装技能：只解析一次技能列表，并发安装（受安装并发上限约束），
+/// 成功项统一写入状态，单个失败不影响其他技能
+pub async fn install_skills_batch(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<InstallBatchPayload>,
+) -> ApiResult<Vec<SkillInstallResult>> {
+    let force = payload.force.unwrap_or(false);
+    let app = parse_skill_app(payload.app)?;
+    let service = SkillService::new_for_app(&app, state.tasks()).map_err(skill_error)?;
+
+    let mut directories: Vec<String> = Vec::with_capacity(payload.directories.len());
+    for directory in payload.directories {
+        let directory = directory.trim().to_string();
+        if !directory.is_empty() && !directories.contains(&directory) {
+            directories.push(directory);
+        }
+    }
+    if directories.is_empty() {
+        return Err(ApiError::bad_request("directories must not be empty"));
+    }
+
+    let (repos, mut repo_cache) = {
+        let cfg = state
+            .config
+            .read()
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        (cfg.skills.repos.clone(), cfg.skills.repo_cache.clone())
+    };
+    let skills = service
+        .list_skills_for_install(repos, &mut repo_cache)
+        .await
+        .map_err(skill_error)?;
+
+    let outcomes = futures::future::join_all(directories.iter().map(|directory| {
+        let service = &service;
+        let skills = &skills;
+        async move {
+            let skill =
+                SkillService::resolve_install_target(skills, directory).map_err(skill_error)?;
+            let resolved_ref = install_resolved_skill(service, skill, directory, force).await?;
+            Ok::<_, ApiError>((skill, resolved_ref))
+        }
+    }))
+    .await;
+
+    let mut results = Vec::with_capacity(directories.len());
+    {
+        let mut cfg = state
+            .config
+            .write()
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        cfg.skills.repo_cache = repo_cache;
+        for (directory, outcome) in directories.into_iter().zip(outcomes) {
+            match outcome {
+                Ok((skill, resolved_ref)) => {
+                    record_installed_skill(
+                        &mut cfg,
+                        &app,
+                        skill,
+                        &directory,
+                        resolved_ref,
+                        state.clock().now(),
+                    );
+                    results.push(SkillInstallResult {
+                        directory,
+                        installed: true,
+                        error: None,
+                    });
+                }
+                Err(err) => {
+                    log::warn!("批量安装技能 {directory} 失败: {}", err.message());
+                    results.push(SkillInstallResult {
+                        directory,
+                        installed: false,
+                        error: Some(err.message().to_string()),
+                    });
+                }
+            }
+        }
+    }
+    state.save().map_err(internal_error)?;
+
+    Ok(Json(results))
+}
+
 pub async fn uninstall_skill(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<InstallPayload>,
 ) -> ApiResult<bool> {
     let app = parse_skill_app(payload.app.clone())?;
-    SkillService::validate_skill_directory(&payload.directory)
-        .map_err(|err| ApiError::bad_request(err.to_string()))?;
-    let service = SkillService::new_for_app(&app).map_err(internal_error)?;
+    SkillService::validate_skill_directory(&payload.directory).map_err(skill_error)?;
+    let service = SkillService::new_for_app(&app, state.tasks()).map_err(skill_error)?;
     service
         .uninstall_skill(payload.directory.clone())
-        .map_err(internal_error)?;
+        .map_err(skill_error)?;
 
     {
         let mut cfg = state
@@ -202,8 +349,59 @@ pub async fn uninstall_skill(
     Ok(Json(true))
 }
 
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifySkillsQuery {
+    pub app: Option<String>,
+    #[serde(default)]
+    pub repair: bool,
+}
+
+pub async fn verify_skills(
+    method: Method,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerifySkillsQuery>,
+) -> ApiResult<SkillVerifyReport> {
+    let app = parse_skill_app(query.app)?;
+    // repair 会删除目录，要求走 POST 以获得 CSRF 校验
+    if query.repair && method == Method::GET {
+        return Err(ApiError::bad_request(
+            "repair=true requires POST /api/skills/verify",
+        ));
+    }
+    let service = SkillService::new_for_app(&app, state.tasks()).map_err(skill_error)?;
+
+    let mut states = {
+        let cfg = state
+            .config
+            .read()
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        cfg.skills.skills.clone()
+    };
+    let report = service
+        .verify_installed_skills(&mut states, query.repair)
+        .map_err(skill_error)?;
+
+    if !report.removed_states.is_empty() {
+        {
+            let mut cfg = state
+                .config
+                .write()
+                .map_err(AppError::from)
+                .map_err(ApiError::from)?;
+            for key in &report.removed_states {
+                cfg.skills.skills.remove(key);
+            }
+        }
+        state.save().map_err(internal_error)?;
+    }
+
+    Ok(Json(report))
+}
+
 pub async fn list_repos(State(state): State<Arc<AppState>>) -> ApiResult<Vec<SkillRepo>> {
-    let service = SkillService::new().map_err(internal_error)?;
+    let service = SkillService::new(state.tasks()).map_err(skill_error)?;
     let repos = {
         let cfg = state
             .config
@@ -219,7 +417,7 @@ pub async fn add_repo(
     State(state): State<Arc<AppState>>,
     Json(repo): Json<SkillRepo>,
 ) -> ApiResult<bool> {
-    let service = SkillService::new().map_err(internal_error)?;
+    let service = SkillService::new(state.tasks()).map_err(skill_error)?;
     {
         let mut cfg = state
             .config
@@ -228,7 +426,7 @@ pub async fn add_repo(
             .map_err(ApiError::from)?;
         service
             .add_repo(&mut cfg.skills, repo)
-            .map_err(internal_error)?;
+            .map_err(skill_error)?;
     }
     state.save().map_err(internal_error)?;
     Ok(Json(true))
@@ -238,7 +436,7 @@ pub async fn remove_repo(
     State(state): State<Arc<AppState>>,
     Path((owner, name)): Path<(String, String)>,
 ) -> ApiResult<bool> {
-    let service = SkillService::new().map_err(internal_error)?;
+    let service = SkillService::new(state.tasks()).map_err(skill_error)?;
     {
         let mut cfg = state
             .config
@@ -247,12 +445,81 @@ pub async fn remove_repo(
             .map_err(ApiError::from)?;
         service
             .remove_repo(&mut cfg.skills, owner, name)
-            .map_err(internal_error)?;
+            .map_err(skill_error)?;
     }
     state.save().map_err(internal_error)?;
     Ok(Json(true))
 }
 
+#[derive(serde::Deserialize)]
+pub struct ManifestQuery {
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+}
+
+/// 安装前预览仓库归档中的文件列表（仅列出，不解压）
+pub async fn repo_manifest(
+    State(state): State<Arc<AppState>>,
+    Path((owner, name)): Path<(String, String)>,
+    Query(query): Query<ManifestQuery>,
+) -> ApiResult<SkillRepoManifest> {
+    if !SkillService::is_valid_repo_segment(&owner) || !SkillService::is_valid_repo_segment(&name) {
+        return Err(ApiError::bad_request("invalid repository owner or name"));
+    }
+
+    // 已配置的仓库沿用其分支与固定 ref，查询参数中的 ref 优先
+    let mut repo = {
+        let cfg = state
+            .config
+            .read()
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        cfg.skills
+            .repos
+            .iter()
+            .find(|repo| repo.owner == owner && repo.name == name)
+            .cloned()
+    }
+    .unwrap_or_else(|| SkillRepo {
+        owner,
+        name,
+        branch: "main".to_string(),
+        git_ref: None,
+        enabled: true,
+        skills_path: None,
+        exclude: Vec::new(),
+    });
+    if let Some(git_ref) = query.git_ref.filter(|value| !value.trim().is_empty()) {
+        repo.git_ref = Some(git_ref);
+    }
+
+    let service = SkillService::new(state.tasks()).map_err(skill_error)?;
+    let manifest = service.repo_manifest(&repo).await.map_err(skill_error)?;
+    Ok(Json(manifest))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelInstallPayload {
+    pub directory: String,
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// 取消进行中的技能安装，返回是否确实取消了某个安装
+pub async fn cancel_install(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CancelInstallPayload>,
+) -> ApiResult<bool> {
+    let app = parse_skill_app(payload.app)?;
+    SkillService::validate_skill_directory(&payload.directory).map_err(skill_error)?;
+    let service = SkillService::new_for_app(&app, state.tasks()).map_err(skill_error)?;
+    let cancelled = service
+        .cancel_install(&payload.directory)
+        .map_err(skill_error)?;
+    Ok(Json(cancelled))
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InstallPayload {
@@ -267,6 +534,43 @@ fn internal_error(err: impl ToString) -> ApiError {
     ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
 }
 
+/// 按 `format_skill_error` 的错误码区分状态码：
+/// 用户输入或远端资源不存在返回 4xx，其余（IO、解压、网络等）仍按 500 处理
+fn skill_error(err: impl ToString) -> ApiError {
+    let message = err.to_string();
+    let status = serde_json::from_str::<serde_json::Value>(&message)
+        .ok()
+        .map(|value| skill_error_status(&value))
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    ApiError::new(status, message)
+}
+
+fn skill_error_status(error: &serde_json::Value) -> StatusCode {
+    let code = error.get("code").and_then(|v| v.as_str()).unwrap_or("");
+    match code {
+        "SKILL_NOT_FOUND" | "SKILL_DIR_NOT_FOUND" => StatusCode::NOT_FOUND,
+        "SKILL_PATH_INVALID"
+        | "SKILL_DIR_INVALID"
+        | "SKILL_INSTALL_PATH_CONFLICT"
+        | "MISSING_REPO_INFO"
+        | "SKILL_REPO_INVALID"
+        | "APP_NOT_SUPPORTED" => StatusCode::BAD_REQUEST,
+        "INSTALL_IN_PROGRESS" => StatusCode::CONFLICT,
+        "DOWNLOAD_FAILED" => {
+            let status = error
+                .pointer("/context/status")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            if status == "404" {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSkillsQuery {
@@ -287,11 +591,11 @@ pub async fn list_skills(
         (cfg.skills.repos.clone(), cfg.skills.repo_cache.clone())
     };
 
-    let service = SkillService::new_for_app(&app).map_err(internal_error)?;
-    let result = service
+    let service = SkillService::new_for_app(&app, state.tasks()).map_err(skill_error)?;
+    let mut result = service
         .list_skills(repos, &mut repo_cache)
         .await
-        .map_err(internal_error)?;
+        .map_err(skill_error)?;
     {
         let mut cfg = state
             .config
@@ -299,6 +603,7 @@ pub async fn list_skills(
             .map_err(AppError::from)
             .map_err(ApiError::from)?;
         cfg.skills.repo_cache = repo_cache;
+        service.attach_install_states(&mut result.skills, &cfg.skills.skills);
     }
     state.save().map_err(internal_error)?;
     let skills = result.skills.into_iter().map(SkillResponse::from).collect();
@@ -310,18 +615,75 @@ pub async fn list_skills(
     }))
 }
 
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillCacheQuery {
+    /// `owner/name`，仅清除该仓库的缓存
+    pub repo: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearSkillCacheResponse {
+    pub removed: Vec<String>,
+}
+
+pub async fn list_skill_cache(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Vec<SkillRepoCacheSummary>> {
+    let service = SkillService::new(state.tasks()).map_err(skill_error)?;
+    let entries = {
+        let cfg = state
+            .config
+            .read()
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        service.list_repo_cache(&cfg.skills)
+    };
+    Ok(Json(entries))
+}
+
+pub async fn clear_skill_cache(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SkillCacheQuery>,
+) -> ApiResult<ClearSkillCacheResponse> {
+    let repo = query
+        .repo
+        .map(|repo| repo.trim().to_string())
+        .filter(|repo| !repo.is_empty());
+    if let Some(repo) = repo.as_deref() {
+        let valid = repo.split_once('/').is_some_and(|(owner, name)| {
+            !owner.is_empty() && !name.is_empty() && !name.contains('/')
+        });
+        if !valid {
+            return Err(ApiError::bad_request("repo must be in owner/name format"));
+        }
+    }
+
+    let service = SkillService::new(state.tasks()).map_err(skill_error)?;
+    let removed = {
+        let mut cfg = state
+            .config
+            .write()
+            .map_err(AppError::from)
+            .map_err(ApiError::from)?;
+        service.clear_repo_cache(&mut cfg.skills, repo.as_deref())
+    };
+    state.save().map_err(internal_error)?;
+    Ok(Json(ClearSkillCacheResponse { removed }))
+}
+
 fn parse_skill_app(raw: Option<String>) -> Result<AppType, ApiError> {
     match raw {
-        Some(value) => AppType::parse_supported(&value)
-            .map_err(|e: AppError| ApiError::bad_request(e.to_string())),
+        Some(value) => super::parse_app_type(&value),
         None => Ok(AppType::Claude),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_skill_app;
-    use crate::AppType;
+    use super::{parse_skill_app, skill_error};
+    use crate::{error::format_skill_error, AppType};
     use axum::http::StatusCode;
 
     #[test]
@@ -341,4 +703,45 @@ mod tests {
             err.message
         );
     }
+
+    #[test]
+    fn skill_error_maps_user_errors_to_client_status() {
+        let not_found = skill_error(format_skill_error(
+            "SKILL_NOT_FOUND",
+            &[("directory", "missing")],
+            None,
+        ));
+        assert_eq!(not_found.status, StatusCode::NOT_FOUND);
+
+        let invalid = skill_error(format_skill_error(
+            "SKILL_PATH_INVALID",
+            &[("path", "../etc")],
+            None,
+        ));
+        assert_eq!(invalid.status, StatusCode::BAD_REQUEST);
+
+        let missing_repo = skill_error(format_skill_error(
+            "DOWNLOAD_FAILED",
+            &[("status", "404")],
+            Some("http404"),
+        ));
+        assert_eq!(missing_repo.status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn skill_error_keeps_internal_failures_at_500() {
+        let rate_limited = skill_error(format_skill_error(
+            "DOWNLOAD_FAILED",
+            &[("status", "429")],
+            Some("http429"),
+        ));
+        assert_eq!(rate_limited.status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let zip = skill_error(format_skill_error("EMPTY_ARCHIVE", &[], None));
+        assert_eq!(zip.status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let io = skill_error("permission denied");
+        assert_eq!(io.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(io.message, "permission denied");
+    }
 }
@@ -1,18 +1,52 @@
 #![cfg(feature = "web-server")]
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use axum::{extract::Extension, http::StatusCode, response::IntoResponse, Json};
-use serde::Deserialize;
+use axum::{
+    extract::{Extension, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use super::{ApiError, ApiResult};
+use super::{config::get_supported_config_dir, ApiError, ApiResult};
 use crate::{
+    app_config::AppType,
     error::AppError,
-    web_api::{persist_web_credentials, SharedWebAuth},
+    services::{
+        config::ConfigStats,
+        preflight::{self, PreflightReport},
+        ConfigService,
+    },
+    store::{AppState, BackgroundTask},
+    web_api::{
+        embedded_assets_check, enabled_apps, logging, persist_web_credentials,
+        reload_web_credentials, runtime_config_flags, web_credentials_info, AssetsCheck,
+        CredentialsInfo, RuntimeConfigFlags, SharedCors, SharedCsrf, SharedWebAuth,
+    },
 };
 
 const MIN_WEB_PASSWORD_LEN: usize = 8;
 
+/// 返回当前生效的运行时开关（CORS、HSTS、出站策略等），便于远程排查配置问题
+pub async fn config_flags(Extension(cors): Extension<SharedCors>) -> ApiResult<RuntimeConfigFlags> {
+    Ok(Json(runtime_config_flags(&cors)))
+}
+
+/// List the apps enabled for this server (`ENABLED_APPS`, defaults to every supported app).
+pub async fn list_apps() -> ApiResult<Vec<AppType>> {
+    Ok(Json(enabled_apps()))
+}
+
+/// Report whether the embedded frontend (index.html and friends) made it into the binary.
+/// A missing index.html or zero assets means `dist-web` was empty at build time (blank page).
+pub async fn assets_check() -> ApiResult<AssetsCheck> {
+    Ok(Json(embedded_assets_check()))
+}
+
 /// Stub handler for tray updates in web mode.
 pub async fn update_tray() -> ApiResult<bool> {
     Ok(Json(true))
@@ -75,11 +109,225 @@ pub(crate) async fn update_credentials(
     Ok(Json(true))
 }
 
-/// Return the current CSRF token for the session.
+/// Re-read the password/username files so an externally rotated password takes effect
+/// without restarting. Requires the currently valid credentials like every other API call.
+pub(crate) async fn reload_credentials(
+    Extension(auth_state): Extension<SharedWebAuth>,
+) -> ApiResult<bool> {
+    reload_web_credentials(&auth_state)?;
+    log::info!("Web credentials reloaded from disk via web API");
+    Ok(Json(true))
+}
+
+/// Report where the web password lives and whether its file permissions are locked down.
+/// Never returns the password itself.
+pub async fn credentials_info() -> ApiResult<CredentialsInfo> {
+    Ok(Json(web_credentials_info()))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CorsOriginsPayload {
+    pub origins: Vec<String>,
+}
+
+/// Return the CORS origin allow-list currently in effect (initially `CORS_ALLOW_ORIGINS`).
+pub async fn get_cors_origins(
+    Extension(cors): Extension<SharedCors>,
+) -> ApiResult<CorsOriginsPayload> {
+    Ok(Json(CorsOriginsPayload {
+        origins: cors.origins(),
+    }))
+}
+
+/// Replace the CORS origin allow-list at runtime without restarting.
+/// Each entry must be an absolute http(s) origin without wildcard or path; an empty list
+/// restores same-origin only. Changes are not persisted and reset to `CORS_ALLOW_ORIGINS` on restart.
+pub async fn update_cors_origins(
+    Extension(cors): Extension<SharedCors>,
+    Json(payload): Json<CorsOriginsPayload>,
+) -> ApiResult<CorsOriginsPayload> {
+    let origins = cors.replace(&payload.origins)?;
+    log::info!("CORS origins updated via web API: {origins:?}");
+    Ok(Json(CorsOriginsPayload { origins }))
+}
+
+#[derive(Deserialize)]
+pub struct LogLevelPayload {
+    pub level: String,
+}
+
+#[derive(Serialize)]
+pub struct LogLevelResponse {
+    pub level: String,
+}
+
+/// Adjust the active log level at runtime (e.g. `debug` while diagnosing a LAN client).
+pub async fn set_log_level(Json(payload): Json<LogLevelPayload>) -> ApiResult<LogLevelResponse> {
+    let filter = logging::set_log_level(&payload.level)?;
+    log::info!("Log level changed to {filter} via web API");
+    Ok(Json(LogLevelResponse {
+        level: filter.to_string().to_ascii_lowercase(),
+    }))
+}
+
+const DEFAULT_LOG_TAIL_LINES: usize = 200;
+
+#[derive(Deserialize)]
+pub struct LogTailQuery {
+    pub lines: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogTailResponse {
+    pub capacity: usize,
+    pub lines: Vec<String>,
+}
+
+fn log_buffer() -> Result<&'static logging::LogBuffer, ApiError> {
+    logging::log_buffer().ok_or_else(|| {
+        ApiError::from(AppError::NotFound(
+            "Log buffer is disabled (set ENABLE_LOG_BUFFER=1)".to_string(),
+        ))
+    })
+}
+
+/// Return the most recent buffered log lines (secrets already redacted), oldest first.
+/// Lets LAN users attach server logs to bug reports without shell access.
+pub async fn get_logs(Query(query): Query<LogTailQuery>) -> ApiResult<LogTailResponse> {
+    let buffer = log_buffer()?;
+    let limit = query.lines.unwrap_or(DEFAULT_LOG_TAIL_LINES);
+    Ok(Json(LogTailResponse {
+        capacity: buffer.capacity(),
+        lines: buffer.tail(limit),
+    }))
+}
+
+/// Clear the in-memory log buffer; returns the number of discarded lines.
+pub async fn clear_logs() -> ApiResult<usize> {
+    let cleared = log_buffer()?.clear();
+    log::info!("Cleared {cleared} buffered log lines via web API");
+    Ok(Json(cleared))
+}
+
+/// 支持包：提交问题时附带的诊断信息，所有疑似凭证均已遮蔽
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    pub version: &'static str,
+    pub generated_at: DateTime<Utc>,
+    /// `os/arch`，如 `linux/x86_64`
+    pub platform: String,
+    pub app_config_path: String,
+    /// 各应用的 live 配置目录
+    pub app_dirs: BTreeMap<String, String>,
+    pub config_flags: RuntimeConfigFlags,
+    pub stats: ConfigStats,
+    /// 遮蔽后的 config.json
+    pub config: Value,
+    /// 最近的日志（仅在启用 `ENABLE_LOG_BUFFER` 时提供）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logs: Option<Vec<String>>,
+}
+
+/// Collect redacted diagnostics (version, paths, runtime flags, counts, config, recent logs)
+/// into a single downloadable JSON document for bug reports.
+pub async fn support_bundle(
+    State(state): State<Arc<AppState>>,
+    Extension(cors): Extension<SharedCors>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut config = {
+        let guard = state.config.read().map_err(AppError::from)?;
+        serde_json::to_value(&*guard).map_err(|source| AppError::JsonSerialize { source })?
+    };
+    logging::redact_json_secrets(&mut config);
+
+    let app_dirs = [
+        AppType::Claude,
+        AppType::Codex,
+        AppType::Gemini,
+        AppType::Opencode,
+        AppType::Omo,
+    ]
+    .into_iter()
+    .filter_map(|app_type| {
+        let dir = get_supported_config_dir(app_type.clone()).ok()?;
+        Some((
+            app_type.as_str().to_string(),
+            dir.to_string_lossy().to_string(),
+        ))
+    })
+    .collect();
+
+    let bundle = SupportBundle {
+        version: env!("CARGO_PKG_VERSION"),
+        generated_at: Utc::now(),
+        platform: format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH),
+        app_config_path: crate::config::get_app_config_path()?
+            .to_string_lossy()
+            .to_string(),
+        app_dirs,
+        config_flags: runtime_config_flags(&cors),
+        stats: ConfigService::stats(&state)?,
+        config,
+        logs: logging::log_buffer().map(|buffer| buffer.tail(DEFAULT_LOG_TAIL_LINES)),
+    };
+
+    Ok((
+        [
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"cc-switch-support-bundle.json\"",
+            ),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        Json(bundle),
+    ))
+}
+
+/// Check that each app's live config dir/files exist and are writable, without modifying them.
+pub async fn preflight() -> ApiResult<PreflightReport> {
+    let report = preflight::run_preflight()?;
+    Ok(Json(report))
+}
+
+/// Report config.json size and entity counts for diagnostics and dashboards.
+pub async fn stats(State(state): State<Arc<AppState>>) -> ApiResult<ConfigStats> {
+    let stats = ConfigService::stats(&state)?;
+    Ok(Json(stats))
+}
+
+/// List background tasks that are currently running (skill installs, repo refreshes).
+/// Useful for spotting installs that appear stuck.
+pub async fn list_tasks(State(state): State<Arc<AppState>>) -> ApiResult<Vec<BackgroundTask>> {
+    Ok(Json(state.tasks().list()))
+}
+
+/// Return the live CSRF token accepted by the CSRF check.
+/// Authoritative fallback when the token injected into index.html (script/meta) is unavailable,
+/// and the way for a cached SPA to re-sync after a `CSRF_VALIDATION_FAILED` 403 without reloading.
 /// This endpoint requires Basic Auth but does NOT require CSRF token (it's a GET request).
-pub async fn get_csrf_token(Extension(csrf): Extension<Option<Arc<String>>>) -> impl IntoResponse {
-    match csrf {
-        Some(token) => Json(serde_json::json!({ "csrfToken": token.as_str() })),
-        None => Json(serde_json::json!({ "csrfToken": null })),
-    }
+///
+/// The token only changes via `POST /system/rotate-csrf`, so `expiresAt` is always `null` for now.
+pub async fn get_csrf_token(Extension(csrf): Extension<SharedCsrf>) -> impl IntoResponse {
+    csrf_token_response(csrf.token())
+}
+
+/// Force-rotate the CSRF token (e.g. after it leaked): persists a new token to `web_env`,
+/// re-injects it into index.html and returns it. The old token is rejected immediately.
+pub async fn rotate_csrf_token(
+    Extension(csrf): Extension<SharedCsrf>,
+) -> Result<impl IntoResponse, ApiError> {
+    let token = csrf.rotate()?;
+    log::info!("CSRF token rotated via web API");
+    Ok(csrf_token_response(token))
+}
+
+fn csrf_token_response(token: String) -> impl IntoResponse {
+    let body = serde_json::json!({
+        "csrfToken": token,
+        "expiresAt": null,
+    });
+    // 禁止缓存，避免浏览器或反代返回过期的 Token
+    ([(header::CACHE_CONTROL, "no-store")], Json(body))
 }
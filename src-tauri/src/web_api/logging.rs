@@ -0,0 +1,274 @@
+//! Web 服务器日志：在 env_logger 之上包一层可热替换的过滤器，支持运行时调整日志级别；
+//! 设置 `ENABLE_LOG_BUFFER=1` 时另在内存中保留最近的日志行，供无 shell 权限时通过 API 排查。
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock, RwLock};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use regex::Regex;
+
+use crate::error::AppError;
+
+/// 内存日志缓冲默认保留的行数（可用 `LOG_BUFFER_LINES` 覆盖）
+pub const DEFAULT_LOG_BUFFER_LINES: usize = 1000;
+
+struct ReloadableLogger {
+    inner: RwLock<env_logger::Logger>,
+    buffer: Option<LogBuffer>,
+}
+
+/// 最近日志行的环形缓冲，写入前已遮蔽密钥
+pub struct LogBuffer {
+    capacity: usize,
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn push(&self, line: String) {
+        let mut lines = self
+            .lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// 最近的 `limit` 行，按时间先后排列
+    pub fn tail(&self, limit: usize) -> Vec<String> {
+        let lines = self
+            .lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let skip = lines.len().saturating_sub(limit);
+        lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// 清空缓冲，返回清除的行数
+    pub fn clear(&self) -> usize {
+        let mut lines = self
+            .lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let cleared = lines.len();
+        lines.clear();
+        cleared
+    }
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner
+            .read()
+            .map(|logger| logger.enabled(metadata))
+            .unwrap_or(false)
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(logger) = self.inner.read() {
+            if let Some(buffer) = &self.buffer {
+                if logger.matches(record) {
+                    buffer.push(format_buffered_line(record));
+                }
+            }
+            logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(logger) = self.inner.read() {
+            logger.flush();
+        }
+    }
+}
+
+static LOGGER: OnceLock<ReloadableLogger> = OnceLock::new();
+
+/// 初始化全局日志（RUST_LOG 优先，未设置时使用 default_filter）
+pub fn init_logger(default_filter: &str) {
+    let logger =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+            .build();
+    let max_level = logger.filter();
+    let buffer = log_buffer_enabled().then(|| {
+        let capacity = std::env::var("LOG_BUFFER_LINES")
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(DEFAULT_LOG_BUFFER_LINES);
+        LogBuffer::new(capacity)
+    });
+    let reloadable = LOGGER.get_or_init(|| ReloadableLogger {
+        inner: RwLock::new(logger),
+        buffer,
+    });
+    if log::set_logger(reloadable).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// 是否通过 `ENABLE_LOG_BUFFER` 启用了内存日志缓冲（在 [`init_logger`] 时读取）
+pub fn log_buffer_enabled() -> bool {
+    std::env::var("ENABLE_LOG_BUFFER")
+        .is_ok_and(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
+}
+
+/// 内存日志缓冲；未启用或日志尚未初始化时为 `None`
+pub fn log_buffer() -> Option<&'static LogBuffer> {
+    LOGGER.get().and_then(|logger| logger.buffer.as_ref())
+}
+
+fn format_buffered_line(record: &Record) -> String {
+    let line = format!(
+        "{} {} {}: {}",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        record.level(),
+        record.target(),
+        record.args()
+    );
+    redact_secrets(&line)
+}
+
+/// 遮蔽日志行中的凭证：Authorization 方案后的值、`key=`/`token=` 等键值对以及 `sk-` 开头的密钥
+pub fn redact_secrets(line: &str) -> String {
+    static PATTERNS: OnceLock<[(Regex, &'static str); 3]> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            (
+                Regex::new(r"(?i)\b(bearer|basic)\s+[A-Za-z0-9._~+/=-]+").expect("auth pattern"),
+                "$1 ****",
+            ),
+            (
+                Regex::new(
+                    r#"(?i)((?:api[_-]?key|access[_-]?token|token|secret|password|passwd)["']?\s*[:=]\s*["']?)[^\s"'&,;]+"#,
+                )
+                .expect("key-value pattern"),
+                "${1}****",
+            ),
+            (
+                Regex::new(r"\bsk-[A-Za-z0-9_-]{8,}").expect("api key pattern"),
+                "sk-****",
+            ),
+        ]
+    });
+    let mut redacted = line.to_string();
+    for (pattern, replacement) in patterns {
+        redacted = pattern.replace_all(&redacted, *replacement).into_owned();
+    }
+    redacted
+}
+
+/// 递归遮蔽 JSON 中的凭证：键名像密钥（key/token/secret/password 等）的字符串整体替换为 `****`，
+/// 其余字符串按 [`redact_secrets`] 处理（如 Codex `config.toml` 文本中的 `token = "..."`）
+pub fn redact_json_secrets(value: &mut serde_json::Value) {
+    const SENSITIVE_KEY_PARTS: [&str; 8] = [
+        "key",
+        "token",
+        "secret",
+        "password",
+        "passwd",
+        "authorization",
+        "cookie",
+        "credential",
+    ];
+
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let lower = key.to_ascii_lowercase();
+                match value {
+                    serde_json::Value::String(secret)
+                        if SENSITIVE_KEY_PARTS.iter().any(|part| lower.contains(part)) =>
+                    {
+                        if !secret.is_empty() {
+                            *secret = "****".to_string();
+                        }
+                    }
+                    _ => redact_json_secrets(value),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json_secrets),
+        serde_json::Value::String(text) => *text = redact_secrets(text),
+        _ => {}
+    }
+}
+
+/// 解析日志级别（off/error/warn/info/debug/trace，大小写不敏感）
+pub fn parse_log_level(level: &str) -> Result<LevelFilter, AppError> {
+    level.trim().parse::<LevelFilter>().map_err(|_| {
+        AppError::InvalidInput(format!(
+            "无效的日志级别 '{level}'，可选: off, error, warn, info, debug, trace"
+        ))
+    })
+}
+
+/// 运行时切换全局日志级别；替换 RUST_LOG 中的按模块过滤规则
+pub fn set_log_level(level: &str) -> Result<LevelFilter, AppError> {
+    let filter = parse_log_level(level)?;
+    if let Some(reloadable) = LOGGER.get() {
+        let logger = env_logger::Builder::new().filter_level(filter).build();
+        *reloadable.inner.write()? = logger;
+    }
+    log::set_max_level(filter);
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_common_secret_shapes() {
+        let line = redact_secrets(
+            "Authorization: Bearer abc.def-123 apiKey=\"s3cr3t\" password: hunter2 key sk-ant-abcdefgh1234",
+        );
+        assert_eq!(
+            line,
+            "Authorization: Bearer **** apiKey=\"****\" password: **** key sk-****"
+        );
+    }
+
+    #[test]
+    fn redacts_sensitive_json_fields() {
+        let mut value = serde_json::json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": "sk-live", "ANTHROPIC_BASE_URL": "https://relay.example" },
+            "config": "model = \"gpt\"\nexperimental_bearer_token = \"abc123\"",
+            "headers": [{ "Authorization": "Bearer xyz" }],
+            "timeout": 30
+        });
+        redact_json_secrets(&mut value);
+        assert_eq!(value["env"]["ANTHROPIC_AUTH_TOKEN"], "****");
+        assert_eq!(value["env"]["ANTHROPIC_BASE_URL"], "https://relay.example");
+        assert_eq!(
+            value["config"],
+            "model = \"gpt\"\nexperimental_bearer_token = \"****\""
+        );
+        assert_eq!(value["headers"][0]["Authorization"], "****");
+        assert_eq!(value["timeout"], 30);
+    }
+
+    #[test]
+    fn buffer_keeps_only_recent_lines() {
+        let buffer = LogBuffer::new(2);
+        for line in ["a", "b", "c"] {
+            buffer.push(line.to_string());
+        }
+        assert_eq!(buffer.tail(10), vec!["b", "c"]);
+        assert_eq!(buffer.tail(1), vec!["c"]);
+        assert_eq!(buffer.clear(), 2);
+        assert!(buffer.tail(10).is_empty());
+    }
+}
@@ -2,14 +2,14 @@
 
 use std::{
     env, fs,
-    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     path::{Path as StdPath, PathBuf},
-    sync::{Arc, RwLock},
+    sync::{Arc, OnceLock, RwLock},
     time::{Duration, Instant},
 };
 
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     extract::{DefaultBodyLimit, Extension, Path},
     http::{
         header::{
@@ -19,16 +19,22 @@ use axum::{
     },
     middleware,
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{any, on, MethodFilter},
     Router,
 };
 use base64::Engine;
 use mime_guess::mime;
+use regex::Regex;
 use rust_embed::RustEmbed;
 use tokio::sync::Mutex;
-use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::{limit::GlobalConcurrencyLimitLayer, Layer, Service};
 use tower_http::{
+    compression::{
+        predicate::{NotForContentType, Predicate, SizeAbove},
+        CompressionLayer,
+    },
     cors::{AllowOrigin, CorsLayer},
+    decompression::RequestDecompressionLayer,
     validate_request::ValidateRequestHeaderLayer,
 };
 use url::Url;
@@ -37,12 +43,15 @@ use url::Url;
 use std::os::unix::fs::PermissionsExt;
 
 use crate::{
+    app_config::AppType,
     config::{atomic_write, get_home_dir},
     error::AppError,
     store::AppState,
 };
 
+pub mod client_ip;
 pub mod handlers;
+pub mod logging;
 pub mod routes;
 
 /// Shared application state for the web server.
@@ -65,6 +74,72 @@ impl Default for WebAuthCredentials {
 
 pub type SharedWebAuth = Arc<RwLock<WebAuthCredentials>>;
 
+/// 当前接受的 CSRF Token 与注入了该 Token 的 index.html，轮换时二者一并更新
+pub struct CsrfState {
+    token: RwLock<String>,
+    index_html: RwLock<Option<Arc<Bytes>>>,
+    api_base: String,
+    base_path: Option<String>,
+}
+
+pub type SharedCsrf = Arc<CsrfState>;
+
+impl CsrfState {
+    fn new(token: String, api_base: String, base_path: Option<String>) -> Self {
+        let index_html = render_index_html(&token, &api_base, base_path.as_deref());
+        Self {
+            token: RwLock::new(token),
+            index_html: RwLock::new(index_html),
+            api_base,
+            base_path,
+        }
+    }
+
+    pub fn token(&self) -> String {
+        self.token
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    fn matches(&self, candidate: Option<&str>) -> bool {
+        let token = self
+            .token
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        candidate == Some(token.as_str())
+    }
+
+    fn index_html(&self) -> Option<Arc<Bytes>> {
+        self.index_html
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// 生成新 Token 写回 `~/.cc-switch/web_env` 并重新注入 index.html，旧 Token 立即失效。
+    /// 通过 `WEB_CSRF_TOKEN` 环境变量固定的 Token 不支持轮换（重启后仍会以环境变量为准）。
+    pub(crate) fn rotate(&self) -> Result<String, AppError> {
+        if env_csrf_token().is_some() {
+            return Err(AppError::InvalidInput(
+                "WEB_CSRF_TOKEN is pinned via environment variable; change it there and restart"
+                    .to_string(),
+            ));
+        }
+        let token = generate_token(16);
+        persist_csrf_token(&token)?;
+
+        let index_html = render_index_html(&token, &self.api_base, self.base_path.as_deref());
+        // 同时持有两把写锁再替换，避免请求看到新页面但旧 Token（或反之）的中间状态；
+        // 读取方每次只取其中一把锁，固定先 token 后 index_html 的顺序不会死锁
+        let mut current_token = self.token.write().map_err(AppError::from)?;
+        let mut current_html = self.index_html.write().map_err(AppError::from)?;
+        *current_token = token.clone();
+        *current_html = index_html;
+        Ok(token)
+    }
+}
+
 #[derive(RustEmbed)]
 #[folder = "../dist-web"]
 struct WebAssets;
@@ -77,15 +152,17 @@ struct WebTokens {
 const DEFAULT_API_PREFIX: &str = "/api";
 const DEFAULT_WEB_BODY_LIMIT_BYTES: usize = 2_097_152;
 const DEFAULT_WEB_GLOBAL_CONCURRENCY: usize = 32;
+/// 小于该字节数的响应不压缩，避免为很小的 JSON 浪费 CPU
+const DEFAULT_COMPRESSION_MIN_BYTES: u16 = 1024;
 const DEFAULT_WEB_USERNAME: &str = "admin";
 const DEFAULT_WEB_PASSWORD_LEN: usize = 24;
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
 
 /// Serve embedded static assets with index.html fallback for SPA routes.
 async fn serve_static(
     path: Option<Path<String>>,
     headers: HeaderMap,
-    tokens: Arc<WebTokens>,
-    api_base: Arc<String>,
+    index_html: Option<Arc<Bytes>>,
 ) -> impl IntoResponse {
     let requested_path = path.map(|Path(p)| p).unwrap_or_default();
     let requested_path = requested_path.trim_start_matches('/');
@@ -95,84 +172,334 @@ async fn serve_static(
         requested_path
     };
 
+    // index.html 始终使用注入好当前 Token 的缓存内容
+    if target_path == "index.html" {
+        return index_response(index_html);
+    }
+
+    // WEB_BRANDING_DIR 中的同名文件（如 favicon.ico、logo.svg）优先于内嵌资源
+    if let Some(data) = read_branding_override(target_path) {
+        return static_file_response(target_path, data, false);
+    }
+
     // Try the requested file first; fall back to index.html for SPA routes.
-    let (asset, served_path) = match WebAssets::get(target_path) {
-        Some(content) => (content, target_path),
-        None => {
-            let has_extension = StdPath::new(target_path)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| !ext.is_empty())
-                .unwrap_or(false);
-            let accepts_html = headers
-                .get(ACCEPT)
-                .and_then(|v| v.to_str().ok())
-                .map(|value| value.to_ascii_lowercase().contains("text/html"))
-                .unwrap_or(false);
-            if !has_extension || accepts_html {
-                match WebAssets::get("index.html") {
-                    Some(content) => (content, "index.html"),
-                    None => return StatusCode::NOT_FOUND.into_response(),
-                }
-            } else {
-                return StatusCode::NOT_FOUND.into_response();
+    // Missing files with an extension (e.g. `/assets/x.js`) get a real 404 unless HTML is accepted.
+    let Some(asset) = WebAssets::get(target_path) else {
+        let has_extension = StdPath::new(target_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| !ext.is_empty())
+            .unwrap_or(false);
+        let accepts_html = headers
+            .get(ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .map(|value| value.to_ascii_lowercase().contains("text/html"))
+            .unwrap_or(false);
+        if !has_extension || accepts_html {
+            return index_response(index_html);
+        }
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    static_file_response(
+        target_path,
+        asset.data.into_owned(),
+        is_hashed_asset(target_path),
+    )
+}
+
+fn static_file_response(path: &str, data: Vec<u8>, immutable: bool) -> Response {
+    let mime = mime_guess::from_path(path).first_or(mime::APPLICATION_OCTET_STREAM);
+    let mut response = Response::new(Body::from(data));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(mime.as_ref())
+            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
+    );
+    if immutable {
+        response.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL),
+        );
+    }
+
+    response
+}
+
+/// WEB_BRANDING_DIR：品牌定制目录，未设置或为空时不启用
+fn branding_dir() -> Option<PathBuf> {
+    env::var_os("WEB_BRANDING_DIR")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 读取品牌定制目录中的覆盖文件；路径只允许普通的相对分量，
+/// 并在解析符号链接后再次确认仍位于该目录内，防止目录穿越
+fn read_branding_override(target_path: &str) -> Option<Vec<u8>> {
+    let dir = branding_dir()?;
+    let relative = StdPath::new(target_path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)))
+    {
+        return None;
+    }
+    let dir = dir.canonicalize().ok()?;
+    let candidate = dir.join(relative).canonicalize().ok()?;
+    if !candidate.starts_with(&dir) || !candidate.is_file() {
+        return None;
+    }
+    fs::read(&candidate).ok()
+}
+
+/// Content-hashed build output (e.g. `app.abcd1234.js`, or Vite's base64url `index-DiwrgTda.js`)
+/// is immutable and safe to cache long-term.
+fn is_hashed_asset(path: &str) -> bool {
+    static HASHED_ASSET_RE: OnceLock<Regex> = OnceLock::new();
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    HASHED_ASSET_RE
+        .get_or_init(|| {
+            Regex::new(r"[.-](?:[0-9a-fA-F]{8,}|[A-Za-z0-9_-]{8})\.[A-Za-z0-9]+$")
+                .expect("valid hashed asset regex")
+        })
+        .is_match(file_name)
+}
+
+fn index_response(index_html: Option<Arc<Bytes>>) -> Response {
+    let Some(index_html) = index_html else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut response = Response::new(Body::from(index_html.as_ref().clone()));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    // index.html 引用的资源带哈希，必须每次重新校验才能拿到新版本
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+/// Render index.html with injected tokens; re-rendered only when the CSRF token is rotated.
+fn render_index_html(
+    csrf_token: &str,
+    api_base: &str,
+    base_path: Option<&str>,
+) -> Option<Arc<Bytes>> {
+    let asset = WebAssets::get("index.html")?;
+    let content = match std::str::from_utf8(&asset.data) {
+        Ok(html) => {
+            let mut html = inject_index_tokens(html, csrf_token, api_base);
+            if let Some(base_path) = base_path {
+                html = inject_base_path(&html, base_path);
             }
+            Bytes::from(html)
         }
+        Err(_) => Bytes::from(asset.data.into_owned()),
     };
+    Some(Arc::new(content))
+}
+
+/// Inject `<base href>` and `window.__CC_SWITCH_BASE__` for sub-path mounts.
+/// The base tag goes right after the opening `<head>` so it applies to every relative URL.
+fn inject_base_path(html: &str, base_path: &str) -> String {
+    let base_href = format!("{base_path}/");
+    let base_json = serde_json::to_string(&base_href)
+        .unwrap_or_else(|_| "\"/\"".to_string())
+        .replace('<', "\\u003c");
+    let injection = format!(
+        r#"<base href="{href}">
+<script>window.__CC_SWITCH_BASE__ = {base};</script>"#,
+        href = escape_html_attr(&base_href),
+        base = base_json
+    );
+
+    let lower = html.to_ascii_lowercase();
+    let pos = find_open_tag_end(&lower, "head")
+        .or_else(|| find_injection_point(html))
+        .unwrap_or(0);
+    let mut html = html.to_string();
+    html.insert_str(pos, &injection);
+    html
+}
 
-    let mime = mime_guess::from_path(served_path).first_or(mime::APPLICATION_OCTET_STREAM);
-    let mut content = asset.data.into_owned();
-
-    if served_path == "index.html" {
-        if let Ok(mut html) = String::from_utf8(content.clone()) {
-            let csrf_token_json = serde_json::to_string(&tokens.csrf_token)
-                .unwrap_or_else(|_| "\"\"".to_string())
-                .replace('<', "\\u003c");
-            let api_base_json = serde_json::to_string(api_base.as_str())
-                .unwrap_or_else(|_| "\"/api\"".to_string())
-                .replace('<', "\\u003c");
-            let injection = format!(
-                r#"<script>
+/// Inject the API base and CSRF token into index.html.
+///
+/// The SPA resolves the CSRF token in this order:
+/// 1. `window.__CC_SWITCH_TOKENS__.csrfToken` (inline script)
+/// 2. `<meta name="csrf-token">` (survives proxies that strip inline scripts)
+/// 3. `GET /api/system/csrf-token` (authoritative fallback, requires Basic Auth)
+fn inject_index_tokens(html: &str, csrf_token: &str, api_base: &str) -> String {
+    let csrf_token_json = serde_json::to_string(csrf_token)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace('<', "\\u003c");
+    let api_base_json = serde_json::to_string(api_base)
+        .unwrap_or_else(|_| "\"/api\"".to_string())
+        .replace('<', "\\u003c");
+    let injection = format!(
+        r#"<meta name="csrf-token" content="{csrf_attr}">
+<script>
 window.__CC_SWITCH_API_BASE__ = {api_base};
 window.__CC_SWITCH_TOKENS__ = {{
   csrfToken: {csrf}
 }};
 </script>"#,
-                csrf = csrf_token_json,
-                api_base = api_base_json
-            );
-            if let Some(pos) = html.find("</head>") {
-                html.insert_str(pos, &injection);
-            } else {
-                html.push_str(&injection);
+        csrf_attr = escape_html_attr(csrf_token),
+        csrf = csrf_token_json,
+        api_base = api_base_json
+    );
+
+    let mut html = html.to_string();
+    let pos = find_injection_point(&html).unwrap_or(html.len());
+    html.insert_str(pos, &injection);
+    html
+}
+
+/// Locate where the token injection goes, tolerating minified/uppercase markup:
+/// before `</head>`, else right after the opening `<head ...>`/`<head/>`, else after `<body ...>`.
+fn find_injection_point(html: &str) -> Option<usize> {
+    // ASCII 小写化不改变字节偏移，可直接用于原字符串
+    let lower = html.to_ascii_lowercase();
+    if let Some(pos) = lower.find("</head>") {
+        return Some(pos);
+    }
+    find_open_tag_end(&lower, "head").or_else(|| find_open_tag_end(&lower, "body"))
+}
+
+/// Return the byte offset just past the opening `<tag ...>` (matching `<tag>`, `<tag attr>` and `<tag/>`).
+fn find_open_tag_end(lower: &str, tag: &str) -> Option<usize> {
+    let needle = format!("<{tag}");
+    let mut search_from = 0;
+    while let Some(rel) = lower[search_from..].find(&needle) {
+        let start = search_from + rel;
+        let after_name = start + needle.len();
+        match lower.as_bytes().get(after_name) {
+            Some(b'>' | b'/' | b' ' | b'\t' | b'\n' | b'\r') => {
+                return lower[after_name..]
+                    .find('>')
+                    .map(|end| after_name + end + 1);
             }
-            content = html.into_bytes();
+            // 跳过 `<header>` 等同前缀标签
+            _ => search_from = after_name,
         }
     }
+    None
+}
 
-    let body = Body::from(content);
+fn escape_html_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
 
-    let mut response = Response::new(body);
-    response.headers_mut().insert(
-        header::CONTENT_TYPE,
-        HeaderValue::from_str(mime.as_ref())
-            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
-    );
+/// 运行时可更新的 CORS 来源白名单，初始值取自 `CORS_ALLOW_ORIGINS`；
+/// `ALLOW_LAN_CORS` 仍需重启生效
+pub struct CorsState {
+    origins: RwLock<Vec<String>>,
+    allow_lan: bool,
+}
 
-    response
+pub type SharedCors = Arc<CorsState>;
+
+impl CorsState {
+    fn from_env() -> Self {
+        let origins = match env::var("CORS_ALLOW_ORIGINS")
+            .ok()
+            .as_deref()
+            .map(str::trim)
+        {
+            Some("*") => {
+                // 显式禁止生产中的通配符，防止意外放开
+                log::warn!("CORS_ALLOW_ORIGINS='*' 已被忽略，请使用逗号分隔的白名单");
+                Vec::new()
+            }
+            Some(list) => list
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty() && HeaderValue::from_str(entry).is_ok())
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        };
+        Self {
+            origins: RwLock::new(origins),
+            allow_lan: env_truthy("ALLOW_LAN_CORS") || env_truthy("CC_SWITCH_LAN_CORS"),
+        }
+    }
+
+    pub fn origins(&self) -> Vec<String> {
+        self.origins
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// 校验并整体替换白名单，返回规范化后的来源列表；清空后若未启用 LAN CORS 则恢复同源策略
+    pub(crate) fn replace(&self, origins: &[String]) -> Result<Vec<String>, AppError> {
+        let mut normalized = Vec::with_capacity(origins.len());
+        for origin in origins {
+            let origin = normalize_cors_origin(origin)?;
+            if !normalized.contains(&origin) {
+                normalized.push(origin);
+            }
+        }
+        *self.origins.write().map_err(AppError::from)? = normalized.clone();
+        Ok(normalized)
+    }
+
+    fn is_active(&self) -> bool {
+        self.allow_lan
+            || !self
+                .origins
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .is_empty()
+    }
+
+    fn allows(&self, origin: &HeaderValue) -> bool {
+        let listed = origin.to_str().is_ok_and(|origin| {
+            self.origins
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .iter()
+                .any(|allowed| allowed == origin)
+        });
+        listed || (self.allow_lan && is_private_origin(origin))
+    }
+}
+
+/// 规范化单个来源为 `scheme://host[:port]`：仅接受 http/https 的绝对来源，
+/// 不允许通配符、路径、查询参数或用户信息
+fn normalize_cors_origin(raw: &str) -> Result<String, AppError> {
+    let trimmed = raw.trim();
+    let invalid =
+        |reason: &str| AppError::InvalidInput(format!("Invalid CORS origin `{trimmed}`: {reason}"));
+    if trimmed.contains('*') {
+        return Err(invalid("wildcards are not allowed"));
+    }
+    let url = Url::parse(trimmed).map_err(|e| invalid(&e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("scheme must be http or https"));
+    }
+    if url.host_str().is_none() {
+        return Err(invalid("missing host"));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(invalid("credentials are not allowed"));
+    }
+    if url.path() != "/" || url.query().is_some() || url.fragment().is_some() {
+        return Err(invalid("must not contain a path, query or fragment"));
+    }
+    Ok(url.origin().ascii_serialization())
 }
 
-fn cors_layer() -> Option<CorsLayer> {
+fn cors_layer(cors: SharedCors) -> CorsLayer {
     // Production-safe CORS defaults. Enable explicitly via env when cross-origin access is needed.
-    let allow_origins = env::var("CORS_ALLOW_ORIGINS").ok().and_then(|value| {
-        let trimmed = value.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
-    });
-    let allow_lan = env_truthy("ALLOW_LAN_CORS") || env_truthy("CC_SWITCH_LAN_CORS");
+    let allow_lan = cors.allow_lan;
     let allow_credentials = env_truthy("CORS_ALLOW_CREDENTIALS");
 
     let mut layer = CorsLayer::new()
@@ -181,44 +508,23 @@ fn cors_layer() -> Option<CorsLayer> {
             Method::HEAD,
             Method::POST,
             Method::PUT,
+            Method::PATCH,
             Method::DELETE,
         ])
         .allow_headers([
             ACCEPT,
             AUTHORIZATION,
             CONTENT_TYPE,
+            header::IF_MATCH,
+            header::IF_NONE_MATCH,
             header::HeaderName::from_static("x-csrf-token"),
-        ]);
-
-    let origins = match allow_origins.as_deref() {
-        Some("*") => {
-            // 显式禁止生产中的通配符，防止意外放开
-            log::warn!("CORS_ALLOW_ORIGINS='*' 已被忽略，请使用逗号分隔的白名单");
-            Vec::new()
-        }
-        Some(list) => list
-            .split(',')
-            .filter_map(|entry| {
-                let trimmed = entry.trim();
-                if trimmed.is_empty() {
-                    None
-                } else {
-                    HeaderValue::from_str(trimmed).ok()
-                }
-            })
-            .collect(),
-        None => Vec::new(),
-    };
-
-    if origins.is_empty() && !allow_lan {
-        // No CORS allow-list provided -> rely on same-origin; do not loosen automatically.
-        return None;
-    }
-
-    layer = layer.allow_origin(AllowOrigin::predicate(move |origin, _| {
-        let is_listed = origins.iter().any(|allowed| allowed == origin);
-        is_listed || (allow_lan && is_private_origin(origin))
-    }));
+            header::HeaderName::from_static("idempotency-key"),
+        ])
+        .expose_headers([
+            header::ETAG,
+            header::HeaderName::from_static(CONFIG_REVISION_HEADER),
+        ])
+        .allow_origin(AllowOrigin::predicate(move |origin, _| cors.allows(origin)));
 
     if allow_lan {
         layer = layer.allow_private_network(true);
@@ -228,7 +534,29 @@ fn cors_layer() -> Option<CorsLayer> {
         layer = layer.allow_credentials(true);
     }
 
-    Some(layer)
+    layer
+}
+
+/// 仅在白名单非空（或启用 LAN CORS）时交给 CORS 层处理，否则保持同源策略，
+/// 使运行时更新白名单后无需重建路由即可生效
+async fn dynamic_cors_middleware(
+    cors: SharedCors,
+    layer: CorsLayer,
+    req: Request<Body>,
+    next: middleware::Next,
+) -> Response {
+    if !cors.is_active() {
+        return next.run(req).await;
+    }
+    let mut service = layer.layer(next);
+    let result = match std::future::poll_fn(|cx| service.poll_ready(cx)).await {
+        Ok(()) => service.call(req).await,
+        Err(never) => match never {},
+    };
+    match result {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
 }
 
 fn env_truthy(name: &str) -> bool {
@@ -294,12 +622,17 @@ fn is_private_ip(ip: IpAddr) -> bool {
 }
 
 fn normalize_api_prefix(raw: &str) -> Option<String> {
+    normalize_path_prefix("WEB_API_PREFIX", raw)
+}
+
+/// Normalize a path prefix to `/segment` form (leading slash, no trailing slash).
+fn normalize_path_prefix(name: &str, raw: &str) -> Option<String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         return None;
     }
     if trimmed.contains("://") {
-        log::warn!("WEB_API_PREFIX expects a path like /api, got {}", trimmed);
+        log::warn!("{name} expects a path like /api, got {}", trimmed);
         return None;
     }
 
@@ -329,6 +662,13 @@ fn web_api_prefix() -> String {
     configured.unwrap_or_else(|| DEFAULT_API_PREFIX.to_string())
 }
 
+/// Optional sub-path mount (e.g. `/cc-switch`) for serving behind a reverse proxy.
+fn web_base_path() -> Option<String> {
+    env::var("WEB_BASE_PATH")
+        .ok()
+        .and_then(|value| normalize_path_prefix("WEB_BASE_PATH", &value))
+}
+
 fn parse_env_usize(name: &str) -> Option<usize> {
     env::var(name)
         .ok()
@@ -341,6 +681,210 @@ fn parse_env_u64(name: &str) -> Option<u64> {
         .and_then(|value| value.trim().parse().ok())
 }
 
+const SUPPORTED_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::Opencode,
+];
+
+/// ENABLED_APPS：逗号分隔的应用白名单（如 `claude,codex`）；未设置或没有可识别的应用时启用全部已支持应用
+pub(crate) fn enabled_apps() -> Vec<AppType> {
+    let Ok(raw) = env::var("ENABLED_APPS") else {
+        return SUPPORTED_APPS.to_vec();
+    };
+    let listed: Vec<AppType> = raw
+        .split(',')
+        .filter_map(|name| AppType::parse_supported(name).ok())
+        .collect();
+    if listed.is_empty() {
+        return SUPPORTED_APPS.to_vec();
+    }
+    SUPPORTED_APPS
+        .into_iter()
+        .filter(|app| listed.contains(app))
+        .collect()
+}
+
+/// omo（oh-my-opencode）随 OpenCode 一起启用或禁用
+pub(crate) fn app_enabled(app: &AppType) -> bool {
+    let app = match app {
+        AppType::Omo => &AppType::Opencode,
+        other => other,
+    };
+    enabled_apps().contains(app)
+}
+
+/// WEB_READ_ONLY：只读模式，拒绝所有非 GET/HEAD 的 API 请求
+fn read_only_enabled() -> bool {
+    env_truthy("WEB_READ_ONLY")
+}
+
+/// WEB_API_ONLY：仅提供 API，不挂载内嵌前端，非 API 路径返回 JSON 404
+fn api_only_enabled() -> bool {
+    env_truthy("WEB_API_ONLY")
+}
+
+fn hsts_enabled() -> bool {
+    env::var("ENABLE_HSTS")
+        .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
+        .unwrap_or(true)
+}
+
+/// 当前生效的运行时开关快照（只读诊断，绝不包含密码、Token 等敏感信息）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfigFlags {
+    pub cors_enabled: bool,
+    pub cors_allow_credentials: bool,
+    pub lan_cors: bool,
+    pub hsts_enabled: bool,
+    pub api_prefix: String,
+    pub base_path: Option<String>,
+    pub max_body_bytes: usize,
+    pub global_concurrency: usize,
+    pub rate_limit_num: Option<u64>,
+    pub rate_limit_window_secs: Option<u64>,
+    #[serde(flatten)]
+    pub egress: crate::usage_script::EgressFlags,
+    pub expand_env_in_mcp: bool,
+    pub allow_shell_metachars_in_mcp: bool,
+    pub mcp_allowed_commands: Option<Vec<String>>,
+    pub compression_min_bytes: u16,
+    pub trusted_proxies: Vec<String>,
+    pub log_buffer: bool,
+    pub read_only: bool,
+    pub api_only: bool,
+    pub branding_dir: Option<String>,
+    /// 生成的密码/Token 文件权限（八进制字符串，如 `0600`）
+    pub file_mode: String,
+    pub enabled_apps: Vec<AppType>,
+}
+
+/// `cors` 为当前生效的 CORS 状态：白名单可在运行时修改，不能只看环境变量
+pub(crate) fn runtime_config_flags(cors: &CorsState) -> RuntimeConfigFlags {
+    RuntimeConfigFlags {
+        cors_enabled: cors.is_active(),
+        cors_allow_credentials: env_truthy("CORS_ALLOW_CREDENTIALS"),
+        lan_cors: env_truthy("ALLOW_LAN_CORS") || env_truthy("CC_SWITCH_LAN_CORS"),
+        hsts_enabled: hsts_enabled(),
+        api_prefix: web_api_prefix(),
+        base_path: web_base_path(),
+        max_body_bytes: parse_env_usize("WEB_MAX_BODY_BYTES")
+            .unwrap_or(DEFAULT_WEB_BODY_LIMIT_BYTES),
+        global_concurrency: parse_env_usize("WEB_GLOBAL_CONCURRENCY")
+            .unwrap_or(DEFAULT_WEB_GLOBAL_CONCURRENCY),
+        rate_limit_num: parse_env_u64("WEB_RATE_LIMIT_NUM").filter(|value| *value > 0),
+        rate_limit_window_secs: parse_env_u64("WEB_RATE_LIMIT_WINDOW_SECS")
+            .filter(|value| *value > 0),
+        egress: crate::usage_script::egress_flags(),
+        expand_env_in_mcp: crate::mcp::conversion::expand_env_enabled(),
+        allow_shell_metachars_in_mcp: crate::mcp::validation::allow_shell_metachars(),
+        mcp_allowed_commands: crate::mcp::validation::allowed_commands(),
+        compression_min_bytes: compression_min_bytes(),
+        trusted_proxies: client_ip::TrustedProxies::from_env().describe(),
+        log_buffer: logging::log_buffer().is_some(),
+        read_only: read_only_enabled(),
+        api_only: api_only_enabled(),
+        branding_dir: branding_dir().map(|dir| dir.to_string_lossy().to_string()),
+        file_mode: format!("{:04o}", effective_file_mode()),
+        enabled_apps: enabled_apps(),
+    }
+}
+
+/// 内嵌前端资源自检结果，用于远程排查空白页（如构建时 `dist-web` 为空）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetsCheck {
+    pub index_html_present: bool,
+    /// index.html 的原始字节数（注入 Token 之前）
+    pub index_html_bytes: Option<usize>,
+    pub asset_count: usize,
+}
+
+pub(crate) fn embedded_assets_check() -> AssetsCheck {
+    let index_html = WebAssets::get("index.html");
+    AssetsCheck {
+        index_html_present: index_html.is_some(),
+        index_html_bytes: index_html.map(|asset| asset.data.len()),
+        asset_count: WebAssets::iter().count(),
+    }
+}
+
+/// 启动时输出的安全相关生效配置摘要（单行 key=value），便于运维仅凭日志审计部署
+pub fn security_posture_summary(bind_addr: SocketAddr, allow_insecure_http: bool) -> String {
+    format_security_posture(
+        &runtime_config_flags(&CorsState::from_env()),
+        bind_addr,
+        allow_insecure_http,
+    )
+}
+
+fn format_security_posture(
+    flags: &RuntimeConfigFlags,
+    bind_addr: SocketAddr,
+    allow_insecure_http: bool,
+) -> String {
+    let public_bind = match bind_addr.ip() {
+        IpAddr::V4(v4) => !ipv4_is_loopback(v4),
+        IpAddr::V6(v6) => !ipv6_is_loopback(v6),
+    };
+    let list_or_any = |values: &Option<Vec<String>>| {
+        values
+            .as_ref()
+            .map(|values| values.join(","))
+            .unwrap_or_else(|| "any".to_string())
+    };
+    let rate_limit = match (flags.rate_limit_num, flags.rate_limit_window_secs) {
+        (Some(num), Some(window)) => format!("{num}/{window}s"),
+        _ => "off".to_string(),
+    };
+
+    format!(
+        "security posture: bind={bind_addr} public_bind={public_bind} \
+         insecure_http={allow_insecure_http} hsts={} cors={} cors_credentials={} lan_cors={} \
+         trusted_proxies={} egress_policy={} allowed_hosts={} redirects={} rate_limit={rate_limit} \
+         mcp_allowed_commands={} mcp_shell_metachars={} mcp_env_expansion={} read_only={}",
+        flags.hsts_enabled,
+        flags.cors_enabled,
+        flags.cors_allow_credentials,
+        flags.lan_cors,
+        if flags.trusted_proxies.is_empty() {
+            "none".to_string()
+        } else {
+            flags.trusted_proxies.join(",")
+        },
+        flags.egress.egress_policy,
+        list_or_any(&flags.egress.allowed_hosts),
+        flags.egress.allow_redirects,
+        list_or_any(&flags.mcp_allowed_commands),
+        flags.allow_shell_metachars_in_mcp,
+        flags.expand_env_in_mcp,
+        flags.read_only,
+    )
+}
+
+/// COMPRESSION_MIN_BYTES：压缩阈值（字节），超过 u16 上限时按上限处理
+fn compression_min_bytes() -> u16 {
+    parse_env_u64("COMPRESSION_MIN_BYTES")
+        .map(|value| value.min(u64::from(u16::MAX)) as u16)
+        .unwrap_or(DEFAULT_COMPRESSION_MIN_BYTES)
+}
+
+/// 响应压缩：仅压缩超过阈值的响应，并跳过图片、压缩包、字体等已压缩的内容以及 SSE
+fn compression_layer() -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(compression_min_bytes())
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE)
+        .and(NotForContentType::const_new("application/zip"))
+        .and(NotForContentType::const_new("application/gzip"))
+        .and(NotForContentType::const_new("font/woff"))
+        .and(NotForContentType::const_new("video/"))
+        .and(NotForContentType::const_new("audio/"));
+    CompressionLayer::new().compress_when(predicate)
+}
+
 struct RateLimitState {
     window_start: Instant,
     count: u64,
@@ -376,6 +920,10 @@ async fn rate_limit_middleware(
 }
 
 pub fn web_password_path() -> Option<PathBuf> {
+    // WEB_PASSWORD_FILE 指向外部管理的密码文件（如容器挂载的 secret）
+    if let Some(path) = env::var_os("WEB_PASSWORD_FILE").filter(|value| !value.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
     get_home_dir().map(|home| home.join(".cc-switch").join("web_password"))
 }
 
@@ -488,6 +1036,70 @@ pub fn load_or_generate_web_credentials() -> Result<(SharedWebAuth, PathBuf), Ap
     Ok((build_shared_web_auth(username, password), password_path))
 }
 
+/// 重新读取磁盘上的用户名与密码并替换当前生效的凭据，外部轮换密码后无需重启。
+/// 密码文件缺失或为空时返回错误，现有凭据保持不变。
+pub fn reload_web_credentials(auth_state: &SharedWebAuth) -> Result<(), AppError> {
+    let path = web_password_path().ok_or_else(|| {
+        AppError::Config("Unable to locate home directory for web password".into())
+    })?;
+    let content = fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))?;
+    let password = content.trim();
+    if password.is_empty() {
+        return Err(AppError::Config(format!(
+            "Web password file is empty: {}",
+            path.display()
+        )));
+    }
+    let username = load_web_username();
+
+    let mut guard = auth_state.write().map_err(AppError::from)?;
+    guard.username = username;
+    guard.password = password.to_string();
+    Ok(())
+}
+
+/// Web 凭据存放位置与状态（绝不包含密码本身）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialsInfo {
+    /// `env`：密码文件由 `WEB_PASSWORD_FILE` 指定；`file`：默认的 `~/.cc-switch/web_password`
+    pub source: &'static str,
+    pub path: Option<String>,
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+    /// 密码文件存在且权限不宽于 `CC_SWITCH_FILE_MODE`（Unix 下默认 0600）
+    pub permissions_ok: bool,
+}
+
+pub(crate) fn web_credentials_info() -> CredentialsInfo {
+    let source = if env::var_os("WEB_PASSWORD_FILE").is_some_and(|value| !value.is_empty()) {
+        "env"
+    } else {
+        "file"
+    };
+    let path = web_password_path();
+    let metadata = path.as_deref().and_then(|path| fs::metadata(path).ok());
+    CredentialsInfo {
+        source,
+        path: path.map(|path| path.to_string_lossy().to_string()),
+        last_modified: metadata
+            .as_ref()
+            .and_then(|meta| meta.modified().ok())
+            .map(chrono::DateTime::<chrono::Utc>::from),
+        permissions_ok: metadata.as_ref().is_some_and(owner_only_permissions),
+    }
+}
+
+#[cfg(unix)]
+fn owner_only_permissions(metadata: &fs::Metadata) -> bool {
+    metadata.permissions().mode() & 0o777 & !effective_file_mode() == 0
+}
+
+// Windows 上的 ACL 由 icacls 尽力设置，无法仅凭元数据判断，文件存在即视为通过
+#[cfg(not(unix))]
+fn owner_only_permissions(metadata: &fs::Metadata) -> bool {
+    metadata.is_file()
+}
+
 pub fn build_shared_web_auth(username: String, password: String) -> SharedWebAuth {
     Arc::new(RwLock::new(WebAuthCredentials { username, password }))
 }
@@ -508,16 +1120,21 @@ pub fn create_router_with_credentials(
 }
 
 pub fn create_router_with_auth_state(state: SharedState, auth_state: SharedWebAuth) -> Router {
-    let tokens = Arc::new(load_or_generate_tokens());
-    let csrf_token = Some(Arc::new(tokens.csrf_token.clone()));
+    let tokens = load_or_generate_tokens();
     let api_prefix = web_api_prefix();
-    let api_prefix_arc = Arc::new(api_prefix.clone());
+    let base_path = web_base_path();
+    let spa_api_base = format!("{}{}", base_path.as_deref().unwrap_or_default(), api_prefix);
+    // 校验器、index.html 与 `/system/csrf-token` 共用同一份 Token，保证返回的就是校验时接受的值
+    let csrf: SharedCsrf = Arc::new(CsrfState::new(
+        tokens.csrf_token,
+        spa_api_base,
+        base_path.clone(),
+    ));
+    let cors: SharedCors = Arc::new(CorsState::from_env());
 
-    let hsts_enabled = env::var("ENABLE_HSTS")
-        .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
-        .unwrap_or(true);
+    let hsts_enabled = hsts_enabled();
 
-    let auth_validator = AuthValidator::new(auth_state.clone(), Some(tokens.csrf_token.clone()));
+    let auth_validator = AuthValidator::new(auth_state.clone(), csrf.clone());
 
     let body_limit = parse_env_usize("WEB_MAX_BODY_BYTES").unwrap_or(DEFAULT_WEB_BODY_LIMIT_BYTES);
     let global_concurrency =
@@ -525,9 +1142,23 @@ pub fn create_router_with_auth_state(state: SharedState, auth_state: SharedWebAu
     let rate_limit_num = parse_env_u64("WEB_RATE_LIMIT_NUM").filter(|value| *value > 0);
     let rate_limit_window = parse_env_u64("WEB_RATE_LIMIT_WINDOW_SECS").filter(|value| *value > 0);
 
-    let mut router = routes::create_router(state)
+    // 以显式通配路由代替 fallback：嵌套路由的 fallback 会被根路由的 SPA `/*path` 抢先匹配
+    let mut router = routes::create_router(state.clone())
+        .route("/*rest", any(api_not_found))
         .fallback(api_not_found)
-        .layer(Extension(csrf_token))
+        // 位于认证之内：未认证请求无法借此探测修订号
+        .layer(middleware::from_fn(move |req, next| {
+            config_revision_middleware(state.clone(), req, next)
+        }));
+    if read_only_enabled() {
+        // 同样位于认证之内：未认证请求仍返回 401，不暴露只读状态
+        router = router.layer(middleware::from_fn(read_only_middleware));
+    }
+    let mut router = router
+        // 透明解压 `Content-Encoding: gzip` 请求体；DefaultBodyLimit 作用于解压后的数据，防止解压炸弹
+        .layer(RequestDecompressionLayer::new())
+        .layer(Extension(csrf.clone()))
+        .layer(Extension(cors.clone()))
         .layer(Extension(auth_state))
         .layer(ValidateRequestHeaderLayer::custom(auth_validator.clone()));
 
@@ -536,38 +1167,66 @@ pub fn create_router_with_auth_state(state: SharedState, auth_state: SharedWebAu
     }
 
     // Only apply CORS when a valid allow-list is configured; default to same-origin.
-    let router = if let Some(cors) = cors_layer() {
-        router.layer(cors)
-    } else {
-        router
+    let router = {
+        let layer = cors_layer(cors.clone());
+        router.layer(middleware::from_fn(move |req, next| {
+            dynamic_cors_middleware(cors.clone(), layer.clone(), req, next)
+        }))
     };
 
+    // 静态资源同时响应 GET 与 HEAD（部分健康检查/代理使用 HEAD），HEAD 响应由 axum 去除 body
+    const STATIC_METHODS: MethodFilter = MethodFilter::GET.or(MethodFilter::HEAD);
     let static_router = Router::new()
         .route(
             "/",
-            get({
-                let tokens = tokens.clone();
-                let api_base = api_prefix_arc.clone();
-                move |path, headers| serve_static(path, headers, tokens.clone(), api_base.clone())
+            on(STATIC_METHODS, {
+                let csrf = csrf.clone();
+                move |path, headers| serve_static(path, headers, csrf.index_html())
             }),
         )
         .route(
             "/*path",
-            get({
-                let tokens = tokens.clone();
-                let api_base = api_prefix_arc.clone();
-                move |path, headers| serve_static(path, headers, tokens.clone(), api_base.clone())
+            on(STATIC_METHODS, {
+                let csrf = csrf.clone();
+                move |path, headers| serve_static(path, headers, csrf.index_html())
             }),
         )
-        .layer(ValidateRequestHeaderLayer::custom(auth_validator));
-
-    let mut root = Router::new()
-        .nest(api_prefix.as_str(), router)
-        .merge(static_router)
-        .layer(middleware::from_fn({
-            let hsts_enabled = hsts_enabled;
-            move |req, next| add_hsts_header(hsts_enabled, req, next)
-        }));
+        .layer(ValidateRequestHeaderLayer::custom(auth_validator.clone()));
+
+    let api_only = api_only_enabled();
+    let mut root = Router::new().nest(api_prefix.as_str(), router);
+    root = if api_only {
+        // 纯 API 模式：不挂载内嵌前端，其余路径返回 JSON 404，避免真实的 404 被 index.html 掩盖
+        root.fallback(non_api_not_found)
+    } else {
+        root.merge(static_router)
+    };
+    if let Some(base_path) = base_path.as_deref() {
+        // 子路径挂载：仅响应 `/base` 下的请求，根路径返回 404
+        root = if api_only {
+            Router::new()
+                .nest(base_path, root)
+                .fallback(non_api_not_found)
+        } else {
+            // axum 的 nest 不匹配 `/base/`，单独注册以支持带尾部斜杠的入口
+            let index_route = on(STATIC_METHODS, {
+                let csrf = csrf.clone();
+                move || {
+                    let index_html = csrf.index_html();
+                    async move { index_response(index_html) }
+                }
+            })
+            .layer(ValidateRequestHeaderLayer::custom(auth_validator.clone()));
+            Router::new()
+                .route(&format!("{base_path}/"), index_route)
+                .nest(base_path, root)
+        };
+    }
+    // API JSON 与静态资源统一在最外层按阈值压缩
+    let mut root = root.layer(compression_layer()).layer(middleware::from_fn({
+        let hsts_enabled = hsts_enabled;
+        move |req, next| add_hsts_header(hsts_enabled, req, next)
+    }));
 
     if global_concurrency > 0 {
         root = root.layer(GlobalConcurrencyLimitLayer::new(global_concurrency));
@@ -583,26 +1242,135 @@ pub fn create_router_with_auth_state(state: SharedState, auth_state: SharedWebAu
             move |req, next| rate_limit_middleware(state.clone(), num, window, req, next)
         }));
     }
+    // 最外层解析真实客户端 IP：仅当对端属于 TRUSTED_PROXIES 时才采信转发头
+    let trusted_proxies = client_ip::TrustedProxies::from_env();
+    root = root.layer(middleware::from_fn(move |req, next| {
+        client_ip::client_ip_middleware(trusted_proxies.clone(), req, next)
+    }));
 
     root
 }
 
-async fn api_not_found() -> StatusCode {
-    StatusCode::NOT_FOUND
+/// 只读模式：除 GET/HEAD（及 CORS 预检 OPTIONS）外的请求一律返回 403，无论 CSRF 是否有效
+async fn read_only_middleware(req: Request<Body>, next: middleware::Next) -> Response {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(req).await;
+    }
+    handlers::ApiError::new(
+        StatusCode::FORBIDDEN,
+        "Web console is in read-only mode (WEB_READ_ONLY=1); modifications are disabled",
+    )
+    .into_response()
+}
+
+const CONFIG_REVISION_HEADER: &str = "x-config-revision";
+
+/// 条件写入：变更请求携带 `If-Match` 且不包含当前配置修订号时返回 409，避免多个客户端互相覆盖；
+/// 所有 API 响应都附带 `X-Config-Revision`，客户端据此记录最新修订号
+///
+/// 这里的比较只是提前拒绝明显过期的请求；权威校验在写入配置时进行，与递增修订号处于同一写锁内
+/// （见 [`AppState::with_expected_revision`]），因此携带相同 `If-Match` 的并发写入只有一个成功。
+async fn config_revision_middleware(
+    state: SharedState,
+    req: Request<Body>,
+    next: middleware::Next,
+) -> Response {
+    let is_mutation = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let expected = req
+        .headers()
+        .get(header::IF_MATCH)
+        .filter(|_| is_mutation)
+        .map(|if_match| if_match.to_str().ok().and_then(parse_if_match));
+
+    // 冲突响应同样附带修订号，客户端无需额外请求即可刷新
+    let mut response = match expected {
+        None | Some(Some(IfMatch::Any)) => next.run(req).await,
+        Some(Some(IfMatch::Revisions(allowed))) => {
+            let current = current_config_revision(&state);
+            if allowed.contains(&current) {
+                AppState::with_expected_revision(allowed, next.run(req)).await
+            } else {
+                handlers::ApiError::new(
+                    StatusCode::CONFLICT,
+                    format!(
+                        "Config has been modified (current revision {current}); reload and retry"
+                    ),
+                )
+                .into_response()
+            }
+        }
+        Some(None) => handlers::ApiError::bad_request("Invalid If-Match header").into_response(),
+    };
+    response.headers_mut().insert(
+        CONFIG_REVISION_HEADER,
+        HeaderValue::from(current_config_revision(&state)),
+    );
+    response
+}
+
+fn current_config_revision(state: &AppState) -> u64 {
+    state
+        .config
+        .read()
+        .map(|config| config.revision)
+        .unwrap_or_else(|poisoned| poisoned.into_inner().revision)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum IfMatch {
+    /// `*`：匹配任意修订号
+    Any,
+    Revisions(Vec<u64>),
+}
+
+/// 解析 `If-Match`：`*` 匹配任意修订号，否则为逗号分隔的 `"3"`、`W/"3"` 或裸数字；格式无效时返回 `None`
+fn parse_if_match(value: &str) -> Option<IfMatch> {
+    let value = value.trim();
+    if value == "*" {
+        return Some(IfMatch::Any);
+    }
+    value
+        .split(',')
+        .map(|tag| {
+            let tag = tag.trim();
+            let tag = tag.strip_prefix("W/").unwrap_or(tag);
+            let tag = tag
+                .strip_prefix('"')
+                .and_then(|tag| tag.strip_suffix('"'))
+                .unwrap_or(tag);
+            tag.parse::<u64>().ok()
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(IfMatch::Revisions)
+}
+
+/// 未匹配的 `/api/*` 请求返回 JSON 404，而不是落入 SPA 的 index.html
+async fn api_not_found(method: Method, uri: axum::http::Uri) -> handlers::ApiError {
+    handlers::ApiError::new(
+        StatusCode::NOT_FOUND,
+        format!("API route not found: {method} {}", uri.path()),
+    )
+}
+
+async fn non_api_not_found(method: Method, uri: axum::http::Uri) -> handlers::ApiError {
+    handlers::ApiError::new(
+        StatusCode::NOT_FOUND,
+        format!(
+            "Not found: {method} {} (WEB_API_ONLY=1, the web UI is not served)",
+            uri.path()
+        ),
+    )
 }
 
 #[derive(Clone)]
 struct AuthValidator {
     credentials: SharedWebAuth,
-    csrf_token: Option<Arc<String>>,
+    csrf: SharedCsrf,
 }
 
 impl AuthValidator {
-    fn new(credentials: SharedWebAuth, csrf_token: Option<String>) -> Self {
-        Self {
-            credentials,
-            csrf_token: csrf_token.map(Arc::new),
-        }
+    fn new(credentials: SharedWebAuth, csrf: SharedCsrf) -> Self {
+        Self { credentials, csrf }
     }
 
     fn is_authorized(&self, auth_value: &str) -> bool {
@@ -672,15 +1440,13 @@ impl tower_http::validate_request::ValidateRequest<Body> for AuthValidator {
             return Err(Self::unauthorized());
         }
 
-        if let Some(csrf) = &self.csrf_token {
-            if request.method() != Method::GET && request.method() != Method::HEAD {
-                let token = request
-                    .headers()
-                    .get("x-csrf-token")
-                    .and_then(|v| v.to_str().ok());
-                if token != Some(csrf.as_str()) {
-                    return Err(Self::forbidden_csrf());
-                }
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            let token = request
+                .headers()
+                .get("x-csrf-token")
+                .and_then(|v| v.to_str().ok());
+            if !self.csrf.matches(token) {
+                return Err(Self::forbidden_csrf());
             }
         }
 
@@ -718,9 +1484,50 @@ fn token_store_path() -> Option<PathBuf> {
     get_home_dir().map(|home| home.join(".cc-switch").join("web_env"))
 }
 
+const DEFAULT_FILE_MODE: u32 = 0o600;
+
+/// 读取 `CC_SWITCH_FILE_MODE`（八进制，如 `0640`），作为生成的密码/Token 文件权限。
+/// 未设置时为 `0600`；拒绝其他用户可写、或所有者不可读写的模式
+pub fn file_mode() -> Result<u32, AppError> {
+    let Ok(raw) = env::var("CC_SWITCH_FILE_MODE") else {
+        return Ok(DEFAULT_FILE_MODE);
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(DEFAULT_FILE_MODE);
+    }
+    let digits = trimmed.strip_prefix("0o").unwrap_or(trimmed);
+    let mode = u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o777)
+        .ok_or_else(|| {
+            AppError::Config(format!(
+                "CC_SWITCH_FILE_MODE must be an octal file mode such as 0640, got `{trimmed}`"
+            ))
+        })?;
+    if mode & 0o002 != 0 {
+        return Err(AppError::Config(format!(
+            "CC_SWITCH_FILE_MODE {mode:04o} is world-writable"
+        )));
+    }
+    if mode & 0o600 != 0o600 {
+        return Err(AppError::Config(format!(
+            "CC_SWITCH_FILE_MODE {mode:04o} must keep owner read/write"
+        )));
+    }
+    Ok(mode)
+}
+
+fn effective_file_mode() -> u32 {
+    file_mode().unwrap_or_else(|err| {
+        log::warn!("{err}; falling back to {DEFAULT_FILE_MODE:04o}");
+        DEFAULT_FILE_MODE
+    })
+}
+
 #[cfg(unix)]
 fn enforce_permissions(path: &StdPath) -> std::io::Result<()> {
-    fs::set_permissions(path, PermissionsExt::from_mode(0o600))
+    fs::set_permissions(path, PermissionsExt::from_mode(effective_file_mode()))
 }
 
 #[cfg(windows)]
@@ -745,17 +1552,35 @@ fn enforce_permissions(_path: &StdPath) -> std::io::Result<()> {
     Ok(())
 }
 
-fn load_or_generate_tokens() -> WebTokens {
-    let env_csrf = env::var("WEB_CSRF_TOKEN").ok().and_then(|val| {
+fn env_csrf_token() -> Option<String> {
+    env::var("WEB_CSRF_TOKEN").ok().and_then(|val| {
         let trimmed = val.trim();
         if trimmed.is_empty() {
             None
         } else {
             Some(trimmed.to_string())
         }
-    });
+    })
+}
 
-    if let Some(csrf) = env_csrf {
+/// 将 CSRF Token 写回 `web_env`，保留文件中的其他行
+fn persist_csrf_token(csrf: &str) -> Result<(), AppError> {
+    let path = token_store_path()
+        .ok_or_else(|| AppError::Config("Unable to locate home directory for web tokens".into()))?;
+    let mut content: String = fs::read_to_string(&path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.starts_with("WEB_CSRF_TOKEN="))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    content.push_str(&format!("WEB_CSRF_TOKEN={csrf}\n"));
+    atomic_write(&path, content.as_bytes())?;
+    enforce_permissions(&path).map_err(|e| AppError::io(&path, e))?;
+    Ok(())
+}
+
+fn load_or_generate_tokens() -> WebTokens {
+    if let Some(csrf) = env_csrf_token() {
         return WebTokens { csrf_token: csrf };
     }
 
@@ -836,3 +1661,136 @@ fn generate_password(length: usize) -> String {
     chars.shuffle(&mut rng);
     chars.into_iter().collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inject_index_tokens_adds_script_and_meta_tag() {
+        let html = "<html><head><title>cc</title></head><body></body></html>";
+
+        let injected = inject_index_tokens(html, "tok\"en", "/api");
+
+        assert!(injected.contains(r#"<meta name="csrf-token" content="tok&quot;en">"#));
+        assert!(injected.contains("window.__CC_SWITCH_TOKENS__"));
+        assert!(injected.contains(r#"csrfToken: "tok\"en""#));
+        assert!(injected.contains(r#"window.__CC_SWITCH_API_BASE__ = "/api";"#));
+        let head_end = injected.find("</head>").expect("head end");
+        assert!(injected.find("<meta name=\"csrf-token\"").unwrap() < head_end);
+        assert!(injected.find("<script>").unwrap() < head_end);
+    }
+
+    #[test]
+    fn if_match_accepts_quoted_weak_and_wildcard_revisions() {
+        assert_eq!(parse_if_match("\"7\""), Some(IfMatch::Revisions(vec![7])));
+        assert_eq!(parse_if_match("W/\"7\""), Some(IfMatch::Revisions(vec![7])));
+        assert_eq!(
+            parse_if_match("\"3\", \"7\""),
+            Some(IfMatch::Revisions(vec![3, 7]))
+        );
+        assert_eq!(parse_if_match("*"), Some(IfMatch::Any));
+        assert_eq!(parse_if_match("6"), Some(IfMatch::Revisions(vec![6])));
+        assert_eq!(parse_if_match("\"abc\""), None);
+    }
+
+    #[test]
+    fn security_posture_reflects_configured_flags() {
+        let mut flags = runtime_config_flags(&CorsState::from_env());
+        flags.hsts_enabled = false;
+        flags.cors_enabled = true;
+        flags.cors_allow_credentials = true;
+        flags.lan_cors = false;
+        flags.trusted_proxies = vec!["10.0.0.0/8".into()];
+        flags.rate_limit_num = Some(30);
+        flags.rate_limit_window_secs = Some(60);
+        flags.egress.egress_policy = "strict";
+        flags.egress.allowed_hosts = Some(vec!["api.example.com".into(), "example.org".into()]);
+        flags.egress.allow_redirects = true;
+        flags.mcp_allowed_commands = None;
+        flags.allow_shell_metachars_in_mcp = false;
+        flags.expand_env_in_mcp = true;
+        flags.read_only = true;
+
+        let summary = format_security_posture(&flags, "0.0.0.0:3000".parse().unwrap(), true);
+
+        for expected in [
+            "bind=0.0.0.0:3000",
+            "public_bind=true",
+            "insecure_http=true",
+            "hsts=false",
+            "cors=true",
+            "cors_credentials=true",
+            "lan_cors=false",
+            "trusted_proxies=10.0.0.0/8",
+            "egress_policy=strict",
+            "allowed_hosts=api.example.com,example.org",
+            "redirects=true",
+            "rate_limit=30/60s",
+            "mcp_allowed_commands=any",
+            "mcp_shell_metachars=false",
+            "mcp_env_expansion=true",
+            "read_only=true",
+        ] {
+            assert!(
+                summary.contains(expected),
+                "missing `{expected}` in {summary}"
+            );
+        }
+        assert!(!summary.contains('\n'));
+
+        let summary = format_security_posture(&flags, "127.0.0.1:3000".parse().unwrap(), false);
+        assert!(summary.contains("public_bind=false"));
+        assert!(summary.contains("insecure_http=false"));
+    }
+
+    #[test]
+    fn is_hashed_asset_detects_content_hashes() {
+        assert!(is_hashed_asset("assets/app.abcd1234.js"));
+        assert!(is_hashed_asset("assets/index-0f3e9a7c1b.css"));
+        // Vite 默认的 8 位 base64url 哈希
+        assert!(is_hashed_asset("assets/index-DiwrgTda.js"));
+        assert!(is_hashed_asset("assets/vendor-B_x-9Qz1.css"));
+        assert!(!is_hashed_asset("index.html"));
+        assert!(!is_hashed_asset("assets/settings.js"));
+        assert!(!is_hashed_asset("assets/logo-abc.png"));
+    }
+
+    #[test]
+    fn inject_index_tokens_handles_uppercase_head() {
+        let html = "<HTML><HEAD><TITLE>cc</TITLE></HEAD><BODY></BODY></HTML>";
+
+        let injected = inject_index_tokens(html, "tok", "/api");
+
+        let script = injected.find("<script>").expect("script injected");
+        assert!(script < injected.find("</HEAD>").expect("head end"));
+        assert!(injected.ends_with("</HTML>"));
+    }
+
+    #[test]
+    fn inject_index_tokens_inserts_after_attributed_or_self_closing_head() {
+        let attributed = "<html><head data-x=\"1\"><title>cc</title><body></body></html>";
+        let injected = inject_index_tokens(attributed, "tok", "/api");
+        assert!(injected.starts_with("<html><head data-x=\"1\"><meta name=\"csrf-token\""));
+
+        let self_closing = "<html><head/><body></body></html>";
+        let injected = inject_index_tokens(self_closing, "tok", "/api");
+        assert!(injected.starts_with("<html><head/><meta name=\"csrf-token\""));
+    }
+
+    #[test]
+    fn inject_index_tokens_falls_back_to_body_without_head() {
+        let html =
+            "<html><header></header><BODY class=\"app\"><div id=\"root\"></div></BODY></html>";
+
+        let injected = inject_index_tokens(html, "tok", "/api");
+
+        let body_open = injected.find("<BODY class=\"app\">").expect("body tag");
+        let meta = injected
+            .find("<meta name=\"csrf-token\"")
+            .expect("meta injected");
+        assert!(meta > body_open);
+        assert!(meta < injected.find("<div id=\"root\">").expect("root div"));
+        assert!(injected.ends_with("</html>"));
+    }
+}
@@ -1,7 +1,7 @@
 #![cfg(feature = "web-server")]
 
 use axum::{
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 
@@ -19,9 +19,36 @@ pub fn create_router(state: SharedState) -> Router {
         .nest("/skills", skill_routes())
         .nest("/settings", settings_routes())
         .nest("/config", config_routes())
+        .route("/usage-script/parse", post(providers::parse_usage_script))
+        .route(
+            "/usage-script/variables",
+            get(providers::usage_script_variables),
+        )
         .route("/tray/update", post(system::update_tray))
         .route("/system/csrf-token", get(system::get_csrf_token))
+        .route("/system/rotate-csrf", post(system::rotate_csrf_token))
         .route("/system/credentials", put(system::update_credentials))
+        .route("/system/credentials-info", get(system::credentials_info))
+        .route(
+            "/system/reload-credentials",
+            post(system::reload_credentials),
+        )
+        .route(
+            "/system/cors-origins",
+            get(system::get_cors_origins).put(system::update_cors_origins),
+        )
+        .route("/system/log-level", put(system::set_log_level))
+        .route(
+            "/system/logs",
+            get(system::get_logs).delete(system::clear_logs),
+        )
+        .route("/system/preflight", get(system::preflight))
+        .route("/system/config-flags", get(system::config_flags))
+        .route("/system/apps", get(system::list_apps))
+        .route("/system/assets-check", get(system::assets_check))
+        .route("/system/stats", get(system::stats))
+        .route("/system/tasks", get(system::list_tasks))
+        .route("/system/support-bundle", get(system::support_bundle))
         .route("/system/open-external", post(system::open_external))
         .route("/fs/pick-directory", post(config::pick_directory))
         .route("/fs/save-file", post(config::save_file_dialog))
@@ -36,17 +63,34 @@ fn provider_routes() -> Router<SharedState> {
             get(providers::list_providers).post(providers::add_provider),
         )
         .route("/:app/current", get(providers::current_provider))
+        .route("/:app/groups", get(providers::list_provider_groups))
+        .route("/:app/tags", get(providers::list_provider_tags))
+        .route("/:app/duplicates", get(providers::list_duplicate_providers))
+        .route("/:app/switch-history", get(providers::switch_history))
         .route(
             "/:app/live-settings",
             get(providers::read_live_provider_settings),
         )
+        .route("/:app/import-live", post(providers::import_live_provider))
+        .route("/:app/validate", post(providers::validate_settings))
         .route(
             "/:app/:id",
-            put(providers::update_provider).delete(providers::delete_provider),
+            get(providers::get_provider)
+                .put(providers::update_provider)
+                .delete(providers::delete_provider),
         )
         .route("/:app/:id/switch", post(providers::switch_provider))
+        .route("/:app/:id/preflight", post(providers::preflight_switch))
+        .route(
+            "/:app/:id/copy-to/:to_app",
+            post(providers::copy_provider_to_app),
+        )
         .route("/:app/:id/usage", post(providers::query_provider_usage))
         .route("/:app/:id/usage/test", post(providers::test_usage_script))
+        .route(
+            "/:app/:id/usage/test-history",
+            get(providers::usage_test_history),
+        )
         .route(
             "/:app/import-default",
             post(providers::import_default_config),
@@ -65,12 +109,15 @@ fn provider_routes() -> Router<SharedState> {
 fn mcp_routes() -> Router<SharedState> {
     Router::new()
         .route("/status", get(mcp::get_status))
+        .route("/schema", get(mcp::get_schema))
         .route("/config/claude", get(mcp::read_config))
         .route(
             "/config/claude/servers/:id",
             put(mcp::upsert_claude_server).delete(mcp::delete_claude_server),
         )
         .route("/validate", post(mcp::validate_command))
+        .route("/test-url", post(mcp::test_url))
+        .route("/codex/preview", post(mcp::preview_codex_toml))
         .route("/config/:app", get(mcp::get_config))
         .route(
             "/config/:app/servers/:id",
@@ -78,11 +125,25 @@ fn mcp_routes() -> Router<SharedState> {
         )
         .route("/config/:app/servers/:id/enabled", post(mcp::set_enabled))
         .route("/servers", get(mcp::list_servers).post(mcp::upsert_server))
+        .route("/servers/duplicates", get(mcp::list_duplicate_servers))
+        .route(
+            "/enable-matrix",
+            get(mcp::get_enable_matrix).post(mcp::apply_enable_matrix),
+        )
+        .route("/servers/merge", post(mcp::merge_servers))
+        .route("/servers/delete-batch", post(mcp::delete_servers))
         .route(
             "/servers/:id",
-            put(mcp::update_server).delete(mcp::delete_server),
+            get(mcp::get_server)
+                .put(mcp::update_server)
+                .patch(mcp::patch_server)
+                .delete(mcp::delete_server),
         )
+        .route("/servers/:id/rename", post(mcp::rename_server))
+        .route("/servers/:id/apps", post(mcp::set_apps))
         .route("/servers/:id/apps/:app", post(mcp::toggle_app))
+        .route("/import/:app", post(mcp::import_from_app))
+        .route("/import-url", post(mcp::import_from_url))
 }
 
 fn prompt_routes() -> Router<SharedState> {
@@ -90,20 +151,35 @@ fn prompt_routes() -> Router<SharedState> {
         .route("/:app", get(prompts::list_prompts))
         .route(
             "/:app/:id",
-            put(prompts::upsert_prompt).delete(prompts::delete_prompt),
+            get(prompts::get_prompt)
+                .put(prompts::upsert_prompt)
+                .delete(prompts::delete_prompt),
         )
         .route("/:app/:id/enable", post(prompts::enable_prompt))
         .route("/:app/import-from-file", post(prompts::import_from_file))
         .route("/:app/current-file", get(prompts::current_file_content))
+        .route("/:app/export", get(prompts::export_bundle))
+        .route("/:app/import-bundle", post(prompts::import_bundle))
 }
 
 fn skill_routes() -> Router<SharedState> {
     Router::new()
         .route("/", get(skills::list_skills))
         .route("/install", post(skills::install_skill))
+        .route("/install-batch", post(skills::install_skills_batch))
         .route("/uninstall", post(skills::uninstall_skill))
+        .route("/cancel", post(skills::cancel_install))
+        .route(
+            "/verify",
+            get(skills::verify_skills).post(skills::verify_skills),
+        )
+        .route(
+            "/cache",
+            get(skills::list_skill_cache).delete(skills::clear_skill_cache),
+        )
         .route("/repos", get(skills::list_repos).post(skills::add_repo))
         .route("/repos/:owner/:name", delete(skills::remove_repo))
+        .route("/:owner/:name/manifest", get(skills::repo_manifest))
 }
 
 fn settings_routes() -> Router<SharedState> {
@@ -115,14 +191,18 @@ fn settings_routes() -> Router<SharedState> {
 
 fn config_routes() -> Router<SharedState> {
     Router::new()
+        .route("/", patch(config::patch_config))
         .route(
             "/export",
             get(config::export_config_snapshot).post(config::export_config),
         )
         .route("/import", post(config::import_config))
+        .route("/diff", post(config::diff_configs))
+        .route("/sync-all", post(config::sync_all_live))
         .route("/:app/dir", get(config::get_config_dir))
         .route("/:app/dir-info", get(config::get_config_dir_info))
         .route("/:app/open", post(config::open_config_folder))
+        .route("/:app/reset", post(config::reset_app_config))
         .route(
             "/claude-code/path",
             get(config::get_claude_code_config_path),
@@ -139,6 +219,7 @@ fn config_routes() -> Router<SharedState> {
                 .put(config::set_claude_common_config_snippet),
         )
         .route("/claude/plugin", post(config::apply_claude_plugin_config))
+        .route("/codex/effective", get(config::get_codex_effective_config))
         .route(
             "/:app/common-snippet",
             get(config::get_common_config_snippet).put(config::set_common_config_snippet),
@@ -0,0 +1,45 @@
+use std::{fs, sync::Arc};
+
+use cc_switch_lib::{AppState, ConfigService, FixedClock, MultiAppConfig};
+use chrono::{Duration, TimeZone, Utc};
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, test_mutex};
+
+#[test]
+fn create_backup_id_embeds_injected_time() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let config_dir = home.join(".cc-switch");
+    let config_path = config_dir.join("config.json");
+    fs::create_dir_all(&config_dir).expect("prepare config dir");
+    fs::write(&config_path, r#"{"version":2}"#).expect("write config file");
+
+    let fixed = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+    let clock = Arc::new(FixedClock::new(fixed));
+    let state = AppState::new(MultiAppConfig::default()).with_clock(clock.clone());
+
+    let first = ConfigService::create_backup(&config_path, state.clock()).expect("backup success");
+    clock.advance(Duration::milliseconds(1500));
+    let second = ConfigService::create_backup(&config_path, state.clock()).expect("backup success");
+
+    let expected_first = format!("backup_{}_", fixed.timestamp_millis());
+    let expected_second = format!(
+        "backup_{}_",
+        (fixed + Duration::milliseconds(1500)).timestamp_millis()
+    );
+    assert!(
+        first.starts_with(&expected_first),
+        "backup id {first} should start with {expected_first}"
+    );
+    assert!(
+        second.starts_with(&expected_second),
+        "backup id {second} should start with {expected_second}"
+    );
+    assert!(config_dir
+        .join("backups")
+        .join(format!("{first}.json"))
+        .exists());
+}
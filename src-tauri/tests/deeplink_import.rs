@@ -1,5 +1,3 @@
-use std::sync::RwLock;
-
 use cc_switch_lib::{
     import_provider_from_deeplink, parse_deeplink_url, AppState, AppType, MultiAppConfig,
 };
@@ -20,9 +18,7 @@ fn deeplink_import_claude_provider_persists_to_config() {
     let mut config = MultiAppConfig::default();
     config.ensure_app(&AppType::Claude);
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     let provider_id = import_provider_from_deeplink(&state, request.clone())
         .expect("import provider from deeplink");
@@ -73,9 +69,7 @@ fn deeplink_import_codex_provider_builds_auth_and_config() {
     let mut config = MultiAppConfig::default();
     config.ensure_app(&AppType::Codex);
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     let provider_id = import_provider_from_deeplink(&state, request.clone())
         .expect("import provider from deeplink");
@@ -2,13 +2,12 @@ use serde_json::json;
 use std::{
     fs,
     path::{Path, PathBuf},
-    sync::RwLock,
 };
 use tauri::async_runtime;
 
 use cc_switch_lib::{
     get_claude_settings_path, read_json_file, AppError, AppState, AppType, ConfigService,
-    MultiAppConfig, Provider, ProviderMeta,
+    MultiAppConfig, Provider, ProviderMeta, SystemClock,
 };
 
 #[path = "support.rs"]
@@ -503,7 +502,9 @@ url = "https://example.com"
     .expect("write codex config");
 
     let mut config = MultiAppConfig::default();
-    let changed = cc_switch_lib::import_from_codex(&mut config).expect("import codex");
+    let changed = cc_switch_lib::import_from_codex(&mut config)
+        .expect("import codex")
+        .imported;
     assert!(changed >= 2, "should import both servers");
 
     // v3.7.0: 检查统一结构
@@ -578,10 +579,13 @@ command = "echo"
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            protected: false,
         },
     );
 
-    let changed = cc_switch_lib::import_from_codex(&mut config).expect("import codex");
+    let changed = cc_switch_lib::import_from_codex(&mut config)
+        .expect("import codex")
+        .imported;
     assert!(changed >= 1, "should mark change for enabled flag");
 
     // v3.7.0: 检查统一结构
@@ -701,10 +705,13 @@ fn import_from_claude_merges_into_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            protected: false,
         },
     );
 
-    let changed = cc_switch_lib::import_from_claude(&mut config).expect("import from claude");
+    let changed = cc_switch_lib::import_from_claude(&mut config)
+        .expect("import from claude")
+        .imported;
     assert!(changed >= 1, "should mark at least one change");
 
     // v3.7.0: 检查统一结构
@@ -739,7 +746,7 @@ fn create_backup_skips_missing_file() {
     let config_path = home.join(".cc-switch").join("config.json");
 
     // 未创建文件时应返回空字符串，不报错
-    let result = ConfigService::create_backup(&config_path).expect("create backup");
+    let result = ConfigService::create_backup(&config_path, &SystemClock).expect("create backup");
     assert!(
         result.is_empty(),
         "expected empty backup id when config file missing"
@@ -756,7 +763,8 @@ fn create_backup_generates_snapshot_file() {
     fs::create_dir_all(&config_dir).expect("prepare config dir");
     fs::write(&config_path, r#"{"version":2}"#).expect("write config file");
 
-    let backup_id = ConfigService::create_backup(&config_path).expect("backup success");
+    let backup_id =
+        ConfigService::create_backup(&config_path, &SystemClock).expect("backup success");
     assert!(
         !backup_id.is_empty(),
         "backup id should contain timestamp information"
@@ -795,8 +803,8 @@ fn create_backup_retains_only_latest_entries() {
 
     std::thread::sleep(std::time::Duration::from_secs(1));
 
-    let latest_backup_id =
-        ConfigService::create_backup(&config_path).expect("create backup with cleanup");
+    let latest_backup_id = ConfigService::create_backup(&config_path, &SystemClock)
+        .expect("create backup with cleanup");
     assert!(
         !latest_backup_id.is_empty(),
         "backup id should not be empty when config exists"
@@ -872,9 +880,7 @@ fn import_config_from_path_overwrites_state_and_creates_backup() {
     )
     .expect("write import file");
 
-    let app_state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let app_state = AppState::new(MultiAppConfig::default());
 
     let backup_id = ConfigService::import_config_from_path(&import_path, &app_state)
         .expect("import should succeed");
@@ -916,6 +922,59 @@ fn import_config_from_path_overwrites_state_and_creates_backup() {
     );
 }
 
+#[test]
+fn import_config_from_cli_writes_config_and_creates_backup() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let config_dir = home.join(".cc-switch");
+    fs::create_dir_all(&config_dir).expect("create config dir");
+    let config_path = config_dir.join("config.json");
+    fs::write(
+        &config_path,
+        serde_json::to_string(&MultiAppConfig::default()).expect("serialize original config"),
+    )
+    .expect("seed original config");
+
+    // 部署脚本中的导入文件通常不在配置目录内
+    let import_dir = home.join("provisioning");
+    fs::create_dir_all(&import_dir).expect("create import dir");
+    let import_path = import_dir.join("config.json");
+    let import_payload = serde_json::json!({
+        "version": 2,
+        "claude": {
+            "providers": {
+                "p-cli": {
+                    "id": "p-cli",
+                    "name": "CLI Claude",
+                    "settingsConfig": { "env": { "ANTHROPIC_API_KEY": "cli-key" } }
+                }
+            },
+            "current": "p-cli"
+        }
+    });
+    fs::write(&import_path, import_payload.to_string()).expect("write import file");
+
+    let backup_id =
+        ConfigService::import_config_from_cli(&import_path).expect("cli import should succeed");
+
+    let backup_path = config_dir.join("backups").join(format!("{backup_id}.json"));
+    assert!(
+        backup_path.exists(),
+        "backup file should exist at {}",
+        backup_path.display()
+    );
+    let parsed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).expect("read updated config"))
+            .expect("parse updated config");
+    assert_eq!(
+        parsed.pointer("/claude/current").and_then(|v| v.as_str()),
+        Some("p-cli"),
+        "config.json should contain the imported provider"
+    );
+}
+
 #[test]
 fn import_config_from_path_invalid_json_returns_error() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -928,9 +987,7 @@ fn import_config_from_path_invalid_json_returns_error() {
     let invalid_path = config_dir.join("broken.json");
     fs::write(&invalid_path, "{ not-json ").expect("write invalid json");
 
-    let app_state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let app_state = AppState::new(MultiAppConfig::default());
 
     let err = ConfigService::import_config_from_path(&invalid_path, &app_state)
         .expect_err("import should fail");
@@ -955,9 +1012,7 @@ fn import_config_from_path_missing_file_produces_io_error() {
     } else {
         PathBuf::from("/nonexistent/import.json")
     };
-    let app_state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let app_state = AppState::new(MultiAppConfig::default());
 
     let err = ConfigService::import_config_from_path(missing_path.as_path(), &app_state)
         .expect_err("import should fail for missing file");
@@ -1016,6 +1071,47 @@ fn sync_gemini_packycode_sets_security_selected_type() {
     );
 }
 
+#[test]
+fn sync_gemini_rejects_provider_missing_api_key() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Gemini)
+            .expect("gemini manager");
+        manager.current = "relay-gemini".to_string();
+        manager.providers.insert(
+            "relay-gemini".to_string(),
+            Provider::with_id(
+                "relay-gemini".to_string(),
+                "Relay".to_string(),
+                json!({
+                    "env": {
+                        "GOOGLE_GEMINI_BASE_URL": "https://relay.example.com"
+                    }
+                }),
+                None,
+            ),
+        );
+    }
+
+    let err = ConfigService::sync_current_providers_to_live(&mut config)
+        .expect_err("provider without GEMINI_API_KEY should be rejected");
+    match err {
+        AppError::Localized { key, .. } => {
+            assert_eq!(key, "gemini.validation.missing_api_key");
+        }
+        other => panic!("expected localized validation error, got {other:?}"),
+    }
+    assert!(
+        !home.join(".gemini").join(".env").exists(),
+        "nothing should be written when validation fails"
+    );
+}
+
 #[test]
 fn sync_gemini_google_official_sets_oauth_security() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
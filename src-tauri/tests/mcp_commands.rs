@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fs, path::PathBuf, sync::RwLock};
+use std::{collections::HashMap, fs, path::PathBuf};
 
 use serde_json::json;
 
@@ -39,9 +39,7 @@ fn import_default_config_claude_persists_provider() {
 
     let mut config = MultiAppConfig::default();
     config.ensure_app(&AppType::Claude);
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     import_default_config_test_hook(&state, AppType::Claude)
         .expect("import default config succeeds");
@@ -73,9 +71,7 @@ fn import_default_config_without_live_file_returns_error() {
     reset_test_fs();
     let home = ensure_test_home();
 
-    let state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let state = AppState::new(MultiAppConfig::default());
 
     let err = import_default_config_test_hook(&state, AppType::Claude)
         .expect_err("missing live file should error");
@@ -119,9 +115,7 @@ fn import_mcp_from_claude_creates_config_and_enables_servers() {
     )
     .expect("seed ~/.claude.json");
 
-    let state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let state = AppState::new(MultiAppConfig::default());
 
     let changed = McpService::import_from_claude(&state).expect("import mcp from claude succeeds");
     assert!(
@@ -162,9 +156,7 @@ fn import_mcp_from_claude_invalid_json_preserves_state() {
     fs::write(&mcp_path, "{\"mcpServers\":") // 不完整 JSON
         .expect("seed invalid ~/.claude.json");
 
-    let state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let state = AppState::new(MultiAppConfig::default());
 
     let err =
         McpService::import_from_claude(&state).expect_err("invalid json should bubble up error");
@@ -183,8 +175,8 @@ fn import_mcp_from_claude_invalid_json_preserves_state() {
     );
 }
 
-#[test]
-fn set_mcp_enabled_for_codex_writes_live_config() {
+#[tokio::test]
+async fn set_mcp_enabled_for_codex_writes_live_config() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let home = ensure_test_home();
@@ -223,15 +215,15 @@ fn set_mcp_enabled_for_codex_writes_live_config() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            protected: false,
         },
     );
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     // v3.7.0: 使用 toggle_app 替代 set_enabled
     McpService::toggle_app(&state, "codex-server", AppType::Codex, true)
+        .await
         .expect("toggle_app should succeed");
 
     let guard = state.config.read().expect("lock config");
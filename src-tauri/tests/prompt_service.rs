@@ -1,15 +1,14 @@
 use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::RwLock;
 
 use cc_switch_lib::{
     get_claude_settings_path, get_codex_auth_path, update_settings, AppError, AppSettings,
-    AppState, AppType, MultiAppConfig, Prompt, PromptService,
+    AppState, AppType, MultiAppConfig, Prompt, PromptBundleEntry, PromptBundleImportMode,
+    PromptService,
 };
 
 #[path = "support.rs"]
-#[allow(dead_code)]
 mod support;
 use support::test_mutex;
 
@@ -59,9 +58,7 @@ fn setup_test_home(test_name: &str) -> TestHome {
 }
 
 fn build_state() -> AppState {
-    AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    }
+    AppState::new(MultiAppConfig::default())
 }
 
 fn make_prompt(id: &str, content: &str, enabled: bool) -> Prompt {
@@ -158,8 +155,8 @@ fn get_prompts_returns_all_for_app() {
     assert!(gemini.is_empty());
 }
 
-#[test]
-fn upsert_prompt_create_and_update() {
+#[tokio::test]
+async fn upsert_prompt_create_and_update() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let _home = setup_test_home("upsert-create-update");
     let state = build_state();
@@ -170,6 +167,7 @@ fn upsert_prompt_create_and_update() {
         "prompt-1",
         make_prompt("prompt-1", "first", false),
     )
+    .await
     .expect("create prompt");
 
     let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("get prompts");
@@ -184,6 +182,7 @@ fn upsert_prompt_create_and_update() {
         "prompt-1",
         make_prompt("prompt-1", "second", false),
     )
+    .await
     .expect("update prompt");
 
     let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("get prompts");
@@ -193,8 +192,8 @@ fn upsert_prompt_create_and_update() {
     );
 }
 
-#[test]
-fn upsert_prompt_id_conflict_overwrites_entry() {
+#[tokio::test]
+async fn upsert_prompt_id_conflict_overwrites_entry() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let _home = setup_test_home("upsert-id-conflict");
     let state = build_state();
@@ -205,6 +204,7 @@ fn upsert_prompt_id_conflict_overwrites_entry() {
         "key-1",
         make_prompt("prompt-1", "first", false),
     )
+    .await
     .expect("create prompt");
 
     PromptService::upsert_prompt(
@@ -213,6 +213,7 @@ fn upsert_prompt_id_conflict_overwrites_entry() {
         "key-1",
         make_prompt("prompt-2", "second", false),
     )
+    .await
     .expect("overwrite prompt");
 
     let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("get prompts");
@@ -220,8 +221,8 @@ fn upsert_prompt_id_conflict_overwrites_entry() {
     assert_eq!(prompts.get("key-1").expect("prompt exists").id, "prompt-2");
 }
 
-#[test]
-fn disable_prompt_clears_file_when_last_enabled() {
+#[tokio::test]
+async fn disable_prompt_clears_file_when_last_enabled() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let home = setup_test_home("disable-last-enabled");
     let state = build_state();
@@ -232,6 +233,7 @@ fn disable_prompt_clears_file_when_last_enabled() {
         "prompt-1",
         make_prompt("prompt-1", "active content", true),
     )
+    .await
     .expect("create enabled prompt");
 
     let path = expected_prompt_path(&AppType::Claude, home.path());
@@ -244,6 +246,7 @@ fn disable_prompt_clears_file_when_last_enabled() {
         "prompt-1",
         make_prompt("prompt-1", "active content", false),
     )
+    .await
     .expect("disable prompt");
 
     let content = fs::read_to_string(&path).expect("read CLAUDE.md");
@@ -253,8 +256,8 @@ fn disable_prompt_clears_file_when_last_enabled() {
     );
 }
 
-#[test]
-fn delete_prompt_success_and_missing() {
+#[tokio::test]
+async fn delete_prompt_success_and_missing() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let _home = setup_test_home("delete-success-missing");
     let state = build_state();
@@ -265,19 +268,24 @@ fn delete_prompt_success_and_missing() {
         "prompt-1",
         make_prompt("prompt-1", "remove me", false),
     )
+    .await
     .expect("create prompt");
 
-    PromptService::delete_prompt(&state, AppType::Codex, "prompt-1")
-        .expect("delete existing prompt");
+    assert!(
+        PromptService::delete_prompt(&state, AppType::Codex, "prompt-1")
+            .expect("delete existing prompt")
+    );
     let prompts = PromptService::get_prompts(&state, AppType::Codex).expect("get prompts");
     assert!(!prompts.contains_key("prompt-1"));
 
-    PromptService::delete_prompt(&state, AppType::Codex, "missing")
-        .expect("delete missing prompt should succeed");
+    assert!(
+        !PromptService::delete_prompt(&state, AppType::Codex, "missing")
+            .expect("delete missing prompt should succeed")
+    );
 }
 
-#[test]
-fn delete_prompt_rejects_active() {
+#[tokio::test]
+async fn delete_prompt_rejects_active() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let _home = setup_test_home("delete-active");
     let state = build_state();
@@ -288,6 +296,7 @@ fn delete_prompt_rejects_active() {
         "prompt-1",
         make_prompt("prompt-1", "active", true),
     )
+    .await
     .expect("create active prompt");
 
     let err = PromptService::delete_prompt(&state, AppType::Gemini, "prompt-1")
@@ -295,8 +304,8 @@ fn delete_prompt_rejects_active() {
     assert!(matches!(err, AppError::InvalidInput(_)));
 }
 
-#[test]
-fn enable_prompt_writes_file_and_disables_previous() {
+#[tokio::test]
+async fn enable_prompt_writes_file_and_disables_previous() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let home = setup_test_home("enable-switch");
     let state = build_state();
@@ -313,7 +322,9 @@ fn enable_prompt_writes_file_and_disables_previous() {
             .insert("new".to_string(), make_prompt("new", "new content", false));
     }
 
-    PromptService::enable_prompt(&state, AppType::Codex, "new").expect("enable prompt");
+    PromptService::enable_prompt(&state, AppType::Codex, "new")
+        .await
+        .expect("enable prompt");
 
     let prompts = PromptService::get_prompts(&state, AppType::Codex).expect("get prompts");
     assert!(prompts.get("new").expect("new prompt").enabled);
@@ -324,19 +335,54 @@ fn enable_prompt_writes_file_and_disables_previous() {
     assert_eq!(content, "new content");
 }
 
-#[test]
-fn import_prompt_from_file_missing() {
+#[tokio::test]
+async fn enable_prompt_waits_for_live_lock() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    let home = setup_test_home("enable-live-lock");
+    let state = build_state();
+    state
+        .config
+        .write()
+        .expect("write config")
+        .prompts
+        .codex
+        .prompts
+        .insert("new".to_string(), make_prompt("new", "new content", false));
+
+    let path = expected_prompt_path(&AppType::Codex, home.path());
+    let live = state.lock_live(&AppType::Codex).await;
+    let enable = PromptService::enable_prompt(&state, AppType::Codex, "new");
+    tokio::pin!(enable);
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(50), &mut enable)
+            .await
+            .is_err(),
+        "enable_prompt should wait while another writer holds the live lock"
+    );
+    assert!(!path.exists());
+
+    drop(live);
+    enable.await.expect("enable prompt");
+    assert_eq!(
+        fs::read_to_string(&path).expect("read AGENTS.md"),
+        "new content"
+    );
+}
+
+#[tokio::test]
+async fn import_prompt_from_file_missing() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let _home = setup_test_home("import-missing");
     let state = build_state();
 
     let err = PromptService::import_from_file(&state, AppType::Claude)
+        .await
         .expect_err("missing prompt file should fail");
     assert!(matches!(err, AppError::Message(_)));
 }
 
-#[test]
-fn import_prompt_from_file_existing() {
+#[tokio::test]
+async fn import_prompt_from_file_existing() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let home = setup_test_home("import-existing");
     let state = build_state();
@@ -347,8 +393,9 @@ fn import_prompt_from_file_existing() {
     }
     fs::write(&path, "imported content").expect("write GEMINI.md");
 
-    let id =
-        PromptService::import_from_file(&state, AppType::Gemini).expect("import prompt from file");
+    let id = PromptService::import_from_file(&state, AppType::Gemini)
+        .await
+        .expect("import prompt from file");
     assert!(id.starts_with("imported-"));
 
     let prompts = PromptService::get_prompts(&state, AppType::Gemini).expect("get prompts");
@@ -357,8 +404,8 @@ fn import_prompt_from_file_existing() {
     assert!(!prompt.enabled);
 }
 
-#[test]
-fn prompt_file_ops_write_and_permissions() {
+#[tokio::test]
+async fn prompt_file_ops_write_and_permissions() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     let home = setup_test_home("file-ops-permissions");
     let state = build_state();
@@ -373,6 +420,7 @@ fn prompt_file_ops_write_and_permissions() {
     for (app, content) in cases {
         let id = format!("{app:?}-prompt");
         PromptService::upsert_prompt(&state, app.clone(), &id, make_prompt(&id, content, true))
+            .await
             .expect("upsert prompt");
 
         let path = expected_prompt_path(&app, home.path());
@@ -382,3 +430,103 @@ fn prompt_file_ops_write_and_permissions() {
         assert_private_permissions(&path);
     }
 }
+
+fn bundle_entry(id: &str, content: &str) -> PromptBundleEntry {
+    PromptBundleEntry {
+        id: id.to_string(),
+        name: format!("Bundle {id}"),
+        content: content.to_string(),
+        enabled: true,
+    }
+}
+
+#[test]
+fn export_bundle_returns_sorted_prompts() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    let _home = setup_test_home("export-bundle");
+    let state = build_state();
+
+    {
+        let mut cfg = state.config.write().expect("write config");
+        cfg.prompts
+            .codex
+            .prompts
+            .insert("b".to_string(), make_prompt("b", "second", true));
+        cfg.prompts
+            .codex
+            .prompts
+            .insert("a".to_string(), make_prompt("a", "first", false));
+    }
+
+    let bundle = PromptService::export_bundle(&state, AppType::Codex).expect("export bundle");
+    assert_eq!(
+        bundle,
+        vec![
+            PromptBundleEntry {
+                id: "a".to_string(),
+                name: "Prompt a".to_string(),
+                content: "first".to_string(),
+                enabled: false,
+            },
+            PromptBundleEntry {
+                id: "b".to_string(),
+                name: "Prompt b".to_string(),
+                content: "second".to_string(),
+                enabled: true,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn import_bundle_handles_id_collisions_per_mode() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    let _home = setup_test_home("import-bundle-collision");
+    let state = build_state();
+
+    PromptService::upsert_prompt(
+        &state,
+        AppType::Claude,
+        "shared",
+        make_prompt("shared", "original", false),
+    )
+    .await
+    .expect("seed prompt");
+
+    let bundle = vec![
+        bundle_entry("shared", "from bundle"),
+        bundle_entry("new", "fresh"),
+    ];
+    let result = PromptService::import_bundle(
+        &state,
+        AppType::Claude,
+        bundle.clone(),
+        PromptBundleImportMode::Skip,
+    )
+    .await
+    .expect("import bundle with skip");
+    assert_eq!(result.imported, vec!["new".to_string()]);
+    assert_eq!(result.skipped, vec!["shared".to_string()]);
+
+    let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("get prompts");
+    assert_eq!(prompts["shared"].content, "original");
+    assert_eq!(prompts["new"].content, "fresh");
+    // 批量导入不会启用新条目
+    assert!(!prompts["new"].enabled);
+
+    let result = PromptService::import_bundle(
+        &state,
+        AppType::Claude,
+        bundle,
+        PromptBundleImportMode::Merge,
+    )
+    .await
+    .expect("import bundle with merge");
+    assert_eq!(result.merged, vec!["shared".to_string(), "new".to_string()]);
+    assert!(result.imported.is_empty());
+
+    let prompts = PromptService::get_prompts(&state, AppType::Claude).expect("get prompts");
+    assert_eq!(prompts["shared"].content, "from bundle");
+    assert_eq!(prompts["shared"].name, "Bundle shared");
+    assert_eq!(prompts.len(), 2);
+}
@@ -1,5 +1,5 @@
 use serde_json::json;
-use std::{collections::HashMap, path::PathBuf, sync::RwLock};
+use std::{collections::HashMap, path::PathBuf};
 
 use cc_switch_lib::{
     get_codex_auth_path, get_codex_config_path, read_json_file, switch_provider_test_hook,
@@ -76,9 +76,7 @@ command = "say"
         }),
     );
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     switch_provider_test_hook(&app_state, AppType::Codex, "new-provider")
         .expect("switch provider should succeed");
@@ -148,9 +146,7 @@ fn switch_provider_missing_provider_returns_error() {
         .expect("claude manager")
         .current = "does-not-exist".to_string();
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     let err = switch_provider_test_hook(&app_state, AppType::Claude, "missing-provider")
         .expect_err("switching to a missing provider should fail");
@@ -216,9 +212,7 @@ fn switch_provider_updates_claude_live_and_state() {
         );
     }
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     switch_provider_test_hook(&app_state, AppType::Claude, "new-provider")
         .expect("switch provider should succeed");
@@ -310,9 +304,7 @@ fn switch_provider_codex_missing_auth_returns_error_and_keeps_state() {
         );
     }
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     let err = switch_provider_test_hook(&app_state, AppType::Codex, "invalid")
         .expect_err("switching should fail when auth missing");
@@ -409,12 +401,11 @@ fn switch_provider_omo_rolls_back_opencode_plugin_when_post_commit_fails() {
             homepage: None,
             docs: None,
             tags: Vec::new(),
+            protected: false,
         },
     )]));
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     let err = switch_provider_test_hook(&app_state, AppType::Omo, "new-omo")
         .expect_err("post-commit failure should bubble up");
@@ -503,9 +494,7 @@ fn switch_provider_omo_replaces_old_plugin_versions_with_latest() {
         );
     }
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     switch_provider_test_hook(&app_state, AppType::Omo, "omo")
         .expect("switch provider should succeed");
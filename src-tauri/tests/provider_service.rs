@@ -1,5 +1,6 @@
 use serde_json::json;
-use std::{path::PathBuf, sync::RwLock};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use cc_switch_lib::{
     get_claude_settings_path, get_codex_config_path, read_json_file, write_codex_live_atomic,
@@ -24,8 +25,8 @@ fn sanitize_provider_name(name: &str) -> String {
         .to_lowercase()
 }
 
-#[test]
-fn provider_service_switch_codex_updates_live_and_config() {
+#[tokio::test]
+async fn provider_service_switch_codex_updates_live_and_config() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let _home = ensure_test_home();
@@ -85,11 +86,10 @@ command = "say"
         }),
     );
 
-    let state = AppState {
-        config: RwLock::new(initial_config),
-    };
+    let state = AppState::new(initial_config);
 
     ProviderService::switch(&state, AppType::Codex, "new-provider")
+        .await
         .expect("switch provider should succeed");
 
     let auth_path = unwrap_path(cc_switch_lib::get_codex_auth_path());
@@ -143,8 +143,8 @@ command = "say"
     );
 }
 
-#[test]
-fn switch_packycode_gemini_updates_security_selected_type() {
+#[tokio::test]
+async fn switch_packycode_gemini_updates_security_selected_type() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let home = ensure_test_home();
@@ -171,11 +171,10 @@ fn switch_packycode_gemini_updates_security_selected_type() {
         );
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::switch(&state, AppType::Gemini, "packy-gemini")
+        .await
         .expect("switching to PackyCode Gemini should succeed");
 
     let settings_path = home.join(".cc-switch").join("settings.json");
@@ -197,8 +196,8 @@ fn switch_packycode_gemini_updates_security_selected_type() {
     );
 }
 
-#[test]
-fn packycode_partner_meta_triggers_security_flag_even_without_keywords() {
+#[tokio::test]
+async fn packycode_partner_meta_triggers_security_flag_even_without_keywords() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let home = ensure_test_home();
@@ -227,11 +226,10 @@ fn packycode_partner_meta_triggers_security_flag_even_without_keywords() {
         manager.providers.insert("packy-meta".to_string(), provider);
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::switch(&state, AppType::Gemini, "packy-meta")
+        .await
         .expect("switching to partner meta provider should succeed");
 
     let settings_path = home.join(".cc-switch").join("settings.json");
@@ -253,8 +251,8 @@ fn packycode_partner_meta_triggers_security_flag_even_without_keywords() {
     );
 }
 
-#[test]
-fn switch_google_official_gemini_sets_oauth_security() {
+#[tokio::test]
+async fn switch_google_official_gemini_sets_oauth_security() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let home = ensure_test_home();
@@ -282,11 +280,10 @@ fn switch_google_official_gemini_sets_oauth_security() {
             .insert("google-official".to_string(), provider);
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::switch(&state, AppType::Gemini, "google-official")
+        .await
         .expect("switching to Google official Gemini should succeed");
 
     let settings_path = home.join(".cc-switch").join("settings.json");
@@ -325,8 +322,8 @@ fn switch_google_official_gemini_sets_oauth_security() {
     );
 }
 
-#[test]
-fn provider_service_switch_claude_updates_live_and_state() {
+#[tokio::test]
+async fn provider_service_switch_claude_updates_live_and_state() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let _home = ensure_test_home();
@@ -380,11 +377,10 @@ fn provider_service_switch_claude_updates_live_and_state() {
         );
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::switch(&state, AppType::Claude, "new-provider")
+        .await
         .expect("switch provider should succeed");
 
     let live_after: serde_json::Value =
@@ -450,9 +446,7 @@ fn sync_default_provider_from_live_preserves_current_and_category() {
             .insert("default".to_string(), default_provider);
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::sync_default_provider_from_live(
         &state,
@@ -508,9 +502,7 @@ fn sync_default_provider_from_live_creates_default_when_missing() {
         );
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::sync_default_provider_from_live(
         &state,
@@ -574,9 +566,7 @@ fn sync_default_provider_from_live_updates_current_opencode_provider_without_def
         );
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::sync_default_provider_from_live(
         &state,
@@ -642,9 +632,7 @@ fn sync_default_provider_from_live_updates_current_omo_provider_without_default(
         );
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::sync_default_provider_from_live(
         &state,
@@ -704,9 +692,7 @@ fn import_default_config_opencode_uses_deterministic_current_provider() {
     )
     .expect("write opencode config");
 
-    let state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+    let state = AppState::new(MultiAppConfig::default());
 
     ProviderService::import_default_config(&state, AppType::Opencode)
         .expect("import default opencode config should succeed");
@@ -721,13 +707,12 @@ fn import_default_config_opencode_uses_deterministic_current_provider() {
     );
 }
 
-#[test]
-fn provider_service_switch_missing_provider_returns_error() {
-    let state = AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    };
+#[tokio::test]
+async fn provider_service_switch_missing_provider_returns_error() {
+    let state = AppState::new(MultiAppConfig::default());
 
     let err = ProviderService::switch(&state, AppType::Claude, "missing")
+        .await
         .expect_err("switching missing provider should fail");
     match err {
         AppError::Localized { key, .. } => assert_eq!(key, "provider.not_found"),
@@ -735,8 +720,8 @@ fn provider_service_switch_missing_provider_returns_error() {
     }
 }
 
-#[test]
-fn provider_service_switch_codex_missing_auth_returns_error() {
+#[tokio::test]
+async fn provider_service_switch_codex_missing_auth_returns_error() {
     let mut config = MultiAppConfig::default();
     {
         let manager = config
@@ -755,11 +740,10 @@ fn provider_service_switch_codex_missing_auth_returns_error() {
         );
     }
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     let err = ProviderService::switch(&state, AppType::Codex, "invalid")
+        .await
         .expect_err("switching should fail without auth");
     match err {
         AppError::Config(msg) => assert!(
@@ -816,9 +800,7 @@ fn provider_service_delete_codex_removes_provider_and_files() {
     std::fs::write(&auth_path, "{}").expect("seed auth file");
     std::fs::write(&cfg_path, "base_url = \"https://example\"").expect("seed config file");
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     ProviderService::delete(&app_state, AppType::Codex, "to-delete")
         .expect("delete provider should succeed");
@@ -879,9 +861,7 @@ fn provider_service_delete_claude_removes_provider_files() {
     std::fs::write(&by_name, "{}").expect("seed settings by name");
     std::fs::write(&by_id, "{}").expect("seed settings by id");
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     ProviderService::delete(&app_state, AppType::Claude, "delete").expect("delete claude provider");
 
@@ -920,9 +900,7 @@ fn provider_service_delete_current_provider_returns_error() {
         );
     }
 
-    let app_state = AppState {
-        config: RwLock::new(config),
-    };
+    let app_state = AppState::new(config);
 
     let err = ProviderService::delete(&app_state, AppType::Claude, "keep")
         .expect_err("deleting current provider should fail");
@@ -939,8 +917,8 @@ fn provider_service_delete_current_provider_returns_error() {
     }
 }
 
-#[test]
-fn provider_service_update_current_codex_preserves_mcp_in_live_and_snapshot() {
+#[tokio::test]
+async fn provider_service_update_current_codex_preserves_mcp_in_live_and_snapshot() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
     reset_test_fs();
     let _home = ensure_test_home();
@@ -977,9 +955,7 @@ fn provider_service_update_current_codex_preserves_mcp_in_live_and_snapshot() {
         }),
     );
 
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    let state = AppState::new(config);
 
     ProviderService::update(
         &state,
@@ -994,6 +970,7 @@ fn provider_service_update_current_codex_preserves_mcp_in_live_and_snapshot() {
             None,
         ),
     )
+    .await
     .expect("updating current codex provider should succeed");
 
     let auth_path = unwrap_path(cc_switch_lib::get_codex_auth_path());
@@ -1033,3 +1010,124 @@ fn provider_service_update_current_codex_preserves_mcp_in_live_and_snapshot() {
         "stored provider snapshot should match live config with MCP servers"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_codex_switches_keep_live_config_consistent() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut initial_config = MultiAppConfig::default();
+    {
+        let manager = initial_config
+            .get_manager_mut(&AppType::Codex)
+            .expect("codex manager");
+        for id in ["alpha", "beta"] {
+            manager.providers.insert(
+                id.to_string(),
+                Provider::with_id(
+                    id.to_string(),
+                    id.to_string(),
+                    json!({
+                        "auth": {"OPENAI_API_KEY": format!("{id}-key")},
+                        "config": format!("model = \"{id}-model\"\n")
+                    }),
+                    None,
+                ),
+            );
+        }
+    }
+    let state = Arc::new(AppState::new(initial_config));
+
+    let tasks: Vec<_> = ["alpha", "beta"]
+        .into_iter()
+        .map(|id| {
+            let state = state.clone();
+            tokio::spawn(async move {
+                for _ in 0..10 {
+                    ProviderService::switch(&state, AppType::Codex, id)
+                        .await
+                        .expect("concurrent switch should succeed");
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.expect("switch task should not panic");
+    }
+
+    let current = state
+        .config
+        .read()
+        .expect("read config after switches")
+        .get_manager(&AppType::Codex)
+        .expect("codex manager")
+        .current
+        .clone();
+
+    let config_path = unwrap_path(get_codex_config_path());
+    let config_text = std::fs::read_to_string(&config_path).expect("read config.toml");
+    let parsed: toml::Table = toml::from_str(&config_text).expect("config.toml should stay valid");
+    assert_eq!(
+        parsed.get("model").and_then(|v| v.as_str()),
+        Some(format!("{current}-model").as_str()),
+        "config.toml should belong to the current provider"
+    );
+
+    let auth_path = unwrap_path(cc_switch_lib::get_codex_auth_path());
+    let auth_value: serde_json::Value = read_json_file(&auth_path).expect("read auth.json");
+    assert_eq!(
+        auth_value.get("OPENAI_API_KEY").and_then(|v| v.as_str()),
+        Some(format!("{current}-key").as_str()),
+        "auth.json should match config.toml"
+    );
+}
+
+#[tokio::test]
+async fn update_current_provider_waits_for_live_lock() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let provider = |key: &str| {
+        Provider::with_id(
+            "current".to_string(),
+            "Current Codex".to_string(),
+            json!({
+                "auth": {"OPENAI_API_KEY": key},
+                "config": "model = \"gpt-5\"\n"
+            }),
+            None,
+        )
+    };
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Codex)
+            .expect("codex manager");
+        manager.current = "current".to_string();
+        manager
+            .providers
+            .insert("current".to_string(), provider("stale-key"));
+    }
+    let state = AppState::new(config);
+
+    let live = state.lock_live(&AppType::Codex).await;
+    let update = ProviderService::update(&state, AppType::Codex, provider("fresh-key"));
+    tokio::pin!(update);
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(50), &mut update)
+            .await
+            .is_err(),
+        "updating the current provider should wait for the Codex live lock"
+    );
+
+    drop(live);
+    update.await.expect("update current provider");
+    let auth_path = unwrap_path(cc_switch_lib::get_codex_auth_path());
+    let auth_value: serde_json::Value = read_json_file(&auth_path).expect("read auth.json");
+    assert_eq!(
+        auth_value.get("OPENAI_API_KEY").and_then(|v| v.as_str()),
+        Some("fresh-key")
+    );
+}
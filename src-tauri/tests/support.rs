@@ -1,3 +1,6 @@
+// 各测试文件只用到其中一部分辅助函数
+#![allow(dead_code)]
+
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
@@ -45,3 +48,127 @@ pub fn test_mutex() -> &'static Mutex<()> {
     static MUTEX: OnceLock<Mutex<()>> = OnceLock::new();
     MUTEX.get_or_init(|| Mutex::new(()))
 }
+
+#[cfg(feature = "web-server")]
+#[allow(unused_imports)]
+pub use web::*;
+
+/// Web API 测试共用的请求构造与响应解析；默认账号为 `admin:password`，CSRF token 为 `csrf-token`。
+#[cfg(feature = "web-server")]
+mod web {
+    use std::sync::Arc;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+    };
+    use base64::Engine;
+    use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    pub fn basic_auth_header(user: &str, password: &str) -> HeaderValue {
+        let raw = format!("{user}:{password}");
+        let encoded = base64::engine::general_purpose::STANDARD.encode(raw.as_bytes());
+        HeaderValue::from_str(&format!("Basic {encoded}")).expect("basic auth header")
+    }
+
+    /// 以给定配置创建路由
+    pub fn make_app(config: MultiAppConfig) -> axum::Router {
+        make_app_with_state(Arc::new(AppState::new(config)))
+    }
+
+    /// 以共享状态创建路由，便于测试在请求之后检查状态
+    pub fn make_app_with_state(state: Arc<AppState>) -> axum::Router {
+        std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+        web_api::create_router(state, "password".to_string())
+    }
+
+    /// 带认证头的 GET 请求
+    pub fn get_request(uri: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    /// 带认证头与 CSRF token 的 JSON 请求
+    pub fn json_request(method: Method, uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+            .header("content-type", HeaderValue::from_static("application/json"))
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    pub async fn response_json(res: axum::response::Response) -> Value {
+        let bytes = to_bytes(res.into_body(), usize::MAX)
+            .await
+            .expect("response body");
+        serde_json::from_slice(&bytes).expect("response json")
+    }
+
+    /// 发送请求并返回状态码与响应体（非 JSON 响应体解析为 `Null`）
+    pub async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .header("x-csrf-token", HeaderValue::from_static("csrf-token"));
+        let body = match body {
+            Some(value) => {
+                builder =
+                    builder.header("content-type", HeaderValue::from_static("application/json"));
+                Body::from(value.to_string())
+            }
+            None => Body::empty(),
+        };
+        let res = app
+            .clone()
+            .oneshot(builder.body(body).expect("build request"))
+            .await
+            .expect("router response");
+        let status = res.status();
+        let bytes = to_bytes(res.into_body(), usize::MAX)
+            .await
+            .expect("response body");
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    /// 发送请求并断言成功，返回 JSON 响应体
+    pub async fn send_ok(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> Value {
+        let (status, body) = send(app, method.clone(), uri, body).await;
+        assert_eq!(status, StatusCode::OK, "{method} {uri}: {body}");
+        body
+    }
+
+    /// GET 并返回状态码与响应体
+    pub async fn get(app: &axum::Router, uri: &str) -> (StatusCode, Value) {
+        send(app, Method::GET, uri, None).await
+    }
+
+    /// GET 并断言成功，返回 JSON 响应体
+    pub async fn get_json(app: axum::Router, uri: &str) -> Value {
+        let res = app.oneshot(get_request(uri)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        response_json(res).await
+    }
+}
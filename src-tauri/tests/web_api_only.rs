@@ -0,0 +1,53 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get, reset_test_fs, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn api_only_mode_returns_json_404_for_non_api_paths() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::set_var("WEB_API_ONLY", "1");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    for path in ["/", "/index.html", "/settings/providers"] {
+        let (status, body) = get(&app, path).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "path {path}");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap_or_default()
+                .contains("WEB_API_ONLY"),
+            "expected JSON 404 for {path}, got {body}"
+        );
+    }
+
+    let (status, _) = get(&app, "/api/config/app/path").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, flags) = get(&app, "/api/system/config-flags").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags["apiOnly"], true);
+
+    // 未知的 API 路径仍由 API 自身的 404 处理
+    let (status, body) = get(&app, "/api/does-not-exist").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("API route not found"));
+
+    std::env::remove_var("WEB_API_ONLY");
+}
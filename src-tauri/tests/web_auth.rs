@@ -1,31 +1,25 @@
 #![cfg(feature = "web-server")]
 
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 use axum::{
-    body::Body,
-    http::{header::AUTHORIZATION, header::CONTENT_TYPE, HeaderValue, Method, Request, StatusCode},
+    body::{to_bytes, Body},
+    http::{
+        header::AUTHORIZATION, header::CACHE_CONTROL, header::CONTENT_TYPE, HeaderValue, Method,
+        Request, StatusCode,
+    },
 };
-use base64::Engine;
 use cc_switch_lib::{web_api, AppState, MultiAppConfig};
 use serial_test::serial;
 use tower::ServiceExt;
 
 #[path = "support.rs"]
 mod support;
-use support::{ensure_test_home, reset_test_fs, test_mutex};
-
-fn basic_auth_header(user: &str, password: &str) -> HeaderValue {
-    let raw = format!("{user}:{password}");
-    let encoded = base64::engine::general_purpose::STANDARD.encode(raw.as_bytes());
-    HeaderValue::from_str(&format!("Basic {encoded}")).expect("basic auth header")
-}
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
 
 fn make_app(password: &str, csrf: &str) -> axum::Router {
     std::env::set_var("WEB_CSRF_TOKEN", csrf);
-    let state = Arc::new(AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    });
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
     web_api::create_router(state, password.to_string())
 }
 
@@ -173,6 +167,49 @@ async fn test_csrf_invalid_token() {
     assert_eq!(res.status(), StatusCode::FORBIDDEN);
 }
 
+#[tokio::test]
+#[serial]
+async fn test_csrf_token_endpoint_returns_accepted_token() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    // 不固定 Token，走自动生成路径，确保接口返回的就是校验器实际使用的值
+    std::env::remove_var("WEB_CSRF_TOKEN");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/csrf-token")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .unwrap();
+    let res = dispatch(app.clone(), req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()
+            .get(CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok()),
+        Some("no-store")
+    );
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    let token = body["csrfToken"].as_str().expect("csrf token").to_string();
+    assert!(!token.is_empty());
+    assert!(body["expiresAt"].is_null());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/tray/update")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_str(&token).unwrap())
+        .body(Body::empty())
+        .unwrap();
+    let res = dispatch(app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_security_headers_present() {
@@ -277,6 +314,61 @@ async fn test_update_credentials_persists_and_rotates_auth() {
     assert_eq!(password.trim(), "new-pass");
 }
 
+#[tokio::test]
+#[serial]
+async fn test_reload_credentials_picks_up_rotated_password() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::env::remove_var("WEB_PASSWORD_FILE");
+
+    let app = make_app("password", "csrf-token");
+
+    // 模拟外部工具轮换密码文件
+    let password_path = home.join(".cc-switch").join("web_password");
+    std::fs::create_dir_all(password_path.parent().unwrap()).expect("create config dir");
+    std::fs::write(&password_path, "rotated-pass\n").expect("write rotated password");
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/system/reload-credentials")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = dispatch(app.clone(), req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req_old = Request::builder()
+        .method(Method::GET)
+        .uri("/api/config/app/path")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .unwrap();
+    let res_old = dispatch(app.clone(), req_old).await;
+    assert_eq!(res_old.status(), StatusCode::UNAUTHORIZED);
+
+    let req_new = Request::builder()
+        .method(Method::GET)
+        .uri("/api/config/app/path")
+        .header(AUTHORIZATION, basic_auth_header("admin", "rotated-pass"))
+        .body(Body::empty())
+        .unwrap();
+    let res_new = dispatch(app.clone(), req_new).await;
+    assert_eq!(res_new.status(), StatusCode::OK);
+
+    // 重新加载需要当前有效的凭据
+    let req_unauth = Request::builder()
+        .method(Method::POST)
+        .uri("/api/system/reload-credentials")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res_unauth = dispatch(app, req_unauth).await;
+    assert_eq!(res_unauth.status(), StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_update_credentials_rotates_auth() {
@@ -408,3 +500,159 @@ async fn test_update_credentials_rejects_short_password() {
     let res = dispatch(app, req).await;
     assert_eq!(res.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+#[serial]
+async fn test_rotate_csrf_invalidates_old_token() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    // 环境变量固定的 Token 不可轮换，这里走 web_env 文件路径
+    std::env::remove_var("WEB_CSRF_TOKEN");
+    let web_env = home.join(".cc-switch").join("web_env");
+    std::fs::create_dir_all(web_env.parent().unwrap()).expect("create .cc-switch dir");
+    std::fs::write(&web_env, "WEB_CSRF_TOKEN=old-token\n").expect("seed web_env");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let post = |uri: &str, token: &str| {
+        Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .header("x-csrf-token", HeaderValue::from_str(token).unwrap())
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = dispatch(app.clone(), post("/api/system/rotate-csrf", "wrong-token")).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = dispatch(app.clone(), post("/api/system/rotate-csrf", "old-token")).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    let new_token = body["csrfToken"].as_str().expect("csrf token").to_string();
+    assert!(!new_token.is_empty());
+    assert_ne!(new_token, "old-token");
+
+    let persisted = std::fs::read_to_string(&web_env).expect("read web_env");
+    assert_eq!(persisted, format!("WEB_CSRF_TOKEN={new_token}\n"));
+
+    let res = dispatch(app.clone(), post("/api/tray/update", "old-token")).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = dispatch(app.clone(), post("/api/tray/update", &new_token)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/csrf-token")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .unwrap();
+    let res = dispatch(app, req).await;
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    assert_eq!(body["csrfToken"].as_str(), Some(new_token.as_str()));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn test_credentials_info_reports_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::env::remove_var("WEB_PASSWORD_FILE");
+
+    let app = make_app("password", "csrf-token");
+    let password_path = home.join(".cc-switch").join("web_password");
+    std::fs::create_dir_all(password_path.parent().unwrap()).expect("create config dir");
+    std::fs::write(&password_path, "password\n").expect("write password");
+
+    let fetch_info = |app: axum::Router| async move {
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/api/system/credentials-info")
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .body(Body::empty())
+            .unwrap();
+        let res = dispatch(app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).expect("parse json")
+    };
+
+    std::fs::set_permissions(&password_path, std::fs::Permissions::from_mode(0o644))
+        .expect("loosen permissions");
+    let info = fetch_info(app.clone()).await;
+    assert_eq!(info["source"], "file");
+    assert_eq!(
+        info["path"].as_str(),
+        Some(password_path.to_string_lossy().as_ref())
+    );
+    assert!(info["lastModified"].is_string());
+    assert_eq!(info["permissionsOk"], false);
+    assert!(!info.to_string().contains("\"password\""));
+
+    std::fs::set_permissions(&password_path, std::fs::Permissions::from_mode(0o600))
+        .expect("restrict permissions");
+    let info = fetch_info(app.clone()).await;
+    assert_eq!(info["permissionsOk"], true);
+
+    // WEB_PASSWORD_FILE 指定的外部文件
+    let external = home.join("secret-password");
+    std::fs::write(&external, "password\n").expect("write external password");
+    std::fs::set_permissions(&external, std::fs::Permissions::from_mode(0o600))
+        .expect("restrict permissions");
+    std::env::set_var("WEB_PASSWORD_FILE", &external);
+    let info = fetch_info(app).await;
+    std::env::remove_var("WEB_PASSWORD_FILE");
+    assert_eq!(info["source"], "env");
+    assert_eq!(
+        info["path"].as_str(),
+        Some(external.to_string_lossy().as_ref())
+    );
+    assert_eq!(info["permissionsOk"], true);
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_file_mode_env_applies_to_generated_password_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    ensure_test_home();
+    std::env::remove_var("WEB_PASSWORD_FILE");
+
+    let mode_of = |path: &std::path::Path| {
+        std::fs::metadata(path)
+            .expect("password file metadata")
+            .permissions()
+            .mode()
+            & 0o777
+    };
+
+    std::env::set_var("CC_SWITCH_FILE_MODE", "0640");
+    assert_eq!(web_api::file_mode().expect("valid mode"), 0o640);
+    let path = web_api::persist_web_password("custom-mode-pass").expect("persist password");
+    assert_eq!(mode_of(&path), 0o640);
+
+    // 其他用户可写的模式被拒绝，写入时回退到 0600
+    std::env::set_var("CC_SWITCH_FILE_MODE", "0666");
+    assert!(web_api::file_mode().is_err());
+    let path = web_api::persist_web_password("fallback-mode-pass").expect("persist password");
+    assert_eq!(mode_of(&path), 0o600);
+
+    std::env::set_var("CC_SWITCH_FILE_MODE", "rw-r-----");
+    assert!(web_api::file_mode().is_err());
+
+    std::env::remove_var("CC_SWITCH_FILE_MODE");
+    assert_eq!(web_api::file_mode().expect("default mode"), 0o600);
+}
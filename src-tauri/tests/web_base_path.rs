@@ -0,0 +1,63 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{body::to_bytes, http::StatusCode};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get_request, reset_test_fs, test_mutex};
+
+fn make_app_with_base(base_path: &str) -> axum::Router {
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::set_var("WEB_BASE_PATH", base_path);
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let router = web_api::create_router(state, "password".to_string());
+    std::env::remove_var("WEB_BASE_PATH");
+    router
+}
+
+#[tokio::test]
+#[serial]
+async fn test_routes_resolve_under_base_path() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    // 尾部斜杠会被规范化
+    let app = make_app_with_base("cc-switch/");
+
+    for uri in [
+        "/cc-switch",
+        "/cc-switch/",
+        "/cc-switch/some/spa/route",
+        "/cc-switch/api/config/app/path",
+    ] {
+        let res = app.clone().oneshot(get_request(uri)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{uri} should resolve");
+    }
+
+    let res = app.oneshot(get_request("/cc-switch/")).await.unwrap();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let html = String::from_utf8(bytes.to_vec()).expect("utf-8 index");
+    assert!(html.contains(r#"<base href="/cc-switch/">"#));
+    assert!(html.contains(r#"window.__CC_SWITCH_API_BASE__ = "/cc-switch/api";"#));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_root_returns_404_when_base_path_set() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app_with_base("/cc-switch");
+
+    for uri in ["/", "/api/config/app/path", "/some/spa/route"] {
+        let res = app.clone().oneshot(get_request(uri)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND, "{uri} should 404");
+    }
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get_json, reset_test_fs, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn test_codex_effective_config_combines_live_files() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let codex_dir = home.join(".codex");
+    std::fs::create_dir_all(&codex_dir).expect("create codex dir");
+    let auth = json!({
+        "OPENAI_API_KEY": "sk-live-0123456789abcd",
+        "tokens": { "access_token": "at-0123456789wxyz", "account_id": "acct-1" }
+    });
+    std::fs::write(codex_dir.join("auth.json"), auth.to_string()).expect("write auth.json");
+    let config_toml = "model_provider = \"relay\"\nmodel = \"gpt-5\"\n";
+    std::fs::write(codex_dir.join("config.toml"), config_toml).expect("write config.toml");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let redacted = get_json(app.clone(), "/api/config/codex/effective").await;
+    assert_eq!(redacted["configToml"], config_toml);
+    assert_eq!(redacted["auth"]["OPENAI_API_KEY"], "****abcd");
+    assert_eq!(redacted["auth"]["tokens"]["access_token"], "****wxyz");
+    assert_eq!(redacted["auth"]["tokens"]["account_id"], "acct-1");
+    assert!(!redacted.to_string().contains("sk-live-0123456789abcd"));
+
+    let revealed = get_json(app, "/api/config/codex/effective?redact=false").await;
+    assert_eq!(revealed["auth"], auth);
+    assert_eq!(revealed["configToml"], config_toml);
+}
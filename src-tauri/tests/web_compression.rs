@@ -0,0 +1,78 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{
+        header::{ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING},
+        HeaderValue, Method, Request, StatusCode,
+    },
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig, Provider};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn state_with_many_providers() -> Arc<AppState> {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    for idx in 0..40 {
+        let id = format!("provider-{idx}");
+        manager.providers.insert(
+            id.clone(),
+            Provider::with_id(
+                id,
+                format!("Provider number {idx}"),
+                json!({ "env": { "ANTHROPIC_BASE_URL": "https://relay.example.com" } }),
+                None,
+            ),
+        );
+    }
+    Arc::new(AppState::new(config))
+}
+
+async fn gzip_get(app: axum::Router, uri: &str) -> (StatusCode, Option<HeaderValue>) {
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header(ACCEPT_ENCODING, HeaderValue::from_static("gzip"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    (res.status(), res.headers().get(CONTENT_ENCODING).cloned())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_only_responses_above_threshold_are_compressed() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::remove_var("COMPRESSION_MIN_BYTES");
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let app = web_api::create_router(state_with_many_providers(), "password".to_string());
+
+    // CSRF Token 响应只有几十字节，低于默认 1KB 阈值
+    let (status, encoding) = gzip_get(app.clone(), "/api/system/csrf-token").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        encoding.is_none(),
+        "small response should not be compressed"
+    );
+
+    let (status, encoding) = gzip_get(app, "/api/providers/claude").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        encoding.as_ref().and_then(|v| v.to_str().ok()),
+        Some("gzip")
+    );
+}
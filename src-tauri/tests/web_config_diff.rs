@@ -0,0 +1,102 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{
+    basic_auth_header, ensure_test_home, make_app, reset_test_fs, response_json, test_mutex,
+};
+
+fn diff_request(left: &str, right: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/diff")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            serde_json::json!({ "left": left, "right": right }).to_string(),
+        ))
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_diff_current_against_backup_reports_added_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let backup_dir = home.join(".cc-switch").join("backups");
+    std::fs::create_dir_all(&backup_dir).expect("create backup dir");
+    let backup = serde_json::to_string(&MultiAppConfig::default()).expect("serialize backup");
+    std::fs::write(backup_dir.join("backup_1_0.json"), backup).expect("write backup");
+
+    let mut current = MultiAppConfig::default();
+    current
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "p1".to_string(),
+            Provider::with_id(
+                "p1".into(),
+                "Provider One".into(),
+                serde_json::json!({ "env": {} }),
+                None,
+            ),
+        );
+
+    let res = make_app(current)
+        .oneshot(diff_request("backup_1_0", "current"))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = response_json(res).await;
+    assert_eq!(body["identical"], serde_json::json!(false));
+    assert_eq!(
+        body["apps"]["claude"]["providers"]["added"],
+        serde_json::json!(["p1"])
+    );
+    assert_eq!(
+        body["apps"]["claude"]["providers"]["removed"],
+        serde_json::json!([])
+    );
+    assert!(body["apps"].get("codex").is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_diff_rejects_invalid_backup_id() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(diff_request("../config", "current"))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_diff_missing_backup_returns_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(diff_request("backup_missing", "current"))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
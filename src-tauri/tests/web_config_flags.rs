@@ -0,0 +1,81 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
+};
+use cc_switch_lib::MultiAppConfig;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, make_app, reset_test_fs, test_mutex};
+
+async fn get_flags(authorized: bool) -> (StatusCode, serde_json::Value) {
+    let mut req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/config-flags");
+    if authorized {
+        req = req.header(AUTHORIZATION, basic_auth_header("admin", "password"));
+    }
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(req.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_flags_report_defaults() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    for name in [
+        "CORS_ALLOW_ORIGINS",
+        "ENABLE_HSTS",
+        "USAGE_SCRIPT_EGRESS_POLICY",
+        "USAGE_SCRIPT_ALLOW_REDIRECTS",
+        "USAGE_SCRIPT_MAX_RESPONSE_BYTES",
+        "WEB_MAX_BODY_BYTES",
+    ] {
+        std::env::remove_var(name);
+    }
+
+    let (status, flags) = get_flags(true).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags["corsEnabled"], false);
+    assert_eq!(flags["hstsEnabled"], true);
+    assert_eq!(flags["egressPolicy"], "trusted");
+    assert_eq!(flags["allowRedirects"], false);
+    assert_eq!(flags["maxResponseBytes"], 1_048_576);
+    assert_eq!(flags["maxBodyBytes"], 2_097_152);
+    assert_eq!(flags["apiPrefix"], "/api");
+    // 快照中不应出现任何凭据
+    let raw = flags.to_string();
+    assert!(!raw.contains("password"));
+    assert!(!raw.contains("csrf-token"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_config_flags_reflect_env_and_require_auth() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("USAGE_SCRIPT_EGRESS_POLICY", "strict");
+    std::env::set_var("ENABLE_HSTS", "false");
+    let (status, flags) = get_flags(true).await;
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    std::env::remove_var("ENABLE_HSTS");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags["egressPolicy"], "strict");
+    assert_eq!(flags["hstsEnabled"], false);
+
+    let (status, _) = get_flags(false).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,237 @@
+#![cfg(feature = "web-server")]
+
+use std::io::Write;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppState, AppType, MultiAppConfig, Provider};
+use flate2::{write::GzEncoder, Compression};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{
+    basic_auth_header, ensure_test_home, make_app, make_app_with_state, reset_test_fs,
+    response_json, test_mutex,
+};
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).expect("gzip write");
+    encoder.finish().expect("gzip finish")
+}
+
+fn gzip_import_request(payload: &serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/import")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .header("content-encoding", HeaderValue::from_static("gzip"))
+        .body(Body::from(gzip(payload.to_string().as_bytes())))
+        .unwrap()
+}
+
+fn import_request(file_path: &std::path::Path) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/import")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            serde_json::json!({ "filePath": file_path.to_string_lossy() }).to_string(),
+        ))
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_rejects_file_over_size_limit() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let config_dir = home.join(".cc-switch");
+    std::fs::create_dir_all(&config_dir).expect("create config dir");
+    let import_path = config_dir.join("import.json");
+    let content = serde_json::to_string(&MultiAppConfig::default()).expect("serialize config");
+    std::fs::write(&import_path, &content).expect("write import file");
+
+    std::env::set_var("CONFIG_IMPORT_MAX_BYTES", "8");
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(import_request(&import_path))
+        .await
+        .expect("router response");
+    std::env::remove_var("CONFIG_IMPORT_MAX_BYTES");
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = response_json(res).await;
+    assert!(body["error"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("exceeds 8 bytes")));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn test_import_rejects_non_regular_file() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let config_dir = home.join(".cc-switch");
+    std::fs::create_dir_all(&config_dir).expect("create config dir");
+    let fifo_path = config_dir.join("import.fifo");
+    let status = std::process::Command::new("mkfifo")
+        .arg(&fifo_path)
+        .status()
+        .expect("run mkfifo");
+    assert!(status.success());
+
+    // 若未拦截，读取 FIFO 会一直阻塞
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(import_request(&fifo_path))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = response_json(res).await;
+    assert!(body["error"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("not a regular file")));
+
+    let dir_path = config_dir.join("import-dir");
+    std::fs::create_dir_all(&dir_path).expect("create dir");
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(import_request(&dir_path))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_accepts_gzip_encoded_body() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut imported = MultiAppConfig::default();
+    imported
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "gz".to_string(),
+            Provider::with_id(
+                "gz".into(),
+                "Gzip Provider".into(),
+                serde_json::json!({ "env": {} }),
+                None,
+            ),
+        );
+    let content = serde_json::to_string(&imported).expect("serialize config");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let res = make_app_with_state(state.clone())
+        .oneshot(gzip_import_request(
+            &serde_json::json!({ "content": content }),
+        ))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(response_json(res).await["success"], true);
+    assert!(state
+        .config
+        .read()
+        .unwrap()
+        .get_manager(&AppType::Claude)
+        .is_some_and(|manager| manager.providers.contains_key("gz")));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_gzip_body_limit_applies_after_decompression() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    // 压缩后很小，但解压后超过 body 上限
+    let padding = "a".repeat(64 * 1024);
+    let payload = serde_json::json!({ "content": padding });
+    assert!(gzip(payload.to_string().as_bytes()).len() < 4096);
+
+    std::env::set_var("WEB_MAX_BODY_BYTES", "4096");
+    let app = make_app(MultiAppConfig::default());
+    std::env::remove_var("WEB_MAX_BODY_BYTES");
+
+    let res = app
+        .oneshot(gzip_import_request(&payload))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_dry_run_import_reports_diff_without_applying() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let mut imported = MultiAppConfig::default();
+    imported
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "preview".to_string(),
+            Provider::with_id(
+                "preview".into(),
+                "Preview Provider".into(),
+                serde_json::json!({ "env": {} }),
+                None,
+            ),
+        );
+    let content = serde_json::to_string(&imported).expect("serialize config");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/import?dryRun=true")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            serde_json::json!({ "content": content }).to_string(),
+        ))
+        .unwrap();
+    let res = make_app_with_state(state.clone())
+        .oneshot(req)
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = response_json(res).await;
+    assert_eq!(body["success"], true);
+    assert!(body["backupId"].is_null());
+    assert_eq!(body["diff"]["identical"], false);
+    assert_eq!(
+        body["diff"]["apps"]["claude"]["providers"]["added"],
+        serde_json::json!(["preview"])
+    );
+
+    assert!(state
+        .config
+        .read()
+        .unwrap()
+        .get_manager(&AppType::Claude)
+        .is_some_and(|manager| manager.providers.is_empty()));
+    let config_dir = home.join(".cc-switch");
+    assert!(!config_dir.join("config.json").exists());
+    assert!(!config_dir.join("backups").exists());
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, header::CONTENT_TYPE, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn seeded_state() -> Arc<AppState> {
+    let config: MultiAppConfig = serde_json::from_value(json!({
+        "version": 2,
+        "claude": {
+            "providers": { "c1": { "id": "c1", "name": "c1", "settingsConfig": {} } },
+            "current": "c1"
+        },
+        "mcp": {
+            "servers": {
+                "remote": {
+                    "id": "remote",
+                    "name": "remote",
+                    "server": {
+                        "type": "http",
+                        "url": "https://mcp.example.com",
+                        "headers": { "X-Team": "a", "X-Old": "legacy" }
+                    },
+                    "apps": { "claude": true, "codex": false, "gemini": false }
+                }
+            }
+        }
+    }))
+    .expect("seeded config");
+    config.save().expect("save config");
+    Arc::new(AppState::new(config))
+}
+
+async fn send_patch(
+    app: axum::Router,
+    patch: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let req = Request::builder()
+        .method(Method::PATCH)
+        .uri("/api/config")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(patch.to_string()))
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).expect("response json"),
+    )
+}
+
+fn remote_headers(cfg: &MultiAppConfig) -> serde_json::Value {
+    cfg.mcp.servers.as_ref().expect("mcp servers")["remote"].server["headers"].clone()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_patch_config_applies_add_replace_remove() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = seeded_state();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let (status, body) = send_patch(
+        app,
+        json!([
+            { "op": "replace", "path": "/mcp/servers/remote/server/headers/X-Team", "value": "b" },
+            { "op": "add", "path": "/mcp/servers/remote/server/headers/X-Trace", "value": "on" },
+            { "op": "remove", "path": "/mcp/servers/remote/server/headers/X-Old" }
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "unexpected body: {body}");
+    assert!(body["backupId"].as_str().is_some_and(|id| !id.is_empty()));
+
+    let expected = json!({ "X-Team": "b", "X-Trace": "on" });
+    assert_eq!(remote_headers(&state.config.read().unwrap()), expected);
+
+    // 补丁结果已写入磁盘
+    let saved = MultiAppConfig::load().expect("reload config");
+    assert_eq!(remote_headers(&saved), expected);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_patch_config_rejects_invalid_result() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = seeded_state();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    // 当前供应商指向不存在的条目
+    let (status, _) = send_patch(
+        app.clone(),
+        json!([{ "op": "replace", "path": "/claude/current", "value": "missing" }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // http 类型的 MCP 服务器缺少 url
+    let (status, _) = send_patch(
+        app.clone(),
+        json!([{ "op": "remove", "path": "/mcp/servers/remote/server/url" }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // 类型不匹配，无法反序列化为 MultiAppConfig
+    let (status, _) = send_patch(
+        app,
+        json!([{ "op": "replace", "path": "/claude/providers", "value": "oops" }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let check = |cfg: &MultiAppConfig| {
+        let claude = cfg.get_manager(&AppType::Claude).expect("claude manager");
+        assert_eq!(claude.current, "c1");
+        assert!(remote_headers(cfg)["X-Old"].is_string());
+    };
+    check(&state.config.read().unwrap());
+    check(&MultiAppConfig::load().expect("reload config"));
+}
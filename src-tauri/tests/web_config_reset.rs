@@ -0,0 +1,96 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn seeded_config() -> MultiAppConfig {
+    let provider = |id: &str| json!({ "id": id, "name": id, "settingsConfig": {} });
+    let prompt = |id: &str| json!({ "id": id, "name": id, "content": "hello" });
+    serde_json::from_value(json!({
+        "version": 2,
+        "claude": { "providers": { "c1": provider("c1") }, "current": "c1" },
+        "gemini": { "providers": { "g1": provider("g1") }, "current": "g1" },
+        "mcp": {
+            "servers": {
+                "shared": {
+                    "id": "shared",
+                    "name": "shared",
+                    "server": { "type": "stdio", "command": "echo" },
+                    "apps": { "claude": true, "codex": false, "gemini": true }
+                }
+            }
+        },
+        "prompts": {
+            "claude": { "prompts": { "p1": prompt("p1") } },
+            "gemini": { "prompts": { "p2": prompt("p2") } }
+        }
+    }))
+    .expect("seeded config")
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reset_gemini_preserves_claude_config() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let config = seeded_config();
+    config.save().expect("save config");
+    let state = Arc::new(AppState::new(config));
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/gemini/reset")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    let backup_id = body["backupId"].as_str().expect("backup id");
+    assert!(!backup_id.is_empty());
+    assert!(home
+        .join(".cc-switch")
+        .join("backups")
+        .join(format!("{backup_id}.json"))
+        .exists());
+
+    let check = |cfg: &MultiAppConfig| {
+        let gemini = cfg.get_manager(&AppType::Gemini).expect("gemini manager");
+        assert!(gemini.providers.is_empty());
+        assert!(gemini.current.is_empty());
+        assert!(cfg.prompts.gemini.prompts.is_empty());
+
+        let claude = cfg.get_manager(&AppType::Claude).expect("claude manager");
+        assert!(claude.providers.contains_key("c1"));
+        assert_eq!(claude.current, "c1");
+        assert!(cfg.prompts.claude.prompts.contains_key("p1"));
+
+        let servers = cfg.mcp.servers.as_ref().expect("mcp servers");
+        let shared = servers.get("shared").expect("shared server kept");
+        assert!(shared.apps.claude);
+        assert!(!shared.apps.gemini);
+    };
+    check(&state.config.read().unwrap());
+
+    // 重置结果已写入磁盘
+    let saved = MultiAppConfig::load().expect("reload config");
+    check(&saved);
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppError, AppState, MultiAppConfig};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn import_request(if_match: &'static str) -> Request<Body> {
+    let payload = serde_json::to_value(MultiAppConfig::default()).expect("serialize config");
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/import")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .header("if-match", HeaderValue::from_static(if_match))
+        .body(Body::from(payload.to_string()))
+        .expect("build request")
+}
+
+fn revision_header(res: &axum::response::Response) -> &str {
+    res.headers()
+        .get("x-config-revision")
+        .and_then(|value| value.to_str().ok())
+        .expect("revision header")
+}
+
+#[tokio::test]
+#[serial]
+async fn stale_if_match_import_is_rejected_with_conflict() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/config/export")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.clone().oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(revision_header(&res), "0");
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let snapshot: serde_json::Value = serde_json::from_slice(&bytes).expect("snapshot json");
+    assert_eq!(snapshot["revision"], 0);
+
+    // 携带当前修订号的导入成功，修订号递增
+    let res = app
+        .clone()
+        .oneshot(import_request("\"0\""))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(revision_header(&res), "1");
+    assert_eq!(state.config.read().expect("read config").revision, 1);
+    let on_disk: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(home.join(".cc-switch").join("config.json"))
+            .expect("read config.json"),
+    )
+    .expect("config json");
+    assert_eq!(on_disk["revision"], 1);
+
+    // 另一个客户端仍持有旧修订号，写入被拒绝且配置不变
+    let res = app
+        .oneshot(import_request("\"0\""))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    assert_eq!(revision_header(&res), "1");
+    assert_eq!(state.config.read().expect("read config").revision, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn writers_sharing_if_match_revision_only_first_saves() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = AppState::new(MultiAppConfig::default());
+
+    // 两个写入方都基于修订号 0；校验发生在保存的写锁内，后到者看到的已是 1
+    let first = AppState::with_expected_revision(vec![0], async { state.save() }).await;
+    let second = AppState::with_expected_revision(vec![0], async { state.save() }).await;
+
+    assert!(first.is_ok(), "first save: {first:?}");
+    assert!(
+        matches!(
+            second,
+            Err(AppError::Localized {
+                key: "config.revision_conflict",
+                ..
+            })
+        ),
+        "second save: {second:?}"
+    );
+    assert_eq!(state.config.read().expect("read config").revision, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[serial]
+async fn concurrent_imports_with_same_if_match_conflict() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let first = tokio::spawn(app.clone().oneshot(import_request("\"0\"")));
+    let second = tokio::spawn(app.oneshot(import_request("\"0\"")));
+    let mut statuses = vec![
+        first
+            .await
+            .expect("join")
+            .expect("router response")
+            .status(),
+        second
+            .await
+            .expect("join")
+            .expect("router response")
+            .status(),
+    ];
+    statuses.sort();
+
+    assert_eq!(statuses, vec![StatusCode::OK, StatusCode::CONFLICT]);
+    assert_eq!(state.config.read().expect("read config").revision, 1);
+}
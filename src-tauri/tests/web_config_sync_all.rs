@@ -0,0 +1,163 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{
+    get_claude_mcp_path, get_claude_settings_path, read_json_file, web_api, AppState, AppType,
+    MultiAppConfig,
+};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn seeded_config() -> MultiAppConfig {
+    serde_json::from_value(json!({
+        "version": 2,
+        "claude": {
+            "providers": {
+                "c1": {
+                    "id": "c1",
+                    "name": "c1",
+                    "settingsConfig": { "env": { "ANTHROPIC_AUTH_TOKEN": "sk-old" } }
+                }
+            },
+            "current": "c1"
+        },
+        "mcp": { "servers": {} },
+        "prompts": {
+            "claude": {
+                "prompts": {
+                    "p1": { "id": "p1", "name": "p1", "content": "old prompt", "enabled": true }
+                }
+            }
+        }
+    }))
+    .expect("seeded config")
+}
+
+#[tokio::test]
+#[serial]
+async fn test_sync_all_rewrites_live_files_from_memory() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let state = Arc::new(AppState::new(seeded_config()));
+
+    // 仅修改内存中的配置，live 文件此时尚未更新
+    {
+        let mut cfg = state.config.write().unwrap();
+        let manager = cfg
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.get_mut("c1").unwrap().settings_config =
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-new" } });
+        cfg.mcp.servers = Some(
+            serde_json::from_value(json!({
+                "echo": {
+                    "id": "echo",
+                    "name": "echo",
+                    "server": { "type": "stdio", "command": "echo" },
+                    "apps": { "claude": true, "codex": false, "gemini": false }
+                }
+            }))
+            .expect("mcp servers"),
+        );
+        cfg.prompts.claude.prompts.get_mut("p1").unwrap().content = "new prompt".to_string();
+    }
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/sync-all")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    assert_eq!(status, StatusCode::OK, "unexpected body: {body}");
+
+    let reports = body.as_array().expect("report array");
+    let claude = reports
+        .iter()
+        .find(|r| r["app"] == "claude")
+        .expect("claude report");
+    assert_eq!(claude["providerSynced"], true);
+    assert_eq!(claude["mcpServers"], 1);
+    assert_eq!(claude["promptSynced"], true);
+    assert!(
+        claude.get("errors").is_none(),
+        "unexpected errors: {claude}"
+    );
+
+    let settings: serde_json::Value =
+        read_json_file(&get_claude_settings_path().expect("claude settings path"))
+            .expect("claude settings");
+    assert_eq!(settings["env"]["ANTHROPIC_AUTH_TOKEN"], "sk-new");
+
+    let mcp: serde_json::Value =
+        read_json_file(&get_claude_mcp_path().expect("claude mcp path")).expect("claude mcp");
+    assert_eq!(mcp["mcpServers"]["echo"]["command"], "echo");
+
+    let prompt = std::fs::read_to_string(home.join(".claude").join("CLAUDE.md"))
+        .expect("claude prompt file");
+    assert_eq!(prompt, "new prompt");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_sync_all_keeps_stored_server_with_shell_operators() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let mut config = seeded_config();
+    // 已保存的服务器不受提交时的 shell 元字符检查影响，全量同步不应将其移除
+    config.mcp.servers = Some(
+        serde_json::from_value(json!({
+            "wrapped": {
+                "id": "wrapped",
+                "name": "wrapped",
+                "server": { "type": "stdio", "command": "bash", "args": ["-c", "cd x && npx y"] },
+                "apps": { "claude": true, "codex": true, "gemini": false }
+            }
+        }))
+        .expect("mcp servers"),
+    );
+    let state = Arc::new(AppState::new(config));
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state, "password".to_string());
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/config/sync-all")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let mcp: serde_json::Value =
+        read_json_file(&get_claude_mcp_path().expect("claude mcp path")).expect("claude mcp");
+    assert_eq!(
+        mcp["mcpServers"]["wrapped"]["args"],
+        json!(["-c", "cd x && npx y"])
+    );
+
+    let codex = std::fs::read_to_string(home.join(".codex").join("config.toml"))
+        .expect("codex config.toml");
+    assert!(codex.contains("cd x && npx y"), "codex config: {codex}");
+}
@@ -0,0 +1,223 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{
+        header::{
+            ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN,
+            ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_REQUEST_HEADERS,
+            ACCESS_CONTROL_REQUEST_METHOD, AUTHORIZATION, CONTENT_TYPE, ETAG, ORIGIN,
+        },
+        HeaderValue, Method, Request, StatusCode,
+    },
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+async fn request_json(
+    app: &axum::Router,
+    method: Method,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri("/api/system/cors-origins")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", "csrf-token");
+    let body = match body {
+        Some(value) => {
+            builder = builder.header(CONTENT_TYPE, "application/json");
+            Body::from(value.to_string())
+        }
+        None => Body::empty(),
+    };
+    let res = app
+        .clone()
+        .oneshot(builder.body(body).expect("build request"))
+        .await
+        .expect("router response");
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn preflight(app: &axum::Router, origin: &str) -> Option<HeaderValue> {
+    let req = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/providers/claude")
+        .header(ORIGIN, origin)
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.clone().oneshot(req).await.expect("router response");
+    res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).cloned()
+}
+
+/// 运行时开关与支持包中报告的 `corsEnabled`
+async fn reported_cors_enabled(app: &axum::Router) -> (Value, Value) {
+    let mut reported = Vec::new();
+    for uri in ["/api/system/config-flags", "/api/system/support-bundle"] {
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .body(Body::empty())
+            .expect("build request");
+        let res = app.clone().oneshot(req).await.expect("router response");
+        assert_eq!(res.status(), StatusCode::OK, "{uri}");
+        let bytes = to_bytes(res.into_body(), usize::MAX)
+            .await
+            .expect("response body");
+        reported.push(serde_json::from_slice::<Value>(&bytes).expect("json body"));
+    }
+    (
+        reported[0]["corsEnabled"].clone(),
+        reported[1]["configFlags"]["corsEnabled"].clone(),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn cors_origins_can_be_updated_at_runtime() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::remove_var("CORS_ALLOW_ORIGINS");
+    std::env::remove_var("ALLOW_LAN_CORS");
+    std::env::remove_var("CC_SWITCH_LAN_CORS");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let (status, body) = request_json(&app, Method::GET, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({ "origins": [] }));
+    assert!(preflight(&app, "https://app.example.com").await.is_none());
+    assert_eq!(
+        reported_cors_enabled(&app).await,
+        (json!(false), json!(false))
+    );
+
+    let (status, body) = request_json(
+        &app,
+        Method::PUT,
+        Some(json!({ "origins": ["https://app.example.com/", "http://localhost:5173"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        json!({ "origins": ["https://app.example.com", "http://localhost:5173"] })
+    );
+
+    assert_eq!(
+        preflight(&app, "https://app.example.com").await,
+        Some(HeaderValue::from_static("https://app.example.com"))
+    );
+    assert!(preflight(&app, "https://other.example.com").await.is_none());
+    assert_eq!(
+        reported_cors_enabled(&app).await,
+        (json!(true), json!(true))
+    );
+
+    for invalid in [
+        "*",
+        "https://*.example.com",
+        "app.example.com",
+        "https://app.example.com/path",
+    ] {
+        let (status, body) =
+            request_json(&app, Method::PUT, Some(json!({ "origins": [invalid] }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "origin {invalid}");
+        assert!(body["error"]
+            .as_str()
+            .is_some_and(|msg| msg.contains("CORS origin")));
+    }
+    // 校验失败不改变现有白名单
+    let (_, body) = request_json(&app, Method::GET, None).await;
+    assert_eq!(body["origins"].as_array().map(Vec::len), Some(2));
+
+    let (status, _) = request_json(&app, Method::PUT, Some(json!({ "origins": [] }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(preflight(&app, "https://app.example.com").await.is_none());
+    assert_eq!(
+        reported_cors_enabled(&app).await,
+        (json!(false), json!(false))
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn cors_preflight_allows_client_request_headers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::remove_var("CORS_ALLOW_ORIGINS");
+    std::env::remove_var("ALLOW_LAN_CORS");
+    std::env::remove_var("CC_SWITCH_LAN_CORS");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+    let (status, _) = request_json(
+        &app,
+        Method::PUT,
+        Some(json!({ "origins": ["https://app.example.com"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let req = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/providers/claude")
+        .header(ORIGIN, "https://app.example.com")
+        .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(
+            ACCESS_CONTROL_REQUEST_HEADERS,
+            "content-type,x-csrf-token,idempotency-key,if-none-match",
+        )
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.clone().oneshot(req).await.expect("router response");
+    let allowed = res
+        .headers()
+        .get(ACCESS_CONTROL_ALLOW_HEADERS)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    assert!(allowed.contains("idempotency-key"), "allowed: {allowed}");
+    assert!(allowed.contains("if-none-match"), "allowed: {allowed}");
+
+    // 跨域轮询需要读到 ETag 才能发起条件请求
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/providers/claude")
+        .header(ORIGIN, "https://app.example.com")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.clone().oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().contains_key(ETAG));
+    let exposed = res
+        .headers()
+        .get(ACCESS_CONTROL_EXPOSE_HEADERS)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    assert!(exposed.contains("etag"), "exposed: {exposed}");
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get, reset_test_fs, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn disabled_apps_are_hidden_and_return_not_found() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let (status, apps) = get(&app, "/api/system/apps").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(apps, json!(["claude", "codex", "gemini", "opencode"]));
+
+    std::env::set_var("ENABLED_APPS", "claude, codex");
+    let (status, apps) = get(&app, "/api/system/apps").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(apps, json!(["claude", "codex"]));
+
+    let (status, _) = get(&app, "/api/providers/claude").await;
+    assert_eq!(status, StatusCode::OK);
+    for uri in [
+        "/api/providers/gemini",
+        "/api/providers/opencode/current",
+        "/api/prompts/gemini",
+        "/api/skills/verify?app=gemini",
+    ] {
+        let (status, body) = get(&app, uri).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{uri}: {body}");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap_or_default()
+                .contains("ENABLED_APPS"),
+            "{uri}: {body}"
+        );
+    }
+
+    std::env::remove_var("ENABLED_APPS");
+    let (status, _) = get(&app, "/api/providers/gemini").await;
+    assert_eq!(status, StatusCode::OK);
+}
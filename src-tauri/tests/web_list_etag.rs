@@ -0,0 +1,158 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{
+        header::{AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        HeaderValue, Method, Request, StatusCode,
+    },
+};
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, make_app, reset_test_fs, test_mutex};
+
+fn seeded_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    for id in ["alpha", "beta", "gamma"] {
+        manager.providers.insert(
+            id.to_string(),
+            Provider::with_id(
+                id.to_string(),
+                id.to_string(),
+                json!({ "env": { "ANTHROPIC_BASE_URL": format!("https://{id}.example.com") } }),
+                None,
+            ),
+        );
+    }
+    config
+}
+
+/// GET 列表，返回状态码、ETag 与响应体长度
+async fn fetch(
+    app: &axum::Router,
+    uri: &str,
+    if_none_match: Option<&str>,
+) -> (StatusCode, String, usize) {
+    let mut builder = Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"));
+    if let Some(etag) = if_none_match {
+        builder = builder.header(IF_NONE_MATCH, etag);
+    }
+    let res = app
+        .clone()
+        .oneshot(builder.body(Body::empty()).expect("build request"))
+        .await
+        .expect("router response");
+    let status = res.status();
+    let etag = res
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .expect("etag header")
+        .to_string();
+    assert_eq!(
+        res.headers().get(CACHE_CONTROL),
+        Some(&HeaderValue::from_static("no-cache"))
+    );
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    (status, etag, bytes.len())
+}
+
+async fn put(app: &axum::Router, uri: &str, body: Value) {
+    let req = Request::builder()
+        .method(Method::PUT)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", "csrf-token")
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("build request");
+    let res = app.clone().oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK, "PUT {uri}");
+}
+
+async fn assert_conditional_get(
+    app: &axum::Router,
+    uri: &str,
+    mutate: impl std::future::Future<Output = ()>,
+) {
+    let (status, etag, len) = fetch(app, uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(etag.starts_with("W/\""), "weak etag expected, got {etag}");
+    assert!(len > 0);
+
+    // 内容未变（即便 HashMap 迭代顺序不同）时 ETag 稳定，命中返回 304 且无响应体
+    let (status, same_etag, len) = fetch(app, uri, Some(&etag)).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert_eq!(same_etag, etag);
+    assert_eq!(len, 0);
+    let (status, _, _) = fetch(app, uri, Some(&format!("\"stale\", {etag}"))).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+
+    mutate.await;
+
+    let (status, new_etag, len) = fetch(app, uri, Some(&etag)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(new_etag, etag);
+    assert!(len > 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn list_endpoints_support_conditional_get() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    let app = make_app(seeded_config());
+
+    assert_conditional_get(&app, "/api/providers/claude", async {
+        put(
+            &app,
+            "/api/providers/claude/alpha",
+            json!({
+                "id": "alpha",
+                "name": "Alpha renamed",
+                "settingsConfig": { "env": { "ANTHROPIC_BASE_URL": "https://alpha.example.com" } },
+            }),
+        )
+        .await;
+    })
+    .await;
+
+    assert_conditional_get(&app, "/api/mcp/servers", async {
+        put(
+            &app,
+            "/api/mcp/servers/echo",
+            json!({
+                "id": "echo",
+                "name": "echo",
+                "server": { "type": "stdio", "command": "echo" },
+                "apps": { "claude": false, "codex": false, "gemini": false, "opencode": false },
+            }),
+        )
+        .await;
+    })
+    .await;
+
+    assert_conditional_get(&app, "/api/prompts/claude", async {
+        put(
+            &app,
+            "/api/prompts/claude/review",
+            json!({ "id": "review", "name": "Review", "content": "Review the diff." }),
+        )
+        .await;
+    })
+    .await;
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, MultiAppConfig};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, make_app};
+
+fn log_level_request(level: &str, password: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::PUT)
+        .uri("/api/system/log-level")
+        .header(AUTHORIZATION, basic_auth_header("admin", password))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            serde_json::json!({ "level": level }).to_string(),
+        ))
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_set_log_level_accepts_debug() {
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(log_level_request("DEBUG", "password"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["level"], "debug");
+    assert_eq!(log::max_level(), log::LevelFilter::Debug);
+
+    web_api::logging::set_log_level("info").expect("restore log level");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_set_log_level_rejects_invalid_level_and_requires_auth() {
+    let app = make_app(MultiAppConfig::default());
+
+    let res = app
+        .clone()
+        .oneshot(log_level_request("verbose", "password"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(log_level_request("debug", "wrong-password"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "web-server")]
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::http::{Method, StatusCode};
+use cc_switch_lib::{web_api, AppState, McpApps, McpServer, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send, test_mutex};
+
+fn codex_server(id: &str, spec: Value) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        name: id.to_string(),
+        server: spec,
+        apps: McpApps {
+            claude: false,
+            codex: true,
+            gemini: false,
+            opencode: false,
+        },
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+        protected: false,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn codex_preview_matches_synced_config_toml() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let config_path = home.join(".codex").join("config.toml");
+    std::fs::create_dir_all(config_path.parent().unwrap()).expect("create codex dir");
+    let original = "# keep me\nmodel = \"gpt-5\"\n";
+    std::fs::write(&config_path, original).expect("seed config.toml");
+
+    let mut config = MultiAppConfig::default();
+    let servers: HashMap<String, McpServer> = [
+        codex_server(
+            "fetch",
+            json!({ "type": "stdio", "command": "uvx", "args": ["mcp-server-fetch"], "env": { "LOG": "1" }, "startup_timeout_sec": 20 }),
+        ),
+        codex_server(
+            "remote",
+            json!({ "type": "http", "url": "https://mcp.example.com/mcp" }),
+        ),
+    ]
+    .into_iter()
+    .map(|server| (server.id.clone(), server))
+    .collect();
+    config.mcp.servers = Some(servers);
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/mcp/codex/preview",
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let preview = body["toml"].as_str().expect("toml text").to_string();
+    assert!(preview.contains("[mcp_servers.fetch]"), "{preview}");
+    assert!(preview.contains("startup_timeout_sec = 20"), "{preview}");
+    // 预览不写文件
+    assert_eq!(
+        std::fs::read_to_string(&config_path).expect("read config.toml"),
+        original
+    );
+
+    let (status, body) = send(&app, Method::POST, "/api/config/sync-all", Some(json!({}))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(
+        std::fs::read_to_string(&config_path).expect("read config.toml"),
+        preview
+    );
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/mcp/codex/preview",
+        Some(json!({ "id": "solo", "spec": { "command": "npx", "args": ["-y", "pkg"] } })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let snippet = body["toml"].as_str().expect("toml text");
+    assert!(snippet.contains("[mcp_servers.solo]"), "{snippet}");
+    assert!(snippet.contains("command = \"npx\""), "{snippet}");
+    assert!(!snippet.contains("model"), "{snippet}");
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        "/api/mcp/codex/preview",
+        Some(json!({ "spec": { "type": "http" } })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
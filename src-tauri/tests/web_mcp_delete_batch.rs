@@ -0,0 +1,97 @@
+#![cfg(feature = "web-server")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::http::Method;
+use cc_switch_lib::{web_api, AppState, McpApps, McpServer, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send_ok, test_mutex};
+
+fn codex_server(id: &str) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        name: id.to_string(),
+        server: json!({ "type": "stdio", "command": "echo" }),
+        apps: McpApps {
+            codex: true,
+            ..Default::default()
+        },
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+        protected: false,
+    }
+}
+
+fn codex_server_ids(home: &std::path::Path) -> Vec<String> {
+    let codex_toml: toml::Table = toml::from_str(
+        &std::fs::read_to_string(home.join(".codex").join("config.toml"))
+            .expect("read codex config"),
+    )
+    .expect("parse codex config");
+    let mut ids: Vec<String> = codex_toml
+        .get("mcp_servers")
+        .and_then(|servers| servers.as_table())
+        .map(|servers| servers.keys().cloned().collect())
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+#[serial]
+async fn delete_batch_removes_servers_from_config_and_live_toml() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let mut config = MultiAppConfig::default();
+    config.mcp.servers = Some(HashMap::from([
+        ("alpha".to_string(), codex_server("alpha")),
+        ("beta".to_string(), codex_server("beta")),
+        ("gamma".to_string(), codex_server("gamma")),
+    ]));
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    send_ok(&app, Method::POST, "/api/config/sync-all", Some(json!({}))).await;
+    assert_eq!(codex_server_ids(home), ["alpha", "beta", "gamma"]);
+
+    let results = send_ok(
+        &app,
+        Method::POST,
+        "/api/mcp/servers/delete-batch",
+        Some(json!({ "ids": ["alpha", "beta", "ghost"] })),
+    )
+    .await;
+    assert_eq!(
+        results,
+        json!([
+            { "id": "alpha", "deleted": true },
+            { "id": "beta", "deleted": true },
+            { "id": "ghost", "deleted": false },
+        ])
+    );
+
+    let remaining: Vec<String> = {
+        let cfg = state.config.read().expect("read config");
+        let mut ids: Vec<String> = cfg
+            .mcp
+            .servers
+            .as_ref()
+            .map(|servers| servers.keys().cloned().collect())
+            .unwrap_or_default();
+        ids.sort();
+        ids
+    };
+    assert_eq!(remaining, ["gamma"]);
+    assert_eq!(codex_server_ids(home), ["gamma"]);
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "web-server")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::http::Method;
+use cc_switch_lib::{web_api, AppState, McpApps, McpServer, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send_ok, test_mutex};
+
+fn server(id: &str, apps: McpApps) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        name: id.to_string(),
+        server: json!({ "type": "stdio", "command": "echo" }),
+        apps,
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+        protected: false,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn enable_matrix_round_trips_and_syncs_live_configs() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let mut config = MultiAppConfig::default();
+    config.mcp.servers = Some(HashMap::from([
+        (
+            "alpha".to_string(),
+            server(
+                "alpha",
+                McpApps {
+                    claude: true,
+                    ..Default::default()
+                },
+            ),
+        ),
+        ("beta".to_string(), server("beta", McpApps::default())),
+    ]));
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let exported = send_ok(&app, Method::GET, "/api/mcp/enable-matrix", None).await;
+    assert_eq!(exported["alpha"]["claude"], true);
+    assert_eq!(exported["beta"]["codex"], false);
+
+    // 模拟在另一台机器上编辑后的矩阵，附带一个本机不存在的服务器
+    let mut edited = exported.clone();
+    edited["alpha"]["claude"] = json!(false);
+    edited["alpha"]["codex"] = json!(true);
+    edited["beta"]["codex"] = json!(true);
+    edited["ghost"] = json!({ "claude": true });
+    let report = send_ok(
+        &app,
+        Method::POST,
+        "/api/mcp/enable-matrix",
+        Some(edited.clone()),
+    )
+    .await;
+    assert_eq!(report["updated"], json!(["alpha", "beta"]));
+    assert_eq!(report["unknown"], json!(["ghost"]));
+
+    let reimported = send_ok(&app, Method::GET, "/api/mcp/enable-matrix", None).await;
+    edited
+        .as_object_mut()
+        .expect("matrix object")
+        .remove("ghost");
+    assert_eq!(reimported, edited);
+
+    let codex_toml: toml::Table = toml::from_str(
+        &std::fs::read_to_string(home.join(".codex").join("config.toml"))
+            .expect("read codex config"),
+    )
+    .expect("parse codex config");
+    let codex_servers = codex_toml["mcp_servers"]
+        .as_table()
+        .expect("mcp_servers table");
+    assert!(codex_servers.contains_key("alpha"));
+    assert!(codex_servers.contains_key("beta"));
+
+    // 再次应用相同矩阵不应产生任何变更
+    let report = send_ok(
+        &app,
+        Method::POST,
+        "/api/mcp/enable-matrix",
+        Some(reimported),
+    )
+    .await;
+    assert_eq!(report["updated"], json!([]));
+}
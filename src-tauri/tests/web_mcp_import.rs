@@ -0,0 +1,119 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{
+    basic_auth_header, ensure_test_home, make_app_with_state, reset_test_fs, response_json,
+    test_mutex,
+};
+
+fn import_request(app: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(format!("/api/mcp/import/{app}"))
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_from_claude_reports_skipped_servers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    std::fs::write(
+        home.join(".claude.json"),
+        serde_json::to_string_pretty(&json!({
+            "mcpServers": {
+                "valid": { "type": "stdio", "command": "echo" },
+                "no-command": { "type": "stdio" }
+            }
+        }))
+        .unwrap(),
+    )
+    .expect("write claude json");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let res = make_app_with_state(state.clone())
+        .oneshot(import_request("claude"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = response_json(res).await;
+    assert_eq!(body["imported"], 1);
+    let skipped = body["skipped"].as_array().expect("skipped array");
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0]["id"], "no-command");
+    assert!(skipped[0]["reason"].as_str().is_some_and(|r| !r.is_empty()));
+
+    let cfg = state.config.read().unwrap();
+    let servers = cfg.mcp.servers.as_ref().expect("servers");
+    assert!(servers.contains_key("valid"));
+    assert!(!servers.contains_key("no-command"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_from_codex_reports_unknown_type() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let codex_dir = home.join(".codex");
+    std::fs::create_dir_all(&codex_dir).expect("create codex dir");
+    std::fs::write(
+        codex_dir.join("config.toml"),
+        r#"[mcp_servers.ok]
+command = "echo"
+
+[mcp_servers.weird]
+type = "websocket"
+url = "ws://localhost"
+"#,
+    )
+    .expect("write codex config");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let res = make_app_with_state(state)
+        .oneshot(import_request("codex"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = response_json(res).await;
+    assert_eq!(body["imported"], 1);
+    assert_eq!(body["skipped"][0]["id"], "weird");
+    assert!(body["skipped"][0]["reason"]
+        .as_str()
+        .is_some_and(|r| r.contains("websocket")));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_rejects_unsupported_app() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let res = make_app_with_state(state)
+        .oneshot(import_request("omo"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+/// 在 127.0.0.1 上启动一个简单的 HTTP 服务，模拟 gist 的 raw 链接
+async fn spawn_mock_server(document: Value) -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server addr");
+    let router = axum::Router::new().route(
+        "/raw/mcp.json",
+        axum::routing::get(move || {
+            let document = document.clone();
+            async move { axum::Json(document) }
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.ok();
+    });
+    addr
+}
+
+async fn import_url(app: &axum::Router, url: &str) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/mcp/import-url")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "url": url }).to_string()))
+        .unwrap();
+    let res = app.clone().oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn import_url_upserts_valid_servers_and_reports_invalid_ones() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+
+    let addr = spawn_mock_server(json!({
+        "mcpServers": {
+            "fetch": { "command": "uvx", "args": ["mcp-server-fetch"] },
+            "remote": { "type": "http", "url": "https://mcp.example.com/mcp" },
+            "broken": { "type": "stdio" }
+        }
+    }))
+    .await;
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let (status, results) = import_url(&app, &format!("http://{addr}/raw/mcp.json")).await;
+    assert_eq!(status, StatusCode::OK, "unexpected body: {results}");
+    let results = results.as_array().expect("results array");
+    let result = |id: &str| {
+        results
+            .iter()
+            .find(|result| result["id"] == id)
+            .unwrap_or_else(|| panic!("missing result for {id}"))
+    };
+    assert_eq!(result("fetch")["imported"], true);
+    assert_eq!(result("remote")["imported"], true);
+    assert_eq!(result("broken")["imported"], false);
+    assert!(result("broken")["error"].is_string());
+
+    let cfg = state.config.read().expect("read config");
+    let servers = cfg.mcp.servers.as_ref().expect("servers");
+    assert_eq!(servers["fetch"].server["command"], "uvx");
+    assert_eq!(
+        servers["remote"].server["url"],
+        "https://mcp.example.com/mcp"
+    );
+    assert!(!servers.contains_key("broken"));
+}
+
+#[tokio::test]
+#[serial]
+async fn import_url_rejects_non_http_and_blocked_targets() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let (status, _) = import_url(&app, "file:///etc/passwd").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // 云厂商元数据地址（link-local）在任何策略下都会被拦截
+    let (status, _) = import_url(&app, "http://169.254.169.254/latest/meta-data").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // strict 策略下回环/内网地址同样被拒绝
+    std::env::set_var("USAGE_SCRIPT_EGRESS_POLICY", "strict");
+    let addr = spawn_mock_server(json!({ "mcpServers": {} })).await;
+    let (status, _) = import_url(&app, &format!("http://{addr}/raw/mcp.json")).await;
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::http::{Method, StatusCode};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send, test_mutex};
+
+fn server_body(id: &str, protected: bool) -> Value {
+    json!({
+        "id": id,
+        "name": id,
+        "server": { "type": "stdio", "command": "echo" },
+        "apps": { "claude": false, "codex": false, "gemini": false, "opencode": false },
+        "protected": protected,
+    })
+}
+
+#[tokio::test]
+#[serial]
+async fn protected_mcp_server_requires_force_to_delete() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    for (id, protected) in [("critical", true), ("plain", false)] {
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/mcp/servers/{id}"),
+            Some(server_body(id, protected)),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // 字段往返：受保护的服务器返回 `protected: true`，普通服务器省略该字段
+    let (status, body) = send(&app, Method::GET, "/api/mcp/servers/critical", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["protected"], true);
+    let (_, body) = send(&app, Method::GET, "/api/mcp/servers/plain", None).await;
+    assert!(body.get("protected").is_none());
+
+    let (status, body) = send(&app, Method::DELETE, "/api/mcp/servers/critical", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("受保护") && msg.contains("force=true")));
+
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        "/api/mcp/config/codex/servers/critical",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/mcp/servers/delete-batch",
+        Some(json!({ "ids": ["critical", "plain"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        json!([
+            { "id": "critical", "deleted": false },
+            { "id": "plain", "deleted": true },
+        ])
+    );
+
+    let (status, _) = send(&app, Method::GET, "/api/mcp/servers/critical", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &app,
+        Method::DELETE,
+        "/api/mcp/servers/critical?force=true",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!(true));
+
+    let (status, _) = send(&app, Method::GET, "/api/mcp/servers/critical", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
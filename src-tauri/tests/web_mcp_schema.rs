@@ -0,0 +1,172 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, response_json, test_mutex};
+
+fn request(method: Method, uri: &str, body: Option<serde_json::Value>) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap()
+}
+
+fn server_payload(id: &str, spec: serde_json::Value) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": id,
+        "server": spec,
+        "apps": { "claude": false, "codex": false, "gemini": false, "opencode": false }
+    })
+}
+
+#[tokio::test]
+#[serial]
+async fn mcp_schema_is_served_and_enforced_on_upsert() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/mcp/schema", None))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let schema = response_json(res).await;
+    assert_eq!(schema["additionalProperties"], json!(false));
+    assert!(schema["properties"]["command"].is_object());
+
+    let good = server_payload(
+        "good",
+        json!({ "type": "stdio", "command": "npx", "args": ["-y", "pkg"], "env": { "TOKEN": "x" } }),
+    );
+    let res = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/mcp/servers", Some(good)))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let typo = server_payload("typo", json!({ "type": "stdio", "commnd": "npx" }));
+    let res = app
+        .clone()
+        .oneshot(request(Method::POST, "/api/mcp/servers", Some(typo)))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = response_json(res).await;
+    assert!(
+        body.to_string().contains("commnd"),
+        "unexpected body: {body}"
+    );
+
+    let wrong_env = server_payload("good", json!({ "command": "npx", "env": { "PORT": 8080 } }));
+    let res = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            "/api/mcp/servers/good",
+            Some(wrong_env),
+        ))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // 局部更新同样在服务层按 Schema 校验
+    let res = app
+        .oneshot(request(
+            Method::PATCH,
+            "/api/mcp/servers/good",
+            Some(json!({ "timeout": "soon" })),
+        ))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let cfg = state.config.read().expect("read config");
+    let servers = cfg.mcp.servers.as_ref().expect("servers");
+    assert!(servers.contains_key("good"));
+    assert!(!servers.contains_key("typo"));
+    assert_eq!(servers["good"].server["env"]["TOKEN"], "x");
+}
+
+#[tokio::test]
+#[serial]
+async fn mcp_updates_keep_unknown_fields_of_imported_servers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    // 从 Codex 导入的服务器带有 Schema 之外的客户端特有字段
+    let imported =
+        json!({ "command": "npx", "env_vars": ["TOKEN"], "http_headers": { "X-Team": "a" } });
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    state.config.write().expect("write config").mcp.servers = Some(
+        serde_json::from_value(json!({ "imported": server_payload("imported", imported.clone()) }))
+            .expect("servers"),
+    );
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let mut updated = imported.clone();
+    updated["command"] = json!("uvx");
+    let res = app
+        .clone()
+        .oneshot(request(
+            Method::PUT,
+            "/api/mcp/servers/imported",
+            Some(server_payload("imported", updated)),
+        ))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            Method::PATCH,
+            "/api/mcp/servers/imported",
+            Some(json!({ "timeout": 30 })),
+        ))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // 本次新增的未知字段仍被拒绝
+    let res = app
+        .oneshot(request(
+            Method::PATCH,
+            "/api/mcp/servers/imported",
+            Some(json!({ "env_varz": ["TOKEN"] })),
+        ))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let cfg = state.config.read().expect("read config");
+    let spec = &cfg.mcp.servers.as_ref().expect("servers")["imported"].server;
+    assert_eq!(spec["command"], "uvx");
+    assert_eq!(spec["timeout"], 30);
+    assert_eq!(spec["env_vars"], json!(["TOKEN"]));
+    assert!(spec.get("env_varz").is_none());
+}
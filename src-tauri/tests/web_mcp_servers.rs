@@ -0,0 +1,482 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppState, McpApps, McpServer, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{
+    basic_auth_header, ensure_test_home, json_request, make_app_with_state, reset_test_fs,
+    response_json, test_mutex,
+};
+
+fn codex_server(id: &str) -> McpServer {
+    McpServer {
+        id: id.to_string(),
+        name: id.to_string(),
+        server: json!({ "type": "stdio", "command": "echo" }),
+        apps: McpApps {
+            claude: false,
+            codex: true,
+            gemini: false,
+            opencode: false,
+        },
+        description: None,
+        homepage: None,
+        docs: None,
+        tags: Vec::new(),
+        protected: false,
+    }
+}
+
+fn empty_state() -> Arc<AppState> {
+    Arc::new(AppState::new(MultiAppConfig::default()))
+}
+
+#[tokio::test]
+#[serial]
+async fn test_rename_server_moves_codex_toml_key() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+
+    let state = empty_state();
+    let app = make_app_with_state(state.clone());
+
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers",
+            serde_json::to_value(codex_server("old-id")).unwrap(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let toml_path = home.join(".codex").join("config.toml");
+    let before: toml::Table =
+        toml::from_str(&std::fs::read_to_string(&toml_path).unwrap()).expect("parse codex config");
+    assert!(before["mcp_servers"].get("old-id").is_some());
+
+    let res = app
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/old-id/rename",
+            json!({ "newId": "new-id" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = response_json(res).await;
+    assert_eq!(body["id"], "new-id");
+    assert_eq!(body["apps"]["codex"], true);
+
+    let after: toml::Table =
+        toml::from_str(&std::fs::read_to_string(&toml_path).unwrap()).expect("parse codex config");
+    let mcp_servers = after["mcp_servers"].as_table().expect("mcp_servers table");
+    assert!(mcp_servers.get("old-id").is_none());
+    assert_eq!(mcp_servers["new-id"]["command"].as_str(), Some("echo"));
+
+    let cfg = state.config.read().unwrap();
+    let servers = cfg.mcp.servers.as_ref().expect("servers");
+    assert!(!servers.contains_key("old-id"));
+    assert!(servers["new-id"].apps.codex);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_rename_server_rejects_collision_and_missing_source() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    let mut servers = std::collections::HashMap::new();
+    for id in ["a", "b"] {
+        let mut server = codex_server(id);
+        server.apps.codex = false;
+        servers.insert(id.to_string(), server);
+    }
+    config.mcp.servers = Some(servers);
+    let state = Arc::new(AppState::new(config));
+    let app = make_app_with_state(state.clone());
+
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/a/rename",
+            json!({ "newId": "b" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        state
+            .config
+            .read()
+            .unwrap()
+            .mcp
+            .servers
+            .as_ref()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let res = app
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/missing/rename",
+            json!({ "newId": "c" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_patch_server_merges_partial_spec() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut server = codex_server("remote");
+    server.apps.codex = false;
+    server.name = "Remote Server".to_string();
+    server.tags = vec!["prod".to_string()];
+    server.server = json!({
+        "type": "http",
+        "url": "https://old.example/mcp",
+        "headers": { "Authorization": "Bearer t" }
+    });
+    let mut config = MultiAppConfig::default();
+    config.mcp.servers = Some(std::collections::HashMap::from([(
+        "remote".to_string(),
+        server,
+    )]));
+    let state = Arc::new(AppState::new(config));
+    let app = make_app_with_state(state.clone());
+
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::PATCH,
+            "/api/mcp/servers/remote",
+            json!({ "url": "https://new.example/mcp" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = response_json(res).await;
+    assert_eq!(body["server"]["url"], "https://new.example/mcp");
+    assert_eq!(body["server"]["headers"]["Authorization"], "Bearer t");
+    assert_eq!(body["name"], "Remote Server");
+    assert_eq!(body["tags"], json!(["prod"]));
+
+    let stored = state.config.read().unwrap().mcp.servers.as_ref().unwrap()["remote"].clone();
+    assert_eq!(stored.server["type"], "http");
+    assert_eq!(stored.server["headers"]["Authorization"], "Bearer t");
+
+    let res = app
+        .oneshot(json_request(
+            Method::PATCH,
+            "/api/mcp/servers/missing",
+            json!({ "url": "https://x.example" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_env_placeholders_expanded_in_codex_toml_only() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+
+    std::env::set_var("EXPAND_ENV_IN_MCP", "1");
+    std::env::set_var("CC_SWITCH_TEST_MCP_TOKEN", "secret-token");
+
+    let mut server = codex_server("with-token");
+    server.server = json!({
+        "type": "stdio",
+        "command": "echo",
+        "args": ["--token", "${CC_SWITCH_TEST_MCP_TOKEN}"]
+    });
+    let state = empty_state();
+    let res = make_app_with_state(state.clone())
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers",
+            serde_json::to_value(server).unwrap(),
+        ))
+        .await
+        .unwrap();
+    std::env::remove_var("EXPAND_ENV_IN_MCP");
+    std::env::remove_var("CC_SWITCH_TEST_MCP_TOKEN");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let toml_path = home.join(".codex").join("config.toml");
+    let written: toml::Table =
+        toml::from_str(&std::fs::read_to_string(&toml_path).unwrap()).expect("parse codex config");
+    let args = written["mcp_servers"]["with-token"]["args"]
+        .as_array()
+        .expect("args array");
+    assert_eq!(args[1].as_str(), Some("secret-token"));
+
+    // config.json 中保留占位符
+    let cfg = state.config.read().unwrap();
+    let stored = &cfg.mcp.servers.as_ref().expect("servers")["with-token"];
+    assert_eq!(stored.server["args"][1], "${CC_SWITCH_TEST_MCP_TOKEN}");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_set_apps_enables_multiple_apps_in_one_call() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+
+    let mut server = codex_server("multi");
+    server.apps.codex = false;
+    let state = empty_state();
+    let app = make_app_with_state(state.clone());
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers",
+            serde_json::to_value(server).unwrap(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/multi/apps",
+            json!({ "claude": true, "codex": true, "gemini": false }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = response_json(res).await;
+    assert_eq!(body["claude"], true);
+    assert_eq!(body["codex"], true);
+    assert_eq!(body["gemini"], false);
+    assert_eq!(body["opencode"], false);
+
+    let claude: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(home.join(".claude.json")).expect("read claude mcp"),
+    )
+    .expect("parse claude mcp");
+    assert!(claude["mcpServers"].get("multi").is_some());
+
+    let codex: toml::Table =
+        toml::from_str(&std::fs::read_to_string(home.join(".codex").join("config.toml")).unwrap())
+            .expect("parse codex config");
+    assert!(codex["mcp_servers"].get("multi").is_some());
+
+    let gemini_settings = home.join(".gemini").join("settings.json");
+    if gemini_settings.exists() {
+        let gemini: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&gemini_settings).unwrap())
+                .expect("parse gemini settings");
+        assert!(gemini["mcpServers"].get("multi").is_none());
+    }
+
+    let res = app
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/missing/apps",
+            json!({ "claude": true }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_merge_identical_stdio_servers_unions_apps() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+
+    let mut from_claude = codex_server("echo-claude");
+    from_claude.apps.codex = false;
+    from_claude.apps.claude = true;
+    let from_codex = codex_server("echo-codex");
+    let mut other = codex_server("other");
+    other.server = json!({ "type": "stdio", "command": "cat" });
+
+    let state = empty_state();
+    let app = make_app_with_state(state.clone());
+    for server in [from_claude, from_codex, other] {
+        let res = app
+            .clone()
+            .oneshot(json_request(
+                Method::POST,
+                "/api/mcp/servers",
+                serde_json::to_value(server).unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::GET)
+                .uri("/api/mcp/servers/duplicates")
+                .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+                .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        response_json(res).await,
+        json!([["echo-claude", "echo-codex"]])
+    );
+
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/merge",
+            json!({ "keepId": "echo-claude", "mergeIds": ["echo-codex"] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = response_json(res).await;
+    assert_eq!(body["id"], "echo-claude");
+    assert_eq!(body["apps"]["claude"], true);
+    assert_eq!(body["apps"]["codex"], true);
+
+    {
+        let cfg = state.config.read().unwrap();
+        let servers = cfg.mcp.servers.as_ref().expect("servers");
+        assert!(servers.contains_key("echo-claude"));
+        assert!(!servers.contains_key("echo-codex"));
+        assert!(servers.contains_key("other"));
+    }
+
+    let codex: toml::Table =
+        toml::from_str(&std::fs::read_to_string(home.join(".codex").join("config.toml")).unwrap())
+            .expect("parse codex config");
+    assert!(codex["mcp_servers"].get("echo-claude").is_some());
+    assert!(codex["mcp_servers"].get("echo-codex").is_none());
+
+    let claude: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(home.join(".claude.json")).expect("read claude mcp"),
+    )
+    .expect("parse claude mcp");
+    assert!(claude["mcpServers"].get("echo-claude").is_some());
+
+    let res = app
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/servers/merge",
+            json!({ "keepId": "echo-claude", "mergeIds": ["missing"] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_server_returns_server_or_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config.mcp.servers = Some(std::collections::HashMap::from([(
+        "echo".to_string(),
+        codex_server("echo"),
+    )]));
+    let state = Arc::new(AppState::new(config));
+    let app = make_app_with_state(state);
+
+    let get = |uri: &str| {
+        Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(get("/api/mcp/servers/echo"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let server = response_json(res).await;
+    assert_eq!(server["id"], "echo");
+    assert_eq!(server["server"]["command"], "echo");
+    assert_eq!(server["apps"]["codex"], true);
+
+    let res = app.oneshot(get("/api/mcp/servers/missing")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_toggle_app_waits_for_codex_live_lock() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::fs::create_dir_all(home.join(".codex")).expect("create codex dir");
+
+    let state = empty_state();
+    let mut server = codex_server("echo");
+    server.apps.codex = false;
+    state.config.write().expect("write config").mcp.servers =
+        Some([("echo".to_string(), server)].into_iter().collect());
+    let app = make_app_with_state(state.clone());
+
+    // 其他写入方（如 Codex 切换）持有 live 锁期间，MCP 同步必须等待
+    let live = state.lock_live(&cc_switch_lib::AppType::Codex).await;
+    let toggle = tokio::spawn(app.oneshot(json_request(
+        Method::POST,
+        "/api/mcp/servers/echo/apps/codex",
+        json!({ "enabled": true }),
+    )));
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!toggle.is_finished());
+    assert!(!home.join(".codex").join("config.toml").exists());
+
+    drop(live);
+    let res = toggle.await.expect("toggle task").expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let config =
+        std::fs::read_to_string(home.join(".codex").join("config.toml")).expect("read config.toml");
+    assert!(config.contains("echo"), "unexpected config.toml: {config}");
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "web-server")]
+
+use axum::http::{Method, StatusCode};
+use cc_switch_lib::MultiAppConfig;
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, json_request, make_app, reset_test_fs, response_json, test_mutex};
+
+/// 在 127.0.0.1 上启动一个简单的 HTTP 服务，模拟 http/sse MCP 端点
+async fn spawn_mock_server() -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server addr");
+    let router = axum::Router::new().route("/mcp", axum::routing::get(|| async { "ok" }));
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.ok();
+    });
+    addr
+}
+
+#[tokio::test]
+#[serial]
+async fn test_url_reports_reachable_mock_server() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+
+    let addr = spawn_mock_server().await;
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({
+                "url": format!("http://{addr}/mcp"),
+                "headers": { "Authorization": "Bearer test" }
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = response_json(res).await;
+    assert_eq!(body["reachable"], true);
+    assert_eq!(body["status"], 200);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_url_blocks_internal_addresses() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    let app = make_app(MultiAppConfig::default());
+
+    // 云厂商元数据地址（link-local）在任何策略下都会被拦截
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({ "url": "http://169.254.169.254/latest/meta-data" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // strict 策略下回环地址同样被拒绝
+    std::env::set_var("USAGE_SCRIPT_EGRESS_POLICY", "strict");
+    let addr = spawn_mock_server().await;
+    let res = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({ "url": format!("http://{addr}/mcp") }),
+        ))
+        .await
+        .unwrap();
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({ "url": "http://1.1.1.1/mcp", "headers": { "Host": "evil" } }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_url_rejects_extra_forbidden_headers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    std::env::set_var(
+        "USAGE_SCRIPT_EXTRA_FORBIDDEN_HEADERS",
+        " X-Internal-Token , cookie",
+    );
+
+    let addr = spawn_mock_server().await;
+    let app = make_app(MultiAppConfig::default());
+    let url = format!("http://{addr}/mcp");
+
+    let blocked = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({ "url": url, "headers": { "x-internal-token": "secret" } }),
+        ))
+        .await
+        .unwrap();
+    let allowed = app
+        .clone()
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({ "url": url, "headers": { "Authorization": "Bearer test" } }),
+        ))
+        .await
+        .unwrap();
+    // 追加的列表只会扩展内置禁止项，不会放开它们
+    let builtin = app
+        .oneshot(json_request(
+            Method::POST,
+            "/api/mcp/test-url",
+            json!({ "url": url, "headers": { "Host": "evil" } }),
+        ))
+        .await
+        .unwrap();
+    std::env::remove_var("USAGE_SCRIPT_EXTRA_FORBIDDEN_HEADERS");
+
+    assert_eq!(blocked.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(allowed.status(), StatusCode::OK);
+    assert_eq!(response_json(allowed).await["reachable"], true);
+    assert_eq!(builtin.status(), StatusCode::BAD_REQUEST);
+}
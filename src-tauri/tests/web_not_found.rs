@@ -0,0 +1,110 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, make_app, reset_test_fs, test_mutex};
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn error_message(res: axum::response::Response) -> String {
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    body["error"].as_str().unwrap_or_default().to_string()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_switch_missing_provider_returns_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(request(
+            Method::POST,
+            "/api/providers/claude/missing/switch",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(error_message(res).await.contains("missing"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_delete_missing_provider_returns_404_but_other_errors_stay_400() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    manager.providers.insert(
+        "p1".to_string(),
+        Provider::with_id(
+            "p1".into(),
+            "P1".into(),
+            serde_json::json!({ "env": {} }),
+            None,
+        ),
+    );
+    manager.current = "p1".to_string();
+    let app = make_app(config);
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::DELETE, "/api/providers/claude/missing"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    // 删除当前供应商属于非法操作，而非目标不存在
+    let res = app
+        .oneshot(request(Method::DELETE, "/api/providers/claude/p1"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_delete_missing_mcp_server_and_prompt_return_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app(MultiAppConfig::default());
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::DELETE, "/api/mcp/servers/missing"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let res = app
+        .oneshot(request(Method::DELETE, "/api/prompts/claude/missing"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
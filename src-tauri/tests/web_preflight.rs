@@ -0,0 +1,140 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
+};
+use cc_switch_lib::MultiAppConfig;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, make_app, reset_test_fs, test_mutex};
+
+async fn get_preflight() -> serde_json::Value {
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/preflight")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .unwrap();
+    let res = make_app(MultiAppConfig::default())
+        .oneshot(req)
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).expect("preflight json")
+}
+
+fn app_entry<'a>(report: &'a serde_json::Value, app: &str) -> &'a serde_json::Value {
+    report["apps"]
+        .as_array()
+        .expect("apps array")
+        .iter()
+        .find(|entry| entry["app"] == app)
+        .expect("app entry")
+}
+
+/// 以 nobody 身份执行检查：root 会绕过权限位，需临时切换有效用户才能观察到“不可写”
+///
+/// 测试 HOME 会交给 nobody，只有测试自行准备的子目录仍属于 root。
+#[cfg(unix)]
+async fn get_preflight_as_unprivileged(home: &std::path::Path) -> serde_json::Value {
+    const NOBODY: libc::uid_t = 65534;
+    if unsafe { libc::geteuid() } != 0 {
+        return get_preflight().await;
+    }
+    std::os::unix::fs::chown(home, Some(NOBODY), Some(NOBODY)).expect("chown test home");
+    assert_eq!(unsafe { libc::seteuid(NOBODY) }, 0, "switch to nobody");
+    let report = get_preflight().await;
+    assert_eq!(unsafe { libc::seteuid(0) }, 0, "restore root");
+    report
+}
+
+#[tokio::test]
+#[serial]
+async fn test_preflight_reports_missing_files_as_creatable() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let report = get_preflight().await;
+    let claude = app_entry(&report, "claude");
+    assert_eq!(claude["ok"], true);
+    assert_eq!(claude["paths"][0]["kind"], "dir");
+    assert_eq!(claude["paths"][0]["exists"], false);
+    assert_eq!(claude["paths"][0]["writable"], true);
+
+    // 只读检查不应创建任何目录
+    assert!(!home.join(".claude").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn test_preflight_reports_read_only_dir_as_not_writable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let codex_dir = home.join(".codex");
+    std::fs::create_dir_all(&codex_dir).expect("create codex dir");
+    std::fs::set_permissions(&codex_dir, std::fs::Permissions::from_mode(0o555))
+        .expect("make codex dir read-only");
+
+    let report = get_preflight_as_unprivileged(home).await;
+
+    std::fs::set_permissions(&codex_dir, std::fs::Permissions::from_mode(0o755))
+        .expect("restore codex dir permissions");
+
+    assert_eq!(report["ok"], false);
+    let codex = app_entry(&report, "codex");
+    assert_eq!(codex["ok"], false);
+    let dir = &codex["paths"][0];
+    assert_eq!(dir["exists"], true);
+    assert_eq!(dir["writable"], false);
+    assert!(dir["reason"].is_string());
+    // config.toml 尚不存在，且所在目录只读
+    assert_eq!(codex["paths"][1]["exists"], false);
+    assert_eq!(codex["paths"][1]["writable"], false);
+
+    assert_eq!(app_entry(&report, "claude")["ok"], true);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn test_preflight_reports_dir_owned_by_other_user_as_not_writable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    // 需要一个属于其他用户、权限为 0755 的目录；非 root 环境无法构造，直接跳过
+    if unsafe { libc::geteuid() } != 0 {
+        eprintln!("skipping: requires root to prepare a directory owned by another uid");
+        return;
+    }
+
+    // 目录属于 root 且权限为 0755：权限位中有写位，但 nobody 并不能写入
+    let codex_dir = home.join(".codex");
+    std::fs::create_dir_all(&codex_dir).expect("create codex dir");
+    std::fs::set_permissions(&codex_dir, std::fs::Permissions::from_mode(0o755))
+        .expect("set codex dir permissions");
+
+    let report = get_preflight_as_unprivileged(home).await;
+
+    let codex = app_entry(&report, "codex");
+    assert_eq!(codex["ok"], false);
+    let dir = &codex["paths"][0];
+    assert_eq!(dir["exists"], true);
+    assert_eq!(dir["writable"], false);
+    assert!(dir["reason"].is_string());
+    assert_eq!(codex["paths"][1]["exists"], false);
+    assert_eq!(codex["paths"][1]["writable"], false);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "web-server")]
+
+use axum::http::StatusCode;
+use cc_switch_lib::{MultiAppConfig, Prompt};
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get, make_app, reset_test_fs, test_mutex};
+
+fn seeded_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    config.prompts.claude.prompts.insert(
+        "review".to_string(),
+        Prompt {
+            id: "review".to_string(),
+            name: "Review".to_string(),
+            content: "Review the diff carefully.".to_string(),
+            description: None,
+            enabled: false,
+            created_at: None,
+            updated_at: None,
+        },
+    );
+    config
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_prompt_returns_prompt() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let (status, prompt) = get(&make_app(seeded_config()), "/api/prompts/claude/review").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(prompt["id"], "review");
+    assert_eq!(prompt["name"], "Review");
+    assert_eq!(prompt["content"], "Review the diff carefully.");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_missing_prompt_returns_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app(seeded_config());
+    let (status, _) = get(&app.clone(), "/api/prompts/claude/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // 提示词按应用隔离
+    let (status, _) = get(&app, "/api/prompts/codex/review").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
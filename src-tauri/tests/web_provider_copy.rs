@@ -0,0 +1,98 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig, Provider};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn claude_state() -> Arc<AppState> {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    manager.providers.insert(
+        "relay".to_string(),
+        Provider::with_id(
+            "relay".into(),
+            "My Relay".into(),
+            json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": "sk-relay",
+                    "ANTHROPIC_BASE_URL": "https://relay.example.com/",
+                    "ANTHROPIC_MODEL": "claude-sonnet"
+                }
+            }),
+            Some("https://relay.example.com".into()),
+        ),
+    );
+    Arc::new(AppState::new(config))
+}
+
+async fn copy(app: axum::Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).expect("response json"),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn test_copy_claude_provider_to_codex_flags_shape_mismatch() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = claude_state();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let (status, body) = copy(app.clone(), "/api/providers/claude/relay/copy-to/codex").await;
+    assert_eq!(status, StatusCode::OK, "unexpected body: {body}");
+    let new_id = body["id"].as_str().expect("new id").to_string();
+    assert_ne!(new_id, "relay");
+    let warnings = body["warnings"].as_array().expect("warnings array");
+    assert_eq!(warnings.len(), 1);
+
+    {
+        let cfg = state.config.read().unwrap();
+        let codex = cfg.get_manager(&AppType::Codex).expect("codex manager");
+        let copied = codex.providers.get(&new_id).expect("copied provider");
+        assert_eq!(copied.name, "My Relay");
+        assert_eq!(copied.settings_config["auth"]["OPENAI_API_KEY"], "sk-relay");
+        let config_toml = copied.settings_config["config"]
+            .as_str()
+            .expect("codex config toml");
+        assert!(config_toml.contains("base_url = \"https://relay.example.com\""));
+        // 源供应商保持不变
+        let claude = cfg.get_manager(&AppType::Claude).expect("claude manager");
+        assert!(claude.providers.contains_key("relay"));
+    }
+
+    // 同一应用内复制不需要转换，也不应产生警告
+    let (status, body) = copy(app.clone(), "/api/providers/claude/relay/copy-to/claude").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["warnings"], json!([]));
+
+    let (status, _) = copy(app, "/api/providers/claude/missing/copy-to/codex").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
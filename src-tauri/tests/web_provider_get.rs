@@ -0,0 +1,59 @@
+#![cfg(feature = "web-server")]
+
+use axum::http::StatusCode;
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serde_json::json;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get, make_app, reset_test_fs, test_mutex};
+
+fn seeded_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    manager.providers.insert(
+        "alpha".to_string(),
+        Provider::with_id(
+            "alpha".to_string(),
+            "Alpha".to_string(),
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://alpha.example.com" } }),
+            None,
+        ),
+    );
+    config
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_provider_returns_single_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let (status, provider) = get(&make_app(seeded_config()), "/api/providers/claude/alpha").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(provider["id"], "alpha");
+    assert_eq!(provider["name"], "Alpha");
+    assert_eq!(
+        provider["settingsConfig"]["env"]["ANTHROPIC_BASE_URL"],
+        "https://alpha.example.com"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_missing_provider_returns_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let (status, body) = get(&make_app(seeded_config()), "/api/providers/claude/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("missing"));
+}
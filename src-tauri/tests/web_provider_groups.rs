@@ -0,0 +1,64 @@
+#![cfg(feature = "web-server")]
+
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, get_json, make_app, reset_test_fs, test_mutex};
+
+fn grouped_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    for (id, group) in [("a", Some("work")), ("b", Some("personal")), ("c", None)] {
+        let mut provider = Provider::with_id(
+            id.to_string(),
+            id.to_uppercase(),
+            serde_json::json!({ "env": {} }),
+            None,
+        );
+        provider.group = group.map(str::to_string);
+        manager.providers.insert(id.to_string(), provider);
+    }
+    config
+}
+
+#[tokio::test]
+#[serial]
+async fn test_list_providers_filters_by_group() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app(grouped_config());
+
+    let work = get_json(app.clone(), "/api/providers/claude?group=work").await;
+    let work = work.as_object().expect("providers map");
+    assert_eq!(work.len(), 1);
+    assert_eq!(work["a"]["group"], "work");
+
+    let all = get_json(app.clone(), "/api/providers/claude").await;
+    assert_eq!(all.as_object().map(|m| m.len()), Some(3));
+
+    let groups = get_json(app, "/api/providers/claude/groups").await;
+    assert_eq!(groups, serde_json::json!(["personal", "work"]));
+}
+
+#[test]
+#[serial]
+fn test_provider_group_round_trips_through_save_and_load() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    grouped_config().save().expect("save config");
+    let loaded = MultiAppConfig::load().expect("load config");
+    let providers = &loaded
+        .get_manager(&AppType::Claude)
+        .expect("claude manager")
+        .providers;
+    assert_eq!(providers["a"].group.as_deref(), Some("work"));
+    assert_eq!(providers["c"].group, None);
+}
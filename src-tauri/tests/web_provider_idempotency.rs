@@ -0,0 +1,130 @@
+#![cfg(feature = "web-server")]
+
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn add_request(id: &str, idempotency_key: &'static str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/claude")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .header("idempotency-key", HeaderValue::from_static(idempotency_key))
+        .body(Body::from(
+            json!({
+                "id": id,
+                "name": "Retried",
+                "settingsConfig": { "env": {} }
+            })
+            .to_string(),
+        ))
+        .expect("build request")
+}
+
+#[tokio::test]
+#[serial]
+async fn repeated_idempotency_key_creates_one_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    // 网络重试：同一个 Idempotency-Key、同样的请求体
+    for _ in 0..2 {
+        let res = app
+            .clone()
+            .oneshot(add_request("retry-a", "add-claude-retry-1"))
+            .await
+            .expect("router response");
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let config = state.config.read().expect("read config");
+    let providers = &config
+        .get_manager(&AppType::Claude)
+        .expect("claude manager")
+        .providers;
+    assert_eq!(providers.len(), 1, "providers: {:?}", providers.keys());
+    assert!(providers.contains_key("retry-a"));
+}
+
+#[tokio::test]
+#[serial]
+async fn in_flight_idempotency_key_is_rejected_without_creating() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    // 模拟首个请求已预留 key、仍在执行
+    state.idempotency().insert(
+        ("claude".to_string(), "add-claude-pending".to_string()),
+        (Instant::now(), "first-body-hash".to_string(), None),
+    );
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let res = app
+        .oneshot(add_request("pending-b", "add-claude-pending"))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let config = state.config.read().expect("read config");
+    assert!(config
+        .get_manager(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn reused_idempotency_key_with_different_body_is_rejected() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let res = app
+        .clone()
+        .oneshot(add_request("reuse-a", "add-claude-reused"))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // key 相同但请求体不同：不能返回首次结果冒充成功
+    let res = app
+        .clone()
+        .oneshot(add_request("reuse-b", "add-claude-reused"))
+        .await
+        .expect("router response");
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let config = state.config.read().expect("read config");
+    let providers = &config
+        .get_manager(&AppType::Claude)
+        .expect("claude manager")
+        .providers;
+    assert_eq!(providers.len(), 1, "providers: {:?}", providers.keys());
+    assert!(providers.contains_key("reuse-a"));
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{
+    get_claude_settings_path, web_api, AppState, AppType, MultiAppConfig, Provider,
+};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn test_import_live_claude_settings_as_new_provider() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let live = json!({
+        "env": {
+            "ANTHROPIC_AUTH_TOKEN": "sk-live",
+            "ANTHROPIC_BASE_URL": "https://live.example.com"
+        }
+    });
+    let settings_path = get_claude_settings_path().expect("claude settings path");
+    std::fs::create_dir_all(settings_path.parent().expect("settings dir")).expect("create dir");
+    std::fs::write(&settings_path, live.to_string()).expect("write live settings");
+
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    manager.providers.insert(
+        "existing".to_string(),
+        Provider::with_id(
+            "existing".into(),
+            "Existing".into(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-old" } }),
+            None,
+        ),
+    );
+    manager.current = "existing".to_string();
+    let state = Arc::new(AppState::new(config));
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/claude/import-live")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "name": "Live Snapshot" }).to_string()))
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+    assert_eq!(body["name"], "Live Snapshot");
+    assert_eq!(body["settingsConfig"], live);
+    let new_id = body["id"].as_str().expect("new id");
+
+    let cfg = state.config.read().unwrap();
+    let manager = cfg.get_manager(&AppType::Claude).expect("claude manager");
+    let imported = manager.providers.get(new_id).expect("imported provider");
+    assert_eq!(imported.settings_config, live);
+    // 导入不会切换当前供应商
+    assert_eq!(manager.current, "existing");
+}
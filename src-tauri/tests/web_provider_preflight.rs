@@ -0,0 +1,97 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, make_app, reset_test_fs, test_mutex};
+
+fn codex_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Codex)
+        .expect("codex manager");
+    manager.providers.insert(
+        "valid".to_string(),
+        Provider::with_id(
+            "valid".into(),
+            "Valid".into(),
+            json!({
+                "auth": { "OPENAI_API_KEY": "sk-test" },
+                "config": "model = \"gpt-5\"\n"
+            }),
+            None,
+        ),
+    );
+    manager.providers.insert(
+        "no-auth".to_string(),
+        Provider::with_id(
+            "no-auth".into(),
+            "No Auth".into(),
+            json!({ "config": "model = \"gpt-5\"\n" }),
+            None,
+        ),
+    );
+    config
+}
+
+async fn preflight(app: axum::Router, id: &str) -> (StatusCode, serde_json::Value) {
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/api/providers/codex/{id}/preflight"))
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).expect("response json"),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn test_preflight_valid_codex_provider_is_ok() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let (status, body) = preflight(make_app(codex_config()), "valid").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["ok"], true, "unexpected issues: {body}");
+    assert_eq!(body["issues"], json!([]));
+    // 预检不应写入任何 live 文件
+    assert!(!home.join(".codex").exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_preflight_reports_missing_codex_auth() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app(codex_config());
+    let (status, body) = preflight(app.clone(), "no-auth").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["ok"], false);
+    let issues = body["issues"].as_array().expect("issues array");
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0]["kind"], "settings");
+    assert!(issues[0]["message"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("auth")));
+
+    let (status, _) = preflight(app, "missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig, Provider};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, make_app, reset_test_fs, test_mutex};
+
+fn switch_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "claude-p".to_string(),
+            Provider::with_id(
+                "claude-p".into(),
+                "Claude P".into(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-claude" } }),
+                None,
+            ),
+        );
+    config
+        .get_manager_mut(&AppType::Codex)
+        .expect("codex manager")
+        .providers
+        .insert(
+            "codex-p".to_string(),
+            Provider::with_id(
+                "codex-p".into(),
+                "Codex P".into(),
+                json!({
+                    "auth": { "OPENAI_API_KEY": "sk-test" },
+                    "config": "model = \"gpt-5\"\n"
+                }),
+                None,
+            ),
+        );
+    config
+}
+
+async fn switch(app: axum::Router, app_type: &str, id: &str) -> (StatusCode, serde_json::Value) {
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/api/providers/{app_type}/{id}/switch"))
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).expect("response json"),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn test_switch_returns_live_file_path_per_app() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let app = make_app(switch_config());
+
+    let (status, body) = switch(app.clone(), "claude", "claude-p").await;
+    assert_eq!(status, StatusCode::OK, "unexpected body: {body}");
+    assert_eq!(body["success"], true);
+    let claude_path = home.join(".claude").join("settings.json");
+    assert_eq!(
+        body["liveFilePath"].as_str(),
+        Some(claude_path.to_string_lossy().as_ref())
+    );
+    assert!(claude_path.exists());
+
+    let (status, body) = switch(app, "codex", "codex-p").await;
+    assert_eq!(status, StatusCode::OK, "unexpected body: {body}");
+    assert_eq!(body["success"], true);
+    let codex_path = home.join(".codex").join("config.toml");
+    assert_eq!(
+        body["liveFilePath"].as_str(),
+        Some(codex_path.to_string_lossy().as_ref())
+    );
+    assert!(codex_path.exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_switch_missing_provider_returns_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let (status, body) = switch(make_app(switch_config()), "claude", "missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_switch_records_last_switched_at_and_history() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = switch_config();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "claude-q".to_string(),
+            Provider::with_id(
+                "claude-q".into(),
+                "Claude Q".into(),
+                json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-other" } }),
+                None,
+            ),
+        );
+    let state = Arc::new(AppState::new(config));
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let before = chrono::Utc::now().timestamp_millis();
+    let (status, _) = switch(app.clone(), "claude", "claude-p").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = switch(app.clone(), "claude", "claude-q").await;
+    assert_eq!(status, StatusCode::OK);
+
+    {
+        let cfg = state.config.read().unwrap();
+        let manager = cfg.get_manager(&AppType::Claude).expect("claude manager");
+        let switched_at = manager.providers["claude-p"]
+            .last_switched_at
+            .expect("lastSwitchedAt set");
+        assert!(switched_at >= before);
+        assert!(manager.providers["claude-q"].last_switched_at >= Some(switched_at));
+        let serialized = serde_json::to_value(&*cfg).expect("serialize config");
+        assert!(serialized["claude"]["providers"]["claude-p"]["lastSwitchedAt"].is_i64());
+    }
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/providers/claude/switch-history")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let history: serde_json::Value = serde_json::from_slice(&bytes).expect("history json");
+    let ids: Vec<&str> = history
+        .as_array()
+        .expect("history array")
+        .iter()
+        .filter_map(|entry| entry["providerId"].as_str())
+        .collect();
+    assert_eq!(ids, vec!["claude-q", "claude-p"]);
+}
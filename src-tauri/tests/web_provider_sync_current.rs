@@ -0,0 +1,95 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{
+    get_claude_settings_path, web_api, AppState, AppType, MultiAppConfig, Provider,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn set_current(config: &mut MultiAppConfig, app: AppType, provider: Provider) {
+    let manager = config.get_manager_mut(&app).expect("manager");
+    manager.current = provider.id.clone();
+    manager.providers.insert(provider.id.clone(), provider);
+}
+
+#[tokio::test]
+#[serial]
+async fn sync_current_reports_per_app_results() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let mut config = MultiAppConfig::default();
+    set_current(
+        &mut config,
+        AppType::Claude,
+        Provider::with_id(
+            "claude-ok".to_string(),
+            "Claude OK".to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-test" } }),
+            None,
+        ),
+    );
+    // Codex 供应商缺少 auth，写入 live 时会失败
+    set_current(
+        &mut config,
+        AppType::Codex,
+        Provider::with_id(
+            "codex-missing-auth".to_string(),
+            "No Auth".to_string(),
+            json!({ "config": "model = \"test\"" }),
+            None,
+        ),
+    );
+    // 通用 Gemini 供应商的 env 为空时按 OAuth 处理，不要求 GEMINI_API_KEY
+    set_current(
+        &mut config,
+        AppType::Gemini,
+        Provider::with_id(
+            "gemini-custom".to_string(),
+            "Custom Gemini".to_string(),
+            json!({ "env": {}, "config": {} }),
+            None,
+        ),
+    );
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/sync-current")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).expect("response json");
+
+    assert_eq!(body["success"], false);
+    assert_eq!(body["claude"], json!({ "ok": true }));
+    assert_eq!(body["gemini"], json!({ "ok": true }));
+    assert_eq!(body["codex"]["ok"], false);
+    assert!(body["codex"]["error"].is_string());
+    assert!(body["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("codex"));
+
+    // Codex 失败不影响其他应用的写入
+    let settings = get_claude_settings_path().expect("claude settings path");
+    assert!(settings.exists(), "claude settings should be written");
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "web-server")]
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppType, MultiAppConfig, Provider};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, get_json, make_app, reset_test_fs, test_mutex};
+
+fn tagged_config() -> MultiAppConfig {
+    let mut config = MultiAppConfig::default();
+    let manager = config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager");
+    for (id, tags) in [
+        ("a", vec!["fast", "paid"]),
+        ("b", vec!["paid"]),
+        ("c", vec![]),
+    ] {
+        let mut provider = Provider::with_id(
+            id.to_string(),
+            id.to_uppercase(),
+            json!({ "env": {} }),
+            None,
+        );
+        provider.tags = tags.into_iter().map(str::to_string).collect();
+        manager.providers.insert(id.to_string(), provider);
+    }
+    config
+}
+
+#[tokio::test]
+#[serial]
+async fn test_list_providers_filters_by_tag() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app(tagged_config());
+
+    let paid = get_json(app.clone(), "/api/providers/claude?tag=paid").await;
+    let paid = paid.as_object().expect("providers map");
+    assert_eq!(paid.len(), 2);
+    assert!(paid.contains_key("a") && paid.contains_key("b"));
+
+    let fast = get_json(app.clone(), "/api/providers/claude?tag=fast").await;
+    assert_eq!(fast.as_object().map(|m| m.len()), Some(1));
+    assert_eq!(fast["a"]["tags"], json!(["fast", "paid"]));
+
+    let tags = get_json(app.clone(), "/api/providers/claude/tags").await;
+    assert_eq!(tags, json!(["fast", "paid"]));
+
+    // 重复标签会被拒绝
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/claude")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            json!({
+                "id": "d",
+                "name": "D",
+                "settingsConfig": { "env": {} },
+                "tags": ["paid", " paid "]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+#[serial]
+fn test_provider_tags_round_trip_through_save_and_load() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    tagged_config().save().expect("save config");
+    let loaded = MultiAppConfig::load().expect("load config");
+    let providers = &loaded
+        .get_manager(&AppType::Claude)
+        .expect("claude manager")
+        .providers;
+    assert_eq!(providers["a"].tags, vec!["fast", "paid"]);
+    assert!(providers["c"].tags.is_empty());
+}
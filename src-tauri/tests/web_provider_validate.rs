@@ -0,0 +1,121 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+async fn validate(app: &axum::Router, app_id: &str, settings: Value) -> Value {
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("/api/providers/{app_id}/validate"))
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "settings": settings }).to_string()))
+        .expect("build request");
+    let res = app.clone().oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    serde_json::from_slice(&bytes).expect("response json")
+}
+
+fn assert_valid(body: &Value) {
+    assert_eq!(body["ok"], json!(true), "{body}");
+    assert_eq!(body["errors"], json!([]), "{body}");
+}
+
+fn assert_invalid(body: &Value, needle: &str) {
+    assert_eq!(body["ok"], json!(false), "{body}");
+    let errors = body["errors"].as_array().expect("errors array");
+    assert!(
+        errors
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|error| error.contains(needle)),
+        "expected `{needle}` in {body}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn validate_reports_settings_shape_errors_without_saving() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    // Claude：必须是 JSON 对象
+    assert_valid(
+        &validate(
+            &app,
+            "claude",
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk" } }),
+        )
+        .await,
+    );
+    assert_invalid(
+        &validate(&app, "claude", json!("not an object")).await,
+        "JSON",
+    );
+
+    // Codex：需要 auth 对象，config 必须是合法 TOML 字符串
+    assert_valid(
+        &validate(
+            &app,
+            "codex",
+            json!({ "auth": { "OPENAI_API_KEY": "sk" }, "config": "model = \"gpt-5\"\n" }),
+        )
+        .await,
+    );
+    assert_invalid(
+        &validate(&app, "codex", json!({ "config": "model = \"gpt-5\"" })).await,
+        "auth",
+    );
+    assert_invalid(
+        &validate(
+            &app,
+            "codex",
+            json!({ "auth": {}, "config": "model = = broken" }),
+        )
+        .await,
+        "TOML",
+    );
+
+    // Gemini：env 必须是对象
+    assert_valid(
+        &validate(
+            &app,
+            "gemini",
+            json!({ "env": { "GEMINI_API_KEY": "key" } }),
+        )
+        .await,
+    );
+    assert_invalid(
+        &validate(&app, "gemini", json!({ "env": "GEMINI_API_KEY=key" })).await,
+        "env",
+    );
+
+    let cfg = state.config.read().expect("read config");
+    for app_type in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+        assert!(cfg
+            .get_manager(&app_type)
+            .map_or(true, |manager| manager.providers.is_empty()));
+    }
+    assert!(!home.join(".cc-switch").join("config.json").exists());
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::http::{Method, StatusCode};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn read_only_mode_allows_reads_and_blocks_writes() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::set_var("WEB_READ_ONLY", "1");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let (status, _) = send(&app, Method::GET, "/api/settings", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, flags) = send(&app, Method::GET, "/api/system/config-flags", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(flags["readOnly"], Value::Bool(true));
+
+    for (method, uri, body) in [
+        (
+            Method::POST,
+            "/api/providers/claude",
+            Some(json!({"provider":{"id":"p1","name":"P1","settingsConfig":{}}})),
+        ),
+        (Method::PUT, "/api/settings", Some(json!({}))),
+        (Method::DELETE, "/api/providers/claude/p1", None),
+    ] {
+        let (status, body) = send(&app, method.clone(), uri, body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}: {body}");
+        assert!(
+            body.to_string().contains("read-only"),
+            "{method} {uri}: {body}"
+        );
+    }
+    assert!(state
+        .config
+        .read()
+        .expect("read config")
+        .get_manager(&cc_switch_lib::AppType::Claude)
+        .map_or(true, |manager| manager.providers.is_empty()));
+
+    std::env::remove_var("WEB_READ_ONLY");
+    let app = web_api::create_router(state, "password".to_string());
+    let (status, _) = send(&app, Method::PUT, "/api/settings", Some(json!({}))).await;
+    assert_ne!(status, StatusCode::FORBIDDEN);
+}
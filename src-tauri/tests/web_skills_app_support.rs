@@ -1,31 +1,22 @@
 #![cfg(feature = "web-server")]
 
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 use axum::{
     body::{to_bytes, Body},
     http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
 };
-use base64::Engine;
 use cc_switch_lib::{web_api, AppState, MultiAppConfig};
 use serial_test::serial;
 use tower::ServiceExt;
 
 #[path = "support.rs"]
 mod support;
-use support::{ensure_test_home, reset_test_fs, test_mutex};
-
-fn basic_auth_header(user: &str, password: &str) -> HeaderValue {
-    let raw = format!("{user}:{password}");
-    let encoded = base64::engine::general_purpose::STANDARD.encode(raw.as_bytes());
-    HeaderValue::from_str(&format!("Basic {encoded}")).expect("basic auth header")
-}
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
 
 fn make_app(password: &str, csrf: &str) -> axum::Router {
     std::env::set_var("WEB_CSRF_TOKEN", csrf);
-    let state = Arc::new(AppState {
-        config: RwLock::new(MultiAppConfig::default()),
-    });
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
     web_api::create_router(state, password.to_string())
 }
 
@@ -101,6 +92,40 @@ async fn skills_install_rejects_upcoming_app_payload() {
     );
 }
 
+#[tokio::test]
+#[serial]
+async fn skills_install_missing_skill_returns_not_found() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    // 不配置任何仓库，避免测试访问网络
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let mut config = MultiAppConfig::default();
+    config.skills.repos.clear();
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/skills/install")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            serde_json::json!({ "directory": "does-not-exist" }).to_string(),
+        ))
+        .expect("build request");
+
+    let res = dispatch(app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let error = response_error_message(res).await;
+    assert!(
+        error.contains("SKILL_NOT_FOUND"),
+        "unexpected error message: {error}"
+    );
+}
+
 #[tokio::test]
 #[serial]
 async fn config_get_dir_supports_opencode() {
@@ -0,0 +1,167 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{AppState, MultiAppConfig};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{
+    basic_auth_header, ensure_test_home, make_app_with_state, reset_test_fs, response_json,
+    test_mutex,
+};
+
+const CACHE_KEY: &str = "cc-switch-test/missing-skills/main";
+
+fn cached_state() -> Arc<AppState> {
+    let mut config = MultiAppConfig::default();
+    config.skills.repos = serde_json::from_value(serde_json::json!([{
+        "owner": "cc-switch-test",
+        "name": "missing-skills",
+        "branch": "main",
+        "enabled": true,
+        "skillsPath": null
+    }]))
+    .expect("repo list");
+    config.skills.repo_cache = serde_json::from_value(serde_json::json!({
+        CACHE_KEY: { "fetchedAt": chrono::Utc::now().to_rfc3339(), "etag": "\"abc\"" }
+    }))
+    .expect("repo cache");
+    Arc::new(AppState::new(config))
+}
+
+fn request(method: Method, uri: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_clear_skill_cache_empties_repo_cache_and_forces_refetch() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    // 默认 TTL 为 0（始终重新校验），这里放宽以便命中缓存
+    std::env::set_var("CC_SWITCH_SKILLS_CACHE_TTL_SECS", "3600");
+    let state = cached_state();
+    let app = make_app_with_state(state.clone());
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/skills/cache"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let entries = response_json(res).await;
+    assert_eq!(entries[0]["key"], CACHE_KEY);
+    assert!(entries[0]["fetchedAt"].is_string());
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/skills"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let listed = response_json(res).await;
+    assert_eq!(listed["cacheHit"], true);
+    assert_eq!(listed["refreshing"], false);
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::DELETE, "/api/skills/cache"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let cleared = response_json(res).await;
+    assert_eq!(cleared["removed"], serde_json::json!([CACHE_KEY]));
+    assert!(state.config.read().unwrap().skills.repo_cache.is_empty());
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::GET, "/api/skills/cache"))
+        .await
+        .unwrap();
+    assert_eq!(response_json(res).await, serde_json::json!([]));
+
+    let res = app
+        .oneshot(request(Method::GET, "/api/skills"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let listed = response_json(res).await;
+    std::env::remove_var("CC_SWITCH_SKILLS_CACHE_TTL_SECS");
+    assert_eq!(listed["cacheHit"], false);
+    assert_eq!(listed["refreshing"], true);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_clear_skill_cache_for_single_repo() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let state = cached_state();
+    let app = make_app_with_state(state.clone());
+
+    let res = app
+        .clone()
+        .oneshot(request(Method::DELETE, "/api/skills/cache?repo=other/repo"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(response_json(res).await["removed"], serde_json::json!([]));
+    assert_eq!(state.config.read().unwrap().skills.repo_cache.len(), 1);
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            Method::DELETE,
+            "/api/skills/cache?repo=cc-switch-test/missing-skills",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(state.config.read().unwrap().skills.repo_cache.is_empty());
+
+    let res = app
+        .oneshot(request(Method::DELETE, "/api/skills/cache?repo=invalid"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_list_skills_reports_cache_flags() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config.skills.repos.clear();
+    let app = make_app_with_state(Arc::new(AppState::new(config)));
+
+    let res = app
+        .oneshot(request(Method::GET, "/api/skills?app=claude"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let listed = response_json(res).await;
+    // 与桌面端 get_skills 返回的 SkillsResponse 保持一致
+    assert_eq!(listed["cacheHit"], true);
+    assert_eq!(listed["refreshing"], false);
+    assert!(listed["skills"].is_array());
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn install_batch_reports_per_directory_results() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    // 本地已存在的技能无需下载；不配置任何仓库，避免测试访问网络
+    let skill_dir = home.join(".claude").join("skills").join("demo");
+    std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+    std::fs::write(
+        skill_dir.join("SKILL.md"),
+        "---\nname: Demo\ndescription: Demo skill\n---\n",
+    )
+    .expect("write SKILL.md");
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let mut config = MultiAppConfig::default();
+    config.skills.repos.clear();
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/skills/install-batch")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(
+            json!({ "directories": ["demo", "does-not-exist", "demo"] }).to_string(),
+        ))
+        .expect("build request");
+    let res = app.oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let results: Value = serde_json::from_slice(&bytes).expect("response json");
+
+    let results = results.as_array().expect("result array");
+    assert_eq!(results.len(), 2, "duplicate directories are installed once");
+    assert_eq!(
+        results[0],
+        json!({ "directory": "demo", "installed": true })
+    );
+    assert_eq!(results[1]["directory"], "does-not-exist");
+    assert_eq!(results[1]["installed"], false);
+    assert!(results[1]["error"]
+        .as_str()
+        .is_some_and(|error| error.contains("SKILL_NOT_FOUND")));
+
+    // 成功项已写入状态（内存与磁盘），失败项不写入
+    let cfg = state.config.read().expect("read config");
+    assert!(cfg
+        .skills
+        .skills
+        .get("claude:demo")
+        .is_some_and(|s| s.installed));
+    assert!(!cfg.skills.skills.contains_key("claude:does-not-exist"));
+    drop(cfg);
+    let saved = std::fs::read_to_string(home.join(".cc-switch").join("config.json"))
+        .expect("read saved config");
+    assert!(saved.contains("claude:demo"));
+}
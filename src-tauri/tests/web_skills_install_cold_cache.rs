@@ -0,0 +1,116 @@
+#![cfg(feature = "web-server")]
+
+use std::io::Write;
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+/// 构造与 GitHub 归档结构一致的 zip：所有文件位于 `<repo>-<branch>/` 下
+fn repo_archive() -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    writer
+        .start_file("toolbox-main/toolkit/SKILL.md", options)
+        .expect("start SKILL.md");
+    writer
+        .write_all(b"---\nname: Toolkit\ndescription: demo\n---\n")
+        .expect("write SKILL.md");
+    writer.finish().expect("finish zip").into_inner()
+}
+
+/// 在 127.0.0.1 上模拟 GitHub 的归档下载地址
+async fn spawn_archive_server() -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind archive server");
+    let addr = listener.local_addr().expect("archive server addr");
+    let archive = repo_archive();
+    let router = axum::Router::new().route(
+        "/acme/toolbox/archive/refs/heads/main.zip",
+        axum::routing::get(move || {
+            let archive = archive.clone();
+            async move { archive }
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.ok();
+    });
+    addr
+}
+
+#[tokio::test]
+#[serial]
+async fn install_fetches_repo_when_cache_is_cold() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let addr = spawn_archive_server().await;
+    std::env::set_var("SKILLS_ARCHIVE_BASE_URL", format!("http://{addr}"));
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    // 没有任何仓库缓存：安装前必须同步下载仓库，不能只依赖后台刷新
+    let config = MultiAppConfig {
+        skills: serde_json::from_value(json!({
+            "skills": {},
+            "repos": [{
+                "owner": "acme",
+                "name": "toolbox",
+                "branch": "main",
+                "enabled": true,
+                "skillsPath": null
+            }]
+        }))
+        .expect("skill store"),
+        ..Default::default()
+    };
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/skills/install")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "directory": "toolkit" }).to_string()))
+        .unwrap();
+    let res = app.clone().oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let body: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    std::env::remove_var("SKILLS_ARCHIVE_BASE_URL");
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body, json!(true));
+    assert!(home
+        .join(".claude")
+        .join("skills")
+        .join("toolkit")
+        .join("SKILL.md")
+        .exists());
+
+    let cfg = state.config.read().expect("read config");
+    assert!(
+        cfg.skills.repo_cache.contains_key("acme/toolbox/main"),
+        "cold fetch should populate the repo cache"
+    );
+    assert!(cfg
+        .skills
+        .skills
+        .get("claude:toolkit")
+        .is_some_and(|record| record.installed));
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::http::Method;
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send_ok, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn install_records_source_repo_and_ref() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    // 技能目录已存在时安装会跳过下载；仓库列表由新鲜的缓存提供，测试不访问网络
+    let skill_dir = home.join(".claude").join("skills").join("toolkit");
+    std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+    std::fs::write(skill_dir.join("SKILL.md"), "---\nname: Toolkit\n---\n")
+        .expect("write SKILL.md");
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let config = MultiAppConfig {
+        skills: serde_json::from_value(json!({
+            "skills": {},
+            "repos": [{
+                "owner": "acme",
+                "name": "toolbox",
+                "branch": "main",
+                "ref": "v1.2.0",
+                "enabled": true,
+                "skillsPath": null
+            }],
+            "repoCache": {
+                "acme/toolbox/v1.2.0": {
+                    "skills": [{
+                        "key": "acme/toolbox:toolkit",
+                        "name": "Toolkit",
+                        "description": "",
+                        "directory": "toolkit",
+                        "readmeUrl": null,
+                        "installed": false,
+                        "repoOwner": "acme",
+                        "repoName": "toolbox",
+                        "repoBranch": "main",
+                        "repoRef": "v1.2.0",
+                        "skillsPath": null
+                    }],
+                    "fetchedAt": chrono::Utc::now()
+                }
+            }
+        }))
+        .expect("skill store"),
+        ..Default::default()
+    };
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    let installed = send_ok(
+        &app,
+        Method::POST,
+        "/api/skills/install",
+        Some(json!({ "directory": "toolkit" })),
+    )
+    .await;
+    assert_eq!(installed, json!(true));
+
+    {
+        let cfg = state.config.read().expect("read config");
+        let record = cfg
+            .skills
+            .skills
+            .get("claude:toolkit")
+            .expect("install state recorded");
+        assert!(record.installed);
+        assert_eq!(record.repo_owner.as_deref(), Some("acme"));
+        assert_eq!(record.repo_name.as_deref(), Some("toolbox"));
+        assert_eq!(record.git_ref.as_deref(), Some("v1.2.0"));
+    }
+
+    let listing = send_ok(&app, Method::GET, "/api/skills", None).await;
+    let toolkit = listing["skills"]
+        .as_array()
+        .expect("skills array")
+        .iter()
+        .find(|skill| skill["directory"] == "toolkit")
+        .expect("toolkit listed")
+        .clone();
+    assert_eq!(toolkit["installState"]["repoOwner"], "acme");
+    assert_eq!(toolkit["installState"]["repoName"], "toolbox");
+    assert_eq!(toolkit["installState"]["ref"], "v1.2.0");
+
+    let report = send_ok(&app, Method::GET, "/api/skills/verify", None).await;
+    assert_eq!(report["installStates"]["toolkit"]["repoOwner"], "acme");
+    assert_eq!(report["installStates"]["toolkit"]["ref"], "v1.2.0");
+}
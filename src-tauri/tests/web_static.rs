@@ -0,0 +1,272 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, get_request, reset_test_fs, test_mutex};
+
+fn make_app(password: &str, csrf: &str) -> axum::Router {
+    std::env::set_var("WEB_CSRF_TOKEN", csrf);
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    web_api::create_router(state, password.to_string())
+}
+
+async fn response_bytes(res: axum::response::Response) -> Vec<u8> {
+    to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body")
+        .to_vec()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_index_html_is_injected_once_and_served_identically() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+
+    let first = app.clone().oneshot(get_request("/")).await.unwrap();
+    assert_eq!(first.status(), StatusCode::OK);
+    let first = response_bytes(first).await;
+
+    let second = app.oneshot(get_request("/index.html")).await.unwrap();
+    assert_eq!(second.status(), StatusCode::OK);
+    let second = response_bytes(second).await;
+
+    let html = String::from_utf8(first.clone()).expect("utf-8 index");
+    assert!(html.contains(r#"csrfToken: "static-csrf-token""#));
+    assert!(html.contains(r#"<meta name="csrf-token" content="static-csrf-token">"#));
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_missing_asset_returns_404_instead_of_index() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+
+    let res = app
+        .clone()
+        .oneshot(get_request("/assets/x.js"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let index = response_bytes(app.clone().oneshot(get_request("/")).await.unwrap()).await;
+    let res = app.oneshot(get_request("/some/spa/route")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let content_type = res
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    assert!(content_type.starts_with("text/html"));
+    assert_eq!(response_bytes(res).await, index);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_missing_asset_falls_back_to_index_when_html_accepted() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/settings/v1.2")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("accept", HeaderValue::from_static("text/html,*/*;q=0.8"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_index_html_is_sent_with_no_cache() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+
+    for uri in ["/", "/index.html", "/some/spa/route"] {
+        let res = app.clone().oneshot(get_request(uri)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()
+                .get("cache-control")
+                .and_then(|v| v.to_str().ok()),
+            Some("no-cache"),
+            "{uri} should not be cached"
+        );
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unknown_api_route_returns_json_404() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+
+    let res = app
+        .clone()
+        .oneshot(get_request("/api/nonexistent"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let content_type = res
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    assert!(content_type.starts_with("application/json"));
+    let body: serde_json::Value =
+        serde_json::from_slice(&response_bytes(res).await).expect("json body");
+    assert!(body["error"]
+        .as_str()
+        .is_some_and(|msg| msg.contains("/nonexistent")));
+
+    // 非 /api 路径仍走 SPA 回退
+    let res = app.oneshot(get_request("/nonexistent")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_head_index_returns_headers_without_body() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+
+    for uri in ["/", "/index.html", "/some/spa/route"] {
+        let req = Request::builder()
+            .method(Method::HEAD)
+            .uri(uri)
+            .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+            .body(Body::empty())
+            .unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "HEAD {uri}");
+        let content_type = res
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        assert!(content_type.starts_with("text/html"), "HEAD {uri}");
+        assert!(res.headers().get("x-content-type-options").is_some());
+        assert!(response_bytes(res).await.is_empty(), "HEAD {uri} body");
+    }
+
+    // HEAD 同样需要认证
+    let req = Request::builder()
+        .method(Method::HEAD)
+        .uri("/")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_assets_check_reports_embedded_index_html() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let app = make_app("password", "static-csrf-token");
+    let res = app
+        .oneshot(get_request("/api/system/assets-check"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: serde_json::Value =
+        serde_json::from_slice(&response_bytes(res).await).expect("assets check json");
+
+    assert_eq!(body["indexHtmlPresent"], true);
+    assert!(body["indexHtmlBytes"]
+        .as_u64()
+        .is_some_and(|bytes| bytes > 0));
+    assert!(body["assetCount"].as_u64().is_some_and(|count| count >= 1));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_branding_dir_overrides_embedded_assets() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let branding = home.join(".cc-switch").join("branding");
+    std::fs::create_dir_all(branding.join("assets")).expect("create branding dir");
+    std::fs::write(branding.join("favicon.ico"), b"custom-favicon").expect("write favicon");
+    std::fs::write(home.join(".cc-switch").join("secret.txt"), b"secret")
+        .expect("write file outside branding dir");
+    std::env::set_var("WEB_BRANDING_DIR", &branding);
+
+    let app = make_app("password", "static-csrf-token");
+
+    let res = app
+        .clone()
+        .oneshot(get_request("/favicon.ico"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(response_bytes(res).await, b"custom-favicon");
+
+    // 没有覆盖文件时回落到内嵌资源
+    let res = app
+        .clone()
+        .oneshot(get_request("/assets/index-abc123.js"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let embedded = response_bytes(res).await;
+    assert_ne!(embedded, b"custom-js");
+
+    // 覆盖文件优先于同名的内嵌资源
+    std::fs::write(
+        branding.join("assets").join("index-abc123.js"),
+        b"custom-js",
+    )
+    .expect("write override asset");
+    let res = app
+        .clone()
+        .oneshot(get_request("/assets/index-abc123.js"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(response_bytes(res).await, b"custom-js");
+
+    // 不能借覆盖目录读取目录之外的文件
+    let res = app.oneshot(get_request("/..%2Fsecret.txt")).await.unwrap();
+    let body = response_bytes(res).await;
+    std::env::remove_var("WEB_BRANDING_DIR");
+    assert_ne!(body, b"secret");
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::http::Method;
+use cc_switch_lib::{web_api, web_api::logging, AppState, MultiAppConfig};
+use serde_json::Value;
+use serial_test::serial;
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, send_ok, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn logged_warning_appears_in_buffer_with_secrets_redacted() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    std::env::set_var("ENABLE_LOG_BUFFER", "1");
+    logging::init_logger("info");
+
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    log::warn!("upstream rejected request with Bearer sk-test-1234567890");
+
+    let body = send_ok(&app, Method::GET, "/api/system/logs?lines=50", None).await;
+    let lines = body["lines"].as_array().expect("lines array");
+    let warning = lines
+        .iter()
+        .filter_map(Value::as_str)
+        .find(|line| line.contains("upstream rejected request"))
+        .unwrap_or_else(|| panic!("warning missing from buffer: {body}"));
+    assert!(warning.contains("WARN"), "unexpected line: {warning}");
+    assert!(!warning.contains("1234567890"), "secret leaked: {warning}");
+
+    let cleared = send_ok(&app, Method::DELETE, "/api/system/logs?lines=50", None).await;
+    assert!(
+        cleared.as_u64().is_some_and(|n| n >= 1),
+        "unexpected: {cleared}"
+    );
+    let body = send_ok(&app, Method::GET, "/api/system/logs?lines=50", None).await;
+    assert!(
+        body["lines"]
+            .as_array()
+            .expect("lines array")
+            .iter()
+            .filter_map(Value::as_str)
+            .all(|line| !line.contains("upstream rejected request")),
+        "buffer should be cleared: {body}"
+    );
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+fn seeded_config() -> MultiAppConfig {
+    let provider = |id: &str| json!({ "id": id, "name": id, "settingsConfig": {} });
+    let prompt = |id: &str| json!({ "id": id, "name": id, "content": "hello" });
+    let server = |id: &str| {
+        json!({
+            "id": id,
+            "name": id,
+            "server": { "type": "stdio", "command": "echo" },
+            "apps": { "claude": true, "codex": false, "gemini": false }
+        })
+    };
+    serde_json::from_value(json!({
+        "version": 2,
+        "claude": {
+            "providers": { "a": provider("a"), "b": provider("b") },
+            "current": "a"
+        },
+        "codex": { "providers": { "c": provider("c") }, "current": "c" },
+        "mcp": { "servers": { "s1": server("s1"), "s2": server("s2"), "s3": server("s3") } },
+        "prompts": {
+            "claude": { "prompts": { "p1": prompt("p1") } },
+            "gemini": { "prompts": { "p2": prompt("p2") } }
+        },
+        "skills": {
+            "skills": {
+                "demo": { "installed": true, "installedAt": "2024-01-01T00:00:00Z" },
+                "removed": { "installed": false, "installedAt": "2024-01-01T00:00:00Z" }
+            },
+            "repos": []
+        }
+    }))
+    .expect("seeded config")
+}
+
+#[tokio::test]
+#[serial]
+async fn test_system_stats_reports_seeded_counts() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let config = seeded_config();
+    config.save().expect("save config");
+    let config_path = home.join(".cc-switch").join("config.json");
+    let config_bytes = std::fs::metadata(&config_path)
+        .expect("config metadata")
+        .len();
+    let backup_dir = home.join(".cc-switch").join("backups");
+    std::fs::create_dir_all(&backup_dir).expect("create backup dir");
+    std::fs::write(backup_dir.join("backup_1_0.json"), "{}").expect("write backup");
+    std::fs::write(backup_dir.join("backup_2_1.json"), "{}").expect("write backup");
+    std::fs::write(backup_dir.join("notes.txt"), "ignored").expect("write non-backup");
+
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/stats")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+
+    assert_eq!(body["configBytes"], json!(config_bytes));
+    assert_eq!(
+        body["providerCounts"],
+        json!({ "claude": 2, "codex": 1, "gemini": 0 })
+    );
+    assert_eq!(body["mcpServerCount"], 3);
+    assert_eq!(body["promptCount"], 2);
+    assert_eq!(body["skillCount"], 1);
+    assert_eq!(body["backupCount"], 2);
+}
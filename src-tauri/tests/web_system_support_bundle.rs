@@ -0,0 +1,101 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig, Provider};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+#[tokio::test]
+#[serial]
+async fn support_bundle_contains_version_and_masks_secrets() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".to_string(),
+                "Relay".to_string(),
+                json!({
+                    "env": {
+                        "ANTHROPIC_AUTH_TOKEN": "sk-bundle-secret-123456",
+                        "ANTHROPIC_BASE_URL": "https://relay.example.com"
+                    }
+                }),
+                None,
+            ),
+        );
+    config
+        .get_manager_mut(&AppType::Codex)
+        .expect("codex manager")
+        .providers
+        .insert(
+            "codex-relay".to_string(),
+            Provider::with_id(
+                "codex-relay".to_string(),
+                "Codex Relay".to_string(),
+                json!({
+                    "auth": { "OPENAI_API_KEY": "codex-bundle-secret" },
+                    "config": "model = \"gpt-5\"\nexperimental_bearer_token = \"toml-bundle-secret\"\n"
+                }),
+                None,
+            ),
+        );
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/support-bundle")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res
+        .headers()
+        .get("content-disposition")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("attachment")));
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let raw = String::from_utf8(bytes.to_vec()).expect("utf8 body");
+    let bundle: Value = serde_json::from_str(&raw).expect("bundle json");
+
+    assert_eq!(bundle["version"], env!("CARGO_PKG_VERSION"));
+    assert!(bundle["appConfigPath"].is_string());
+    assert!(bundle["appDirs"]["claude"].is_string());
+    assert!(bundle["configFlags"].is_object());
+    assert_eq!(bundle["stats"]["providerCounts"]["claude"], 1);
+
+    for secret in [
+        "sk-bundle-secret-123456",
+        "codex-bundle-secret",
+        "toml-bundle-secret",
+    ] {
+        assert!(!raw.contains(secret), "secret {secret} leaked: {raw}");
+    }
+    let claude_env = &bundle["config"]["claude"]["providers"]["relay"]["settingsConfig"]["env"];
+    assert_eq!(claude_env["ANTHROPIC_AUTH_TOKEN"], "****");
+    assert_eq!(
+        claude_env["ANTHROPIC_BASE_URL"],
+        "https://relay.example.com"
+    );
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
+};
+use cc_switch_lib::{store::BackgroundTaskKind, web_api, AppState, MultiAppConfig};
+use serde_json::Value;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+async fn list_tasks(app: axum::Router) -> Vec<Value> {
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/system/tasks")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    serde_json::from_slice(&bytes).expect("tasks json")
+}
+
+#[tokio::test]
+#[serial]
+async fn in_progress_install_is_listed_until_finished() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state.clone(), "password".to_string());
+
+    // 模拟一个进行中的安装
+    let install = state
+        .tasks()
+        .register(BackgroundTaskKind::SkillInstall, "claude:demo-skill");
+
+    let tasks = list_tasks(app.clone()).await;
+    let task = tasks
+        .iter()
+        .find(|task| task["target"] == "claude:demo-skill")
+        .expect("in-progress install should be listed");
+    assert_eq!(task["type"], "skillInstall");
+    assert!(task["startedAt"].is_string(), "startedAt missing: {task}");
+
+    // 注册表属于各自的 AppState，其他实例中的任务不会出现在这里
+    let other = AppState::new(MultiAppConfig::default());
+    let _other_install = other
+        .tasks()
+        .register(BackgroundTaskKind::SkillInstall, "claude:other-skill");
+    let tasks = list_tasks(app.clone()).await;
+    assert!(tasks
+        .iter()
+        .all(|task| task["target"] != "claude:other-skill"));
+
+    drop(install);
+    let tasks = list_tasks(app).await;
+    assert!(
+        tasks
+            .iter()
+            .all(|task| task["target"] != "claude:demo-skill"),
+        "finished install should be removed: {tasks:?}"
+    );
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+/// 模拟一个被限流的用量接口，同时返回敏感与调试用响应头
+async fn spawn_rate_limited_server() -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server addr");
+    let router = axum::Router::new().route(
+        "/usage",
+        axum::routing::get(|| async {
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [
+                    ("x-request-id", "req-123"),
+                    ("retry-after", "30"),
+                    ("x-ratelimit-remaining", "0"),
+                    ("set-cookie", "session=secret"),
+                    ("x-api-key", "sk-echo"),
+                ],
+                "{}",
+            )
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.ok();
+    });
+    addr
+}
+
+#[tokio::test]
+#[serial]
+async fn test_usage_script_returns_allowlisted_response_headers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let addr = spawn_rate_limited_server().await;
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let script = format!(
+        "({{ request: {{ url: \"http://{addr}/usage\", method: \"GET\", headers: {{}} }}, \
+         extractor: function (response) {{ return {{ isValid: true }}; }} }})"
+    );
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/claude/any/usage/test")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "scriptCode": script }).to_string()))
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).expect("response json");
+
+    // 请求失败时也应带回响应头，方便排查限流
+    assert_eq!(body["success"], false, "unexpected body: {body}");
+    let headers = body["responseHeaders"]
+        .as_object()
+        .expect("responseHeaders object");
+    assert_eq!(headers.get("x-request-id"), Some(&json!("req-123")));
+    assert_eq!(headers.get("retry-after"), Some(&json!("30")));
+    assert_eq!(headers.get("x-ratelimit-remaining"), Some(&json!("0")));
+    assert!(!headers.contains_key("set-cookie"));
+    assert!(!headers.contains_key("x-api-key"));
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+async fn parse_script(script: &str) -> Value {
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/usage-script/parse")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "scriptCode": script }).to_string()))
+        .expect("build request");
+    let res = app.oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    serde_json::from_slice(&bytes).expect("response json")
+}
+
+#[tokio::test]
+#[serial]
+async fn parse_accepts_valid_script_without_network() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    // 未替换的模板变量与不可达地址都不影响解析
+    let body = parse_script(
+        "({ request: { url: \"{{baseUrl}}/usage\", method: \"GET\", \
+         headers: { Authorization: \"Bearer {{apiKey}}\" } }, \
+         extractor: function (response) { return { isValid: true }; } })",
+    )
+    .await;
+    assert_eq!(body["ok"], true, "unexpected body: {body}");
+    assert_eq!(body["errors"], json!([]));
+}
+
+#[tokio::test]
+#[serial]
+async fn parse_reports_syntax_errors() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let body = parse_script("({ request: { url: \"https://example.com\" }").await;
+    assert_eq!(body["ok"], false, "unexpected body: {body}");
+    let errors = body["errors"].as_array().expect("errors array");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].as_str().is_some_and(|msg| !msg.is_empty()));
+}
+
+#[tokio::test]
+#[serial]
+async fn variables_endpoint_lists_supported_placeholders() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/usage-script/variables")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .expect("build request");
+    let res = app.oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let body: Value = serde_json::from_slice(&bytes).expect("response json");
+
+    let variables = body.as_array().expect("variables array");
+    let names: Vec<&str> = variables
+        .iter()
+        .filter_map(|variable| variable["name"].as_str())
+        .collect();
+    for expected in ["apiKey", "baseUrl", "accessToken", "userId"] {
+        assert!(names.contains(&expected), "missing {expected}: {body}");
+    }
+    assert!(variables.iter().all(|variable| variable["description"]
+        .as_str()
+        .is_some_and(|d| !d.is_empty())));
+}
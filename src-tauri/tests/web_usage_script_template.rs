@@ -0,0 +1,90 @@
+#![cfg(feature = "web-server")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Query,
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, AppType, MultiAppConfig, Provider};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+/// 原样回显查询参数，便于断言脚本中的占位符被替换成了什么
+async fn spawn_echo_server() -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server addr");
+    let router = axum::Router::new().route(
+        "/usage",
+        axum::routing::get(|Query(query): Query<HashMap<String, String>>| async move {
+            axum::Json(json!(query))
+        }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.ok();
+    });
+    addr
+}
+
+#[tokio::test]
+#[serial]
+async fn usage_script_substitutes_provider_model_and_name() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let addr = spawn_echo_server().await;
+    let mut config = MultiAppConfig::default();
+    config
+        .get_manager_mut(&AppType::Claude)
+        .expect("claude manager")
+        .providers
+        .insert(
+            "model-demo".to_string(),
+            Provider::with_id(
+                "model-demo".to_string(),
+                "Demo".to_string(),
+                json!({ "env": { "ANTHROPIC_MODEL": "claude-sonnet-demo" } }),
+                None,
+            ),
+        );
+    let state = Arc::new(AppState::new(config));
+    let app = web_api::create_router(state, "password".to_string());
+
+    let script = format!(
+        "({{ request: {{ url: \"http://{addr}/usage?model={{{{model}}}}&name={{{{providerName}}}}\", \
+         method: \"GET\", headers: {{}} }}, \
+         extractor: function (response) {{ return {{ isValid: true, planName: response.model, \
+         extra: response.name + ' {{{{userId}}}}' }}; }} }})"
+    );
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/claude/model-demo/usage/test")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "scriptCode": script }).to_string()))
+        .expect("build request");
+    let res = app.oneshot(req).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    let body: Value = serde_json::from_slice(&bytes).expect("response json");
+
+    assert_eq!(body["success"], true, "unexpected body: {body}");
+    assert_eq!(body["data"][0]["planName"], "claude-sonnet-demo");
+    // 未配置的 userId 占位符保持原样
+    assert_eq!(body["data"][0]["extra"], "Demo {{userId}}");
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "web-server")]
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::AUTHORIZATION, HeaderValue, Method, Request, StatusCode},
+};
+use cc_switch_lib::{web_api, AppState, MultiAppConfig};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tower::ServiceExt;
+
+#[path = "support.rs"]
+mod support;
+use support::{basic_auth_header, ensure_test_home, reset_test_fs, test_mutex};
+
+async fn spawn_usage_server() -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server addr");
+    let router = axum::Router::new().route(
+        "/usage",
+        axum::routing::get(|| async { axum::Json(json!({ "balance": 5 })) }),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.ok();
+    });
+    addr
+}
+
+async fn send(app: &axum::Router, request: Request<Body>) -> Value {
+    let res = app.clone().oneshot(request).await.expect("router response");
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("response body");
+    serde_json::from_slice(&bytes).expect("response json")
+}
+
+fn test_request(script: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/providers/claude/history-demo/usage/test")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .header("x-csrf-token", HeaderValue::from_static("csrf-token"))
+        .header("content-type", HeaderValue::from_static("application/json"))
+        .body(Body::from(json!({ "scriptCode": script }).to_string()))
+        .expect("build request")
+}
+
+#[tokio::test]
+#[serial]
+async fn usage_test_history_lists_runs_newest_first() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+    std::env::remove_var("USAGE_SCRIPT_EGRESS_POLICY");
+    std::env::set_var("WEB_CSRF_TOKEN", "csrf-token");
+
+    let addr = spawn_usage_server().await;
+    let state = Arc::new(AppState::new(MultiAppConfig::default()));
+    let app = web_api::create_router(state, "password".to_string());
+
+    // 第一次：语法错误；第二次：正常返回
+    let first = send(&app, test_request("({ request: ")).await;
+    assert_eq!(first["success"], false, "unexpected body: {first}");
+    let script = format!(
+        "({{ request: {{ url: \"http://{addr}/usage\", method: \"GET\", headers: {{}} }}, \
+         extractor: function (response) {{ return {{ isValid: true, remaining: response.balance }}; }} }})"
+    );
+    let second = send(&app, test_request(&script)).await;
+    assert_eq!(second["success"], true, "unexpected body: {second}");
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri("/api/providers/claude/history-demo/usage/test-history")
+        .header(AUTHORIZATION, basic_auth_header("admin", "password"))
+        .body(Body::empty())
+        .expect("build request");
+    let history = send(&app, req).await;
+    let entries = history.as_array().expect("history array");
+    assert_eq!(entries.len(), 2, "unexpected history: {history}");
+    assert_eq!(entries[0]["success"], true);
+    assert!(entries[0].get("error").is_none());
+    assert_eq!(entries[1]["success"], false);
+    assert!(entries[1]["error"].is_string());
+    assert!(entries[0]["testedAt"].as_i64() >= entries[1]["testedAt"].as_i64());
+}
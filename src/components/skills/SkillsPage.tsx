@@ -62,6 +62,8 @@ function SkillsPageContent({ onClose: _onClose, appId }: SkillsPageProps = {}) {
   });
   const loadSkillsRequestId = useRef(0);
   const isMountedRef = useRef(true);
+  const refreshPollTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const refreshPollCount = useRef(0);
   const [repoManagerOpen, setRepoManagerOpen] = useState(false);
   const [searchQuery, setSearchQuery] = useState("");
   const [installFilter, setInstallFilter] = useState<
@@ -113,6 +115,7 @@ function SkillsPageContent({ onClose: _onClose, appId }: SkillsPageProps = {}) {
     return () => {
       isMountedRef.current = false;
       loadSkillsRequestId.current += 1;
+      if (refreshPollTimer.current) clearTimeout(refreshPollTimer.current);
     };
   }, []);
 
@@ -204,6 +207,25 @@ function SkillsPageContent({ onClose: _onClose, appId }: SkillsPageProps = {}) {
     setRepoFilter("all");
   };
 
+  // 后端在后台刷新冷缓存时，稍后自动重新拉取一次列表（有上限，避免无限轮询）
+  const scheduleRefreshPoll = (pending: boolean) => {
+    if (refreshPollTimer.current) {
+      clearTimeout(refreshPollTimer.current);
+      refreshPollTimer.current = null;
+    }
+    if (!pending || refreshPollCount.current >= 10) {
+      refreshPollCount.current = 0;
+      return;
+    }
+    refreshPollCount.current += 1;
+    refreshPollTimer.current = setTimeout(() => {
+      refreshPollTimer.current = null;
+      if (isMountedRef.current) {
+        void loadSkills(undefined, { suppressErrorToast: true });
+      }
+    }, 3000);
+  };
+
   const loadSkills = async (
     afterLoad?: (data: Skill[]) => void,
     options?: { suppressErrorToast?: boolean },
@@ -226,6 +248,7 @@ function SkillsPageContent({ onClose: _onClose, appId }: SkillsPageProps = {}) {
       if (isLatestRequest && isMountedRef.current) {
         setSkills(data);
         setCacheStatus({ cacheHit, refreshing });
+        scheduleRefreshPoll(refreshing && !cacheHit);
       }
       if (afterLoad && isLatestRequest && isMountedRef.current) {
         afterLoad(data);
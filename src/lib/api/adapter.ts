@@ -328,6 +328,13 @@ function getAutoTokens() {
     }
     return { csrfToken: tokens.csrfToken };
   }
+  // 反代剥离内联脚本时，回退读取 <meta name="csrf-token">
+  const metaToken = document
+    .querySelector<HTMLMetaElement>('meta[name="csrf-token"]')
+    ?.content?.trim();
+  if (metaToken) {
+    return { csrfToken: metaToken };
+  }
   return undefined;
 }
 
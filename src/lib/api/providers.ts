@@ -9,6 +9,11 @@ export interface ProviderSortUpdate {
   sortIndex: number;
 }
 
+export interface SwitchProviderResult {
+  success: boolean;
+  liveFilePath?: string;
+}
+
 export interface ProviderSwitchEvent {
   appType: AppId;
   providerId: string;
@@ -44,8 +49,15 @@ export const providersApi = {
   },
 
   async switch(id: string, appId: AppId): Promise<boolean> {
-    const result = await invoke("switch_provider", { id, app: appId });
-    const success = Boolean(result);
+    const result = await invoke<boolean | SwitchProviderResult>(
+      "switch_provider",
+      { id, app: appId },
+    );
+    // Web 模式返回 { success, liveFilePath }，桌面端返回 boolean
+    const success =
+      typeof result === "object" && result !== null
+        ? Boolean(result.success)
+        : Boolean(result);
     if (isWeb()) {
       try {
         await invoke("sync_current_providers_live");
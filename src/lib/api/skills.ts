@@ -30,6 +30,7 @@ export interface SkillRepo {
   branch: string;
   enabled: boolean;
   skillsPath?: string; // 可选：技能所在的子目录路径，如 "skills"
+  exclude?: string[]; // 可选：扫描时跳过的目录 glob，如 "examples"
 }
 
 export interface SkillsResponse {
@@ -12,6 +12,7 @@ export interface SkillError {
 const ERROR_I18N_KEY_MAP: Record<string, string> = {
   SKILL_NOT_FOUND: "skills.error.skillNotFound",
   MISSING_REPO_INFO: "skills.error.missingRepoInfo",
+  SKILL_REPO_INVALID: "skills.error.skillRepoInvalid",
   APP_NOT_SUPPORTED: "skills.error.appNotSupported",
   DOWNLOAD_TIMEOUT: "skills.error.downloadTimeout",
   DOWNLOAD_FAILED: "skills.error.downloadFailed",
@@ -20,6 +21,8 @@ const ERROR_I18N_KEY_MAP: Record<string, string> = {
   SKILL_INSTALL_PATH_CONFLICT: "skills.error.skillInstallPathConflict",
   EMPTY_ARCHIVE: "skills.error.emptyArchive",
   GET_HOME_DIR_FAILED: "skills.error.getHomeDirFailed",
+  INSTALL_CANCELLED: "skills.error.installCancelled",
+  INSTALL_IN_PROGRESS: "skills.error.installInProgress",
 };
 
 const isRecord = (value: unknown): value is Record<string, unknown> =>
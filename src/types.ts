@@ -12,7 +12,12 @@ export interface Provider {
   websiteUrl?: string;
   // 新增：供应商分类（用于差异化提示/能力开关）
   category?: ProviderCategory;
+  // 可选：分组（如 "work"、"personal"）
+  group?: string;
+  // 可选：标签（用于筛选与着色）
+  tags?: string[];
   createdAt?: number; // 添加时间戳（毫秒）
+  lastSwitchedAt?: number; // 最近一次切换到该供应商的时间戳（毫秒）
   sortIndex?: number; // 排序索引（用于自定义拖拽排序）
   // 备注信息
   notes?: string;
@@ -71,6 +76,7 @@ export interface UsageResult {
   success: boolean;
   data?: UsageData[]; // 改为数组，支持返回多个套餐
   error?: string;
+  responseHeaders?: Record<string, string>; // 仅测试脚本时返回的白名单响应头（请求 ID、限流信息）
 }
 
 // 供应商元数据（字段名与后端一致，保持 snake_case）
@@ -149,6 +155,7 @@ export interface McpServer {
   tags?: string[];
   homepage?: string;
   docs?: string;
+  protected?: boolean; // 受保护：删除需显式 force
   // 兼容旧字段（v3.6.x 及以前）
   enabled?: boolean; // 已废弃，v3.7.0 使用 apps 字段
   source?: string;